@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::settings_file_path;
+
+/// Field names are part of the about dialog's contract; don't rename them.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInfo {
+    pub app_name: String,
+    pub app_version: String,
+    pub tauri_version: String,
+    pub os_name: String,
+    pub os_version: String,
+    pub arch: String,
+    pub config_dir: Option<String>,
+    pub data_dir: Option<String>,
+    pub settings_path: Option<String>,
+}
+
+fn path_string<E>(path: Result<PathBuf, E>) -> Option<String> {
+    path.ok().map(|path| path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn get_app_info(app: AppHandle) -> AppInfo {
+    let package = app.package_info();
+    let os = os_info::get();
+
+    AppInfo {
+        app_name: package.name.clone(),
+        app_version: app
+            .config()
+            .version
+            .clone()
+            .unwrap_or_else(|| package.version.to_string()),
+        tauri_version: tauri::VERSION.to_string(),
+        os_name: os.os_type().to_string(),
+        os_version: os.version().to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        config_dir: path_string(app.path().app_config_dir()),
+        data_dir: path_string(app.path().app_data_dir()),
+        settings_path: path_string(settings_file_path(&app)),
+    }
+}
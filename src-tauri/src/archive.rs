@@ -0,0 +1,516 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use time::OffsetDateTime;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::{resolve_command_path, resolve_command_path_in};
+use crate::DesktopFsState;
+
+const ARCHIVE_PROGRESS_EVENT: &str = "archive-progress";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveProgressPayload {
+    archive: String,
+    entry: String,
+    entries_done: usize,
+    entries_total: usize,
+}
+
+struct PendingEntry {
+    path: PathBuf,
+    /// Archive name, always `/`-separated.
+    name: String,
+    is_dir: bool,
+}
+
+fn build_exclude_set(patterns: &[String]) -> Result<GlobSet, LatticeError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns.iter().map(|pattern| pattern.trim()).filter(|pattern| !pattern.is_empty()) {
+        builder.add(Glob::new(pattern).map_err(|error| LatticeError::InvalidInput {
+            message: error.to_string(),
+        })?);
+    }
+    builder.build().map_err(|error| error.to_string().into())
+}
+
+/// A pattern matches either the whole relative path or any single component,
+/// so `.git` and `node_modules` skip those folders wherever they appear.
+fn is_excluded(excludes: &GlobSet, relative: &str) -> bool {
+    excludes.is_match(relative) || relative.split('/').any(|component| excludes.is_match(component))
+}
+
+fn relative_name(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn collect_archive_entries(root: &Path, skip: &Path, excludes: &GlobSet) -> io::Result<Vec<PendingEntry>> {
+    let mut entries = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(directory) = pending.pop() {
+        let mut children = fs::read_dir(&directory)?.collect::<Result<Vec<_>, _>>()?;
+        children.sort_by_key(|entry| entry.file_name());
+        for child in children {
+            let path = child.path();
+            if path == skip {
+                continue;
+            }
+            let relative = relative_name(root, &path);
+            if is_excluded(excludes, &relative) {
+                continue;
+            }
+
+            // Symlinks are left out: the target may be a folder, missing, or outside the
+            // root, and extraction only recreates plain files and folders.
+            let file_type = child.file_type()?;
+            if file_type.is_symlink() {
+                continue;
+            }
+            let is_dir = file_type.is_dir();
+            if is_dir {
+                pending.push(path.clone());
+            }
+            entries.push(PendingEntry {
+                path,
+                name: relative,
+                is_dir,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn zip_timestamp(modified: io::Result<SystemTime>) -> Option<zip::DateTime> {
+    zip::DateTime::try_from(OffsetDateTime::from(modified.ok()?)).ok()
+}
+
+#[cfg(unix)]
+fn unix_permissions(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode())
+}
+
+#[cfg(not(unix))]
+fn unix_permissions(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+fn write_archive<W, F>(writer: W, entries: &[PendingEntry], mut on_entry: F) -> Result<(), LatticeError>
+where
+    W: Write + Seek,
+    F: FnMut(usize, &str),
+{
+    let mut zip = ZipWriter::new(writer);
+    for (index, entry) in entries.iter().enumerate() {
+        let metadata = fs::symlink_metadata(&entry.path).map_err(|error| LatticeError::at_path(&entry.path, error))?;
+        let mut options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        if let Some(timestamp) = zip_timestamp(metadata.modified()) {
+            options = options.last_modified_time(timestamp);
+        }
+        if let Some(mode) = unix_permissions(&metadata) {
+            options = options.unix_permissions(mode);
+        }
+
+        if entry.is_dir {
+            zip.add_directory(format!("{}/", entry.name), options)
+                .map_err(|error| error.to_string())?;
+        } else {
+            zip.start_file(entry.name.as_str(), options).map_err(|error| error.to_string())?;
+            let mut file = fs::File::open(&entry.path).map_err(|error| LatticeError::at_path(&entry.path, error))?;
+            io::copy(&mut file, &mut zip)?;
+        }
+        on_entry(index + 1, &entry.name);
+    }
+    zip.finish().map_err(|error| error.to_string())?;
+    Ok(())
+}
+
+fn create_zip_sync<F>(src_dir: &Path, dest_zip: &Path, exclude: &[String], on_entry: F) -> Result<(), LatticeError>
+where
+    F: FnMut(usize, usize, &str),
+{
+    if !src_dir.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Source is not a directory: {}", src_dir.display()),
+        });
+    }
+    let excludes = build_exclude_set(exclude)?;
+    let entries = collect_archive_entries(src_dir, dest_zip, &excludes)?;
+    write_archive_file(dest_zip, &entries, on_entry)
+}
+
+/// Writes `entries` to a new `dest_zip`, removing it again if anything fails.
+fn write_archive_file<F>(dest_zip: &Path, entries: &[PendingEntry], mut on_entry: F) -> Result<(), LatticeError>
+where
+    F: FnMut(usize, usize, &str),
+{
+    let total = entries.len();
+    let file = fs::File::create(dest_zip).map_err(|error| LatticeError::at_path(dest_zip, error))?;
+    let written = write_archive(file, entries, |done, name| on_entry(done, total, name));
+    if written.is_err() {
+        let _ = fs::remove_file(dest_zip);
+    }
+    written
+}
+
+/// Resolves the parent rather than the path itself, so a selected symlink stays a
+/// symlink inside `base` instead of resolving to wherever it points.
+fn selected_path(base: &Path, path: &str) -> Option<PathBuf> {
+    let path = Path::new(path.trim());
+    let resolved = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => fs::canonicalize(parent).ok()?.join(name),
+        _ => fs::canonicalize(path).ok()?,
+    };
+    resolved.starts_with(base).then_some(resolved)
+}
+
+/// Every selected file and folder (with everything below it), named relative to `base`.
+/// A path selected twice, or inside a selected folder, is archived once.
+fn collect_selection_entries(
+    base: &Path,
+    paths: &[String],
+    dest_zip: &Path,
+) -> Result<Vec<PendingEntry>, LatticeError> {
+    let base = fs::canonicalize(base).map_err(|error| LatticeError::at_path(base, error))?;
+    let mut selected = Vec::with_capacity(paths.len());
+    let mut outside = Vec::new();
+    for path in paths {
+        match selected_path(&base, path) {
+            Some(resolved) => selected.push(resolved),
+            None => outside.push(path.clone()),
+        }
+    }
+    if !outside.is_empty() {
+        return Err(LatticeError::OutsideBase {
+            message: format!("{} selected paths are not inside {}", outside.len(), base.display()),
+            paths: outside,
+        });
+    }
+
+    let no_excludes = GlobSet::empty();
+    let mut names = HashSet::new();
+    let mut entries = Vec::new();
+    for path in selected {
+        let metadata = fs::symlink_metadata(&path).map_err(|error| LatticeError::at_path(&path, error))?;
+        // Selected symlinks are left out like the ones inside selected folders.
+        if metadata.is_symlink() {
+            continue;
+        }
+        let is_dir = metadata.is_dir();
+        let name = relative_name(&base, &path);
+        let mut found = Vec::new();
+        if !name.is_empty() {
+            found.push(PendingEntry {
+                path: path.clone(),
+                name: name.clone(),
+                is_dir,
+            });
+        }
+        if is_dir {
+            for entry in collect_archive_entries(&path, dest_zip, &no_excludes)? {
+                let name = if name.is_empty() { entry.name } else { format!("{name}/{}", entry.name) };
+                found.push(PendingEntry { name, ..entry });
+            }
+        }
+        entries.extend(found.into_iter().filter(|entry| names.insert(entry.name.clone())));
+    }
+    Ok(entries)
+}
+
+fn zip_selection_sync<F>(base: &Path, paths: &[String], dest_zip: &Path, on_entry: F) -> Result<(), LatticeError>
+where
+    F: FnMut(usize, usize, &str),
+{
+    if !base.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Base is not a directory: {}", base.display()),
+        });
+    }
+    let entries = collect_selection_entries(base, paths, dest_zip)?;
+    write_archive_file(dest_zip, &entries, on_entry)
+}
+
+fn extract_zip_sync<F>(zip_path: &Path, dest_dir: &Path, mut on_entry: F) -> Result<(), LatticeError>
+where
+    F: FnMut(usize, usize, &str),
+{
+    let file = fs::File::open(zip_path).map_err(|error| LatticeError::at_path(zip_path, error))?;
+    let mut archive = ZipArchive::new(file).map_err(|error| error.to_string())?;
+    let total = archive.len();
+
+    // Validate every name before writing anything, so a hostile archive leaves no trace.
+    let mut targets = Vec::with_capacity(total);
+    for index in 0..total {
+        let entry = archive.by_index_raw(index).map_err(|error| error.to_string())?;
+        let relative = entry.enclosed_name().ok_or_else(|| LatticeError::UnsafeEntryPath {
+            message: format!("Archive entry escapes the destination: {}", entry.name()),
+        })?;
+        targets.push(dest_dir.join(relative));
+    }
+
+    fs::create_dir_all(dest_dir).map_err(|error| LatticeError::at_path(dest_dir, error))?;
+    for (index, target) in targets.into_iter().enumerate() {
+        let mut entry = archive.by_index(index).map_err(|error| error.to_string())?;
+        if entry.is_dir() {
+            fs::create_dir_all(&target).map_err(|error| LatticeError::at_path(&target, error))?;
+        } else {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|error| LatticeError::at_path(parent, error))?;
+            }
+            let mut file = fs::File::create(&target).map_err(|error| LatticeError::at_path(&target, error))?;
+            io::copy(&mut entry, &mut file)?;
+            let modified = entry
+                .last_modified()
+                .and_then(|timestamp| OffsetDateTime::try_from(timestamp).ok());
+            if let Some(modified) = modified {
+                let _ = file.set_modified(SystemTime::from(modified));
+            }
+            #[cfg(unix)]
+            if let Some(mode) = entry.unix_mode() {
+                use std::os::unix::fs::PermissionsExt;
+                let _ = file.set_permissions(fs::Permissions::from_mode(mode));
+            }
+        }
+        on_entry(index + 1, total, entry.name());
+    }
+    Ok(())
+}
+
+fn progress_emitter(app: AppHandle, archive: String) -> impl FnMut(usize, usize, &str) {
+    move |entries_done, entries_total, entry| {
+        let _ = app.emit(
+            ARCHIVE_PROGRESS_EVENT,
+            ArchiveProgressPayload {
+                archive: archive.clone(),
+                entry: entry.to_string(),
+                entries_done,
+                entries_total,
+            },
+        );
+    }
+}
+
+#[tauri::command]
+pub async fn create_zip(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    src_dir: String,
+    dest_zip: String,
+    exclude: Option<Vec<String>>,
+) -> Result<(), LatticeError> {
+    log_command_async("create_zip", async move {
+        let src_dir = resolve_command_path(&app, &src_dir)?;
+        let dest_zip = resolve_command_path(&app, &dest_zip)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let on_entry = progress_emitter(app, dest_zip.to_string_lossy().to_string());
+            create_zip_sync(&src_dir, &dest_zip, &exclude.unwrap_or_default(), on_entry)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Archives just `paths`, stored relative to `base` so their folders are kept.
+#[tauri::command]
+pub async fn zip_selection(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    base: String,
+    paths: Vec<String>,
+    dest_zip: String,
+) -> Result<(), LatticeError> {
+    log_command_async("zip_selection", async move {
+        let base = resolve_command_path(&app, &base)?;
+        let dest_zip = resolve_command_path(&app, &dest_zip)?;
+        let paths = paths
+            .iter()
+            .map(|path| Ok(resolve_command_path_in(&app, path, &base)?.to_string_lossy().to_string()))
+            .collect::<Result<Vec<_>, LatticeError>>()?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let on_entry = progress_emitter(app, dest_zip.to_string_lossy().to_string());
+            zip_selection_sync(&base, &paths, &dest_zip, on_entry)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn extract_zip(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    zip: String,
+    dest_dir: String,
+) -> Result<(), LatticeError> {
+    log_command_async("extract_zip", async move {
+        let zip_path = resolve_command_path(&app, &zip)?;
+        let dest_dir = resolve_command_path(&app, &dest_dir)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let on_entry = progress_emitter(app, zip_path.to_string_lossy().to_string());
+            extract_zip_sync(&zip_path, &dest_dir, on_entry)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn create_fixture_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("lattice-archive-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("project/notes")).unwrap();
+        fs::create_dir_all(root.join("project/.git")).unwrap();
+        fs::create_dir_all(root.join("project/web/node_modules/pkg")).unwrap();
+        fs::write(root.join("project/notes/a.md"), "alpha").unwrap();
+        fs::write(root.join("project/.git/HEAD"), "ref").unwrap();
+        fs::write(root.join("project/web/node_modules/pkg/index.js"), "js").unwrap();
+        fs::write(root.join("project/readme.md"), "hello").unwrap();
+        root
+    }
+
+    #[test]
+    fn zip_roundtrip_keeps_structure_and_timestamps_and_skips_excludes() {
+        let root = create_fixture_root();
+        let modified = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        fs::File::options()
+            .write(true)
+            .open(root.join("project/notes/a.md"))
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        let archive = root.join("project.zip");
+        let excludes = vec![".git".to_string(), "node_modules".to_string()];
+        let mut names = Vec::new();
+        create_zip_sync(&root.join("project"), &archive, &excludes, |_, _, name| names.push(name.to_string())).unwrap();
+        assert_eq!(names, vec!["notes", "readme.md", "web", "notes/a.md"]);
+
+        let output = root.join("output");
+        extract_zip_sync(&archive, &output, |_, _, _| {}).unwrap();
+        assert_eq!(fs::read_to_string(output.join("notes/a.md")).unwrap(), "alpha");
+        assert!(output.join("web").is_dir());
+        assert!(!output.join(".git").exists());
+        let restored = fs::metadata(output.join("notes/a.md")).unwrap().modified().unwrap();
+        assert_eq!(restored, modified);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_left_out_instead_of_failing_the_archive() {
+        use std::os::unix::fs::symlink;
+
+        let root = create_fixture_root();
+        symlink(root.join("project/notes"), root.join("project/linked-notes")).unwrap();
+        symlink(root.join("project/gone.md"), root.join("project/dangling.md")).unwrap();
+
+        let archive = root.join("project.zip");
+        let mut names = Vec::new();
+        create_zip_sync(&root.join("project"), &archive, &[], |_, _, name| names.push(name.to_string())).unwrap();
+        assert!(names.iter().all(|name| !name.contains("linked-notes") && name != "dangling.md"));
+        assert!(names.contains(&"notes/a.md".to_string()));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn selections_keep_their_relative_paths_once_each() {
+        let root = create_fixture_root();
+        let project = root.join("project");
+        let selection = |paths: &[&str]| -> Vec<String> {
+            paths.iter().map(|path| project.join(path).to_string_lossy().to_string()).collect()
+        };
+
+        let archive = root.join("selection.zip");
+        let mut names = Vec::new();
+        let paths = selection(&["notes", "readme.md", "notes/a.md", "./readme.md"]);
+        zip_selection_sync(&project, &paths, &archive, |_, _, name| names.push(name.to_string())).unwrap();
+        assert_eq!(names, vec!["notes", "notes/a.md", "readme.md"]);
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(project.join("notes"), project.join("linked-notes")).unwrap();
+            std::os::unix::fs::symlink(project.join("gone.md"), project.join("notes/dangling.md")).unwrap();
+            let mut names = Vec::new();
+            let paths = selection(&["linked-notes", "notes"]);
+            zip_selection_sync(&project, &paths, &archive, |_, _, name| names.push(name.to_string())).unwrap();
+            assert_eq!(names, vec!["notes", "notes/a.md"]);
+        }
+
+        let outside = root.join("elsewhere.md");
+        fs::write(&outside, "x").unwrap();
+        let mut paths = selection(&["readme.md", "../elsewhere.md"]);
+        paths.push(outside.to_string_lossy().to_string());
+        let error = zip_selection_sync(&project, &paths, &root.join("bad.zip"), |_, _, _| {}).unwrap_err();
+        assert!(matches!(error, LatticeError::OutsideBase { ref paths, .. } if paths.len() == 2));
+        assert!(!root.join("bad.zip").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn extraction_rejects_entries_that_escape_the_destination() {
+        let root = create_fixture_root();
+        let archive = root.join("evil.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&archive).unwrap());
+        zip.start_file("fine.txt", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"fine").unwrap();
+        zip.start_file("../escaped.txt", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"evil").unwrap();
+        zip.finish().unwrap();
+
+        let output = root.join("output");
+        let error = extract_zip_sync(&archive, &output, |_, _, _| {}).unwrap_err();
+        assert!(matches!(error, LatticeError::UnsafeEntryPath { .. }));
+        assert!(!root.join("escaped.txt").exists());
+        assert!(!output.join("fine.txt").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
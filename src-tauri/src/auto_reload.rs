@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex as StdMutex;
+
+use notify::RecursiveMode;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::paths::resolve_command_path;
+use crate::watcher::{
+    create_event_watcher, register_watch, spawn_debounced_event_loop, FsChange, WatchHandle, WatchKind, WatchOwner,
+};
+use crate::{build_app_settings_from_store, save_app_settings};
+
+const FILE_RELOADED_EVENT: &str = "file-reloaded";
+const FILE_CONFLICT_EVENT: &str = "file-conflict";
+
+struct OpenFile {
+    /// Dropping the entry releases the watch, which ends its event loop.
+    _watch: WatchHandle,
+    /// BLAKE3 of what the buffer was loaded from or last saved as.
+    hash: String,
+    dirty: bool,
+}
+
+/// Files each window has open, keyed by window label and canonical path.
+#[derive(Default)]
+pub struct OpenFilesState {
+    files: StdMutex<HashMap<(String, PathBuf), OpenFile>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileReloadedPayload {
+    path: String,
+    contents: String,
+    hash: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileConflictPayload {
+    path: String,
+    /// The hash now on disk, for a later `write_file_checked`.
+    hash: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ReloadAction {
+    /// Nothing changed from the buffer's point of view, e.g. after our own save.
+    None,
+    Reload,
+    Conflict,
+}
+
+fn reload_action(known_hash: &str, dirty: bool, disk_hash: &str) -> ReloadAction {
+    if known_hash == disk_hash {
+        ReloadAction::None
+    } else if dirty {
+        ReloadAction::Conflict
+    } else {
+        ReloadAction::Reload
+    }
+}
+
+fn touches(batch: &[FsChange], target: &Path) -> bool {
+    batch
+        .iter()
+        .any(|change| change.paths.iter().any(|path| Path::new(path) == target))
+}
+
+fn auto_reload_enabled(app: &AppHandle) -> bool {
+    build_app_settings_from_store(app)
+        .map(|settings| settings.auto_reload)
+        .unwrap_or(false)
+}
+
+/// Runs on the watcher thread after each debounced batch that touches `target`.
+fn handle_external_change(app: &AppHandle, label: &str, target: &Path) {
+    if !auto_reload_enabled(app) {
+        return;
+    }
+    // Removals and half-written files are left to `watch_file`'s events.
+    let Ok(bytes) = fs::read(target) else {
+        return;
+    };
+    let disk_hash = blake3::hash(&bytes).to_hex().to_string();
+
+    let action = {
+        let state = app.state::<OpenFilesState>();
+        let Ok(mut files) = state.files.lock() else {
+            return;
+        };
+        let Some(open) = files.get_mut(&(label.to_string(), target.to_path_buf())) else {
+            return;
+        };
+        let action = reload_action(&open.hash, open.dirty, &disk_hash);
+        if action == ReloadAction::Reload {
+            open.hash = disk_hash.clone();
+        }
+        action
+    };
+
+    let path = target.to_string_lossy().to_string();
+    let _ = match action {
+        ReloadAction::None => return,
+        ReloadAction::Reload => app.emit_to(
+            label,
+            FILE_RELOADED_EVENT,
+            FileReloadedPayload {
+                path,
+                contents: String::from_utf8_lossy(&bytes).into_owned(),
+                hash: disk_hash,
+            },
+        ),
+        ReloadAction::Conflict => app.emit_to(
+            label,
+            FILE_CONFLICT_EVENT,
+            FileConflictPayload { path, hash: disk_hash },
+        ),
+    };
+}
+
+/// Called after `label`'s window writes `path`, so its own save isn't mistaken for an
+/// external change. Other windows with the file open still see it as one. The dirty
+/// flag is left to the frontend, which may have typed on since.
+pub(crate) fn note_saved(app: &AppHandle, label: &str, path: &Path, contents: &[u8]) {
+    let Ok(target) = fs::canonicalize(path) else {
+        return;
+    };
+    let state = app.state::<OpenFilesState>();
+    let Ok(mut files) = state.files.lock() else {
+        return;
+    };
+    if let Some(open) = files.get_mut(&(label.to_string(), target)) {
+        open.hash = blake3::hash(contents).to_hex().to_string();
+    }
+}
+
+pub(crate) fn release_window_files(app: &AppHandle, label: &str) {
+    let released: Vec<OpenFile> = match app.state::<OpenFilesState>().files.lock() {
+        Ok(mut files) => {
+            let keys: Vec<(String, PathBuf)> = files.keys().filter(|(owner, _)| owner == label).cloned().collect();
+            keys.iter().filter_map(|key| files.remove(key)).collect()
+        }
+        Err(_) => Vec::new(),
+    };
+    drop(released);
+}
+
+#[tauri::command]
+pub fn set_auto_reload(app: AppHandle, enabled: bool) -> Result<(), LatticeError> {
+    log_command("set_auto_reload", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.auto_reload = enabled;
+        save_app_settings(&app, settings)?;
+        Ok(())
+    })
+}
+
+/// Marks `path` as open and clean in the calling window with the contents `hash`
+/// (as from `hash_file`). Registering again just updates the hash.
+#[tauri::command]
+pub fn register_open_file(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    state: State<'_, OpenFilesState>,
+    path: String,
+    hash: String,
+) -> Result<(), LatticeError> {
+    log_command("register_open_file", || {
+        let target = fs::canonicalize(resolve_command_path(&app, &path)?)?;
+        if !target.is_file() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Not a file: {}", target.display()),
+            });
+        }
+        let label = window.label().to_string();
+        let key = (label.clone(), target.clone());
+        let hash = hash.trim().to_string();
+
+        let mut files = state.files.lock().map_err(|error| error.to_string())?;
+        if let Some(open) = files.get_mut(&key) {
+            open.hash = hash;
+            open.dirty = false;
+            return Ok(());
+        }
+
+        // The parent is watched so the registration survives saves that replace the file.
+        let parent = target
+            .parent()
+            .ok_or_else(|| format!("File has no parent folder: {}", target.display()))?;
+        let (watcher, receiver) = create_event_watcher(parent, RecursiveMode::NonRecursive)?;
+        let app_for_events = app.clone();
+        let label_for_events = label.clone();
+        let target_for_events = target.clone();
+        spawn_debounced_event_loop(receiver, move |batch| {
+            if touches(&batch, &target_for_events) {
+                handle_external_change(&app_for_events, &label_for_events, &target_for_events);
+            }
+            true
+        });
+
+        // Without its watch the file would never reload, so it stops counting as open.
+        let app_for_release = app.clone();
+        let key_for_release = key.clone();
+        let on_release = move || {
+            let state = app_for_release.state::<OpenFilesState>();
+            let removed = state.files.lock().ok().and_then(|mut files| files.remove(&key_for_release));
+            drop(removed);
+        };
+        let owner = WatchOwner::AutoReload;
+        let watch = register_watch(&app, owner, WatchKind::File, target, Some(label), watcher, on_release);
+
+        files.insert(
+            key,
+            OpenFile {
+                _watch: watch,
+                hash,
+                dirty: false,
+            },
+        );
+        Ok(())
+    })
+}
+
+/// A dirty file gets `file-conflict` instead of `file-reloaded` when it changes on disk.
+#[tauri::command]
+pub fn set_open_file_dirty(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    state: State<'_, OpenFilesState>,
+    path: String,
+    dirty: bool,
+) -> Result<(), LatticeError> {
+    log_command("set_open_file_dirty", || {
+        let target = fs::canonicalize(resolve_command_path(&app, &path)?)?;
+        let mut files = state.files.lock().map_err(|error| error.to_string())?;
+        let open = files
+            .get_mut(&(window.label().to_string(), target.clone()))
+            .ok_or_else(|| LatticeError::NotFound {
+                message: format!("File is not registered as open: {}", target.display()),
+            })?;
+        open.dirty = dirty;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn unregister_open_file(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    state: State<'_, OpenFilesState>,
+    path: String,
+) -> Result<(), LatticeError> {
+    log_command("unregister_open_file", || {
+        let target = resolve_command_path(&app, &path)?;
+        let target = fs::canonicalize(&target).unwrap_or(target);
+        let removed = state
+            .files
+            .lock()
+            .map_err(|error| error.to_string())?
+            .remove(&(window.label().to_string(), target));
+        drop(removed);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_external_changes_reload_and_dirty_buffers_conflict() {
+        assert_eq!(reload_action("abc", false, "abc"), ReloadAction::None);
+        assert_eq!(reload_action("abc", true, "abc"), ReloadAction::None);
+        assert_eq!(reload_action("abc", false, "def"), ReloadAction::Reload);
+        assert_eq!(reload_action("abc", true, "def"), ReloadAction::Conflict);
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::{build_app_settings_from_store, save_app_settings};
+
+const AUTOSAVE_TICK_EVENT: &str = "autosave-tick";
+const MIN_INTERVAL_SECS: u32 = 5;
+const MAX_INTERVAL_SECS: u32 = 24 * 60 * 60;
+
+/// Each (re)start bumps the generation; a timer stops once it is no longer current.
+#[derive(Default)]
+pub struct AutosaveState {
+    generation: AtomicU64,
+}
+
+/// `0` disables autosave like `None`; anything else is kept within sane bounds.
+pub(crate) fn normalize_autosave_interval(secs: Option<u32>) -> Option<u32> {
+    secs.filter(|secs| *secs > 0)
+        .map(|secs| secs.clamp(MIN_INTERVAL_SECS, MAX_INTERVAL_SECS))
+}
+
+fn restart_timer(app: &AppHandle, interval_secs: Option<u32>) {
+    let generation = app.state::<AutosaveState>().generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let Some(interval_secs) = interval_secs else {
+        return;
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let period = Duration::from_secs(u64::from(interval_secs));
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if app.state::<AutosaveState>().generation.load(Ordering::SeqCst) != generation {
+                break;
+            }
+            let _ = app.emit(AUTOSAVE_TICK_EVENT, ());
+        }
+    });
+}
+
+/// Starts the saved autosave timer during `setup`.
+pub(crate) fn restore_autosave(app: &AppHandle) -> Result<(), String> {
+    let interval = build_app_settings_from_store(app)?.autosave_interval_secs;
+    restart_timer(app, interval);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_autosave_interval(app: AppHandle) -> Result<Option<u32>, LatticeError> {
+    log_command("get_autosave_interval", || Ok(build_app_settings_from_store(&app)?.autosave_interval_secs))
+}
+
+/// Returns the interval actually applied after clamping.
+#[tauri::command]
+pub fn set_autosave_interval(app: AppHandle, secs: Option<u32>) -> Result<Option<u32>, LatticeError> {
+    log_command("set_autosave_interval", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.autosave_interval_secs = secs;
+        let interval = save_app_settings(&app, settings)?.autosave_interval_secs;
+        restart_timer(&app, interval);
+        Ok(interval)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_are_clamped_and_zero_disables_autosave() {
+        assert_eq!(normalize_autosave_interval(None), None);
+        assert_eq!(normalize_autosave_interval(Some(0)), None);
+        assert_eq!(normalize_autosave_interval(Some(1)), Some(MIN_INTERVAL_SECS));
+        assert_eq!(normalize_autosave_interval(Some(30)), Some(30));
+        assert_eq!(normalize_autosave_interval(Some(u32::MAX)), Some(MAX_INTERVAL_SECS));
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::case_collisions::{collisions_among, is_case_insensitive, refuse_collisions};
+use crate::duplicates::hash_file;
+use crate::error::LatticeError;
+use crate::ignore_rules::{matcher_for, IgnoreMatcher};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::transfer::TransferFailure;
+use crate::DesktopFsState;
+
+const BACKUP_PROGRESS_EVENT: &str = "backup-progress";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// FAT and some network shares round timestamps to two seconds.
+const MTIME_TOLERANCE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupMode {
+    /// Make `dest` identical to `src`, deleting whatever `src` no longer has.
+    Mirror,
+    /// Only copy new and changed files; nothing in `dest` is deleted.
+    Additive,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupReport {
+    pub copied: usize,
+    pub skipped: usize,
+    /// Entries removed from `dest` by a mirror; a removed folder counts once.
+    pub deleted: usize,
+    pub failed: Vec<TransferFailure>,
+    pub bytes_copied: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupProgressPayload {
+    src: String,
+    dest: String,
+    files_done: usize,
+    files_total: usize,
+}
+
+/// Canonicalizes the deepest existing ancestor, since the backup folder may not exist yet.
+fn resolve_destination(dest: &Path) -> std::io::Result<PathBuf> {
+    let mut missing = Vec::new();
+    let mut current = dest;
+    loop {
+        match fs::canonicalize(current) {
+            Ok(canonical) => return Ok(missing.iter().rev().fold(canonical, |path, name| path.join(name))),
+            Err(error) => match (current.parent(), current.file_name()) {
+                (Some(parent), Some(name)) => {
+                    missing.push(name.to_os_string());
+                    current = parent;
+                }
+                _ => return Err(error),
+            },
+        }
+    }
+}
+
+/// Relative paths of the folders and files to back up, plus the folders that couldn't be
+/// read. Symlinked folders are left out so a link can't loop the walk; symlinked files are
+/// backed up as their contents.
+#[derive(Debug, Default)]
+struct Sources {
+    dirs: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+    unread: Vec<PathBuf>,
+}
+
+fn collect_sources(src: &Path, ignore: &IgnoreMatcher, report: &mut BackupReport) -> Sources {
+    let mut sources = Sources::default();
+    let (dirs, files) = (&mut sources.dirs, &mut sources.files);
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let entries = match fs::read_dir(src.join(&relative)) {
+            Ok(entries) => entries,
+            Err(error) => {
+                record_failure(report, &src.join(&relative), error);
+                sources.unread.push(relative);
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let is_dir = file_type.is_dir() || (file_type.is_symlink() && path.is_dir());
+            if ignore.is_ignored(&path, is_dir) || (is_dir && file_type.is_symlink()) {
+                continue;
+            }
+            let entry_relative = relative.join(entry.file_name());
+            if is_dir {
+                dirs.push(entry_relative.clone());
+                pending.push(entry_relative);
+            } else {
+                files.push(entry_relative);
+            }
+        }
+    }
+    dirs.sort();
+    files.sort();
+    sources
+}
+
+fn record_failure(report: &mut BackupReport, path: &Path, error: impl ToString) {
+    report.failed.push(TransferFailure {
+        path: path.to_string_lossy().to_string(),
+        error: error.to_string(),
+    });
+}
+
+fn same_mtime(left: SystemTime, right: SystemTime) -> bool {
+    let difference = left.duration_since(right).or_else(|_| right.duration_since(left));
+    difference.is_ok_and(|difference| difference < MTIME_TOLERANCE)
+}
+
+fn is_unchanged(source: &Path, target: &Path, verify: bool) -> std::io::Result<bool> {
+    let Ok(existing) = fs::metadata(target) else {
+        return Ok(false);
+    };
+    let metadata = fs::metadata(source)?;
+    if !existing.is_file() || existing.len() != metadata.len() {
+        return Ok(false);
+    }
+    if verify {
+        return Ok(hash_file(source)? == hash_file(target)?);
+    }
+    Ok(same_mtime(metadata.modified()?, existing.modified()?))
+}
+
+/// Keeps the source's mtime on the copy, which is what the next run compares against.
+fn copy_file(source: &Path, target: &Path) -> std::io::Result<u64> {
+    if fs::symlink_metadata(target).is_ok_and(|metadata| metadata.is_dir()) {
+        fs::remove_dir_all(target)?;
+    }
+    let bytes = fs::copy(source, target)?;
+    let modified = fs::metadata(source)?.modified()?;
+    fs::OpenOptions::new().write(true).open(target)?.set_modified(modified)?;
+    Ok(bytes)
+}
+
+/// Removes everything under `dest` that isn't one of the backed-up folders or files. Folders
+/// whose source couldn't be read are left alone: their contents are unknown, not gone.
+fn delete_extras(dest: &Path, sources: &Sources, report: &mut BackupReport) {
+    let keep_dirs: HashSet<&Path> = sources.dirs.iter().map(PathBuf::as_path).collect();
+    let keep_files: HashSet<&Path> = sources.files.iter().map(PathBuf::as_path).collect();
+    let unread: HashSet<&Path> = sources.unread.iter().map(PathBuf::as_path).collect();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        if unread.contains(relative.as_path()) {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(dest.join(&relative)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let entry_relative = relative.join(entry.file_name());
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            if is_dir && keep_dirs.contains(entry_relative.as_path()) {
+                pending.push(entry_relative);
+                continue;
+            }
+            if !is_dir && keep_files.contains(entry_relative.as_path()) {
+                continue;
+            }
+            let removed = if is_dir {
+                fs::remove_dir_all(entry.path())
+            } else {
+                fs::remove_file(entry.path())
+            };
+            match removed {
+                Ok(()) => report.deleted += 1,
+                Err(error) => record_failure(report, &entry.path(), error),
+            }
+        }
+    }
+}
+
+fn backup_folder_sync(
+    src: &Path,
+    dest: &Path,
+    mode: BackupMode,
+    verify: bool,
+    check_case: bool,
+    ignore: &IgnoreMatcher,
+    on_progress: &mut dyn FnMut(usize, usize),
+) -> Result<BackupReport, LatticeError> {
+    let mut report = BackupReport::default();
+    let sources = collect_sources(src, ignore, &mut report);
+    let (dirs, files) = (&sources.dirs, &sources.files);
+    if check_case && is_case_insensitive(dest)? {
+        let paths: Vec<PathBuf> = dirs.iter().chain(files).map(|relative| src.join(relative)).collect();
+        refuse_collisions(collisions_among(paths.iter().map(PathBuf::as_path)), dest)?;
+    }
+    fs::create_dir_all(dest).map_err(|error| LatticeError::at_path(dest, error))?;
+
+    // Deleting first frees space and clears folders that are about to become files.
+    if mode == BackupMode::Mirror {
+        delete_extras(dest, &sources, &mut report);
+    }
+
+    for dir in dirs {
+        let target = dest.join(dir);
+        if fs::symlink_metadata(&target).is_ok_and(|metadata| !metadata.is_dir()) {
+            let _ = fs::remove_file(&target);
+        }
+        if let Err(error) = fs::create_dir_all(&target) {
+            record_failure(&mut report, &target, error);
+        }
+    }
+
+    let mut last_progress = Instant::now();
+    for (index, file) in files.iter().enumerate() {
+        let (source, target) = (src.join(file), dest.join(file));
+        match is_unchanged(&source, &target, verify) {
+            Ok(true) => report.skipped += 1,
+            Ok(false) => match copy_file(&source, &target) {
+                Ok(bytes) => {
+                    report.copied += 1;
+                    report.bytes_copied += bytes;
+                }
+                Err(error) => record_failure(&mut report, &source, error),
+            },
+            Err(error) => record_failure(&mut report, &source, error),
+        }
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            on_progress(index + 1, files.len());
+            last_progress = Instant::now();
+        }
+    }
+
+    on_progress(files.len(), files.len());
+    Ok(report)
+}
+
+/// Backs `src` up into `dest`, copying only files whose size or mtime changed, or whose
+/// contents differ when `verify` is set. Ignored files are left out. With `check_case`,
+/// names that a case-insensitive `dest` would merge are refused before anything is copied.
+#[tauri::command]
+pub async fn backup_folder(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    src: String,
+    dest: String,
+    mode: BackupMode,
+    verify: Option<bool>,
+    check_case: Option<bool>,
+) -> Result<BackupReport, LatticeError> {
+    log_command_async("backup_folder", async move {
+        let src = resolve_command_path(&app, &src)?;
+        let src = fs::canonicalize(&src).map_err(|error| LatticeError::at_path(&src, error))?;
+        if !src.is_dir() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Backup source is not a folder: {}", src.display()),
+            });
+        }
+        let dest = resolve_command_path(&app, &dest)?;
+        let dest = resolve_destination(&dest).map_err(|error| LatticeError::at_path(&dest, error))?;
+        if dest.starts_with(&src) {
+            return Err(LatticeError::DestinationInsideSource {
+                message: format!("Cannot back up {} into itself: {}", src.display(), dest.display()),
+            });
+        }
+        // A mirror of a subfolder into its parent would delete the source itself.
+        if src.starts_with(&dest) {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Backup destination contains the source: {}", dest.display()),
+            });
+        }
+
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let ignore = matcher_for(&app, &src);
+            let mut emit_progress = |files_done: usize, files_total: usize| {
+                let _ = app.emit(
+                    BACKUP_PROGRESS_EVENT,
+                    BackupProgressPayload {
+                        src: src.to_string_lossy().to_string(),
+                        dest: dest.to_string_lossy().to_string(),
+                        files_done,
+                        files_total,
+                    },
+                );
+            };
+            backup_folder_sync(
+                &src,
+                &dest,
+                mode,
+                verify.unwrap_or(false),
+                check_case.unwrap_or(false),
+                &ignore,
+                &mut emit_progress,
+            )
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backups_copy_changes_and_mirrors_delete_extras() {
+        let root = fs::canonicalize(std::env::temp_dir())
+            .unwrap()
+            .join(format!("lattice-backup-{}", uuid::Uuid::new_v4()));
+        let (src, dest) = (root.join("src"), root.join("dest"));
+        fs::create_dir_all(src.join("notes")).unwrap();
+        fs::create_dir_all(src.join("cache")).unwrap();
+        fs::write(src.join("notes/a.md"), "alpha").unwrap();
+        fs::write(src.join("cache/blob.bin"), "cached").unwrap();
+        fs::write(src.join(".gitignore"), "cache/\n").unwrap();
+        let ignore = IgnoreMatcher::new(&src, &[]);
+        let mut no_progress = |_: usize, _: usize| {};
+
+        let first =
+            backup_folder_sync(&src, &dest, BackupMode::Additive, false, false, &ignore, &mut no_progress).unwrap();
+        assert_eq!((first.copied, first.skipped), (2, 0));
+        assert_eq!(fs::read_to_string(dest.join("notes/a.md")).unwrap(), "alpha");
+        assert!(!dest.join("cache").exists());
+
+        fs::write(dest.join("stale.md"), "old").unwrap();
+        let second =
+            backup_folder_sync(&src, &dest, BackupMode::Additive, true, false, &ignore, &mut no_progress).unwrap();
+        assert_eq!((second.copied, second.skipped, second.deleted), (0, 2, 0));
+        assert!(dest.join("stale.md").exists());
+
+        fs::write(src.join("notes/a.md"), "alpha, edited").unwrap();
+        let mirror =
+            backup_folder_sync(&src, &dest, BackupMode::Mirror, false, false, &ignore, &mut no_progress).unwrap();
+        assert_eq!((mirror.copied, mirror.skipped, mirror.deleted), (1, 1, 1));
+        assert!(!dest.join("stale.md").exists());
+        assert_eq!(fs::read_to_string(dest.join("notes/a.md")).unwrap(), "alpha, edited");
+        assert_eq!(resolve_destination(&dest.join("new/deeper")).unwrap(), dest.join("new/deeper"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn mirrors_keep_the_backup_of_folders_that_could_not_be_read() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("lattice-backup-unread-{}", uuid::Uuid::new_v4()));
+        let (src, dest) = (root.join("src"), root.join("dest"));
+        fs::create_dir_all(src.join("locked")).unwrap();
+        fs::create_dir_all(dest.join("locked")).unwrap();
+        fs::write(src.join("a.md"), "alpha").unwrap();
+        fs::write(dest.join("locked/kept.md"), "backed up earlier").unwrap();
+        fs::write(dest.join("stale.md"), "old").unwrap();
+        fs::set_permissions(src.join("locked"), fs::Permissions::from_mode(0o000)).unwrap();
+        let ignore = IgnoreMatcher::new(&src, &[]);
+        let mut no_progress = |_: usize, _: usize| {};
+
+        let report = backup_folder_sync(&src, &dest, BackupMode::Mirror, false, false, &ignore, &mut no_progress);
+        fs::set_permissions(src.join("locked"), fs::Permissions::from_mode(0o755)).unwrap();
+        let report = report.unwrap();
+        // Privileged users read the folder anyway, leaving nothing to protect.
+        if !report.failed.is_empty() {
+            assert!(report.failed[0].path.ends_with("locked"));
+            assert_eq!(report.deleted, 1);
+            assert!(dest.join("locked/kept.md").exists());
+        }
+        assert!(!dest.join("stale.md").exists());
+
+        // The same walk as an unprivileged user sees it, and then with an unreadable root.
+        fs::write(dest.join("stale.md"), "old").unwrap();
+        fs::write(dest.join("locked/kept.md"), "backed up earlier").unwrap();
+        let mut report = BackupReport::default();
+        let sources = Sources {
+            dirs: vec![PathBuf::from("locked")],
+            files: vec![PathBuf::from("a.md")],
+            unread: vec![PathBuf::from("locked")],
+        };
+        delete_extras(&dest, &sources, &mut report);
+        assert_eq!(report.deleted, 1);
+        assert!(dest.join("locked/kept.md").exists());
+
+        let mut report = BackupReport::default();
+        let sources = Sources {
+            unread: vec![PathBuf::new()],
+            ..Sources::default()
+        };
+        delete_extras(&dest, &sources, &mut report);
+        assert_eq!(report.deleted, 0);
+        assert!(dest.join("a.md").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,478 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::error::LatticeError;
+use crate::fileops::{rename_no_replace, validate_entry_name, PathRenamedPayload, PATH_RENAMED_EVENT};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::{is_path_within_root, DesktopFsState, DesktopPreviewState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CaseTransform {
+    Lower,
+    Upper,
+    /// Capitalizes each word, where words are split on spaces, `-` and `_`.
+    Title,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RenameStatus {
+    /// Dry run only: the rename would go ahead.
+    Planned,
+    Renamed,
+    /// Skipped because another file has, or would end up with, the same name.
+    Conflict,
+    /// Skipped because the new name isn't a valid file name.
+    Invalid,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePlan {
+    pub from: String,
+    pub to: String,
+    pub status: RenameStatus,
+    pub error: Option<String>,
+    /// Where the file was left when it failed and another file had taken its old name.
+    pub left_at: Option<String>,
+}
+
+enum NameMatcher {
+    Literal(String),
+    Regex(Regex),
+}
+
+/// Names only; paths are attached once the plan is final.
+struct PlannedRename {
+    from: String,
+    to: String,
+    status: RenameStatus,
+    error: Option<String>,
+    left_at: Option<PathBuf>,
+}
+
+impl PlannedRename {
+    fn skip(&mut self, status: RenameStatus, error: String) {
+        self.status = status;
+        self.error = Some(error);
+    }
+}
+
+/// Case-insensitive filesystems see `A.md` and `a.md` as the same name.
+fn name_key(name: &str) -> String {
+    if cfg!(any(windows, target_os = "macos")) {
+        name.to_lowercase()
+    } else {
+        name.to_string()
+    }
+}
+
+static NUMBERING_TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{n(?::(\d{1,2}))?\}").expect("numbering token pattern is valid"));
+
+/// `{n}` is the file's position among the matches, from 1; `{n:3}` pads it to three digits.
+fn expand_numbering(replacement: &str, number: usize) -> String {
+    NUMBERING_TOKEN
+        .replace_all(replacement, |captures: &regex::Captures<'_>| {
+            let width = captures
+                .get(1)
+                .and_then(|width| width.as_str().parse().ok())
+                .unwrap_or(0);
+            format!("{number:0width$}")
+        })
+        .to_string()
+}
+
+fn title_case(text: &str) -> String {
+    let mut titled = String::with_capacity(text.len());
+    let mut word_start = true;
+    for character in text.chars() {
+        if word_start {
+            titled.extend(character.to_uppercase());
+        } else {
+            titled.extend(character.to_lowercase());
+        }
+        word_start = matches!(character, ' ' | '-' | '_');
+    }
+    titled
+}
+
+/// Applied to the stem so extensions keep their casing.
+fn transform_case(name: &str, case: CaseTransform) -> String {
+    let (stem, extension) = match name.rfind('.').filter(|index| *index > 0) {
+        Some(index) => name.split_at(index),
+        None => (name, ""),
+    };
+    let stem = match case {
+        CaseTransform::Lower => stem.to_lowercase(),
+        CaseTransform::Upper => stem.to_uppercase(),
+        CaseTransform::Title => title_case(stem),
+    };
+    format!("{stem}{extension}")
+}
+
+fn plan_names(
+    names: &[String],
+    matcher: &NameMatcher,
+    replacement: &str,
+    case: Option<CaseTransform>,
+) -> Vec<PlannedRename> {
+    let mut number = 0;
+    let mut planned = Vec::new();
+    for name in names {
+        let is_match = match matcher {
+            NameMatcher::Literal(pattern) => name.contains(pattern.as_str()),
+            NameMatcher::Regex(pattern) => pattern.is_match(name),
+        };
+        if !is_match {
+            continue;
+        }
+        number += 1;
+        let replacement = expand_numbering(replacement, number);
+        let renamed = match matcher {
+            NameMatcher::Literal(pattern) => name.replace(pattern.as_str(), &replacement),
+            NameMatcher::Regex(pattern) => pattern.replace_all(name, replacement.as_str()).to_string(),
+        };
+        let renamed = match case {
+            Some(case) => transform_case(&renamed, case),
+            None => renamed,
+        };
+        if &renamed != name {
+            planned.push(PlannedRename {
+                from: name.clone(),
+                to: renamed,
+                status: RenameStatus::Planned,
+                error: None,
+                left_at: None,
+            });
+        }
+    }
+    planned
+}
+
+/// Marks invalid names, targets shared by several files, and targets held by a file that
+/// stays put. Skipping one rename can leave its file in another's way, so conflicts are
+/// resolved until nothing changes.
+fn mark_conflicts(planned: &mut [PlannedRename], existing: &[String]) {
+    for rename in planned.iter_mut() {
+        if let Err(error) = validate_entry_name(&rename.to) {
+            rename.skip(RenameStatus::Invalid, error);
+        }
+    }
+
+    let mut targets: HashMap<String, Vec<String>> = HashMap::new();
+    for rename in planned.iter().filter(|rename| rename.status == RenameStatus::Planned) {
+        targets
+            .entry(name_key(&rename.to))
+            .or_default()
+            .push(rename.from.clone());
+    }
+    for rename in planned
+        .iter_mut()
+        .filter(|rename| rename.status == RenameStatus::Planned)
+    {
+        let sources = &targets[&name_key(&rename.to)];
+        if sources.len() > 1 {
+            let others: Vec<&str> = sources
+                .iter()
+                .filter(|source| **source != rename.from)
+                .map(String::as_str)
+                .collect();
+            rename.skip(
+                RenameStatus::Conflict,
+                format!("Also the new name for {}.", others.join(", ")),
+            );
+        }
+    }
+
+    loop {
+        let moving: HashSet<String> = planned
+            .iter()
+            .filter(|rename| rename.status == RenameStatus::Planned)
+            .map(|rename| name_key(&rename.from))
+            .collect();
+        let staying: HashSet<String> = existing
+            .iter()
+            .map(|name| name_key(name))
+            .filter(|key| !moving.contains(key))
+            .collect();
+
+        let mut changed = false;
+        for rename in planned
+            .iter_mut()
+            .filter(|rename| rename.status == RenameStatus::Planned)
+        {
+            let target = name_key(&rename.to);
+            if target != name_key(&rename.from) && staying.contains(&target) {
+                rename.skip(RenameStatus::Conflict, format!("{} already exists.", rename.to));
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+}
+
+/// Every source moves to a temporary name first, so chains (`a→b`, `b→c`) and swaps
+/// never clobber a file that hasn't moved yet. Anything that can't reach its new name
+/// is put back, unless a chained rename has taken its old name meanwhile; then it stays at
+/// the temporary name, which is reported.
+fn execute_renames(dir: &Path, planned: &mut [PlannedRename]) {
+    let mut staged = Vec::new();
+    for (index, rename) in planned.iter_mut().enumerate() {
+        if rename.status != RenameStatus::Planned {
+            continue;
+        }
+        let temp = dir.join(format!(".{}.lattice-rename-{}", rename.from, Uuid::new_v4()));
+        match fs::rename(dir.join(&rename.from), &temp) {
+            Ok(()) => staged.push((index, temp)),
+            Err(error) => rename.skip(RenameStatus::Failed, error.to_string()),
+        }
+    }
+
+    for (index, temp) in staged {
+        let rename = &mut planned[index];
+        let outcome = rename_no_replace(&temp, &dir.join(&rename.to)).map_err(|error| match error.kind() {
+            std::io::ErrorKind::AlreadyExists => (RenameStatus::Conflict, format!("{} already exists.", rename.to)),
+            _ => (RenameStatus::Failed, error.to_string()),
+        });
+        match outcome {
+            Ok(()) => rename.status = RenameStatus::Renamed,
+            Err((status, error)) => match rename_no_replace(&temp, &dir.join(&rename.from)) {
+                Ok(()) => rename.skip(status, error),
+                Err(restore_error) => {
+                    let left_at = temp.display();
+                    rename.skip(status, format!("{error}; could not restore it ({restore_error}), left at {left_at}"));
+                    rename.left_at = Some(temp);
+                }
+            },
+        }
+    }
+}
+
+fn file_names(dir: &Path) -> Result<(Vec<String>, Vec<String>), LatticeError> {
+    let mut files = Vec::new();
+    let mut all = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if fs::metadata(entry.path()).is_ok_and(|metadata| metadata.is_file()) {
+            files.push(name.clone());
+        }
+        all.push(name);
+    }
+    files.sort_by_key(|name| name.to_lowercase());
+    Ok((files, all))
+}
+
+fn batch_rename_sync(
+    dir: &Path,
+    matcher: &NameMatcher,
+    replacement: &str,
+    case: Option<CaseTransform>,
+    dry_run: bool,
+) -> Result<Vec<RenamePlan>, LatticeError> {
+    let (files, existing) = file_names(dir)?;
+    let mut planned = plan_names(&files, matcher, replacement, case);
+    mark_conflicts(&mut planned, &existing);
+    if !dry_run {
+        execute_renames(dir, &mut planned);
+    }
+
+    Ok(planned
+        .into_iter()
+        .map(|rename| RenamePlan {
+            from: dir.join(&rename.from).to_string_lossy().to_string(),
+            to: dir.join(&rename.to).to_string_lossy().to_string(),
+            status: rename.status,
+            error: rename.error,
+            left_at: rename.left_at.map(|path| path.to_string_lossy().to_string()),
+        })
+        .collect())
+}
+
+/// Renames files directly inside `dir` whose names match `pattern`. `replacement` may use
+/// `{n}` numbering, and `$1`-style groups when `regex` is set.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn batch_rename(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    preview_state: State<'_, DesktopPreviewState>,
+    dir: String,
+    pattern: String,
+    replacement: String,
+    regex: bool,
+    case: Option<CaseTransform>,
+    dry_run: bool,
+) -> Result<Vec<RenamePlan>, LatticeError> {
+    log_command_async("batch_rename", async move {
+        if pattern.is_empty() {
+            return Err(LatticeError::InvalidInput {
+                message: "A pattern is required.".to_string(),
+            });
+        }
+        let matcher = if regex {
+            NameMatcher::Regex(Regex::new(&pattern).map_err(|error| LatticeError::InvalidInput {
+                message: error.to_string(),
+            })?)
+        } else {
+            NameMatcher::Literal(pattern)
+        };
+        let dir = fs::canonicalize(resolve_command_path(&app, &dir)?)?;
+        let root = preview_state
+            .workspace_root
+            .lock()
+            .map_err(|error| error.to_string())?
+            .clone();
+        if root.is_some_and(|root| !is_path_within_root(&dir, &root)) {
+            return Err(LatticeError::OutsideScope {
+                message: format!("Folder is outside the current workspace: {}", dir.display()),
+            });
+        }
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let plans = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            batch_rename_sync(&dir, &matcher, &replacement, case, dry_run)
+        })
+        .await
+        .map_err(|error| error.to_string())??;
+
+        for plan in plans.iter().filter(|plan| plan.status == RenameStatus::Renamed) {
+            crate::tags::follow_rename(&app, Path::new(&plan.from), &PathBuf::from(&plan.to));
+            let _ = app.emit(
+                PATH_RENAMED_EVENT,
+                PathRenamedPayload {
+                    from: plan.from.clone(),
+                    to: plan.to.clone(),
+                },
+            );
+        }
+        Ok(plans)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn plans_apply_numbering_and_case_transforms() {
+        let matcher = NameMatcher::Regex(Regex::new(r"^scan[ _-]?(\d+)").unwrap());
+        let planned = plan_names(
+            &names(&["scan_7.PDF", "readme.md", "Scan 12.pdf"]),
+            &matcher,
+            "page {n:3} of $1",
+            Some(CaseTransform::Title),
+        );
+        let pairs: Vec<(&str, &str)> = planned
+            .iter()
+            .map(|rename| (rename.from.as_str(), rename.to.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("scan_7.PDF", "Page 001 Of 7.PDF")]);
+
+        let literal = NameMatcher::Literal("draft".to_string());
+        let planned = plan_names(
+            &names(&["draft-a.md", "b-draft.md", "final.md"]),
+            &literal,
+            "v{n}",
+            None,
+        );
+        assert_eq!(
+            planned.iter().map(|rename| rename.to.as_str()).collect::<Vec<_>>(),
+            vec!["v1-a.md", "b-v2.md"]
+        );
+        assert_eq!(transform_case("My Notes.MD", CaseTransform::Lower), "my notes.MD");
+    }
+
+    #[test]
+    fn collisions_are_skipped_and_chains_do_not_clobber() {
+        let dir = std::env::temp_dir().join(format!("lattice-batch-rename-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in [
+            ("a.txt", "a"),
+            ("aa.txt", "aa"),
+            ("1.log", "one"),
+            ("01.log", "zero-one"),
+            ("keep.md", "keep"),
+        ] {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+
+        // `a.txt` takes the name `aa.txt` is leaving behind.
+        let chain = NameMatcher::Literal("a".to_string());
+        let plans = batch_rename_sync(&dir, &chain, "aa", None, false).unwrap();
+        assert!(
+            plans.iter().all(|plan| plan.status == RenameStatus::Renamed),
+            "{plans:?}"
+        );
+        assert_eq!(fs::read_to_string(dir.join("aa.txt")).unwrap(), "a");
+        assert_eq!(fs::read_to_string(dir.join("aaaa.txt")).unwrap(), "aa");
+
+        // Both logs would become `1.txt`.
+        let logs = NameMatcher::Regex(Regex::new(r"^0?1\.log$").unwrap());
+        let plans = batch_rename_sync(&dir, &logs, "1.txt", None, false).unwrap();
+        assert!(
+            plans.iter().all(|plan| plan.status == RenameStatus::Conflict),
+            "{plans:?}"
+        );
+
+        let onto_existing = NameMatcher::Regex(Regex::new(r"^1\.log$").unwrap());
+        let plans = batch_rename_sync(&dir, &onto_existing, "keep.md", None, false).unwrap();
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].status, RenameStatus::Conflict);
+        assert_eq!(fs::read_to_string(dir.join("keep.md")).unwrap(), "keep");
+        assert!(dir.join("1.log").is_file());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_failed_rename_never_restores_over_a_name_taken_by_the_chain() {
+        let dir = std::env::temp_dir().join(format!("lattice-batch-rename-restore-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.txt"), "a").unwrap();
+        fs::write(dir.join("b.txt"), "b").unwrap();
+        let planned = |from: &str, to: &str| PlannedRename {
+            from: from.to_string(),
+            to: to.to_string(),
+            status: RenameStatus::Planned,
+            error: None,
+            left_at: None,
+        };
+
+        // `b.txt` can't reach a folder that doesn't exist, and `a.txt` has taken its name.
+        let mut plans = vec![planned("a.txt", "b.txt"), planned("b.txt", "missing/c.txt")];
+        execute_renames(&dir, &mut plans);
+        assert_eq!(plans[0].status, RenameStatus::Renamed);
+        assert_eq!(plans[1].status, RenameStatus::Failed);
+        assert_eq!(fs::read_to_string(dir.join("b.txt")).unwrap(), "a");
+        let left_at = plans[1].left_at.as_ref().expect("the file is reported where it was left");
+        assert_eq!(fs::read_to_string(left_at).unwrap(), "b");
+
+        assert!(validate_entry_name(&"n".repeat(256)).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
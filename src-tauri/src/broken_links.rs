@@ -0,0 +1,209 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::error::LatticeError;
+use crate::ignore_rules::{matcher_for, IgnoreMatcher};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::recycle_bin::{trash_path_sync, TrashBatchReport, TrashFailure};
+use crate::transfer::TransferFailure;
+use crate::DesktopFsState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BrokenLinkKind {
+    /// Nothing exists where the link points.
+    Dangling,
+    /// The link points at another link, somewhere down which the chain breaks.
+    BrokenChain,
+    /// The chain of links leads back to itself.
+    Loop,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenLink {
+    pub path: String,
+    /// The target exactly as stored in the link, which may be relative to its folder.
+    pub target: String,
+    pub kind: BrokenLinkKind,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenLinkReport {
+    pub links: Vec<BrokenLink>,
+    /// Folders that couldn't be listed.
+    pub failed: Vec<TransferFailure>,
+}
+
+/// `None` when `path` is not a symlink or it resolves. Judged by `metadata`, which follows
+/// the whole chain, against `symlink_metadata` of the immediate target.
+fn inspect_link(path: &Path) -> Option<BrokenLink> {
+    let target = fs::read_link(path).ok()?;
+    let error = fs::metadata(path).err()?;
+    let immediate = path.parent().unwrap_or_else(|| Path::new("")).join(&target);
+    let kind = if is_loop_error(&error) {
+        BrokenLinkKind::Loop
+    } else if fs::symlink_metadata(&immediate).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+        BrokenLinkKind::BrokenChain
+    } else {
+        BrokenLinkKind::Dangling
+    };
+    Some(BrokenLink {
+        path: path.to_string_lossy().to_string(),
+        target: target.to_string_lossy().to_string(),
+        kind,
+    })
+}
+
+#[cfg(unix)]
+fn is_loop_error(error: &std::io::Error) -> bool {
+    const ELOOP: i32 = 40;
+    const ELOOP_BSD: i32 = 62;
+    let code = if cfg!(target_os = "linux") { ELOOP } else { ELOOP_BSD };
+    error.raw_os_error() == Some(code)
+}
+
+/// Windows reports `ERROR_CANT_RESOLVE_FILENAME` for a cycle of links.
+#[cfg(windows)]
+fn is_loop_error(error: &std::io::Error) -> bool {
+    const ERROR_CANT_RESOLVE_FILENAME: i32 = 1921;
+    error.raw_os_error() == Some(ERROR_CANT_RESOLVE_FILENAME)
+}
+
+/// Links are inspected but never followed, so linked folders can't loop the walk.
+fn find_broken_links(root: &Path, ignore: &IgnoreMatcher) -> BrokenLinkReport {
+    let mut report = BrokenLinkReport::default();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(error) => {
+                report.failed.push(TransferFailure {
+                    path: dir.to_string_lossy().to_string(),
+                    error: error.to_string(),
+                });
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            if ignore.is_ignored(&path, file_type.is_dir()) {
+                continue;
+            }
+            if file_type.is_symlink() {
+                report.links.extend(inspect_link(&path));
+            } else if file_type.is_dir() {
+                pending.push(path);
+            }
+        }
+    }
+    report.links.sort_by(|left, right| left.path.cmp(&right.path));
+    report
+}
+
+#[tauri::command]
+pub async fn find_broken_symlinks(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+) -> Result<BrokenLinkReport, LatticeError> {
+    log_command_async("find_broken_symlinks", async move {
+        let root = resolve_command_path(&app, &root)?;
+        if !root.is_dir() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Path is not a directory: {}", root.display()),
+            });
+        }
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            find_broken_links(&root, &matcher_for(&app, &root))
+        })
+        .await
+        .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+/// Trashes each listed link, re-checking first so a link repaired since the scan is kept.
+#[tauri::command]
+pub async fn remove_broken_symlinks(app: AppHandle, paths: Vec<String>) -> Result<TrashBatchReport, LatticeError> {
+    log_command_async("remove_broken_symlinks", async move {
+        tokio::task::spawn_blocking(move || {
+            let mut report = TrashBatchReport::default();
+            for path in paths {
+                let result = resolve_command_path(&app, &path).and_then(|target| match inspect_link(&target) {
+                    Some(_) => trash_path_sync(&target),
+                    None if fs::symlink_metadata(&target).is_err() => Err(LatticeError::NotFound {
+                        message: format!("Path not found: {}", target.display()),
+                    }),
+                    None => Err(LatticeError::Failed {
+                        message: format!("{} is not a broken link.", target.display()),
+                    }),
+                });
+                match result {
+                    Ok(()) => report.trashed.push(path),
+                    Err(error) => report.failed.push(TrashFailure { path, error }),
+                }
+            }
+            report
+        })
+        .await
+        .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn classifies_dangling_chained_and_looping_links() {
+        use std::os::unix::fs::symlink;
+
+        let root = std::env::temp_dir().join(format!("lattice-broken-links-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::write(root.join("notes/a.md"), "alpha").unwrap();
+        symlink("a.md", root.join("notes/ok.md")).unwrap();
+        symlink("gone.md", root.join("notes/dangling.md")).unwrap();
+        symlink("dangling.md", root.join("notes/chained.md")).unwrap();
+        symlink("loop-b", root.join("notes/loop-a")).unwrap();
+        symlink("loop-a", root.join("notes/loop-b")).unwrap();
+        // A valid link to an ancestor folder must not be walked into.
+        symlink("..", root.join("notes/up")).unwrap();
+
+        let report = find_broken_links(&root, &IgnoreMatcher::new(&root, &[]));
+        let found: Vec<(String, BrokenLinkKind)> = report
+            .links
+            .iter()
+            .map(|link| (Path::new(&link.path).file_name().unwrap().to_string_lossy().to_string(), link.kind))
+            .collect();
+        let expected = [
+            ("chained.md", BrokenLinkKind::BrokenChain),
+            ("dangling.md", BrokenLinkKind::Dangling),
+            ("loop-a", BrokenLinkKind::Loop),
+            ("loop-b", BrokenLinkKind::Loop),
+        ];
+        assert_eq!(found, expected.map(|(name, kind)| (name.to_string(), kind)));
+        assert_eq!(report.links[1].target, "gone.md");
+        assert!(inspect_link(&root.join("notes/ok.md")).is_none());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+/// Entries of one directory whose names differ only by case.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaseCollision {
+    pub dir: String,
+    /// Sorted, so the same tree always reports the same groups.
+    pub paths: Vec<String>,
+}
+
+/// Groups `paths` by parent and case-folded name, keeping groups of two or more.
+pub(crate) fn collisions_among<'a>(paths: impl IntoIterator<Item = &'a Path>) -> Vec<CaseCollision> {
+    let mut groups: BTreeMap<(&Path, String), Vec<String>> = BTreeMap::new();
+    for path in paths {
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            continue;
+        };
+        groups
+            .entry((parent, name.to_string_lossy().to_lowercase()))
+            .or_default()
+            .push(path.to_string_lossy().to_string());
+    }
+    groups
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((parent, _), mut paths)| {
+            paths.sort();
+            CaseCollision {
+                dir: parent.to_string_lossy().to_string(),
+                paths,
+            }
+        })
+        .collect()
+}
+
+/// Every entry below `root`, without following symlinks; unreadable folders are skipped.
+fn descendants(root: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                pending.push(entry.path());
+            }
+            paths.push(entry.path());
+        }
+    }
+    paths
+}
+
+pub(crate) fn find_case_collisions(root: &Path) -> Vec<CaseCollision> {
+    let paths = descendants(root);
+    collisions_among(paths.iter().map(PathBuf::as_path))
+}
+
+/// Probes the filesystem `path` is on (or would be created on) by writing a lowercase
+/// file and looking it up in uppercase.
+pub(crate) fn is_case_insensitive(path: &Path) -> std::io::Result<bool> {
+    let dir = path
+        .ancestors()
+        .find(|ancestor| ancestor.is_dir())
+        .ok_or_else(|| {
+            let message = format!("{} does not exist", path.display());
+            std::io::Error::new(std::io::ErrorKind::NotFound, message)
+        })?;
+    let name = format!(".lattice-case-probe-{}", Uuid::new_v4().simple());
+    let probe = dir.join(&name);
+    fs::File::create_new(&probe)?;
+    let insensitive = fs::symlink_metadata(dir.join(name.to_uppercase())).is_ok();
+    fs::remove_file(&probe)?;
+    Ok(insensitive)
+}
+
+/// The refusal for `collisions` found while copying onto the case-insensitive `dest`.
+pub(crate) fn refuse_collisions(collisions: Vec<CaseCollision>, dest: &Path) -> Result<(), LatticeError> {
+    if collisions.is_empty() {
+        return Ok(());
+    }
+    let names = collisions.iter().flat_map(|collision| &collision.paths).count();
+    Err(LatticeError::CaseCollisions {
+        message: format!(
+            "{names} names would merge into {} on {}, which ignores case.",
+            collisions.len(),
+            dest.display()
+        ),
+        collisions,
+    })
+}
+
+/// The refusal `copy_path` returns when `src` can't land on `dest` intact.
+pub(crate) fn check_destination(src: &Path, dest: &Path) -> Result<(), LatticeError> {
+    if !src.is_dir() || !is_case_insensitive(dest)? {
+        return Ok(());
+    }
+    refuse_collisions(find_case_collisions(src), dest)
+}
+
+/// Finds names anywhere below `root` that a case-insensitive filesystem would merge.
+#[tauri::command]
+pub async fn check_case_collisions(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+) -> Result<Vec<CaseCollision>, LatticeError> {
+    log_command_async("check_case_collisions", async move {
+        let root = resolve_command_path(&app, &root)?;
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            if !root.is_dir() {
+                return Err(LatticeError::InvalidInput {
+                    message: format!("Path is not a directory: {}", root.display()),
+                });
+            }
+            Ok(find_case_collisions(&root))
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_differing_only_by_case_are_grouped_per_directory() {
+        let paths = [
+            "/n/File.txt",
+            "/n/file.txt",
+            "/n/other.txt",
+            "/n/sub/FILE.TXT",
+            "/m/file.txt",
+            "/n/Ünï",
+            "/n/ünï",
+        ];
+        let collisions = collisions_among(paths.iter().map(Path::new));
+        assert_eq!(
+            collisions,
+            vec![
+                CaseCollision {
+                    dir: "/n".to_string(),
+                    paths: vec!["/n/File.txt".to_string(), "/n/file.txt".to_string()],
+                },
+                CaseCollision {
+                    dir: "/n".to_string(),
+                    paths: vec!["/n/Ünï".to_string(), "/n/ünï".to_string()],
+                },
+            ]
+        );
+
+        let root = std::env::temp_dir().join(format!("lattice-case-{}", Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let insensitive = is_case_insensitive(&root.join("missing/dest")).unwrap();
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+        if !insensitive {
+            fs::write(root.join("Notes.md"), "a").unwrap();
+            fs::write(root.join("notes.md"), "b").unwrap();
+            assert_eq!(find_case_collisions(&root)[0].paths.len(), 2);
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+}
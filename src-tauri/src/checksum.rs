@@ -0,0 +1,335 @@
+use std::fs;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use md5::Digest;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+const CHECKSUM_PROGRESS_EVENT: &str = "checksum-progress";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+const READ_CHUNK_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HashAlgo {
+    Md5,
+    Sha1,
+    Sha256,
+    Blake3,
+}
+
+impl HashAlgo {
+    fn hex_len(self) -> usize {
+        match self {
+            Self::Md5 => 32,
+            Self::Sha1 => 40,
+            Self::Sha256 | Self::Blake3 => 64,
+        }
+    }
+
+    /// From a checksum file's extension, e.g. `data.csv.sha256`.
+    fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "md5" => Some(Self::Md5),
+            "sha1" => Some(Self::Sha1),
+            "sha256" => Some(Self::Sha256),
+            "b3" | "blake3" => Some(Self::Blake3),
+            _ => None,
+        }
+    }
+
+    /// Guesses by digest length; 64 hex digits is taken as SHA-256, the far more common one.
+    fn from_hex_len(len: usize) -> Option<Self> {
+        match len {
+            32 => Some(Self::Md5),
+            40 => Some(Self::Sha1),
+            64 => Some(Self::Sha256),
+            _ => None,
+        }
+    }
+}
+
+enum FileHasher {
+    Md5(md5::Md5),
+    Sha1(sha1::Sha1),
+    Sha256(sha2::Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl FileHasher {
+    fn new(algo: HashAlgo) -> Self {
+        match algo {
+            HashAlgo::Md5 => Self::Md5(md5::Md5::new()),
+            HashAlgo::Sha1 => Self::Sha1(sha1::Sha1::new()),
+            HashAlgo::Sha256 => Self::Sha256(sha2::Sha256::new()),
+            HashAlgo::Blake3 => Self::Blake3(Box::default()),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Md5(hasher) => hasher.update(bytes),
+            Self::Sha1(hasher) => hasher.update(bytes),
+            Self::Sha256(hasher) => hasher.update(bytes),
+            Self::Blake3(hasher) => {
+                hasher.update(bytes);
+            }
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            Self::Md5(hasher) => format!("{:x}", hasher.finalize()),
+            Self::Sha1(hasher) => format!("{:x}", hasher.finalize()),
+            Self::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            Self::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChecksumStatus {
+    Ok,
+    Mismatch,
+    Missing,
+    /// The file exists but couldn't be read; `error` says why.
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecksumResult {
+    pub path: String,
+    pub status: ChecksumStatus,
+    pub expected: String,
+    /// Set whenever the file could be hashed.
+    pub actual: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChecksumProgressPayload {
+    path: String,
+    bytes_done: u64,
+    bytes_total: u64,
+}
+
+/// Reads in fixed chunks so memory stays flat, reporting at most every `PROGRESS_INTERVAL`.
+fn hash_file_with(path: &Path, algo: HashAlgo, on_progress: &mut dyn FnMut(u64, u64)) -> std::io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let bytes_total = file.metadata()?.len();
+    let mut hasher = FileHasher::new(algo);
+    let mut buffer = vec![0u8; READ_CHUNK_BYTES];
+    let mut bytes_done = 0u64;
+    let mut last_progress = Instant::now();
+    loop {
+        let read = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        hasher.update(&buffer[..read]);
+        bytes_done += read as u64;
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            on_progress(bytes_done, bytes_total);
+            last_progress = Instant::now();
+        }
+    }
+    on_progress(bytes_done, bytes_total);
+    Ok(hasher.finalize_hex())
+}
+
+/// Lower-cased, so `expected` can be given in either case.
+fn normalize_digest(expected: &str, algo: HashAlgo) -> Result<String, LatticeError> {
+    let digest = expected.trim().to_ascii_lowercase();
+    if digest.len() != algo.hex_len() || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Expected a {}-digit hex digest, got {:?}", algo.hex_len(), expected.trim()),
+        });
+    }
+    Ok(digest)
+}
+
+fn verify_entry(
+    path: PathBuf,
+    expected: String,
+    algo: HashAlgo,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> ChecksumResult {
+    let (status, actual, error) = match hash_file_with(&path, algo, on_progress) {
+        Ok(actual) if actual == expected => (ChecksumStatus::Ok, Some(actual), None),
+        Ok(actual) => (ChecksumStatus::Mismatch, Some(actual), None),
+        Err(error) if error.kind() == ErrorKind::NotFound => (ChecksumStatus::Missing, None, None),
+        Err(error) => (ChecksumStatus::Failed, None, Some(error.to_string())),
+    };
+    ChecksumResult {
+        path: path.to_string_lossy().to_string(),
+        status,
+        expected,
+        actual,
+        error,
+    }
+}
+
+/// Parses `<hash>  <file>` lines as written by `sha256sum` and friends, including the
+/// ` *<file>` binary-mode marker. Blank lines and `#` comments are skipped.
+fn parse_checksum_lines(text: &str) -> Result<Vec<(String, String)>, LatticeError> {
+    let mut entries = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_start_matches('\u{feff}').trim_end();
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parsed = line.split_once(' ').and_then(|(digest, rest)| {
+            let name = rest.strip_prefix([' ', '*']).unwrap_or(rest);
+            let valid = !digest.is_empty() && digest.chars().all(|c| c.is_ascii_hexdigit()) && !name.is_empty();
+            valid.then(|| (digest.to_ascii_lowercase(), name.to_string()))
+        });
+        match parsed {
+            Some(entry) => entries.push(entry),
+            None => {
+                return Err(LatticeError::InvalidInput {
+                    message: format!("Line {} isn't `<hash>  <file>`: {line:?}", index + 1),
+                })
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn verify_checksum_file_sync(
+    checksum_file: &Path,
+    on_progress: &mut dyn FnMut(&Path, u64, u64),
+) -> Result<Vec<ChecksumResult>, LatticeError> {
+    let text = fs::read_to_string(checksum_file).map_err(|error| LatticeError::at_path(checksum_file, error))?;
+    let entries = parse_checksum_lines(&text)?;
+    let base = checksum_file.parent().unwrap_or_else(|| Path::new("."));
+    let named_algo = HashAlgo::from_extension(checksum_file);
+
+    let mut results = Vec::with_capacity(entries.len());
+    for (digest, name) in entries {
+        let algo = named_algo
+            .or_else(|| HashAlgo::from_hex_len(digest.len()))
+            .ok_or_else(|| LatticeError::InvalidInput {
+                message: format!("Can't tell the hash algorithm of {name} from a {}-digit digest", digest.len()),
+            })?;
+        let expected = normalize_digest(&digest, algo)?;
+        let path = base.join(&name);
+        results.push(verify_entry(path.clone(), expected, algo, &mut |done, total| {
+            on_progress(&path, done, total)
+        }));
+    }
+    Ok(results)
+}
+
+fn emit_progress(app: &AppHandle, path: &Path, bytes_done: u64, bytes_total: u64) {
+    let _ = app.emit(
+        CHECKSUM_PROGRESS_EVENT,
+        ChecksumProgressPayload {
+            path: path.to_string_lossy().to_string(),
+            bytes_done,
+            bytes_total,
+        },
+    );
+}
+
+/// Errors when `file` can't be read; a wrong digest is `Ok(false)`.
+#[tauri::command]
+pub async fn verify_checksum(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    file: String,
+    expected: String,
+    algo: HashAlgo,
+) -> Result<bool, LatticeError> {
+    log_command_async("verify_checksum", async move {
+        let path = resolve_command_path(&app, &file)?;
+        let expected = normalize_digest(&expected, algo)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let actual = hash_file_with(&path, algo, &mut |done, total| emit_progress(&app, &path, done, total))
+                .map_err(|error| LatticeError::at_path(&path, error))?;
+            Ok(actual == expected)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Listed files resolve against the checksum file's folder. The algorithm comes from the
+/// checksum file's extension, or from each digest's length when that doesn't say.
+#[tauri::command]
+pub async fn verify_checksum_file(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    checksum_file: String,
+) -> Result<Vec<ChecksumResult>, LatticeError> {
+    log_command_async("verify_checksum_file", async move {
+        let checksum_file = resolve_command_path(&app, &checksum_file)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            verify_checksum_file_sync(&checksum_file, &mut |path, done, total| {
+                emit_progress(&app, path, done, total)
+            })
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_files_report_matches_mismatches_and_missing_files() {
+        let root = std::env::temp_dir().join(format!("lattice-checksum-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("data")).unwrap();
+        fs::write(root.join("data/a.csv"), "abc").unwrap();
+        fs::write(root.join("data/b.csv"), "tampered").unwrap();
+        let abc_sha256 = "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD";
+        let listing = format!("# sha256sum\n{abc_sha256}  data/a.csv\n{abc_sha256} *data/b.csv\n{abc_sha256}  c.csv\n");
+        fs::write(root.join("SHA256SUMS.sha256"), listing).unwrap();
+
+        let results = verify_checksum_file_sync(&root.join("SHA256SUMS.sha256"), &mut |_, _, _| {}).unwrap();
+        let statuses: Vec<_> = results.iter().map(|result| result.status).collect();
+        assert_eq!(statuses, [ChecksumStatus::Ok, ChecksumStatus::Mismatch, ChecksumStatus::Missing]);
+        assert_eq!(results[0].actual.as_deref(), Some(abc_sha256.to_ascii_lowercase().as_str()));
+
+        let abc = root.join("data/a.csv");
+        let hash = |algo| hash_file_with(&abc, algo, &mut |_, _| {}).unwrap();
+        assert_eq!(hash(HashAlgo::Md5), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(hash(HashAlgo::Sha1), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(hash(HashAlgo::Blake3), blake3::hash(b"abc").to_hex().to_string());
+        assert!(normalize_digest("abc", HashAlgo::Md5).is_err());
+        assert!(parse_checksum_lines("not-a-hash data.csv").is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex as StdMutex;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::LatticeError;
+use crate::file_tree::is_hidden_name;
+use crate::ignore_rules::{matcher_for, IgnoreMatcher};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+const CHILD_COUNT_WORKERS: usize = 8;
+
+/// Counts are `None` alongside `error` when the directory couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildCount {
+    pub path: String,
+    pub files: Option<usize>,
+    pub directories: Option<usize>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Counts {
+    files: usize,
+    directories: usize,
+}
+
+struct CachedCounts {
+    modified: Option<SystemTime>,
+    counts: Counts,
+}
+
+/// Keyed by canonical directory and whether hidden entries were counted. Entries are
+/// also checked against the directory's mtime, which moves whenever a child is added,
+/// removed or renamed.
+#[derive(Default)]
+pub struct ChildCountState {
+    cache: StdMutex<HashMap<(PathBuf, bool), CachedCounts>>,
+}
+
+/// Called for watcher events so counts refresh even where the mtime is too coarse to notice.
+pub(crate) fn invalidate_child_counts(app: &AppHandle, changed: &Path) {
+    let Some(parent) = changed.parent() else {
+        return;
+    };
+    // Cache keys are canonical; the changed entry itself may already be gone.
+    let directory = fs::canonicalize(parent).unwrap_or_else(|_| parent.to_path_buf());
+    let changed = directory.join(changed.file_name().unwrap_or_default());
+    if let Ok(mut cache) = app.state::<ChildCountState>().cache.lock() {
+        cache.retain(|(cached, _), _| !cached.starts_with(&changed) && cached != &directory);
+    }
+}
+
+pub(crate) fn clear_child_counts(app: &AppHandle) {
+    if let Ok(mut cache) = app.state::<ChildCountState>().cache.lock() {
+        *cache = HashMap::new();
+    }
+}
+
+/// Mirrors `list_directory`'s filtering so the numbers match what the tree shows.
+fn count_children(directory: &Path, include_hidden: bool, ignore: &IgnoreMatcher) -> Result<Counts, String> {
+    let mut counts = Counts { files: 0, directories: 0 };
+    for entry in fs::read_dir(directory).map_err(|error| error.to_string())? {
+        let entry = entry.map_err(|error| error.to_string())?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !include_hidden && is_hidden_name(&name) {
+            continue;
+        }
+        // Symlinks count as whatever they point at, like the tree does.
+        let is_dir = fs::metadata(entry.path()).is_ok_and(|metadata| metadata.is_dir());
+        if ignore.is_ignored(&directory.join(&name), is_dir) {
+            continue;
+        }
+        if is_dir {
+            counts.directories += 1;
+        } else {
+            counts.files += 1;
+        }
+    }
+    Ok(counts)
+}
+
+fn child_count_for(app: &AppHandle, path: &str, include_hidden: bool) -> ChildCount {
+    let failed = |error: String| ChildCount {
+        path: path.to_string(),
+        files: None,
+        directories: None,
+        error: Some(error),
+    };
+    let directory = match resolve_command_path(app, path).and_then(|path| Ok(fs::canonicalize(path)?)) {
+        Ok(directory) if directory.is_dir() => directory,
+        Ok(directory) => return failed(format!("Not a directory: {}", directory.display())),
+        Err(error) => return failed(error.to_string()),
+    };
+
+    let modified = fs::metadata(&directory).and_then(|metadata| metadata.modified()).ok();
+    let key = (directory.clone(), include_hidden);
+    let state = app.state::<ChildCountState>();
+    let cached = state.cache.lock().ok().and_then(|cache| {
+        cache
+            .get(&key)
+            .filter(|cached| modified.is_some() && cached.modified == modified)
+            .map(|cached| cached.counts)
+    });
+
+    let counts = match cached {
+        Some(counts) => counts,
+        None => match count_children(&directory, include_hidden, &matcher_for(app, &directory)) {
+            Ok(counts) => {
+                if let Ok(mut cache) = state.cache.lock() {
+                    cache.insert(key, CachedCounts { modified, counts });
+                }
+                counts
+            }
+            Err(error) => return failed(error),
+        },
+    };
+    ChildCount {
+        path: path.to_string(),
+        files: Some(counts.files),
+        directories: Some(counts.directories),
+        error: None,
+    }
+}
+
+/// Counts every path on a small pool of worker threads, keeping results in input order.
+fn child_counts_batch(app: &AppHandle, paths: &[String], include_hidden: bool) -> Vec<ChildCount> {
+    let results: Vec<StdMutex<Option<ChildCount>>> = paths.iter().map(|_| StdMutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    let workers = CHILD_COUNT_WORKERS.min(paths.len());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                let result = child_count_for(app, path, include_hidden);
+                if let Ok(mut slot) = results[index].lock() {
+                    *slot = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .zip(paths)
+        .map(|(slot, path)| {
+            slot.into_inner().ok().flatten().unwrap_or_else(|| ChildCount {
+                path: path.clone(),
+                files: None,
+                directories: None,
+                error: Some("Count worker failed.".to_string()),
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn child_counts(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    paths: Vec<String>,
+    include_hidden: bool,
+) -> Result<Vec<ChildCount>, LatticeError> {
+    log_command_async("child_counts", async move {
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            child_counts_batch(&app, &paths, include_hidden)
+        })
+        .await
+        .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_skip_hidden_and_ignored_entries() {
+        let root = std::env::temp_dir().join(format!("lattice-child-counts-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::create_dir_all(root.join("build")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("a.md"), "a").unwrap();
+        fs::write(root.join("b.log"), "b").unwrap();
+        fs::write(root.join(".env"), "c").unwrap();
+        let root = fs::canonicalize(root).unwrap();
+
+        let ignore = IgnoreMatcher::new(&root, &["*.log".to_string(), "build/".to_string()]);
+        assert_eq!(
+            count_children(&root, false, &ignore).unwrap(),
+            Counts { files: 1, directories: 1 }
+        );
+        assert_eq!(
+            count_children(&root, true, &ignore).unwrap(),
+            Counts { files: 2, directories: 2 }
+        );
+        assert!(count_children(&root.join("missing"), false, &ignore).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
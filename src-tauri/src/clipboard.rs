@@ -0,0 +1,362 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Mutex as StdMutex;
+
+use clipboard_rs::common::RustImage;
+use clipboard_rs::{Clipboard, ClipboardContext, ContentFormat};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use time::OffsetDateTime;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::{resolve_command_path, resolve_command_paths};
+use crate::transfer::{free_path, transfer_path, ConflictPolicy, TransferFailure};
+use crate::DesktopFsState;
+
+const FILE_URI_PREFIX: &str = "file://";
+/// Everything but unreserved characters and the path separator, as file managers expect.
+const FILE_URI_PATH: &AsciiSet = &NON_ALPHANUMERIC.remove(b'/').remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+/// What a paste did, entry by entry: one item that can't be copied doesn't hide the
+/// ones that were.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteReport {
+    pub pasted: Vec<String>,
+    pub failed: Vec<TransferFailure>,
+}
+
+/// Opened on first use and kept: on X11 every context starts a thread that serves the
+/// selection for as long as the app runs.
+#[derive(Default)]
+pub struct ClipboardState {
+    context: StdMutex<Option<ClipboardContext>>,
+}
+
+fn with_clipboard<T>(
+    app: &AppHandle,
+    action: impl FnOnce(&ClipboardContext) -> Result<T, LatticeError>,
+) -> Result<T, LatticeError> {
+    let state = app.state::<ClipboardState>();
+    let mut context = state.context.lock().map_err(|error| error.to_string())?;
+    if context.is_none() {
+        *context = Some(ClipboardContext::new().map_err(|error| LatticeError::ClipboardUnavailable {
+            message: format!("The system clipboard is not available: {error}"),
+        })?);
+    }
+    action(context.as_ref().expect("clipboard context was just opened"))
+}
+
+#[cfg_attr(not(all(unix, not(target_os = "macos"))), allow(dead_code))]
+fn file_uri(path: &Path) -> String {
+    format!(
+        "{FILE_URI_PREFIX}{}",
+        utf8_percent_encode(&path.to_string_lossy(), FILE_URI_PATH)
+    )
+}
+
+/// Windows and macOS hand back plain paths; `text/uri-list` gives percent-encoded URIs.
+fn path_from_clipboard_entry(entry: &str) -> Option<PathBuf> {
+    let entry = entry.trim();
+    let Some(uri) = entry.strip_prefix(FILE_URI_PREFIX) else {
+        return (!entry.is_empty()).then(|| PathBuf::from(entry));
+    };
+    let path = uri.strip_prefix("localhost").unwrap_or(uri);
+    if !path.starts_with('/') {
+        // Files on another host can't be copied from here.
+        return None;
+    }
+    let decoded = percent_decode_str(path).decode_utf8().ok()?;
+    Some(PathBuf::from(decoded.as_ref()))
+}
+
+fn clipboard_entries(paths: &[PathBuf]) -> Result<Vec<String>, LatticeError> {
+    paths
+        .iter()
+        .map(|path| {
+            if !path.is_absolute() || !path.exists() {
+                return Err(LatticeError::NotFound {
+                    message: format!("Cannot copy missing path: {}", path.display()),
+                });
+            }
+            #[cfg(all(unix, not(target_os = "macos")))]
+            return Ok(file_uri(path));
+            #[cfg(not(all(unix, not(target_os = "macos"))))]
+            return Ok(path.to_string_lossy().to_string());
+        })
+        .collect()
+}
+
+/// Copies each clipboard entry into `dest_dir`, reporting where they ended up and which
+/// entries failed.
+fn paste_into(sources: &[PathBuf], dest_dir: &Path, on_conflict: ConflictPolicy) -> Result<PasteReport, LatticeError> {
+    if !dest_dir.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Not a folder: {}", dest_dir.display()),
+        });
+    }
+
+    let mut report = PasteReport::default();
+    for source in sources {
+        let failure = |error: String| TransferFailure {
+            path: source.to_string_lossy().to_string(),
+            error,
+        };
+        let Some(name) = source.file_name() else {
+            report.failed.push(failure("Not a file or folder".to_string()));
+            continue;
+        };
+        let target = dest_dir.join(name);
+        match transfer_path(source, &target, on_conflict, false, &AtomicBool::new(false), &mut |_, _| {}) {
+            Ok(transfer) => {
+                report.failed.extend(transfer.failed);
+                report.pasted.extend(transfer.destination);
+            }
+            Err(error) => report.failed.push(failure(error.to_string())),
+        }
+    }
+    Ok(report)
+}
+
+/// `pasted-20261014-093000.png` and the like, in local time.
+fn pasted_file_name(now: OffsetDateTime, extension: &str) -> String {
+    format!(
+        "pasted-{:04}{:02}{:02}-{:02}{:02}{:02}.{extension}",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    )
+}
+
+/// A free path in `dest_dir` for `name`, which gets `extension` when it has none. Names are
+/// single path components; anything that would land outside `dest_dir` is refused.
+fn paste_target(dest_dir: &Path, name: Option<&str>, extension: &str) -> Result<PathBuf, LatticeError> {
+    if !dest_dir.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Not a folder: {}", dest_dir.display()),
+        });
+    }
+    let name = match name.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(name) => {
+            let is_plain_name = Path::new(name).file_name().is_some_and(|file_name| file_name == name);
+            if !is_plain_name || name.contains(['/', '\\']) {
+                return Err(LatticeError::InvalidInput {
+                    message: format!("Not a valid file name: {name:?}"),
+                });
+            }
+            if Path::new(name).extension().is_some() {
+                name.to_string()
+            } else {
+                format!("{name}.{extension}")
+            }
+        }
+        None => {
+            let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+            pasted_file_name(now, extension)
+        }
+    };
+    free_path(&dest_dir.join(name))
+}
+
+/// `create_new` claims the name, so a file that shows up after the free name was picked is
+/// never replaced; losing that race just means picking the next free name.
+fn save_pasted(dest_dir: &Path, name: Option<&str>, extension: &str, bytes: &[u8]) -> Result<String, LatticeError> {
+    loop {
+        let target = paste_target(dest_dir, name, extension)?;
+        let mut file = match fs::OpenOptions::new().write(true).create_new(true).open(&target) {
+            Ok(file) => file,
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(LatticeError::at_path(&target, error)),
+        };
+        if let Err(error) = file.write_all(bytes).and_then(|()| file.sync_all()) {
+            drop(file);
+            let _ = fs::remove_file(&target);
+            return Err(LatticeError::at_path(&target, error));
+        }
+        return Ok(target.to_string_lossy().to_string());
+    }
+}
+
+#[tauri::command]
+pub async fn clipboard_has_image(app: AppHandle) -> Result<bool, LatticeError> {
+    log_command_async("clipboard_has_image", async move {
+        tokio::task::spawn_blocking(move || with_clipboard(&app, |clipboard| Ok(clipboard.has(ContentFormat::Image))))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Saves a copied image as PNG in `dest_dir`, returning the new file's path. Without a
+/// `name` it's called `pasted-<timestamp>.png`; an existing file is never replaced.
+#[tauri::command]
+pub async fn save_clipboard_image(
+    app: AppHandle,
+    dest_dir: String,
+    name: Option<String>,
+) -> Result<String, LatticeError> {
+    log_command_async("save_clipboard_image", async move {
+        let dest_dir = resolve_command_path(&app, &dest_dir)?;
+        tokio::task::spawn_blocking(move || {
+            let png = with_clipboard(&app, |clipboard| {
+                if !clipboard.has(ContentFormat::Image) {
+                    return Err(LatticeError::ClipboardEmpty {
+                        message: "The clipboard doesn't contain an image.".to_string(),
+                    });
+                }
+                let image = clipboard.get_image().map_err(|error| error.to_string())?;
+                image.to_png().map_err(|error| error.to_string().into())
+            })?;
+            save_pasted(&dest_dir, name.as_deref(), "png", png.get_bytes())
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Like `save_clipboard_image`, for copied text; names default to `pasted-<timestamp>.txt`.
+#[tauri::command]
+pub async fn save_clipboard_text(
+    app: AppHandle,
+    dest_dir: String,
+    name: Option<String>,
+) -> Result<String, LatticeError> {
+    log_command_async("save_clipboard_text", async move {
+        let dest_dir = resolve_command_path(&app, &dest_dir)?;
+        tokio::task::spawn_blocking(move || {
+            let text = with_clipboard(&app, |clipboard| {
+                let text = clipboard.get_text().unwrap_or_default();
+                if text.is_empty() {
+                    return Err(LatticeError::ClipboardEmpty {
+                        message: "The clipboard doesn't contain any text.".to_string(),
+                    });
+                }
+                Ok(text)
+            })?;
+            save_pasted(&dest_dir, name.as_deref(), "txt", text.as_bytes())
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn copy_files_to_clipboard(app: AppHandle, paths: Vec<String>) -> Result<(), LatticeError> {
+    log_command_async("copy_files_to_clipboard", async move {
+        let entries = clipboard_entries(&resolve_command_paths(&app, &paths)?)?;
+        if entries.is_empty() {
+            return Err(LatticeError::InvalidInput {
+                message: "Nothing to copy.".to_string(),
+            });
+        }
+        tokio::task::spawn_blocking(move || {
+            with_clipboard(&app, |clipboard| clipboard.set_files(entries).map_err(|error| error.to_string().into()))
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Pastes files copied here or in the system file manager into `dest_dir`. Entries that
+/// can't be copied are listed in `failed` rather than failing the whole paste.
+#[tauri::command]
+pub async fn paste_files_from_clipboard(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    dest_dir: String,
+    on_conflict: ConflictPolicy,
+) -> Result<PasteReport, LatticeError> {
+    log_command_async("paste_files_from_clipboard", async move {
+        let dest_dir = resolve_command_path(&app, &dest_dir)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let entries = with_clipboard(&app, |clipboard| {
+                clipboard.get_files().map_err(|error| error.to_string().into())
+            })?;
+            let sources: Vec<PathBuf> = entries.iter().filter_map(|entry| path_from_clipboard_entry(entry)).collect();
+            if sources.is_empty() {
+                return Err(LatticeError::ClipboardEmpty {
+                    message: "The clipboard doesn't contain any files.".to_string(),
+                });
+            }
+            paste_into(&sources, &dest_dir, on_conflict)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uri_list_entries_round_trip_to_paths() {
+        let path = Path::new("/home/ada/Notes & Drafts/über.md");
+        let uri = file_uri(path);
+        assert_eq!(uri, "file:///home/ada/Notes%20%26%20Drafts/%C3%BCber.md");
+        assert_eq!(path_from_clipboard_entry(&uri).as_deref(), Some(path));
+        assert_eq!(
+            path_from_clipboard_entry("file://localhost/tmp/a.md").as_deref(),
+            Some(Path::new("/tmp/a.md"))
+        );
+        assert_eq!(path_from_clipboard_entry("file://server/share/a.md"), None);
+        assert_eq!(path_from_clipboard_entry(r"C:\Notes\a.md").as_deref(), Some(Path::new(r"C:\Notes\a.md")));
+    }
+
+    #[test]
+    fn pasted_files_get_free_timestamped_or_given_names() {
+        let root = std::env::temp_dir().join(format!("lattice-paste-target-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let now = OffsetDateTime::from_unix_timestamp(1_791_970_200).unwrap();
+        assert_eq!(pasted_file_name(now, "png"), "pasted-20261014-093000.png");
+
+        assert_eq!(paste_target(&root, Some("diagram"), "png").unwrap(), root.join("diagram.png"));
+        fs::write(root.join("diagram.png"), "taken").unwrap();
+        assert_eq!(paste_target(&root, Some(" diagram "), "png").unwrap(), root.join("diagram (2).png"));
+        assert_eq!(paste_target(&root, Some("notes.md"), "txt").unwrap(), root.join("notes.md"));
+        assert!(paste_target(&root, Some("../escape"), "png").is_err());
+        assert!(paste_target(&root.join("missing"), None, "png").is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn pasting_applies_the_conflict_policy() {
+        let root = std::env::temp_dir().join(format!("lattice-clipboard-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("dest")).unwrap();
+        fs::write(root.join("a.md"), "alpha").unwrap();
+        fs::write(root.join("dest/a.md"), "existing").unwrap();
+
+        let sources = [root.join("missing.md"), root.join("a.md")];
+        let report = paste_into(&sources, &root.join("dest"), ConflictPolicy::Rename).unwrap();
+        assert_eq!(report.pasted, vec![root.join("dest/a (2).md").to_string_lossy().to_string()]);
+        assert_eq!(fs::read_to_string(root.join("dest/a (2).md")).unwrap(), "alpha");
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].path, root.join("missing.md").to_string_lossy());
+        assert!(paste_into(&sources, &root.join("a.md"), ConflictPolicy::Skip).is_err());
+
+        assert_eq!(save_pasted(&root, Some("a"), "md", b"new").unwrap(), root.join("a (2).md").to_string_lossy());
+        assert_eq!(fs::read_to_string(root.join("a.md")).unwrap(), "alpha");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,162 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::file_style::{apply_line_ending, detect_line_ending, LineEndingStyle};
+use crate::fileops::write_bytes_atomic;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+
+const BOM: &str = "\u{feff}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatReport {
+    pub format: ConfigFormat,
+    /// `false` when the file was already formatted and was left untouched.
+    pub changed: bool,
+}
+
+fn config_format(path: &Path) -> Option<ConfigFormat> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "json" => Some(ConfigFormat::Json),
+        "toml" => Some(ConfigFormat::Toml),
+        "yaml" | "yml" => Some(ConfigFormat::Yaml),
+        _ => None,
+    }
+}
+
+/// 1-based line and column of a byte offset.
+fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+/// Comments don't survive; key order does. JSON is read into `serde_yaml::Value`, whose
+/// mappings keep insertion order without switching `serde_json` to it crate-wide.
+fn format_text(text: &str, format: ConfigFormat) -> Result<String, LatticeError> {
+    match format {
+        ConfigFormat::Json => {
+            let value: serde_yaml::Value = serde_json::from_str(text).map_err(|error| LatticeError::ParseError {
+                message: error.to_string(),
+                line: Some(error.line()),
+                column: Some(error.column()),
+            })?;
+            let formatted = serde_json::to_string_pretty(&value).map_err(|error| error.to_string())?;
+            Ok(formatted + "\n")
+        }
+        ConfigFormat::Toml => {
+            let table: toml::Table = toml::from_str(text).map_err(|error| {
+                let location = error.span().map(|span| line_and_column(text, span.start));
+                LatticeError::ParseError {
+                    message: error.message().to_string(),
+                    line: location.map(|(line, _)| line),
+                    column: location.map(|(_, column)| column),
+                }
+            })?;
+            toml::to_string_pretty(&table).map_err(|error| error.to_string().into())
+        }
+        ConfigFormat::Yaml => {
+            let value: serde_yaml::Value = serde_yaml::from_str(text).map_err(|error| {
+                let location = error.location();
+                LatticeError::ParseError {
+                    message: error.to_string(),
+                    line: location.as_ref().map(|location| location.line()),
+                    column: location.as_ref().map(|location| location.column()),
+                }
+            })?;
+            serde_yaml::to_string(&value).map_err(|error| error.to_string().into())
+        }
+    }
+}
+
+/// Keeps the file's BOM and line endings. An unparseable file is never written.
+fn format_file_sync(path: &Path) -> Result<FormatReport, LatticeError> {
+    let format = config_format(path).ok_or_else(|| LatticeError::Unsupported {
+        message: format!("Only JSON, TOML and YAML files can be formatted: {}", path.display()),
+    })?;
+    let original = fs::read_to_string(path).map_err(|error| LatticeError::at_path(path, error))?;
+    let (bom, text) = match original.strip_prefix(BOM) {
+        Some(text) => (BOM, text),
+        None => ("", original.as_str()),
+    };
+    // An empty YAML or TOML file is valid and already as tidy as it gets.
+    if text.trim().is_empty() && format != ConfigFormat::Json {
+        return Ok(FormatReport { format, changed: false });
+    }
+
+    let line_ending = detect_line_ending(text).unwrap_or(LineEndingStyle::Lf);
+    let formatted = format!("{bom}{}", apply_line_ending(&format_text(text, format)?, line_ending));
+    let changed = formatted != original;
+    if changed {
+        write_bytes_atomic(path, formatted.as_bytes())?;
+    }
+    Ok(FormatReport { format, changed })
+}
+
+#[tauri::command]
+pub async fn format_file(app: AppHandle, path: String) -> Result<FormatReport, LatticeError> {
+    log_command_async("format_file", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || format_file_sync(&path))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_keep_key_order_and_report_where_parsing_failed() {
+        let json = format_text(r#"{"zeta":1,"alpha":{"b":[1,2],"a":null}}"#, ConfigFormat::Json).unwrap();
+        let expected = concat!(
+            "{\n  \"zeta\": 1,\n  \"alpha\": {\n    \"b\": [\n      1,\n      2\n    ],\n",
+            "    \"a\": null\n  }\n}\n"
+        );
+        assert_eq!(json, expected);
+        let toml = format_text("zeta = 1\nalpha   =   \"x\"\n", ConfigFormat::Toml).unwrap();
+        assert_eq!(toml, "zeta = 1\nalpha = \"x\"\n");
+        let yaml = format_text("zeta:   1\nalpha: [a, b]\n", ConfigFormat::Yaml).unwrap();
+        assert_eq!(yaml, "zeta: 1\nalpha:\n- a\n- b\n");
+
+        let broken = format_text("{\n  \"a\": 1,\n  oops\n}", ConfigFormat::Json);
+        assert!(matches!(broken, Err(LatticeError::ParseError { line: Some(3), column: Some(3), .. })), "{broken:?}");
+        let broken = format_text("a = 1\nb = \n", ConfigFormat::Toml);
+        assert!(matches!(broken, Err(LatticeError::ParseError { line: Some(2), .. })), "{broken:?}");
+    }
+
+    #[test]
+    fn unparseable_and_formatted_files_are_left_alone() {
+        let root = std::env::temp_dir().join(format!("lattice-format-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join("config.json");
+
+        fs::write(&path, "{\"a\": [1,\r\n2]}\r\n").unwrap();
+        assert!(format_file_sync(&path).unwrap().changed);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\r\n  \"a\": [\r\n    1,\r\n    2\r\n  ]\r\n}\r\n");
+        assert!(!format_file_sync(&path).unwrap().changed);
+
+        fs::write(&path, "{\"a\": }").unwrap();
+        assert!(matches!(format_file_sync(&path), Err(LatticeError::ParseError { .. })));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"a\": }");
+        assert!(matches!(format_file_sync(&root.join("notes.md")), Err(LatticeError::Unsupported { .. })));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::paths::resolve_app_path;
+use crate::settings_store_path;
+
+/// Dropped next to the executable to keep all data beside it (e.g. on a USB stick).
+const PORTABLE_MARKER: &str = "portable.txt";
+const PORTABLE_ENV: &str = "LATTICE_PORTABLE";
+const PORTABLE_DATA_DIR: &str = "data";
+/// Holds a user-chosen data directory. It can't live in the settings store, which moves with it.
+const DATA_DIR_POINTER: &str = "data-dir.txt";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DataDirLayout {
+    dir: PathBuf,
+    /// Where the pointer file is read from and written to.
+    pointer_dir: PathBuf,
+    portable: bool,
+    custom: bool,
+}
+
+/// The layout is resolved once per launch; changing it requires a restart.
+#[derive(Default)]
+pub struct DataDirState {
+    layout: OnceLock<DataDirLayout>,
+}
+
+fn is_truthy(value: &str) -> bool {
+    !matches!(value.trim().to_ascii_lowercase().as_str(), "" | "0" | "false" | "no" | "off")
+}
+
+fn read_pointer(pointer_dir: &Path) -> Option<PathBuf> {
+    let raw = fs::read_to_string(pointer_dir.join(DATA_DIR_POINTER)).ok()?;
+    let path = PathBuf::from(raw.trim());
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+    // Relative paths keep a portable install relocatable.
+    Some(if path.is_absolute() { path } else { pointer_dir.join(path) })
+}
+
+fn resolve_layout(
+    portable_env: Option<&str>,
+    exe_dir: Option<&Path>,
+    config_dir: &Path,
+    app_data_dir: &Path,
+) -> DataDirLayout {
+    let portable_dir = exe_dir.filter(|exe_dir| {
+        portable_env.is_some_and(is_truthy) || exe_dir.join(PORTABLE_MARKER).is_file()
+    });
+    let (pointer_dir, default_dir) = match portable_dir {
+        Some(exe_dir) => (exe_dir.to_path_buf(), exe_dir.join(PORTABLE_DATA_DIR)),
+        None => (config_dir.to_path_buf(), app_data_dir.to_path_buf()),
+    };
+    let custom = read_pointer(&pointer_dir);
+
+    DataDirLayout {
+        custom: custom.is_some(),
+        dir: custom.unwrap_or(default_dir),
+        pointer_dir,
+        portable: portable_dir.is_some(),
+    }
+}
+
+fn current_layout(app: &AppHandle) -> DataDirLayout {
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    let app_data_dir = app.path().app_data_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let config_dir = app.path().app_config_dir().unwrap_or_else(|_| app_data_dir.clone());
+    resolve_layout(
+        std::env::var(PORTABLE_ENV).ok().as_deref(),
+        exe_dir.as_deref(),
+        &config_dir,
+        &app_data_dir,
+    )
+}
+
+fn layout(app: &AppHandle) -> DataDirLayout {
+    app.state::<DataDirState>()
+        .layout
+        .get_or_init(|| current_layout(app))
+        .clone()
+}
+
+/// Root for the settings store and app-level caches.
+pub(crate) fn data_dir(app: &AppHandle) -> PathBuf {
+    layout(app).dir
+}
+
+/// The data directory when it differs from the OS app-data folder, so callers that
+/// otherwise rely on Tauri's defaults (e.g. webview profiles) can follow it.
+pub(crate) fn redirected_data_dir(app: &AppHandle) -> Option<PathBuf> {
+    let layout = layout(app);
+    (layout.portable || layout.custom).then_some(layout.dir)
+}
+
+/// Runs first in `setup`: fixes the layout and opens the settings store at its final path.
+/// Autosave is off because it writes in place; every change goes through `save_settings_store`.
+pub(crate) fn init_data_dir(app: &AppHandle) -> Result<(), String> {
+    let dir = data_dir(app);
+    fs::create_dir_all(&dir).map_err(|error| format!("Failed to create data directory {}: {error}", dir.display()))?;
+    app.store_builder(settings_store_path(app))
+        .disable_auto_save()
+        .build()
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn get_data_dir(app: AppHandle) -> String {
+    data_dir(&app).to_string_lossy().to_string()
+}
+
+/// An empty `path` goes back to the default location. Data is not moved.
+#[tauri::command]
+pub fn set_data_dir(app: AppHandle, path: String) -> Result<String, LatticeError> {
+    log_command("set_data_dir", || {
+        let layout = layout(&app);
+        let pointer = layout.pointer_dir.join(DATA_DIR_POINTER);
+        let path = path.trim();
+
+        if path.is_empty() {
+            match fs::remove_file(&pointer) {
+                Ok(()) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => return Err(error.into()),
+            }
+            return Ok("Lattice will use its default data directory after a restart.".to_string());
+        }
+
+        let target = resolve_app_path(&app, path, Some(&layout.pointer_dir.to_string_lossy()))?;
+        fs::create_dir_all(&target.path)?;
+        fs::create_dir_all(&layout.pointer_dir)?;
+        // Relative input is stored as typed, so a portable install can still be moved.
+        let relative = Path::new(path).is_relative() && !path.starts_with('~');
+        fs::write(&pointer, if relative { path } else { &target.path })?;
+        Ok(format!(
+            "Lattice will use {} after a restart. Existing data is not moved.",
+            target.display
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_dir_follows_app_data_unless_portable() {
+        let root = std::env::temp_dir().join(format!("lattice-data-dir-{}", uuid::Uuid::new_v4()));
+        let exe_dir = root.join("usb/Lattice");
+        let config_dir = root.join("config");
+        let app_data_dir = root.join("app-data");
+        fs::create_dir_all(&exe_dir).unwrap();
+        fs::create_dir_all(&config_dir).unwrap();
+
+        let installed = resolve_layout(None, Some(&exe_dir), &config_dir, &app_data_dir);
+        assert_eq!(installed.dir, app_data_dir);
+        assert!(!installed.portable);
+        assert_eq!(resolve_layout(Some("0"), Some(&exe_dir), &config_dir, &app_data_dir).dir, app_data_dir);
+
+        fs::write(config_dir.join(DATA_DIR_POINTER), root.join("custom").to_string_lossy().as_bytes()).unwrap();
+        assert_eq!(resolve_layout(None, Some(&exe_dir), &config_dir, &app_data_dir).dir, root.join("custom"));
+
+        let from_env = resolve_layout(Some("1"), Some(&exe_dir), &config_dir, &app_data_dir);
+        assert_eq!(from_env.dir, exe_dir.join(PORTABLE_DATA_DIR));
+        assert!(from_env.portable);
+
+        fs::write(exe_dir.join(PORTABLE_MARKER), "").unwrap();
+        let from_marker = resolve_layout(None, Some(&exe_dir), &config_dir, &app_data_dir);
+        assert_eq!(from_marker.dir, exe_dir.join(PORTABLE_DATA_DIR));
+
+        // Portable installs keep their own pointer, relative to the executable.
+        fs::write(exe_dir.join(DATA_DIR_POINTER), "profile\n").unwrap();
+        assert_eq!(resolve_layout(None, Some(&exe_dir), &config_dir, &app_data_dir).dir, exe_dir.join("profile"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+use std::ops::Range;
+use std::path::Path;
+
+use serde::Serialize;
+use similar::{capture_diff_slices, group_diff_ops, Algorithm, DiffTag};
+use tauri::{AppHandle, State};
+
+use crate::duplicates::hash_file;
+use crate::encoding::read_file_contents;
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+/// Unchanged lines kept around each change, as in `git diff`.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// Line numbers are 1-based; only the side(s) the line exists on are set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub op: DiffLineOp,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+fn read_text(path: &Path) -> Result<String, LatticeError> {
+    read_file_contents(path)
+        .map(|contents| contents.text)
+        .map_err(|error| match error {
+            LatticeError::BinaryFile { .. } => LatticeError::BinaryDiff {
+                message: format!("{} is a binary file.", path.display()),
+            },
+            error => error,
+        })
+}
+
+fn comparison_key(line: &str, ignore_whitespace: bool) -> String {
+    if ignore_whitespace {
+        line.split_whitespace().collect()
+    } else {
+        line.to_string()
+    }
+}
+
+fn push_lines(
+    lines: &mut Vec<DiffLine>,
+    op: DiffLineOp,
+    source: &[&str],
+    old: Option<Range<usize>>,
+    new: Option<Range<usize>>,
+) {
+    let (text_range, count) = match (&old, &new) {
+        (Some(range), _) | (None, Some(range)) => (range.clone(), range.len()),
+        (None, None) => return,
+    };
+    for offset in 0..count {
+        lines.push(DiffLine {
+            op,
+            old_line: old.as_ref().map(|range| range.start + offset + 1),
+            new_line: new.as_ref().map(|range| range.start + offset + 1),
+            text: source[text_range.start + offset].to_string(),
+        });
+    }
+}
+
+fn diff_texts(old_text: &str, new_text: &str, ignore_whitespace: bool) -> Vec<DiffHunk> {
+    let old_lines: Vec<&str> = old_text.lines().collect();
+    let new_lines: Vec<&str> = new_text.lines().collect();
+    let old_keys: Vec<String> = old_lines.iter().map(|line| comparison_key(line, ignore_whitespace)).collect();
+    let new_keys: Vec<String> = new_lines.iter().map(|line| comparison_key(line, ignore_whitespace)).collect();
+    let ops = capture_diff_slices(Algorithm::Myers, &old_keys, &new_keys);
+
+    group_diff_ops(ops, CONTEXT_LINES)
+        .into_iter()
+        .filter_map(|group| {
+            let old_range = group.first()?.old_range().start..group.last()?.old_range().end;
+            let new_range = group.first()?.new_range().start..group.last()?.new_range().end;
+            let mut lines = Vec::new();
+            for op in &group {
+                let (tag, old, new) = op.as_tag_tuple();
+                match tag {
+                    DiffTag::Equal => push_lines(&mut lines, DiffLineOp::Equal, &old_lines, Some(old), Some(new)),
+                    DiffTag::Delete => push_lines(&mut lines, DiffLineOp::Delete, &old_lines, Some(old), None),
+                    DiffTag::Insert => push_lines(&mut lines, DiffLineOp::Insert, &new_lines, None, Some(new)),
+                    DiffTag::Replace => {
+                        push_lines(&mut lines, DiffLineOp::Delete, &old_lines, Some(old), None);
+                        push_lines(&mut lines, DiffLineOp::Insert, &new_lines, None, Some(new));
+                    }
+                }
+            }
+            Some(DiffHunk {
+                old_start: old_range.start + 1,
+                old_lines: old_range.len(),
+                new_start: new_range.start + 1,
+                new_lines: new_range.len(),
+                lines,
+            })
+        })
+        .collect()
+}
+
+fn diff_files_sync(left: &Path, right: &Path, ignore_whitespace: bool) -> Result<Vec<DiffHunk>, LatticeError> {
+    // Identical bytes need neither decoding nor a diff.
+    if let (Ok(left_hash), Ok(right_hash)) = (hash_file(left), hash_file(right)) {
+        if left_hash == right_hash {
+            return Ok(Vec::new());
+        }
+    }
+    let old_text = read_text(left)?;
+    let new_text = read_text(right)?;
+    Ok(diff_texts(&old_text, &new_text, ignore_whitespace))
+}
+
+#[tauri::command]
+pub async fn diff_files(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    left: String,
+    right: String,
+    ignore_whitespace: Option<bool>,
+) -> Result<Vec<DiffHunk>, LatticeError> {
+    log_command_async("diff_files", async move {
+        let left = resolve_command_path(&app, &left)?;
+        let right = resolve_command_path(&app, &right)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            diff_files_sync(&left, &right, ignore_whitespace.unwrap_or(false))
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn hunks_report_line_ranges_and_operations() {
+        let old: String = (1..=15).map(|line| format!("line {line}\n")).collect();
+        let new = old.replace("line 2\n", "line two\n") + "line 16\n";
+        let hunks = diff_texts(&old, &new, false);
+
+        assert_eq!(hunks.len(), 2);
+        assert_eq!((hunks[0].old_start, hunks[0].old_lines, hunks[0].new_start, hunks[0].new_lines), (1, 5, 1, 5));
+        let changed: Vec<_> = hunks[0]
+            .lines
+            .iter()
+            .filter(|line| line.op != DiffLineOp::Equal)
+            .map(|line| (line.op, line.old_line, line.new_line, line.text.as_str()))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                (DiffLineOp::Delete, Some(2), None, "line 2"),
+                (DiffLineOp::Insert, None, Some(2), "line two"),
+            ]
+        );
+        assert_eq!((hunks[1].old_start, hunks[1].old_lines, hunks[1].new_lines), (13, 3, 4));
+        assert_eq!(hunks[1].lines.last().map(|line| (line.op, line.new_line)), Some((DiffLineOp::Insert, Some(16))));
+    }
+
+    #[test]
+    fn whitespace_identical_and_binary_files_short_circuit() {
+        assert!(diff_texts("let x = 1;\n", "let  x = 1;   \n", true).is_empty());
+        assert_eq!(diff_texts("let x = 1;\n", "let  x = 1;\n", false).len(), 1);
+
+        let root = std::env::temp_dir().join(format!("lattice-diff-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("a.txt"), "same\n").unwrap();
+        fs::write(root.join("b.txt"), "same\n").unwrap();
+        fs::write(root.join("image.png"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\x01\x02\x00\x00").unwrap();
+
+        assert!(diff_files_sync(&root.join("a.txt"), &root.join("b.txt"), false).unwrap().is_empty());
+        assert!(matches!(
+            diff_files_sync(&root.join("a.txt"), &root.join("image.png"), false),
+            Err(LatticeError::BinaryDiff { .. })
+        ));
+        assert!(matches!(
+            diff_files_sync(&root.join("a.txt"), &root.join("missing.txt"), false),
+            Err(LatticeError::NotFound { .. })
+        ));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,118 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sysinfo::Disks;
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+
+/// Byte counts are `None` when the filesystem doesn't report them, as with some network
+/// mounts; the UI should then skip the low-space warning rather than fail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskSpace {
+    pub total: Option<u64>,
+    pub free: Option<u64>,
+    /// What the current user can actually write; below `free` when space is reserved.
+    pub available: Option<u64>,
+    pub mount_point: Option<String>,
+    pub volume_name: Option<String>,
+}
+
+struct Volume {
+    mount_point: PathBuf,
+    name: String,
+}
+
+/// The most specific mount containing `path`, so `/home` wins over `/` when both exist.
+fn containing_volume(path: &Path, volumes: Vec<Volume>) -> Option<Volume> {
+    volumes
+        .into_iter()
+        .filter(|volume| path.starts_with(&volume.mount_point))
+        .max_by_key(|volume| volume.mount_point.components().count())
+}
+
+fn mounted_volumes() -> Vec<Volume> {
+    Disks::new_with_refreshed_list()
+        .iter()
+        .map(|disk| Volume {
+            mount_point: disk.mount_point().to_path_buf(),
+            name: disk.name().to_string_lossy().to_string(),
+        })
+        .collect()
+}
+
+/// Where the volume holding `path` is mounted, for telling whether two paths share one.
+#[cfg(not(unix))]
+pub(crate) fn volume_mount_point(path: &Path) -> Option<PathBuf> {
+    let path = std::path::absolute(path).ok()?;
+    containing_volume(&path, mounted_volumes()).map(|volume| volume.mount_point)
+}
+
+/// Filesystems without space accounting report zero blocks, which is not a full disk.
+fn reported_space(path: &Path) -> (Option<u64>, Option<u64>, Option<u64>) {
+    match fs4::statvfs(path) {
+        Ok(stats) if stats.total_space() > 0 => (
+            Some(stats.total_space()),
+            Some(stats.free_space()),
+            Some(stats.available_space()),
+        ),
+        _ => (None, None, None),
+    }
+}
+
+fn disk_space_sync(path: &Path) -> Result<DiskSpace, LatticeError> {
+    if !path.exists() {
+        return Err(LatticeError::NotFound {
+            message: format!("Path not found: {}", path.display()),
+        });
+    }
+    // Not canonicalized: on Windows that yields `\\?\` paths that match no mount point.
+    let path = std::path::absolute(path)?;
+    let volume = containing_volume(&path, mounted_volumes());
+    let (total, free, available) = reported_space(&path);
+    Ok(DiskSpace {
+        total,
+        free,
+        available,
+        mount_point: volume
+            .as_ref()
+            .map(|volume| volume.mount_point.to_string_lossy().to_string()),
+        volume_name: volume
+            .map(|volume| volume.name)
+            .filter(|name| !name.is_empty()),
+    })
+}
+
+#[tauri::command]
+pub async fn disk_space(app: AppHandle, path: String) -> Result<DiskSpace, LatticeError> {
+    log_command_async("disk_space", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || disk_space_sync(&path))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_deepest_mount_containing_the_path() {
+        let volume = |mount_point: &str, name: &str| Volume {
+            mount_point: PathBuf::from(mount_point),
+            name: name.to_string(),
+        };
+        let volumes = || vec![volume("/", "root"), volume("/home", "home"), volume("/home-backup", "backup")];
+
+        let home = containing_volume(Path::new("/home/me/notes"), volumes()).unwrap();
+        assert_eq!(home.name, "home");
+        let root = containing_volume(Path::new("/etc"), volumes()).unwrap();
+        assert_eq!(root.name, "root");
+        assert!(containing_volume(Path::new("/etc"), vec![volume("/home", "home")]).is_none());
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ignore::WalkBuilder;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+const DUPLICATES_PROGRESS_EVENT: &str = "duplicates-progress";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+const DEFAULT_MAX_FILE_SIZE: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    /// Hex-encoded BLAKE3 digest shared by every path in the group.
+    pub hash: String,
+    pub size: u64,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DuplicatesProgressPayload {
+    files_hashed: usize,
+    files_to_hash: usize,
+}
+
+fn group_by_size(root: &Path, max_file_size: u64) -> HashMap<u64, Vec<PathBuf>> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    let walker = WalkBuilder::new(root).require_git(false).build();
+    for entry in walker.flatten() {
+        if !entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        // Empty files are trivially identical and only add noise.
+        let size = metadata.len();
+        if size == 0 || size > max_file_size {
+            continue;
+        }
+        by_size.entry(size).or_default().push(entry.into_path());
+    }
+
+    by_size.retain(|_, paths| paths.len() > 1);
+    by_size
+}
+
+/// Hex BLAKE3 digest of the file's bytes; also the token `write_file_checked` compares.
+pub(crate) fn hash_file(path: &Path) -> std::io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(fs::File::open(path)?)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+fn find_duplicate_groups<F>(root: &Path, max_file_size: u64, mut on_progress: F) -> Vec<DuplicateGroup>
+where
+    F: FnMut(usize, usize),
+{
+    // Only files that share a size can share content, so everything else is never read.
+    let candidates = group_by_size(root, max_file_size);
+    let files_to_hash = candidates.values().map(Vec::len).sum();
+    let mut files_hashed = 0usize;
+    let mut last_progress = Instant::now();
+    let mut groups = Vec::new();
+
+    for (size, paths) in candidates {
+        let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+        for path in paths {
+            files_hashed += 1;
+            if let Ok(hash) = hash_file(&path) {
+                by_hash
+                    .entry(hash)
+                    .or_default()
+                    .push(path.to_string_lossy().to_string());
+            }
+            if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                on_progress(files_hashed, files_to_hash);
+                last_progress = Instant::now();
+            }
+        }
+
+        groups.extend(
+            by_hash
+                .into_iter()
+                .filter(|(_, paths)| paths.len() > 1)
+                .map(|(hash, mut paths)| {
+                    paths.sort();
+                    DuplicateGroup { hash, size, paths }
+                }),
+        );
+    }
+
+    on_progress(files_hashed, files_to_hash);
+    // Biggest savings first.
+    groups.sort_by(|left, right| {
+        let wasted = |group: &DuplicateGroup| group.size * (group.paths.len() as u64 - 1);
+        wasted(right).cmp(&wasted(left)).then_with(|| left.paths.cmp(&right.paths))
+    });
+    groups
+}
+
+#[tauri::command]
+pub async fn find_duplicates(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+    max_file_size: Option<u64>,
+) -> Result<Vec<DuplicateGroup>, LatticeError> {
+    log_command_async("find_duplicates", async move {
+        let root = resolve_command_path(&app, &root)?;
+        if !root.is_dir() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Path is not a directory: {}", root.display()),
+            });
+        }
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            find_duplicate_groups(&root, max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE), |files_hashed, files_to_hash| {
+                let _ = app.emit(
+                    DUPLICATES_PROGRESS_EVENT,
+                    DuplicatesProgressPayload {
+                        files_hashed,
+                        files_to_hash,
+                    },
+                );
+            })
+        })
+        .await
+        .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_identical_files_and_respects_the_size_cap() {
+        let root = std::env::temp_dir().join(format!("lattice-duplicates-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("copies")).unwrap();
+        fs::write(root.join("paper.md"), "same contents").unwrap();
+        fs::write(root.join("copies/paper.md"), "same contents").unwrap();
+        fs::write(root.join("other.md"), "diff contents").unwrap();
+        fs::write(root.join("large-a.bin"), vec![7u8; 64]).unwrap();
+        fs::write(root.join("large-b.bin"), vec![7u8; 64]).unwrap();
+        fs::write(root.join("empty-a.md"), "").unwrap();
+        fs::write(root.join("empty-b.md"), "").unwrap();
+
+        let mut final_progress = (0, 0);
+        let groups = find_duplicate_groups(&root, 32, |done, total| final_progress = (done, total));
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].size, 13);
+        assert_eq!(groups[0].hash, blake3::hash(b"same contents").to_hex().to_string());
+        assert!(groups[0].paths[0].ends_with("paper.md") && groups[0].paths[1].ends_with("paper.md"));
+        assert_eq!(final_progress, (3, 3));
+
+        assert_eq!(find_duplicate_groups(&root, DEFAULT_MAX_FILE_SIZE, |_, _| {}).len(), 2);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
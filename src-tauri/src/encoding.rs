@@ -0,0 +1,422 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex as StdMutex;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::write::EncoderStringWriter;
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::error::LatticeError;
+use crate::file_type::detect_file_type_sync;
+use crate::fileops::timestamp_ms;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::search::looks_binary;
+use crate::DesktopFsState;
+
+const SNIFF_BYTES: usize = 8 * 1024;
+const READ_FILES_WORKERS: usize = 8;
+/// Per-file cap for `read_files`, which bounds in-flight memory to roughly workers × cap.
+const READ_FILES_MAX_BYTES: u64 = 32 * 1024 * 1024;
+/// The encoded string is a third larger and crosses IPC as JSON, so keep previews small.
+const READ_BASE64_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+    Mixed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileContents {
+    /// Decoded text with every line break normalized to `\n`.
+    pub text: String,
+    /// WHATWG encoding label, e.g. `UTF-8`, `windows-1252`, `UTF-16LE`.
+    pub encoding: String,
+    pub had_bom: bool,
+    pub line_ending: LineEnding,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Base64File {
+    pub mime: String,
+    /// Standard base64, ready for `data:{mime};base64,{data}`.
+    pub data: String,
+    pub size: u64,
+}
+
+/// One entry of a `read_files` batch; exactly one of `contents` and `error` is set.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileReadResult {
+    pub path: String,
+    /// Unix timestamp in milliseconds, for detecting later external changes.
+    pub modified: Option<u64>,
+    pub contents: Option<FileContents>,
+    pub error: Option<LatticeError>,
+}
+
+/// BOM-less UTF-16 is full of NUL bytes, so it has to be recognized before
+/// the binary sniff rejects it. Mostly-ASCII text leaves every other byte zero.
+fn sniff_bomless_utf16(sample: &[u8]) -> Option<&'static Encoding> {
+    let pairs = sample.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+
+    let (mut even_zeros, mut odd_zeros) = (0usize, 0usize);
+    for pair in sample.chunks_exact(2) {
+        even_zeros += usize::from(pair[0] == 0);
+        odd_zeros += usize::from(pair[1] == 0);
+    }
+
+    let dominant = pairs * 2 / 5;
+    let stray = pairs / 20;
+    if odd_zeros >= dominant && even_zeros <= stray {
+        Some(UTF_16LE)
+    } else if even_zeros >= dominant && odd_zeros <= stray {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+fn detect_encoding(bytes: &[u8]) -> Result<(&'static Encoding, usize), LatticeError> {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+        return Ok((encoding, bom_length));
+    }
+
+    let sample = &bytes[..bytes.len().min(SNIFF_BYTES)];
+    if let Some(encoding) = sniff_bomless_utf16(sample) {
+        return Ok((encoding, 0));
+    }
+    if looks_binary(sample) {
+        return Err(LatticeError::BinaryFile {
+            message: "File appears to be binary.".to_string(),
+        });
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return Ok((UTF_8, 0));
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    Ok((detector.guess(None, true), 0))
+}
+
+fn normalize_line_endings(text: &str) -> (String, LineEnding) {
+    let (mut lf, mut crlf, mut cr) = (0usize, 0usize, 0usize);
+    let mut normalized = String::with_capacity(text.len());
+    let mut characters = text.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\r' if characters.peek() == Some(&'\n') => {
+                characters.next();
+                crlf += 1;
+                normalized.push('\n');
+            }
+            '\r' => {
+                cr += 1;
+                normalized.push('\n');
+            }
+            '\n' => {
+                lf += 1;
+                normalized.push('\n');
+            }
+            other => normalized.push(other),
+        }
+    }
+
+    let line_ending = match (lf > 0, crlf > 0, cr > 0) {
+        (_, true, false) if lf == 0 => LineEnding::Crlf,
+        (false, false, true) => LineEnding::Cr,
+        (_, false, false) => LineEnding::Lf,
+        _ => LineEnding::Mixed,
+    };
+    (normalized, line_ending)
+}
+
+fn decode_file_contents(bytes: &[u8]) -> Result<FileContents, LatticeError> {
+    let (encoding, bom_length) = detect_encoding(bytes)?;
+    let (decoded, _had_errors) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+    let (text, line_ending) = normalize_line_endings(&decoded);
+
+    Ok(FileContents {
+        text,
+        encoding: encoding.name().to_string(),
+        had_bom: bom_length > 0,
+        line_ending,
+    })
+}
+
+pub(crate) fn read_file_contents(path: &Path) -> Result<FileContents, LatticeError> {
+    let bytes = fs::read(path).map_err(|error| LatticeError::at_path(path, error))?;
+    decode_file_contents(&bytes)
+}
+
+fn too_large(path: &Path, size: u64, limit: u64) -> LatticeError {
+    LatticeError::TooLarge {
+        size,
+        limit,
+        message: format!("File is larger than {limit} bytes: {}", path.display()),
+    }
+}
+
+fn read_batch_entry(path: &str, target: Result<PathBuf, LatticeError>, max_bytes: u64) -> FileReadResult {
+    let (modified, outcome) = match target.map(|target| (fs::metadata(&target), target)) {
+        Err(error) => (None, Err(error)),
+        Ok((Err(error), target)) => (None, Err(LatticeError::at_path(&target, error))),
+        Ok((Ok(metadata), target)) if metadata.len() > max_bytes => (
+            timestamp_ms(metadata.modified()),
+            Err(too_large(&target, metadata.len(), max_bytes)),
+        ),
+        Ok((Ok(metadata), target)) => (timestamp_ms(metadata.modified()), read_file_contents(&target)),
+    };
+
+    let (contents, error) = match outcome {
+        Ok(contents) => (Some(contents), None),
+        Err(error) => (None, Some(error)),
+    };
+    FileReadResult {
+        path: path.to_string(),
+        modified,
+        contents,
+        error,
+    }
+}
+
+/// Encodes while reading, so the raw bytes are never held alongside the encoded string.
+fn read_base64_sync(path: &Path, max_bytes: u64) -> Result<Base64File, LatticeError> {
+    let file = fs::File::open(path).map_err(|error| LatticeError::at_path(path, error))?;
+    let size = file.metadata().map_err(|error| LatticeError::at_path(path, error))?.len();
+    if size > max_bytes {
+        return Err(too_large(path, size, max_bytes));
+    }
+    let mime = detect_file_type_sync(path)?.mime;
+
+    let encoded = String::with_capacity((size as usize).div_ceil(3) * 4);
+    let mut writer = EncoderStringWriter::from_consumer(encoded, &STANDARD);
+    // The file may have grown since it was measured.
+    let copied =
+        io::copy(&mut file.take(max_bytes + 1), &mut writer).map_err(|error| LatticeError::at_path(path, error))?;
+    if copied > max_bytes {
+        return Err(too_large(path, copied, max_bytes));
+    }
+    Ok(Base64File {
+        mime,
+        data: writer.into_inner(),
+        size: copied,
+    })
+}
+
+/// Reads every path on a small pool of worker threads, keeping results in input order.
+/// `resolve` turns each requested path into the file to read; results keep the path as requested.
+fn read_files_batch<R>(paths: &[String], max_bytes: u64, resolve: R) -> Vec<FileReadResult>
+where
+    R: Fn(&str) -> Result<PathBuf, LatticeError> + Sync,
+{
+    let results: Vec<StdMutex<Option<FileReadResult>>> = paths.iter().map(|_| StdMutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    let workers = READ_FILES_WORKERS.min(paths.len());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                let result = read_batch_entry(path, resolve(path), max_bytes);
+                if let Ok(mut slot) = results[index].lock() {
+                    *slot = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .zip(paths)
+        .map(|(slot, path)| {
+            slot.into_inner().ok().flatten().unwrap_or_else(|| FileReadResult {
+                path: path.clone(),
+                modified: None,
+                contents: None,
+                error: Some("Read worker failed.".into()),
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn read_file_smart(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+) -> Result<FileContents, LatticeError> {
+    log_command_async("read_file_smart", async move {
+        let path = resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            read_file_contents(&path)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// For ad-hoc previews of files outside the asset protocol's scope.
+#[tauri::command]
+pub async fn read_file_base64(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+    max_bytes: Option<u64>,
+) -> Result<Base64File, LatticeError> {
+    log_command_async("read_file_base64", async move {
+        let path = resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            read_base64_sync(&path, max_bytes.unwrap_or(READ_BASE64_MAX_BYTES))
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn read_files(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    paths: Vec<String>,
+) -> Result<Vec<FileReadResult>, LatticeError> {
+    log_command_async("read_files", async move {
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            read_files_batch(&paths, READ_FILES_MAX_BYTES, |path| resolve_command_path(&app, path))
+        })
+        .await
+        .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_legacy_and_utf16_files_and_normalizes_line_endings() {
+        let windows_1252 = decode_file_contents(b"caf\xe9 cr\xe8me br\xfbl\xe9e\r\nna\xefve\r\n").unwrap();
+        assert_eq!(windows_1252.encoding, "windows-1252");
+        assert_eq!(windows_1252.text, "café crème brûlée\nnaïve\n");
+        assert_eq!(windows_1252.line_ending, LineEnding::Crlf);
+        assert!(!windows_1252.had_bom);
+
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("格致\nlattice\r\n".encode_utf16().flat_map(u16::to_le_bytes));
+        let decoded = decode_file_contents(&utf16).unwrap();
+        assert_eq!(decoded.encoding, "UTF-16LE");
+        assert!(decoded.had_bom);
+        assert_eq!(decoded.text, "格致\nlattice\n");
+        assert_eq!(decoded.line_ending, LineEnding::Mixed);
+
+        let bomless: Vec<u8> = "plain notes\n".encode_utf16().flat_map(u16::to_be_bytes).collect();
+        assert_eq!(decode_file_contents(&bomless).unwrap().encoding, "UTF-16BE");
+    }
+
+    #[test]
+    fn rejects_binary_files_with_a_typed_error() {
+        let result = decode_file_contents(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\x01\x02\x00\x00");
+        assert!(matches!(result, Err(LatticeError::BinaryFile { .. })));
+
+        let utf8 = decode_file_contents("no newline".as_bytes()).unwrap();
+        assert_eq!(utf8.encoding, "UTF-8");
+        assert_eq!(utf8.line_ending, LineEnding::Lf);
+    }
+
+    #[test]
+    fn batch_reads_keep_order_and_report_failures_per_file() {
+        let root = std::env::temp_dir().join(format!("lattice-encoding-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let path = |name: &str| root.join(name).to_string_lossy().to_string();
+        for index in 0..12 {
+            fs::write(root.join(format!("note-{index}.md")), format!("note {index}")).unwrap();
+        }
+        fs::write(root.join("big.md"), "x".repeat(64)).unwrap();
+
+        let mut paths: Vec<String> = (0..12).map(|index| path(&format!("note-{index}.md"))).collect();
+        paths.insert(3, path("missing.md"));
+        paths.push(path("big.md"));
+
+        let results = read_files_batch(&paths, 32, |path| Ok(PathBuf::from(path)));
+        assert_eq!(results.len(), paths.len());
+        assert!(results.iter().zip(&paths).all(|(result, path)| &result.path == path));
+        assert_eq!(results[0].contents.as_ref().unwrap().text, "note 0");
+        assert_eq!(results[12].contents.as_ref().unwrap().text, "note 11");
+        assert!(results[0].modified.is_some());
+        assert!(matches!(results[3].error, Some(LatticeError::NotFound { .. })));
+        assert!(matches!(
+            results[13].error,
+            Some(LatticeError::TooLarge { size: 64, limit: 32, .. })
+        ));
+        assert!(results[13].contents.is_none() && results[13].modified.is_some());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn base64_reads_carry_the_detected_mime_and_respect_the_limit() {
+        let root = std::env::temp_dir().join(format!("lattice-base64-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let image = root.join("pixel.bin");
+        fs::write(&image, b"\x89PNG\r\n\x1a\n").unwrap();
+
+        let encoded = read_base64_sync(&image, 1024).unwrap();
+        assert_eq!((encoded.mime.as_str(), encoded.data.as_str(), encoded.size), ("image/png", "iVBORw0KGgo=", 8));
+        assert!(matches!(
+            read_base64_sync(&image, 4),
+            Err(LatticeError::TooLarge { size: 8, limit: 4, .. })
+        ));
+        assert!(matches!(
+            read_base64_sync(&root.join("missing.png"), 1024),
+            Err(LatticeError::NotFound { .. })
+        ));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::case_collisions::CaseCollision;
+use crate::file_locks::LockHolder;
+
+/// The error every general-purpose command returns. Serializes as `{ "kind", "message" }`
+/// so the frontend can branch on `kind` instead of matching message text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum LatticeError {
+    NotFound { message: String },
+    PermissionDenied { message: String },
+    /// The path resolves outside the opened folder.
+    OutsideScope { message: String },
+    AlreadyExists { message: String },
+    /// A rename target is taken and overwriting wasn't asked for.
+    TargetExists { message: String },
+    InvalidInput { message: String },
+    Unsupported { message: String },
+    #[serde(rename_all = "camelCase")]
+    TooLarge { size: u64, limit: u64, message: String },
+    /// Windows only creates symlinks for administrators or with Developer Mode on.
+    RequiresPrivilege { message: String },
+    /// Hard links and plain renames can't cross volumes.
+    CrossDevice { message: String },
+    /// The file doesn't parse as its format. Lines and columns are 1-based; they're `None`
+    /// when the parser doesn't say where.
+    ParseError {
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+    /// The file isn't text, so it can't be opened or counted as such.
+    BinaryFile { message: String },
+    /// One side of a diff isn't text.
+    BinaryDiff { message: String },
+    /// The image data can't be decoded.
+    Corrupt { message: String },
+    /// The file changed on disk since it was loaded. `current_contents` is `None`
+    /// when it was deleted in the meantime.
+    #[serde(rename_all = "camelCase")]
+    ConflictDetected {
+        current_hash: Option<String>,
+        current_contents: Option<String>,
+        message: String,
+    },
+    /// A backup destination inside its source; every run would copy the previous backup
+    /// into the next one.
+    DestinationInsideSource { message: String },
+    /// Restoring would overwrite something created at an original location since.
+    Conflict { message: String, paths: Vec<String> },
+    /// The OS refused because another process holds the path open.
+    InUse { message: String, holders: Vec<LockHolder> },
+    /// The path is, or contains, the opened folder or a favorite.
+    Guarded { message: String },
+    /// The confirmation token was never issued, or was already used.
+    InvalidToken { message: String },
+    /// The confirmation token was issued but has timed out; ask for a new one.
+    ExpiredToken { message: String },
+    /// A zip entry would land outside the extraction directory (zip-slip).
+    UnsafeEntryPath { message: String },
+    /// Selected paths that aren't inside the base the archive is relative to.
+    OutsideBase { message: String, paths: Vec<String> },
+    /// No `.git` was found at or above the folder; the UI hides badges.
+    NotARepository { message: String },
+    #[cfg_attr(not(all(unix, not(target_os = "macos"))), allow(dead_code))]
+    NoFileManager { message: String },
+    /// No application is registered to open the file.
+    NoHandler { message: String },
+    DisallowedScheme { message: String },
+    /// No native clipboard could be opened, e.g. without a display server.
+    ClipboardUnavailable { message: String },
+    /// The clipboard holds nothing of the requested kind.
+    ClipboardEmpty { message: String },
+    /// Any other I/O failure.
+    Io { message: String },
+    /// Failures that don't come from the filesystem: the settings store, a webview, a task.
+    Failed { message: String },
+    /// Names that differ only by case would merge on the case-insensitive destination.
+    CaseCollisions {
+        message: String,
+        collisions: Vec<CaseCollision>,
+    },
+}
+
+impl LatticeError {
+    pub fn message(&self) -> &str {
+        match self {
+            Self::NotFound { message }
+            | Self::PermissionDenied { message }
+            | Self::OutsideScope { message }
+            | Self::AlreadyExists { message }
+            | Self::TargetExists { message }
+            | Self::InvalidInput { message }
+            | Self::Unsupported { message }
+            | Self::TooLarge { message, .. }
+            | Self::RequiresPrivilege { message }
+            | Self::CrossDevice { message }
+            | Self::ParseError { message, .. }
+            | Self::BinaryFile { message }
+            | Self::BinaryDiff { message }
+            | Self::Corrupt { message }
+            | Self::ConflictDetected { message, .. }
+            | Self::DestinationInsideSource { message }
+            | Self::Conflict { message, .. }
+            | Self::InUse { message, .. }
+            | Self::Guarded { message }
+            | Self::InvalidToken { message }
+            | Self::ExpiredToken { message }
+            | Self::UnsafeEntryPath { message }
+            | Self::OutsideBase { message, .. }
+            | Self::NotARepository { message }
+            | Self::NoFileManager { message }
+            | Self::NoHandler { message }
+            | Self::DisallowedScheme { message }
+            | Self::ClipboardUnavailable { message }
+            | Self::ClipboardEmpty { message }
+            | Self::Io { message }
+            | Self::Failed { message }
+            | Self::CaseCollisions { message, .. } => message,
+        }
+    }
+
+    /// Keeps the kind of an I/O error while naming the path it happened on.
+    pub(crate) fn at_path(path: &Path, error: io::Error) -> Self {
+        let message = format!("{}: {error}", path.display());
+        Self::from(io::Error::new(error.kind(), message))
+    }
+}
+
+impl fmt::Display for LatticeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.message())
+    }
+}
+
+impl std::error::Error for LatticeError {}
+
+impl From<io::Error> for LatticeError {
+    fn from(error: io::Error) -> Self {
+        let message = error.to_string();
+        match error.kind() {
+            io::ErrorKind::NotFound => Self::NotFound { message },
+            io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem => Self::PermissionDenied { message },
+            io::ErrorKind::AlreadyExists => Self::AlreadyExists { message },
+            io::ErrorKind::InvalidInput | io::ErrorKind::InvalidFilename => Self::InvalidInput { message },
+            io::ErrorKind::Unsupported => Self::Unsupported { message },
+            io::ErrorKind::CrossesDevices => Self::CrossDevice { message },
+            _ => Self::Io { message },
+        }
+    }
+}
+
+/// Helpers that still report plain strings surface as `Failed`.
+impl From<String> for LatticeError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+impl From<&str> for LatticeError {
+    fn from(message: &str) -> Self {
+        Self::Failed {
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_errors_map_to_their_kind_and_serialize_with_a_stable_shape() {
+        let missing = fs_error(io::ErrorKind::NotFound);
+        assert!(matches!(missing, LatticeError::NotFound { .. }));
+        assert!(matches!(fs_error(io::ErrorKind::PermissionDenied), LatticeError::PermissionDenied { .. }));
+        assert!(matches!(fs_error(io::ErrorKind::AlreadyExists), LatticeError::AlreadyExists { .. }));
+        assert!(matches!(fs_error(io::ErrorKind::CrossesDevices), LatticeError::CrossDevice { .. }));
+        assert!(matches!(fs_error(io::ErrorKind::Interrupted), LatticeError::Io { .. }));
+
+        assert_eq!(
+            serde_json::to_value(&missing).unwrap(),
+            serde_json::json!({ "kind": "notFound", "message": "gone" })
+        );
+        assert_eq!(
+            serde_json::to_value(LatticeError::from("store unavailable")).unwrap(),
+            serde_json::json!({ "kind": "failed", "message": "store unavailable" })
+        );
+    }
+
+    fn fs_error(kind: io::ErrorKind) -> LatticeError {
+        io::Error::new(kind, "gone").into()
+    }
+}
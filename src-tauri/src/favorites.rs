@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::paths::resolve_command_path;
+use crate::{build_app_settings_from_store, save_app_settings};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FavoriteFolder {
+    pub path: String,
+    /// Favorites are never pruned, so a network drive that is offline just reports `false`.
+    pub exists: bool,
+}
+
+/// Trims and de-duplicates while keeping the user's order.
+pub(crate) fn normalize_favorite_folders(paths: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(paths.len());
+    for path in paths {
+        let trimmed = path.trim();
+        if !trimmed.is_empty() && !normalized.iter().any(|existing| existing == trimmed) {
+            normalized.push(trimmed.to_string());
+        }
+    }
+    normalized
+}
+
+fn with_existence(paths: Vec<String>) -> Vec<FavoriteFolder> {
+    paths
+        .into_iter()
+        .map(|path| FavoriteFolder {
+            exists: Path::new(&path).is_dir(),
+            path,
+        })
+        .collect()
+}
+
+/// `order` must name every current favorite exactly once.
+fn reordered(current: &[String], order: Vec<String>) -> Result<Vec<String>, String> {
+    let order: Vec<String> = order.into_iter().map(|path| path.trim().to_string()).collect();
+    let requested: HashSet<&str> = order.iter().map(String::as_str).collect();
+    let existing: HashSet<&str> = current.iter().map(String::as_str).collect();
+    if requested.len() != order.len() || requested != existing {
+        return Err("New order must contain exactly the current favorite folders.".to_string());
+    }
+    Ok(order)
+}
+
+fn required_folder(folder: &str) -> Result<&str, String> {
+    let trimmed = folder.trim();
+    if trimmed.is_empty() {
+        return Err("Folder path is required.".to_string());
+    }
+    Ok(trimmed)
+}
+
+#[tauri::command]
+pub fn get_favorite_folders(app: AppHandle) -> Result<Vec<FavoriteFolder>, LatticeError> {
+    log_command("get_favorite_folders", || Ok(with_existence(build_app_settings_from_store(&app)?.favorite_folders)))
+}
+
+#[tauri::command]
+pub fn add_favorite_folder(app: AppHandle, folder: String) -> Result<Vec<FavoriteFolder>, LatticeError> {
+    log_command("add_favorite_folder", || {
+        let folder = resolve_command_path(&app, required_folder(&folder)?)?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.favorite_folders.push(folder.to_string_lossy().to_string());
+        Ok(with_existence(save_app_settings(&app, settings)?.favorite_folders))
+    })
+}
+
+#[tauri::command]
+pub fn remove_favorite_folder(app: AppHandle, folder: String) -> Result<Vec<FavoriteFolder>, LatticeError> {
+    log_command("remove_favorite_folder", || {
+        let typed = required_folder(&folder)?;
+        // Favorites saved before paths were resolved are stored as typed.
+        let resolved = resolve_command_path(&app, typed)?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings
+            .favorite_folders
+            .retain(|existing| existing != typed && Path::new(existing) != resolved);
+        Ok(with_existence(save_app_settings(&app, settings)?.favorite_folders))
+    })
+}
+
+#[tauri::command]
+pub fn reorder_favorite_folders(app: AppHandle, order: Vec<String>) -> Result<Vec<FavoriteFolder>, LatticeError> {
+    log_command("reorder_favorite_folders", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.favorite_folders = reordered(&settings.favorite_folders, order)?;
+        Ok(with_existence(save_app_settings(&app, settings)?.favorite_folders))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reordering_requires_exactly_the_current_favorites() {
+        let current = normalize_favorite_folders(vec![
+            " /work ".to_string(),
+            "/notes".to_string(),
+            "/work".to_string(),
+            "".to_string(),
+        ]);
+        assert_eq!(current, vec!["/work", "/notes"]);
+
+        assert_eq!(
+            reordered(&current, vec!["/notes".to_string(), "/work".to_string()]).unwrap(),
+            vec!["/notes", "/work"]
+        );
+        assert!(reordered(&current, vec!["/notes".to_string()]).is_err());
+        assert!(reordered(&current, vec!["/notes".to_string(), "/notes".to_string()]).is_err());
+        assert!(reordered(&current, vec!["/notes".to_string(), "/work".to_string(), "/other".to_string()]).is_err());
+    }
+}
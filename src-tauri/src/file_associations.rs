@@ -0,0 +1,305 @@
+use std::process::Command;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::{build_app_settings_from_store, save_app_settings};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DefaultHandlerRequest {
+    /// Lattice is now the default for the extension.
+    Applied,
+    /// The OS only lets the user make this choice; its settings were opened where it has any.
+    #[cfg_attr(all(unix, not(target_os = "macos")), allow(dead_code))]
+    RequiresUserAction { message: String },
+}
+
+fn normalize_extension(extension: &str) -> Result<String, LatticeError> {
+    let extension = extension.trim().trim_start_matches('.').to_lowercase();
+    let valid = |character: char| character.is_ascii_alphanumeric() || "-_+".contains(character);
+    if extension.is_empty() || !extension.chars().all(valid) {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Not a file extension: {extension:?}"),
+        });
+    }
+    Ok(extension)
+}
+
+/// Only Linux needs this, since `xdg-mime` associates MIME types rather than extensions.
+#[cfg_attr(not(all(unix, not(target_os = "macos"))), allow(dead_code))]
+fn mime_type(extension: &str) -> Option<&'static str> {
+    Some(match extension {
+        "md" | "markdown" | "mdown" => "text/markdown",
+        "txt" => "text/plain",
+        "pdf" => "application/pdf",
+        "ipynb" => "application/x-ipynb+json",
+        "tex" => "text/x-tex",
+        "json" => "application/json",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "py" => "text/x-python",
+        _ => return None,
+    })
+}
+
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn command_output(program: &str, args: &[&str]) -> Result<String, LatticeError> {
+    let output = Command::new(program).args(args).output().map_err(|error| LatticeError::Unsupported {
+        message: format!("Could not run {program}: {error}"),
+    })?;
+    if !output.status.success() {
+        return Err(LatticeError::Failed {
+            message: format!(
+                "{program} exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::env;
+    use std::path::PathBuf;
+
+    use tauri::AppHandle;
+
+    use super::{command_output, mime_type, DefaultHandlerRequest};
+    use crate::error::LatticeError;
+
+    fn linux_mime_type(extension: &str) -> Result<&'static str, LatticeError> {
+        mime_type(extension).ok_or_else(|| LatticeError::Unsupported {
+            message: format!("Don't know the MIME type of .{extension} files"),
+        })
+    }
+
+    /// The packages name the entry after the binary or the product; either may be installed.
+    fn desktop_entry_names(app: &AppHandle) -> Vec<String> {
+        let mut names = Vec::new();
+        let exe = env::current_exe().ok();
+        if let Some(stem) = exe.as_deref().and_then(|exe| exe.file_stem()) {
+            names.push(format!("{}.desktop", stem.to_string_lossy()));
+        }
+        names.push(format!("{}.desktop", app.package_info().name));
+        names.dedup();
+        names
+    }
+
+    fn installed_desktop_entry(app: &AppHandle) -> Option<String> {
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+        let data_dirs = env::var("XDG_DATA_DIRS").unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+        let roots: Vec<PathBuf> = data_home
+            .into_iter()
+            .chain(data_dirs.split(':').filter(|dir| !dir.is_empty()).map(PathBuf::from))
+            .collect();
+
+        desktop_entry_names(app)
+            .into_iter()
+            .find(|name| roots.iter().any(|root| root.join("applications").join(name).is_file()))
+    }
+
+    pub(super) fn is_default(app: &AppHandle, extension: &str) -> Result<bool, LatticeError> {
+        let current = command_output("xdg-mime", &["query", "default", linux_mime_type(extension)?])?;
+        Ok(desktop_entry_names(app).contains(&current))
+    }
+
+    pub(super) fn request(app: &AppHandle, extension: &str) -> Result<DefaultHandlerRequest, LatticeError> {
+        let mime = linux_mime_type(extension)?;
+        let Some(entry) = installed_desktop_entry(app) else {
+            return Err(LatticeError::Unsupported {
+                message: "Lattice has no installed desktop entry; install it from a package to make it a default app."
+                    .to_string(),
+            });
+        };
+        command_output("xdg-mime", &["default", &entry, mime])?;
+        Ok(DefaultHandlerRequest::Applied)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::env;
+
+    use tauri::AppHandle;
+
+    use super::{command_output, DefaultHandlerRequest};
+    use crate::error::LatticeError;
+
+    /// Launch Services has no synchronous query API left, so ask NSWorkspace through JXA.
+    const DEFAULT_APP_SCRIPT: &str = "function run(argv) {
+        ObjC.import('AppKit');
+        ObjC.import('UniformTypeIdentifiers');
+        const type = $.UTType.typeWithFilenameExtension(argv[0]);
+        if (type.isNil()) return '';
+        const url = $.NSWorkspace.sharedWorkspace.URLForApplicationToOpenContentType(type);
+        return url.isNil() ? '' : ObjC.unwrap(url.path);
+    }";
+
+    pub(super) fn is_default(_app: &AppHandle, extension: &str) -> Result<bool, LatticeError> {
+        let exe = env::current_exe()?;
+        let Some(bundle) = exe.ancestors().find(|path| path.extension().is_some_and(|ext| ext == "app")) else {
+            // Not running from an app bundle, e.g. during development.
+            return Ok(false);
+        };
+        let handler = command_output("osascript", &["-l", "JavaScript", "-e", DEFAULT_APP_SCRIPT, extension])?;
+        Ok(!handler.is_empty() && std::path::Path::new(&handler) == bundle)
+    }
+
+    pub(super) fn request(_app: &AppHandle, extension: &str) -> Result<DefaultHandlerRequest, LatticeError> {
+        Ok(DefaultHandlerRequest::RequiresUserAction {
+            message: format!(
+                "In Finder, choose Get Info on a .{extension} file, pick Lattice under \"Open with\" \
+                 and click Change All."
+            ),
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::env;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    use tauri::AppHandle;
+    use tauri_plugin_opener::OpenerExt;
+
+    use super::DefaultHandlerRequest;
+    use crate::error::LatticeError;
+
+    const CREATE_NO_WINDOW_FLAG: u32 = 0x08000000;
+
+    fn prog_id(extension: &str) -> String {
+        format!("Lattice.{extension}")
+    }
+
+    fn reg(args: &[&str]) -> Result<String, LatticeError> {
+        let output = Command::new("reg")
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW_FLAG)
+            .output()?;
+        if !output.status.success() {
+            return Err(LatticeError::Failed {
+                message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Windows stores the user's pick as the `ProgId` value of the extension's `UserChoice` key.
+    pub(super) fn is_default(_app: &AppHandle, extension: &str) -> Result<bool, LatticeError> {
+        let key = format!(r"HKCU\Software\Microsoft\Windows\CurrentVersion\Explorer\FileExts\.{extension}\UserChoice");
+        // A missing key means the user never chose, so nobody is explicitly the default.
+        let Ok(output) = reg(&["query", &key, "/v", "ProgId"]) else {
+            return Ok(false);
+        };
+        let chosen = output.lines().find_map(|line| {
+            let mut words = line.split_whitespace();
+            (words.next() == Some("ProgId")).then(|| words.skip(1).collect::<Vec<_>>().join(" "))
+        });
+        Ok(chosen.is_some_and(|chosen| chosen.eq_ignore_ascii_case(&prog_id(extension))))
+    }
+
+    /// `UserChoice` is hash-protected, so Lattice can only register itself as a candidate
+    /// and send the user to Default apps to pick it.
+    pub(super) fn request(app: &AppHandle, extension: &str) -> Result<DefaultHandlerRequest, LatticeError> {
+        let exe = env::current_exe()?;
+        let prog_id = prog_id(extension);
+        let open_command = format!("\"{}\" \"%1\"", exe.display());
+        reg(&[
+            "add",
+            &format!(r"HKCU\Software\Classes\{prog_id}\shell\open\command"),
+            "/ve",
+            "/d",
+            &open_command,
+            "/f",
+        ])?;
+        reg(&[
+            "add",
+            &format!(r"HKCU\Software\Classes\.{extension}\OpenWithProgids"),
+            "/v",
+            &prog_id,
+            "/d",
+            "",
+            "/f",
+        ])?;
+
+        app.opener()
+            .open_url("ms-settings:defaultapps", None::<&str>)
+            .map_err(|error| error.to_string())?;
+        Ok(DefaultHandlerRequest::RequiresUserAction {
+            message: format!("Choose Lattice for .{extension} files in Settings > Default apps."),
+        })
+    }
+}
+
+/// Opt-ins are remembered even when the OS still needs the user to confirm them.
+fn record_opt_in(app: &AppHandle, extension: &str) -> Result<(), LatticeError> {
+    let mut settings = build_app_settings_from_store(app)?;
+    if !settings.default_handler_extensions.iter().any(|saved| saved == extension) {
+        settings.default_handler_extensions.push(extension.to_string());
+        save_app_settings(app, settings)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn normalize_default_handler_extensions(extensions: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = extensions
+        .iter()
+        .filter_map(|extension| normalize_extension(extension).ok())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+#[tauri::command]
+pub async fn is_default_handler(app: AppHandle, extension: String) -> Result<bool, LatticeError> {
+    log_command_async("is_default_handler", async move {
+        let extension = normalize_extension(&extension)?;
+        tokio::task::spawn_blocking(move || platform::is_default(&app, &extension))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn request_default_handler(app: AppHandle, extension: String) -> Result<DefaultHandlerRequest, LatticeError> {
+    log_command_async("request_default_handler", async move {
+        let extension = normalize_extension(&extension)?;
+        tokio::task::spawn_blocking(move || {
+            let outcome = platform::request(&app, &extension)?;
+            record_opt_in(&app, &extension)?;
+            Ok(outcome)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensions_are_normalized_before_lookup() {
+        assert_eq!(normalize_extension(" .MD ").unwrap(), "md");
+        assert!(normalize_extension("").is_err());
+        assert!(normalize_extension("md; rm -rf").is_err());
+        assert_eq!(mime_type("markdown"), Some("text/markdown"));
+        assert_eq!(mime_type("xyz"), None);
+        assert_eq!(
+            normalize_default_handler_extensions(vec!["txt".into(), ".md".into(), "MD".into(), " ".into()]),
+            ["md", "txt"]
+        );
+    }
+}
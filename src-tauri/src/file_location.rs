@@ -0,0 +1,147 @@
+use std::fs;
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::recent_files::record_recent_file;
+
+const OPEN_FILE_AT_EVENT: &str = "open-file-at";
+/// Past this much of a file the requested line is trusted rather than counted to.
+const MAX_SCAN_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Lines and columns are 1-based; columns are UTF-16 offsets like search hits use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OpenFileAtPayload {
+    path: String,
+    line: u32,
+    column: u32,
+}
+
+fn utf16_len(line: &[u8]) -> u32 {
+    let text = String::from_utf8_lossy(line);
+    text.trim_end_matches(['\n', '\r']).encode_utf16().count() as u32
+}
+
+/// Pulls `line` back to the last line and `column` to just past the end of its line. When the
+/// scan cap is reached first, the line is kept and only a known column is clamped.
+fn clamp_position(reader: &mut impl BufRead, line: u32, column: u32) -> std::io::Result<FilePosition> {
+    let line = line.max(1);
+    let column = column.max(1);
+    let mut buffer = Vec::new();
+    let mut scanned = 0u64;
+    let mut current = 0u32;
+    let mut last_len = 0u32;
+    let mut ends_with_newline = false;
+    loop {
+        buffer.clear();
+        let read = reader.read_until(b'\n', &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        current += 1;
+        last_len = utf16_len(&buffer);
+        ends_with_newline = buffer.ends_with(b"\n");
+        if current == line {
+            return Ok(FilePosition {
+                line,
+                column: column.min(last_len + 1),
+            });
+        }
+        scanned += read as u64;
+        if scanned >= MAX_SCAN_BYTES {
+            return Ok(FilePosition { line, column });
+        }
+    }
+    // A trailing newline starts an empty last line, which editors show.
+    if ends_with_newline || current == 0 {
+        current += 1;
+        last_len = 0;
+    }
+    Ok(FilePosition {
+        line: current,
+        column: column.min(last_len + 1),
+    })
+}
+
+fn resolve_position(path: &Path, line: u32, column: u32) -> Result<FilePosition, LatticeError> {
+    let file = fs::File::open(path).map_err(|error| match error.kind() {
+        ErrorKind::NotFound => LatticeError::NotFound {
+            message: format!("File not found: {}", path.display()),
+        },
+        _ => LatticeError::at_path(path, error),
+    })?;
+    if !file.metadata()?.is_file() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Not a file: {}", path.display()),
+        });
+    }
+    Ok(clamp_position(&mut BufReader::new(file), line, column)?)
+}
+
+/// Asks the calling window's editor to open `path` and scroll to the position, after
+/// clamping it to the file. Returns the position actually sent.
+#[tauri::command]
+pub async fn open_file_at(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    path: String,
+    line: u32,
+    column: Option<u32>,
+) -> Result<FilePosition, LatticeError> {
+    log_command_async("open_file_at", async move {
+        let path = resolve_command_path(&app, &path)?.to_string_lossy().to_string();
+        let target = path.clone();
+        let position =
+            tokio::task::spawn_blocking(move || resolve_position(Path::new(&target), line, column.unwrap_or(1)))
+                .await
+                .map_err(|error| error.to_string())??;
+
+        record_recent_file(&app, &path)?;
+        app.emit_to(
+            window.label(),
+            OPEN_FILE_AT_EVENT,
+            OpenFileAtPayload {
+                path,
+                line: position.line,
+                column: position.column,
+            },
+        )
+        .map_err(|error| error.to_string())?;
+        Ok(position)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clamp(text: &str, line: u32, column: u32) -> (u32, u32) {
+        let position = clamp_position(&mut text.as_bytes(), line, column).unwrap();
+        (position.line, position.column)
+    }
+
+    #[test]
+    fn positions_are_clamped_to_the_file() {
+        let text = "first\r\n格致 lattice\nlast";
+        assert_eq!(clamp(text, 2, 4), (2, 4));
+        assert_eq!(clamp(text, 2, 99), (2, 11));
+        assert_eq!(clamp(text, 0, 0), (1, 1));
+        assert_eq!(clamp(text, 40, 9), (3, 5));
+        assert_eq!(clamp("one\n", 5, 3), (2, 1));
+        assert_eq!(clamp("", 3, 3), (1, 1));
+    }
+}
@@ -0,0 +1,297 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+
+/// Folders are checked file by file, up to this many.
+const MAX_PROBED_FILES: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockHolder {
+    pub pid: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockInfo {
+    pub path: String,
+    /// Another handle holds the file exclusively; on Windows this is what makes deletes fail.
+    pub locked: bool,
+    /// Other processes with the file open. Only Windows and Linux can tell, so empty elsewhere.
+    pub holders: Vec<LockHolder>,
+}
+
+/// Opening with no sharing fails while any other handle is open. The probe handle is
+/// dropped right away, so nothing stays locked.
+#[cfg(windows)]
+fn probe_locked(path: &Path) -> bool {
+    use std::os::windows::fs::OpenOptionsExt;
+    use windows_sys::Win32::Foundation::{ERROR_LOCK_VIOLATION, ERROR_SHARING_VIOLATION};
+
+    match fs::OpenOptions::new().read(true).share_mode(0).open(path) {
+        Ok(_) => false,
+        Err(error) => error
+            .raw_os_error()
+            .is_some_and(|code| code as u32 == ERROR_SHARING_VIOLATION || code as u32 == ERROR_LOCK_VIOLATION),
+    }
+}
+
+/// Unix never refuses to delete an open file; a held exclusive lock is the closest thing.
+#[cfg(not(windows))]
+fn probe_locked(path: &Path) -> bool {
+    let Ok(file) = fs::File::open(path) else {
+        return false;
+    };
+    matches!(file.try_lock(), Err(fs::TryLockError::WouldBlock))
+}
+
+/// Lattice's own handles aren't worth warning about.
+fn is_other_process(holder: &LockHolder) -> bool {
+    holder.pid != std::process::id()
+}
+
+/// Open files of every process we may inspect, read once so a whole tree is cheap to check.
+#[cfg(target_os = "linux")]
+struct OpenFiles {
+    by_path: std::collections::HashMap<std::path::PathBuf, Vec<LockHolder>>,
+}
+
+#[cfg(target_os = "linux")]
+impl OpenFiles {
+    fn scan() -> Self {
+        let mut by_path: std::collections::HashMap<_, Vec<LockHolder>> = std::collections::HashMap::new();
+        for process in fs::read_dir("/proc").into_iter().flatten().flatten() {
+            let Some(pid) = process.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                continue;
+            };
+            // Other users' descriptors aren't readable without privileges, so those are skipped.
+            let Ok(descriptors) = fs::read_dir(process.path().join("fd")) else {
+                continue;
+            };
+            let name = fs::read_to_string(process.path().join("comm")).unwrap_or_default();
+            let holder = LockHolder {
+                pid,
+                name: name.trim().to_string(),
+            };
+            if !is_other_process(&holder) {
+                continue;
+            }
+            for descriptor in descriptors.flatten() {
+                if let Ok(target) = fs::read_link(descriptor.path()) {
+                    let holders = by_path.entry(target).or_default();
+                    if !holders.contains(&holder) {
+                        holders.push(holder.clone());
+                    }
+                }
+            }
+        }
+        Self { by_path }
+    }
+
+    fn holders(&self, path: &Path, _locked: bool) -> Vec<LockHolder> {
+        fs::canonicalize(path)
+            .ok()
+            .and_then(|path| self.by_path.get(&path).cloned())
+            .unwrap_or_default()
+    }
+}
+
+/// Windows asks the Restart Manager per file, and only for files that turned out locked.
+#[cfg(windows)]
+struct OpenFiles;
+
+#[cfg(windows)]
+impl OpenFiles {
+    fn scan() -> Self {
+        Self
+    }
+
+    fn holders(&self, path: &Path, locked: bool) -> Vec<LockHolder> {
+        if !locked {
+            return Vec::new();
+        }
+        restart_manager_holders(path)
+            .into_iter()
+            .filter(is_other_process)
+            .collect()
+    }
+}
+
+#[cfg(windows)]
+fn restart_manager_holders(path: &Path) -> Vec<LockHolder> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{ERROR_MORE_DATA, ERROR_SUCCESS};
+    use windows_sys::Win32::System::RestartManager::{
+        RmEndSession, RmGetList, RmRegisterResources, RmStartSession, CCH_RM_SESSION_KEY, RM_PROCESS_INFO,
+    };
+
+    let wide_path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut session = 0u32;
+    let mut session_key = [0u16; CCH_RM_SESSION_KEY as usize + 1];
+    let mut processes: Vec<RM_PROCESS_INFO> = Vec::new();
+    // SAFETY: every pointer refers to a live local buffer of the length passed alongside it,
+    // and the session is ended before the buffers go away.
+    unsafe {
+        if RmStartSession(&mut session, 0, session_key.as_mut_ptr()) != ERROR_SUCCESS {
+            return Vec::new();
+        }
+        let files = [wide_path.as_ptr()];
+        let registered = RmRegisterResources(session, 1, files.as_ptr(), 0, std::ptr::null(), 0, std::ptr::null());
+        if registered == ERROR_SUCCESS {
+            let (mut needed, mut count, mut reasons) = (0u32, 0u32, 0u32);
+            let sized = RmGetList(session, &mut needed, &mut count, std::ptr::null_mut(), &mut reasons);
+            if sized == ERROR_MORE_DATA {
+                processes = vec![std::mem::zeroed(); needed as usize];
+                count = needed;
+                let listed = RmGetList(session, &mut needed, &mut count, processes.as_mut_ptr(), &mut reasons);
+                processes.truncate(if listed == ERROR_SUCCESS { count as usize } else { 0 });
+            }
+        }
+        RmEndSession(session);
+    }
+
+    processes
+        .iter()
+        .map(|process| {
+            let name = &process.strAppName;
+            let length = name.iter().position(|unit| *unit == 0).unwrap_or(name.len());
+            LockHolder {
+                pid: process.Process.dwProcessId,
+                name: String::from_utf16_lossy(&name[..length]),
+            }
+        })
+        .collect()
+}
+
+/// Without process attribution, only the locked flag is reported.
+#[cfg(not(any(windows, target_os = "linux")))]
+struct OpenFiles;
+
+#[cfg(not(any(windows, target_os = "linux")))]
+impl OpenFiles {
+    fn scan() -> Self {
+        Self
+    }
+
+    fn holders(&self, _path: &Path, _locked: bool) -> Vec<LockHolder> {
+        Vec::new()
+    }
+}
+
+fn collect_in_use(path: &Path, open_files: &OpenFiles, probed: &mut usize, in_use: &mut Vec<LockInfo>) {
+    if *probed >= MAX_PROBED_FILES {
+        return;
+    }
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    if metadata.is_dir() {
+        for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+            collect_in_use(&entry.path(), open_files, probed, in_use);
+        }
+        return;
+    }
+    // Symlinks are deleted as themselves, so what they point at doesn't matter.
+    if !metadata.is_file() {
+        return;
+    }
+
+    *probed += 1;
+    let locked = probe_locked(path);
+    let holders = open_files.holders(path, locked);
+    if locked || !holders.is_empty() {
+        in_use.push(LockInfo {
+            path: path.to_string_lossy().to_string(),
+            locked,
+            holders,
+        });
+    }
+}
+
+/// Files at or under `path` that are locked or open in another process.
+pub(crate) fn files_in_use(path: &Path) -> Vec<LockInfo> {
+    let mut in_use = Vec::new();
+    collect_in_use(path, &OpenFiles::scan(), &mut 0, &mut in_use);
+    in_use
+}
+
+/// Folds the files under `path` into one answer for it.
+pub(crate) fn check_path_locked_sync(path: &Path) -> Result<LockInfo, LatticeError> {
+    fs::symlink_metadata(path).map_err(|error| LatticeError::at_path(path, error))?;
+    let mut info = LockInfo {
+        path: path.to_string_lossy().to_string(),
+        ..LockInfo::default()
+    };
+    for file in files_in_use(path) {
+        info.locked |= file.locked;
+        for holder in file.holders {
+            if !info.holders.contains(&holder) {
+                info.holders.push(holder);
+            }
+        }
+    }
+    Ok(info)
+}
+
+/// e.g. "WINWORD.EXE (4120), explorer.exe (880)".
+pub(crate) fn describe_holders(holders: &[LockHolder]) -> String {
+    if holders.is_empty() {
+        return "another program".to_string();
+    }
+    holders
+        .iter()
+        .map(|holder| format!("{} ({})", holder.name, holder.pid))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[tauri::command]
+pub async fn check_path_locked(app: AppHandle, path: String) -> Result<LockInfo, LatticeError> {
+    log_command_async("check_path_locked", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || check_path_locked_sync(&path))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn probing_reports_held_locks_without_keeping_one() {
+        let root = std::env::temp_dir().join(format!("lattice-file-locks-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes")).unwrap();
+        let note = root.join("notes/a.md");
+        fs::write(&note, "alpha").unwrap();
+
+        let info = check_path_locked_sync(&root).unwrap();
+        assert!(!info.locked);
+        // The probe's own lock must be gone once it returns.
+        fs::File::open(&note).unwrap().try_lock().unwrap();
+
+        let holder = fs::File::open(&note).unwrap();
+        holder.lock().unwrap();
+        let info = check_path_locked_sync(&root).unwrap();
+        assert!(info.locked);
+        assert_eq!(files_in_use(&root)[0].path, note.to_string_lossy());
+        drop(holder);
+        assert!(!check_path_locked_sync(&note).unwrap().locked);
+
+        let missing = check_path_locked_sync(&root.join("missing.md")).unwrap_err();
+        assert!(matches!(missing, LatticeError::NotFound { .. }));
+        assert_eq!(describe_holders(&[]), "another program");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex as StdMutex;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::LatticeError;
+use crate::frontmatter::frontmatter_end;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::search::{looks_binary, BINARY_SNIFF_BYTES};
+use crate::DesktopFsState;
+
+/// Only the head of each file is read; a preview line further in than this is not worth
+/// finding.
+const PREVIEW_READ_BYTES: u64 = 64 * 1024;
+const PREVIEW_WORKERS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePreview {
+    pub path: String,
+    pub preview: Option<String>,
+    pub error: Option<String>,
+}
+
+struct CachedPreview {
+    modified: Option<SystemTime>,
+    len: u64,
+    preview: Option<String>,
+}
+
+/// Keyed by canonical path and `max_chars`; entries are trusted while the file's size and
+/// mtime are unchanged.
+#[derive(Default)]
+pub struct FilePreviewState {
+    cache: StdMutex<HashMap<(PathBuf, usize), CachedPreview>>,
+}
+
+/// The line with its heading markers and surrounding whitespace removed, if it says
+/// anything. Lines without a letter or digit (rules, code fences, a lone `#`) don't.
+fn meaningful_line(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let line = match line.trim_start_matches('#') {
+        rest if rest.len() < line.len() && (rest.is_empty() || rest.starts_with(char::is_whitespace)) => rest.trim(),
+        _ => line,
+    };
+    line.chars().any(char::is_alphanumeric).then_some(line)
+}
+
+/// The first meaningful line after any frontmatter, cut to `max_chars` characters.
+/// `None` for binary files and files with nothing to show.
+fn preview_text(head: &[u8], max_chars: usize) -> Option<String> {
+    if looks_binary(&head[..head.len().min(BINARY_SNIFF_BYTES)]) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(head);
+    let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+    let body = &text[frontmatter_end(text).unwrap_or(0)..];
+    let line = body.lines().find_map(meaningful_line)?;
+    Some(line.chars().take(max_chars).collect())
+}
+
+fn read_preview(path: &Path, max_chars: usize) -> std::io::Result<Option<String>> {
+    let mut head = Vec::new();
+    fs::File::open(path)?.take(PREVIEW_READ_BYTES).read_to_end(&mut head)?;
+    Ok(preview_text(&head, max_chars))
+}
+
+fn preview_for(app: &AppHandle, path: &str, max_chars: usize) -> Result<Option<String>, LatticeError> {
+    let file = fs::canonicalize(resolve_command_path(app, path)?)?;
+    let metadata = fs::metadata(&file)?;
+    if !metadata.is_file() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Not a file: {}", file.display()),
+        });
+    }
+
+    let modified = metadata.modified().ok();
+    let key = (file.clone(), max_chars);
+    let state = app.state::<FilePreviewState>();
+    let cached = state.cache.lock().ok().and_then(|cache| {
+        cache
+            .get(&key)
+            .filter(|cached| modified.is_some() && cached.modified == modified && cached.len == metadata.len())
+            .map(|cached| cached.preview.clone())
+    });
+    if let Some(preview) = cached {
+        return Ok(preview);
+    }
+
+    let preview = read_preview(&file, max_chars).map_err(|error| LatticeError::at_path(&file, error))?;
+    if let Ok(mut cache) = state.cache.lock() {
+        cache.insert(
+            key,
+            CachedPreview {
+                modified,
+                len: metadata.len(),
+                preview: preview.clone(),
+            },
+        );
+    }
+    Ok(preview)
+}
+
+/// Previews every path on a small pool of worker threads, keeping results in input order.
+fn preview_batch(app: &AppHandle, paths: &[String], max_chars: usize) -> Vec<FilePreview> {
+    let results: Vec<StdMutex<Option<FilePreview>>> = paths.iter().map(|_| StdMutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    let workers = PREVIEW_WORKERS.min(paths.len());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                let (preview, error) = match preview_for(app, path, max_chars) {
+                    Ok(preview) => (preview, None),
+                    Err(error) => (None, Some(error.message().to_string())),
+                };
+                if let Ok(mut slot) = results[index].lock() {
+                    *slot = Some(FilePreview {
+                        path: path.clone(),
+                        preview,
+                        error,
+                    });
+                }
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .zip(paths)
+        .map(|(slot, path)| {
+            slot.into_inner().ok().flatten().unwrap_or_else(|| FilePreview {
+                path: path.clone(),
+                preview: None,
+                error: Some("Preview worker failed.".to_string()),
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn file_preview(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+    max_chars: usize,
+) -> Result<Option<String>, LatticeError> {
+    log_command_async("file_preview", async move {
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            preview_for(&app, &path, max_chars)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// One entry per path, in order; a path that can't be read gets an `error` instead of
+/// failing the batch.
+#[tauri::command]
+pub async fn batch_preview(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    paths: Vec<String>,
+    max_chars: usize,
+) -> Result<Vec<FilePreview>, LatticeError> {
+    log_command_async("batch_preview", async move {
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            preview_batch(&app, &paths, max_chars)
+        })
+        .await
+        .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn previews_skip_frontmatter_and_heading_markers() {
+        let note = "---\ntitle: Plan\n---\n\n## Weekly plan  \nBody";
+        assert_eq!(preview_text(note.as_bytes(), 80).as_deref(), Some("Weekly plan"));
+        assert_eq!(preview_text("\u{feff}# \n---\n#\n#hashtag idea".as_bytes(), 80).as_deref(), Some("#hashtag idea"));
+        assert_eq!(preview_text("# Título largo".as_bytes(), 5).as_deref(), Some("Títul"));
+        assert_eq!(preview_text(b"+++\nunclosed = true\n", 80).as_deref(), Some("unclosed = true"));
+        assert_eq!(preview_text(b"  \n\n", 80), None);
+        assert_eq!(preview_text(b"PK\x03\x04\0\0data", 80), None);
+    }
+}
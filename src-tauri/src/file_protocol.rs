@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::file_type::detect_file_type_sync;
+use crate::paths::{normalize_lexically, without_verbatim_prefix};
+use crate::{
+    build_app_settings_from_store, build_preview_file_response, decode_preview_request_path, is_path_within_root,
+    preview_error_response,
+};
+
+pub(crate) const FILE_PROTOCOL: &str = "lattice";
+const FILE_HOST: &str = "file";
+
+/// The encoded path of `lattice://file/<path>`. Windows webviews only reach custom schemes as
+/// `http://lattice.localhost/...`, so there the `file` segment leads the path instead.
+fn encoded_file_path(uri: &http::Uri) -> Option<&str> {
+    if uri.host() == Some(FILE_HOST) {
+        return Some(uri.path());
+    }
+    uri.path()
+        .strip_prefix('/')?
+        .strip_prefix(FILE_HOST)
+        .filter(|rest| rest.starts_with('/'))
+}
+
+/// Grants are canonical, which on Windows adds `\\?\`; requested paths may lack it.
+fn is_within_grants(path: &Path, granted_folders: &[String]) -> bool {
+    let path = without_verbatim_prefix(path);
+    granted_folders
+        .iter()
+        .any(|folder| is_path_within_root(&path, &without_verbatim_prefix(Path::new(folder))))
+}
+
+/// Only files inside a granted folder are served, so the scheme can't read arbitrary files.
+/// The grants are checked before the disk is touched, so every path outside them gets the
+/// same refusal whether or not it exists.
+fn resolve_granted_file(uri: &http::Uri, granted_folders: &[String]) -> Result<PathBuf, LatticeError> {
+    let encoded = encoded_file_path(uri).ok_or_else(|| LatticeError::InvalidInput {
+        message: format!("Expected {FILE_PROTOCOL}://{FILE_HOST}/<path>: {uri}"),
+    })?;
+    let raw_path = decode_preview_request_path(encoded).map_err(|message| LatticeError::InvalidInput { message })?;
+    let outside = || LatticeError::OutsideScope {
+        message: format!("Path is outside the granted folders: {raw_path}"),
+    };
+    let requested = normalize_lexically(Path::new(&raw_path));
+    if !is_within_grants(&requested, granted_folders) {
+        return Err(outside());
+    }
+    let path = fs::canonicalize(&requested)?;
+    // A symlink inside a granted folder can still lead out of it.
+    if !is_within_grants(&path, granted_folders) {
+        return Err(outside());
+    }
+    if !path.is_file() {
+        return Err(LatticeError::NotFound {
+            message: format!("Not a file: {raw_path}"),
+        });
+    }
+    Ok(path)
+}
+
+fn error_status(error: &LatticeError) -> http::StatusCode {
+    match error {
+        LatticeError::NotFound { .. } => http::StatusCode::NOT_FOUND,
+        LatticeError::OutsideScope { .. } | LatticeError::PermissionDenied { .. } => http::StatusCode::FORBIDDEN,
+        LatticeError::InvalidInput { .. } => http::StatusCode::BAD_REQUEST,
+        _ => http::StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub(crate) fn handle_file_request(app: &AppHandle, request: &http::Request<Vec<u8>>) -> http::Response<Vec<u8>> {
+    let result = build_app_settings_from_store(app)
+        .map_err(LatticeError::from)
+        .and_then(|settings| resolve_granted_file(request.uri(), &settings.granted_folders))
+        .and_then(|path| {
+            let content_type = detect_file_type_sync(&path)?.mime;
+            Ok(build_preview_file_response(&path, &content_type, request)?)
+        });
+
+    match result {
+        Ok(response) => response,
+        Err(error) => preview_error_response(error_status(&error), error.message()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_only_files_inside_granted_folders() {
+        let root = fs::canonicalize(std::env::temp_dir())
+            .unwrap()
+            .join(format!("lattice-file-protocol-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("granted")).unwrap();
+        fs::write(root.join("granted/clip.mp4"), "video").unwrap();
+        fs::write(root.join("secret.txt"), "secret").unwrap();
+        let granted = vec![root.join("granted").to_string_lossy().to_string()];
+        let uri = |path: &Path| -> http::Uri {
+            let path = path.to_string_lossy();
+            let encoded = percent_encoding::utf8_percent_encode(&path, percent_encoding::NON_ALPHANUMERIC);
+            format!("lattice://file/{encoded}").parse().unwrap()
+        };
+
+        let served = resolve_granted_file(&uri(&root.join("granted/clip.mp4")), &granted).unwrap();
+        assert_eq!(served, root.join("granted/clip.mp4"));
+        let escaped = resolve_granted_file(&uri(&root.join("granted/../secret.txt")), &granted).unwrap_err();
+        assert_eq!(error_status(&escaped), http::StatusCode::FORBIDDEN);
+        let missing = resolve_granted_file(&uri(&root.join("granted/missing.mp4")), &granted).unwrap_err();
+        assert_eq!(error_status(&missing), http::StatusCode::NOT_FOUND);
+        let missing_outside = resolve_granted_file(&uri(&root.join("missing.txt")), &granted).unwrap_err();
+        assert_eq!(error_status(&missing_outside), http::StatusCode::FORBIDDEN);
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("secret.txt"), root.join("granted/link.txt")).unwrap();
+            let linked = resolve_granted_file(&uri(&root.join("granted/link.txt")), &granted).unwrap_err();
+            assert_eq!(error_status(&linked), http::StatusCode::FORBIDDEN);
+        }
+
+        let windows_style: http::Uri = "http://lattice.localhost/file/%2Ftmp%2Fa.md".parse().unwrap();
+        assert_eq!(encoded_file_path(&windows_style), Some("/%2Ftmp%2Fa.md"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
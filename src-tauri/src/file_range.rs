@@ -0,0 +1,237 @@
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+/// Upper bound for a single chunk, whatever the caller asks for.
+const MAX_CHUNK_BYTES: usize = 16 * 1024 * 1024;
+const TAIL_BLOCK_BYTES: u64 = 64 * 1024;
+
+/// Bytes `start..end` of a file. A multibyte character split by either boundary is
+/// left out of `text` and returned raw, so consecutive chunks can be stitched by
+/// joining one chunk's `trailing_bytes` with the next chunk's `leading_bytes`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChunk {
+    pub start: u64,
+    pub end: u64,
+    pub total_size: u64,
+    pub text: String,
+    pub leading_bytes: Vec<u8>,
+    pub trailing_bytes: Vec<u8>,
+}
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+/// Length of the incomplete character at the end of `bytes`, if any.
+fn trailing_partial_len(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if is_continuation(byte) {
+            continue;
+        }
+        let needed = match byte {
+            0xF0.. => 4,
+            0xE0.. => 3,
+            0xC0.. => 2,
+            _ => 1,
+        };
+        return if back < needed { back } else { 0 };
+    }
+    0
+}
+
+fn build_chunk(bytes: Vec<u8>, start: u64, total_size: u64) -> FileChunk {
+    // Only a chunk starting mid-file can begin inside a character.
+    let leading = if start > 0 {
+        bytes.iter().take(3).take_while(|byte| is_continuation(**byte)).count()
+    } else {
+        0
+    };
+    let trailing = trailing_partial_len(&bytes[leading..]);
+    let body_end = bytes.len() - trailing;
+
+    FileChunk {
+        start,
+        end: start + bytes.len() as u64,
+        total_size,
+        text: String::from_utf8_lossy(&bytes[leading..body_end]).to_string(),
+        leading_bytes: bytes[..leading].to_vec(),
+        trailing_bytes: bytes[body_end..].to_vec(),
+    }
+}
+
+fn read_range_sync(path: &Path, start: u64, len: usize) -> Result<FileChunk, LatticeError> {
+    let mut file = fs::File::open(path)?;
+    let total_size = file.metadata()?.len();
+    let start = start.min(total_size);
+    let len = (len.min(MAX_CHUNK_BYTES) as u64).min(total_size - start);
+
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::with_capacity(len as usize);
+    file.take(len)
+        .read_to_end(&mut bytes)?;
+    Ok(build_chunk(bytes, start, total_size))
+}
+
+/// Walks back from the end one block at a time until `lines` line breaks are found.
+fn tail_start(file: &mut fs::File, total_size: u64, lines: usize) -> Result<u64, LatticeError> {
+    let floor = total_size.saturating_sub(MAX_CHUNK_BYTES as u64);
+    let mut end = total_size;
+    let mut newlines = 0;
+    let mut skip_final_newline = true;
+    let mut block = vec![0; TAIL_BLOCK_BYTES as usize];
+
+    while end > floor {
+        let block_start = end.saturating_sub(TAIL_BLOCK_BYTES).max(floor);
+        let block = &mut block[..(end - block_start) as usize];
+        file.seek(SeekFrom::Start(block_start))?;
+        file.read_exact(block)?;
+
+        for (offset, byte) in block.iter().enumerate().rev() {
+            if *byte != b'\n' {
+                skip_final_newline = false;
+                continue;
+            }
+            // A newline ending the file terminates the last line rather than starting one.
+            if skip_final_newline {
+                skip_final_newline = false;
+                continue;
+            }
+            newlines += 1;
+            if newlines == lines {
+                return Ok(block_start + offset as u64 + 1);
+            }
+        }
+        end = block_start;
+    }
+    Ok(floor)
+}
+
+fn read_tail_sync(path: &Path, lines: usize) -> Result<FileChunk, LatticeError> {
+    let mut file = fs::File::open(path)?;
+    let total_size = file.metadata()?.len();
+    if lines == 0 {
+        return Ok(build_chunk(Vec::new(), total_size, total_size));
+    }
+    let start = tail_start(&mut file, total_size, lines)?;
+    read_range_sync(path, start, (total_size - start) as usize)
+}
+
+#[tauri::command]
+pub async fn read_file_range(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+    start_byte: u64,
+    len: usize,
+) -> Result<FileChunk, LatticeError> {
+    log_command_async("read_file_range", async move {
+        let path = resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            read_range_sync(&path, start_byte, len)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn read_file_tail(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+    lines: usize,
+) -> Result<FileChunk, LatticeError> {
+    log_command_async("read_file_tail", async move {
+        let path = resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            read_tail_sync(&path, lines)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let root = std::env::temp_dir().join(format!("lattice-file-range-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn ranges_split_multibyte_characters_into_stitchable_bytes() {
+        let path = fixture("utf8.txt", "aé€b".as_bytes());
+
+        // "a" + first byte of "é".
+        let first = read_range_sync(&path, 0, 2).unwrap();
+        assert_eq!((first.start, first.end, first.total_size), (0, 2, 7));
+        assert_eq!(first.text, "a");
+        assert_eq!(first.trailing_bytes, vec![0xC3]);
+
+        // Second byte of "é" + first two bytes of "€".
+        let second = read_range_sync(&path, 2, 3).unwrap();
+        assert_eq!(second.leading_bytes, vec![0xA9]);
+        assert_eq!(second.text, "");
+        assert_eq!(second.trailing_bytes, vec![0xE2, 0x82]);
+
+        let third = read_range_sync(&path, 5, 100).unwrap();
+        assert_eq!((third.end, third.text.as_str()), (7, "b"));
+        assert_eq!(third.leading_bytes, vec![0xAC]);
+
+        let stitched = [first.trailing_bytes, second.leading_bytes].concat();
+        assert_eq!(String::from_utf8(stitched).unwrap(), "é");
+        assert_eq!(read_range_sync(&path, 50, 10).unwrap().end, 7);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn tail_returns_the_last_lines_across_block_boundaries() {
+        let contents: String = (0..20_000).map(|index| format!("line {index}\n")).collect();
+        let path = fixture("app.log", contents.as_bytes());
+
+        let tail = read_tail_sync(&path, 3).unwrap();
+        assert_eq!(tail.text, "line 19997\nline 19998\nline 19999\n");
+        assert_eq!(tail.end, tail.total_size);
+
+        let everything = read_tail_sync(&path, 50_000).unwrap();
+        assert_eq!((everything.start, everything.text.len()), (0, contents.len()));
+        assert_eq!(read_tail_sync(&path, 0).unwrap().text, "");
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,180 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+
+/// Style is judged from the start of the file; huge files don't need a full read.
+const SAMPLE_BYTES: u64 = 1024 * 1024;
+const SAMPLE_INDENTED_LINES: usize = 200;
+const INDENT_WIDTHS: [usize; 3] = [2, 4, 8];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LineEndingStyle {
+    Lf,
+    Crlf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndentStyle {
+    Tabs,
+    Spaces,
+}
+
+/// `None` fields mean the file gave no evidence, so the editor's defaults apply.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStyle {
+    pub line_ending: Option<LineEndingStyle>,
+    pub indent: Option<IndentStyle>,
+    /// Only inferred for space indentation.
+    pub indent_width: Option<usize>,
+}
+
+/// Picks the most frequent value; on a tie, the one seen first wins.
+fn dominant<T: Copy + PartialEq>(seen: &[T]) -> Option<T> {
+    let mut counts: Vec<(T, usize)> = Vec::new();
+    for value in seen {
+        match counts.iter_mut().find(|(existing, _)| existing == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((*value, 1)),
+        }
+    }
+    counts
+        .iter()
+        .fold(None, |best: Option<(T, usize)>, &(value, count)| match best {
+            Some((_, best_count)) if best_count >= count => best,
+            _ => Some((value, count)),
+        })
+        .map(|(value, _)| value)
+}
+
+pub(crate) fn detect_line_ending(text: &str) -> Option<LineEndingStyle> {
+    let endings: Vec<LineEndingStyle> = text
+        .match_indices('\n')
+        .map(|(index, _)| {
+            if text[..index].ends_with('\r') {
+                LineEndingStyle::Crlf
+            } else {
+                LineEndingStyle::Lf
+            }
+        })
+        .collect();
+    dominant(&endings)
+}
+
+/// Width comes from the step between consecutive indentation levels rather than the
+/// absolute depth, so deeply nested lines don't skew it.
+fn detect_indent(text: &str) -> (Option<IndentStyle>, Option<usize>) {
+    let mut styles = Vec::new();
+    let mut widths = Vec::new();
+    let mut previous_spaces = 0;
+
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let spaces = line.len() - line.trim_start_matches(' ').len();
+        if line.starts_with('\t') {
+            styles.push(IndentStyle::Tabs);
+        } else if spaces > 0 {
+            styles.push(IndentStyle::Spaces);
+            let step = spaces.abs_diff(previous_spaces);
+            if INDENT_WIDTHS.contains(&step) {
+                widths.push(step);
+            }
+        }
+        if !line.starts_with('\t') {
+            previous_spaces = spaces;
+        }
+        if styles.len() >= SAMPLE_INDENTED_LINES {
+            break;
+        }
+    }
+
+    let style = dominant(&styles);
+    let width = match style {
+        Some(IndentStyle::Spaces) => dominant(&widths),
+        _ => None,
+    };
+    (style, width)
+}
+
+fn detect_style(text: &str) -> FileStyle {
+    let (indent, indent_width) = detect_indent(text);
+    FileStyle {
+        line_ending: detect_line_ending(text),
+        indent,
+        indent_width,
+    }
+}
+
+/// Rewrites every line break as `line_ending`, for writers preserving a file's style.
+pub(crate) fn apply_line_ending(text: &str, line_ending: LineEndingStyle) -> String {
+    let normalized = text.replace("\r\n", "\n");
+    match line_ending {
+        LineEndingStyle::Lf => normalized,
+        LineEndingStyle::Crlf => normalized.replace('\n', "\r\n"),
+    }
+}
+
+fn detect_file_style_sync(path: &Path) -> Result<FileStyle, LatticeError> {
+    let file = fs::File::open(path)?;
+    let mut sample = Vec::new();
+    file.take(SAMPLE_BYTES)
+        .read_to_end(&mut sample)?;
+    Ok(detect_style(&String::from_utf8_lossy(&sample)))
+}
+
+#[tauri::command]
+pub async fn detect_file_style(app: AppHandle, path: String) -> Result<FileStyle, LatticeError> {
+    log_command_async("detect_file_style", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || detect_file_style_sync(&path))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_dominant_line_endings_and_indentation() {
+        let style = detect_style("fn main() {\r\n    if x {\r\n        y();\r\n    }\r\n}\n");
+        assert_eq!(
+            style,
+            FileStyle {
+                line_ending: Some(LineEndingStyle::Crlf),
+                indent: Some(IndentStyle::Spaces),
+                indent_width: Some(4),
+            }
+        );
+
+        let tabs = detect_style("a:\n\tb\n\tc\n  d\n");
+        assert_eq!((tabs.indent, tabs.indent_width), (Some(IndentStyle::Tabs), None));
+
+        // One LF and one CRLF: the first one seen wins.
+        assert_eq!(detect_style("a\nb\r\n").line_ending, Some(LineEndingStyle::Lf));
+        assert_eq!(
+            detect_style(""),
+            FileStyle {
+                line_ending: None,
+                indent: None,
+                indent_width: None,
+            }
+        );
+    }
+
+    #[test]
+    fn line_endings_are_re_emitted_in_the_chosen_style() {
+        assert_eq!(apply_line_ending("a\r\nb\nc", LineEndingStyle::Crlf), "a\r\nb\r\nc");
+        assert_eq!(apply_line_ending("a\r\nb\nc", LineEndingStyle::Lf), "a\nb\nc");
+    }
+}
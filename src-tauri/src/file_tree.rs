@@ -0,0 +1,500 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::error::LatticeError;
+use crate::ignore_rules::{matcher_for, IgnoreMatcher};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileNode {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+    pub size: u64,
+    /// Unix timestamp in milliseconds.
+    pub modified: Option<u64>,
+    pub depth: u32,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TreeRenderOpts {
+    pub include_sizes: bool,
+    pub dirs_first: bool,
+    pub include_hidden: bool,
+    /// Entries below the root to print before the rest are summarised in a note.
+    pub max_entries: Option<usize>,
+}
+
+const DEFAULT_RENDER_DEPTH: u32 = 16;
+const DEFAULT_RENDER_ENTRIES: usize = 1000;
+
+struct PendingDirectory {
+    path: PathBuf,
+    depth: u32,
+    node_index: Option<usize>,
+}
+
+pub(crate) fn is_hidden_name(name: &str) -> bool {
+    name.starts_with('.')
+}
+
+fn modified_ms(metadata: &fs::Metadata) -> Option<u64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+}
+
+fn build_file_node(path: &Path, name: String, depth: u32) -> FileNode {
+    let link_metadata = fs::symlink_metadata(path);
+    let is_symlink = link_metadata
+        .as_ref()
+        .map(|metadata| metadata.file_type().is_symlink())
+        .unwrap_or(false);
+
+    // Follow symlinks for size/type, but keep broken links visible with their own metadata.
+    match fs::metadata(path).or(link_metadata) {
+        Ok(metadata) => FileNode {
+            name,
+            path: path.to_string_lossy().to_string(),
+            is_dir: metadata.is_dir(),
+            is_symlink,
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified: modified_ms(&metadata),
+            depth,
+            error: None,
+        },
+        Err(error) => FileNode {
+            name,
+            path: path.to_string_lossy().to_string(),
+            is_dir: false,
+            is_symlink,
+            size: 0,
+            modified: None,
+            depth,
+            error: Some(error.to_string()),
+        },
+    }
+}
+
+/// The node for `entry`, or `None` when it's hidden or ignored.
+fn visible_node(
+    entry: &fs::DirEntry,
+    root: &Path,
+    canonical_root: &Path,
+    depth: u32,
+    include_hidden: bool,
+    ignore: &IgnoreMatcher,
+) -> Option<FileNode> {
+    let name = entry.file_name().to_string_lossy().to_string();
+    if !include_hidden && is_hidden_name(&name) {
+        return None;
+    }
+    let entry_path = entry.path();
+    let node = build_file_node(&entry_path, name, depth);
+    // Ignore rules are keyed by canonical paths; entries keep the caller's spelling.
+    let ignore_path = canonical_root.join(entry_path.strip_prefix(root).unwrap_or(&entry_path));
+    (!ignore.is_ignored(&ignore_path, node.is_dir)).then_some(node)
+}
+
+pub(crate) fn walk_directory_tree(
+    root: &Path,
+    max_depth: u32,
+    include_hidden: bool,
+    ignore: &IgnoreMatcher,
+) -> Result<Vec<FileNode>, LatticeError> {
+    let canonical_root = fs::canonicalize(root)?;
+    if !canonical_root.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Path is not a directory: {}", root.display()),
+        });
+    }
+
+    let mut results: Vec<FileNode> = Vec::new();
+    let mut visited = HashSet::from([canonical_root.clone()]);
+    let mut queue = VecDeque::from([PendingDirectory {
+        path: root.to_path_buf(),
+        depth: 0,
+        node_index: None,
+    }]);
+
+    while let Some(directory) = queue.pop_front() {
+        let read_result = fs::read_dir(&directory.path).and_then(|entries| entries.collect::<Result<Vec<_>, _>>());
+        let mut entries = match read_result {
+            Ok(entries) => entries,
+            Err(error) => match directory.node_index {
+                Some(index) => {
+                    results[index].error = Some(error.to_string());
+                    continue;
+                }
+                None => return Err(error.into()),
+            },
+        };
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let depth = directory.depth + 1;
+            let Some(mut node) = visible_node(&entry, root, &canonical_root, depth, include_hidden, ignore) else {
+                continue;
+            };
+            let entry_path = entry.path();
+            let should_descend = node.is_dir && node.error.is_none() && node.depth < max_depth;
+            if should_descend {
+                match fs::canonicalize(&entry_path) {
+                    Ok(canonical) => {
+                        if visited.insert(canonical) {
+                            queue.push_back(PendingDirectory {
+                                path: entry_path,
+                                depth: node.depth,
+                                node_index: Some(results.len()),
+                            });
+                        } else {
+                            node.error = Some("Directory already visited (symlink cycle)".to_string());
+                        }
+                    }
+                    Err(error) => {
+                        node.error = Some(error.to_string());
+                    }
+                }
+            }
+
+            results.push(node);
+        }
+    }
+
+    Ok(results)
+}
+
+/// Terminal columns taken by `character`: CJK and most emoji take two, combining marks none.
+fn char_width(character: char) -> usize {
+    match u32::from(character) {
+        0x0300..=0x036F | 0x200B..=0x200F | 0x20D0..=0x20FF | 0xFE00..=0xFE0F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+struct TreeLine {
+    text: String,
+    size: Option<u64>,
+}
+
+/// Walks depth-first in print order, so it can stop as soon as there's one line more than
+/// will be shown instead of reading a huge tree only to truncate it.
+struct TreeWalk<'a> {
+    root: &'a Path,
+    canonical_root: PathBuf,
+    max_depth: u32,
+    opts: &'a TreeRenderOpts,
+    ignore: &'a IgnoreMatcher,
+    visited: HashSet<PathBuf>,
+    lines: Vec<TreeLine>,
+    limit: usize,
+}
+
+impl TreeWalk<'_> {
+    fn children(&self, dir: &Path, depth: u32) -> std::io::Result<Vec<FileNode>> {
+        let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        let mut nodes: Vec<FileNode> = entries
+            .iter()
+            .filter_map(|entry| {
+                visible_node(entry, self.root, &self.canonical_root, depth + 1, self.opts.include_hidden, self.ignore)
+            })
+            .collect();
+        if self.opts.dirs_first {
+            nodes.sort_by_key(|node| !node.is_dir);
+        }
+        Ok(nodes)
+    }
+
+    fn collect(&mut self, nodes: Vec<FileNode>, prefix: &str) {
+        let count = nodes.len();
+        for (index, mut node) in nodes.into_iter().enumerate() {
+            if self.lines.len() >= self.limit {
+                return;
+            }
+            let mut children = None;
+            if node.is_dir && node.error.is_none() && node.depth < self.max_depth {
+                match fs::canonicalize(&node.path) {
+                    Ok(canonical) => {
+                        if !self.visited.insert(canonical) {
+                            node.error = Some("Directory already visited (symlink cycle)".to_string());
+                        } else {
+                            match self.children(Path::new(&node.path), node.depth) {
+                                Ok(nodes) => children = Some(nodes),
+                                Err(error) => node.error = Some(error.to_string()),
+                            }
+                        }
+                    }
+                    Err(error) => node.error = Some(error.to_string()),
+                }
+            }
+
+            let last = index + 1 == count;
+            let mut text = format!("{prefix}{}{}", if last { "└── " } else { "├── " }, node.name);
+            if let Some(error) = &node.error {
+                text.push_str(&format!(" [{error}]"));
+            }
+            self.lines.push(TreeLine {
+                text,
+                size: (!node.is_dir).then_some(node.size),
+            });
+            if let Some(children) = children {
+                let prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+                self.collect(children, &prefix);
+            }
+        }
+    }
+}
+
+fn render_tree_sync(
+    root: &Path,
+    max_depth: u32,
+    opts: &TreeRenderOpts,
+    ignore: &IgnoreMatcher,
+) -> Result<String, LatticeError> {
+    let canonical_root = fs::canonicalize(root)?;
+    if !canonical_root.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Path is not a directory: {}", root.display()),
+        });
+    }
+    let max_entries = opts.max_entries.unwrap_or(DEFAULT_RENDER_ENTRIES);
+    let mut walk = TreeWalk {
+        root,
+        visited: HashSet::from([canonical_root.clone()]),
+        canonical_root,
+        max_depth,
+        opts,
+        ignore,
+        lines: Vec::new(),
+        limit: max_entries.saturating_add(1),
+    };
+    let top = walk.children(root, 0)?;
+    walk.collect(top, "");
+    let mut lines = walk.lines;
+    let truncated = lines.len() > max_entries;
+    lines.truncate(max_entries);
+
+    let root_name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| root.display().to_string());
+    let mut output = vec![root_name];
+    // Sizes line up in one column, which needs the width each name takes on screen.
+    let column = lines.iter().map(|line| display_width(&line.text)).max().unwrap_or(0) + 2;
+    for line in lines {
+        match line.size.filter(|_| opts.include_sizes) {
+            Some(size) => {
+                let padding = " ".repeat(column - display_width(&line.text));
+                output.push(format!("{}{padding}{}", line.text, format_size(size)));
+            }
+            None => output.push(line.text),
+        }
+    }
+    if truncated {
+        output.push("… more entries not shown".to_string());
+    }
+    Ok(output.join("\n"))
+}
+
+#[tauri::command]
+pub async fn list_directory(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+    max_depth: Option<u32>,
+    include_hidden: bool,
+) -> Result<Vec<FileNode>, LatticeError> {
+    log_command_async("list_directory", async move {
+        let path = resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let ignore = matcher_for(&app, &fs::canonicalize(&path)?);
+            walk_directory_tree(&path, max_depth.unwrap_or(1).max(1), include_hidden, &ignore)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Renders `root` as an indented `├──`/`└──` tree for pasting into documents.
+#[tauri::command]
+pub async fn render_tree(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+    max_depth: Option<u32>,
+    opts: TreeRenderOpts,
+) -> Result<String, LatticeError> {
+    log_command_async("render_tree", async move {
+        let root = resolve_command_path(&app, &root)?;
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let ignore = matcher_for(&app, &fs::canonicalize(&root)?);
+            render_tree_sync(&root, max_depth.unwrap_or(DEFAULT_RENDER_DEPTH).max(1), &opts, &ignore)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_fixture_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("lattice-file-tree-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes/archive")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("notes/todo.md"), "- [ ] write").unwrap();
+        fs::write(root.join("notes/archive/old.md"), "old").unwrap();
+        fs::write(root.join("readme.md"), "hello").unwrap();
+        root
+    }
+
+    #[test]
+    fn walks_breadth_first_up_to_max_depth_and_skips_hidden_entries() {
+        let root = create_fixture_root();
+
+        let no_rules = IgnoreMatcher::new(&root, &[]);
+        let one_level = walk_directory_tree(&root, 1, false, &no_rules).unwrap();
+        let names: Vec<&str> = one_level.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(names, vec!["notes", "readme.md"]);
+        assert_eq!(one_level[1].size, 5);
+
+        let two_levels = walk_directory_tree(&root, 2, true, &no_rules).unwrap();
+        let names: Vec<&str> = two_levels.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(names, vec![".git", "notes", "readme.md", "archive", "todo.md"]);
+        assert!(two_levels.iter().all(|node| node.depth <= 2));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn skips_gitignored_and_user_ignored_entries() {
+        let root = create_fixture_root();
+        fs::write(root.join(".gitignore"), "archive/\n").unwrap();
+        fs::write(root.join("notes/scratch.tmp"), "").unwrap();
+
+        let rules = IgnoreMatcher::new(&fs::canonicalize(&root).unwrap(), &["*.tmp".to_string()]);
+        let nodes = walk_directory_tree(&root, 3, false, &rules).unwrap();
+        let names: Vec<&str> = nodes.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(names, vec!["notes", "readme.md", "todo.md"]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn renders_an_aligned_tree_with_sizes_and_a_truncation_note() {
+        let root = create_fixture_root();
+        fs::write(root.join("notes/日本語.md"), "wide").unwrap();
+        let rules = IgnoreMatcher::new(&root, &[]);
+        let opts = TreeRenderOpts {
+            include_sizes: true,
+            dirs_first: true,
+            ..TreeRenderOpts::default()
+        };
+
+        let rendered = render_tree_sync(&root, 8, &opts, &rules).unwrap();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], root.file_name().unwrap().to_string_lossy());
+        assert_eq!(
+            lines[1..],
+            [
+                "├── notes",
+                "│   ├── archive",
+                "│   │   └── old.md  3 B",
+                "│   ├── todo.md     11 B",
+                "│   └── 日本語.md   4 B",
+                "└── readme.md       5 B",
+            ]
+        );
+
+        let truncated = TreeRenderOpts {
+            max_entries: Some(2),
+            ..TreeRenderOpts::default()
+        };
+        let rendered = render_tree_sync(&root, 8, &truncated, &rules).unwrap();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[1..], ["├── notes", "│   ├── archive", "… more entries not shown"]);
+        assert_eq!(format_size(1536), "1.5 KB");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stops_at_symlink_cycles_instead_of_recursing_forever() {
+        let root = create_fixture_root();
+        std::os::unix::fs::symlink(&root, root.join("notes/loop")).unwrap();
+
+        let nodes = walk_directory_tree(&root, 16, false, &IgnoreMatcher::new(&root, &[])).unwrap();
+        let loop_node = nodes.iter().find(|node| node.name == "loop").unwrap();
+        assert!(loop_node.is_symlink);
+        assert!(loop_node.error.is_some());
+        assert!(nodes.len() < 16);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
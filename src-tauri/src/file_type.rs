@@ -0,0 +1,201 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use infer::MatcherType;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::search::looks_binary;
+
+/// Enough for every signature `infer` knows and for a fair look at text content.
+const SNIFF_BYTES: u64 = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileCategory {
+    Image,
+    Text,
+    Audio,
+    Video,
+    Archive,
+    Binary,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DetectedBy {
+    /// A known signature in the first bytes.
+    Magic,
+    /// No signature; the bytes look like text or binary.
+    Content,
+    Extension,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileType {
+    pub mime: String,
+    pub category: FileCategory,
+    pub detected_by: DetectedBy,
+    /// Only set for text; judged on the sampled bytes.
+    pub is_utf8: Option<bool>,
+}
+
+fn category_for(matcher: MatcherType) -> FileCategory {
+    match matcher {
+        MatcherType::Image => FileCategory::Image,
+        MatcherType::Text => FileCategory::Text,
+        MatcherType::Audio => FileCategory::Audio,
+        MatcherType::Video => FileCategory::Video,
+        MatcherType::Archive => FileCategory::Archive,
+        MatcherType::App | MatcherType::Book | MatcherType::Doc | MatcherType::Font | MatcherType::Custom => {
+            FileCategory::Binary
+        }
+    }
+}
+
+/// Only consulted once the content itself gives no signature.
+fn from_extension(path: &Path) -> Option<(&'static str, FileCategory)> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "md" | "markdown" => ("text/markdown", FileCategory::Text),
+        "txt" | "log" => ("text/plain", FileCategory::Text),
+        "json" => ("application/json", FileCategory::Text),
+        "csv" => ("text/csv", FileCategory::Text),
+        "toml" => ("application/toml", FileCategory::Text),
+        "yaml" | "yml" => ("application/yaml", FileCategory::Text),
+        "tex" => ("application/x-tex", FileCategory::Text),
+        "py" => ("text/x-python", FileCategory::Text),
+        "js" | "mjs" => ("text/javascript", FileCategory::Text),
+        "ts" | "tsx" => ("text/typescript", FileCategory::Text),
+        "rs" => ("text/rust", FileCategory::Text),
+        "css" => ("text/css", FileCategory::Text),
+        "svg" => ("image/svg+xml", FileCategory::Image),
+        "ipynb" => ("application/x-ipynb+json", FileCategory::Text),
+        _ => return None,
+    })
+}
+
+/// A sample cut mid-character still counts as UTF-8 when the file goes on past it.
+fn is_utf8(sample: &[u8], truncated: bool) -> bool {
+    match std::str::from_utf8(sample) {
+        Ok(_) => true,
+        Err(error) => truncated && error.error_len().is_none(),
+    }
+}
+
+fn classify(path: &Path, sample: &[u8], truncated: bool) -> FileType {
+    if let Some(kind) = infer::get(sample) {
+        let category = category_for(kind.matcher_type());
+        return FileType {
+            mime: kind.mime_type().to_string(),
+            category,
+            detected_by: DetectedBy::Magic,
+            is_utf8: (category == FileCategory::Text).then(|| is_utf8(sample, truncated)),
+        };
+    }
+
+    let extension = from_extension(path);
+    if sample.is_empty() {
+        return match extension {
+            Some((mime, category)) => FileType {
+                mime: mime.to_string(),
+                category,
+                detected_by: DetectedBy::Extension,
+                is_utf8: (category == FileCategory::Text).then_some(true),
+            },
+            None => FileType {
+                mime: "inode/x-empty".to_string(),
+                category: FileCategory::Unknown,
+                detected_by: DetectedBy::Content,
+                is_utf8: None,
+            },
+        };
+    }
+    if looks_binary(sample) {
+        return match extension.filter(|(_, category)| *category != FileCategory::Text) {
+            Some((mime, category)) => FileType {
+                mime: mime.to_string(),
+                category,
+                detected_by: DetectedBy::Extension,
+                is_utf8: None,
+            },
+            None => FileType {
+                mime: "application/octet-stream".to_string(),
+                category: FileCategory::Binary,
+                detected_by: DetectedBy::Content,
+                is_utf8: None,
+            },
+        };
+    }
+
+    let is_utf8 = Some(is_utf8(sample, truncated));
+    match extension {
+        Some((mime, category)) => FileType {
+            mime: mime.to_string(),
+            category,
+            detected_by: DetectedBy::Extension,
+            is_utf8,
+        },
+        None => FileType {
+            mime: "text/plain".to_string(),
+            category: FileCategory::Text,
+            detected_by: DetectedBy::Content,
+            is_utf8,
+        },
+    }
+}
+
+pub(crate) fn detect_file_type_sync(path: &Path) -> Result<FileType, LatticeError> {
+    let file = fs::File::open(path)?;
+    let length = file.metadata()?.len();
+    let mut sample = Vec::new();
+    file.take(SNIFF_BYTES)
+        .read_to_end(&mut sample)?;
+    Ok(classify(path, &sample, length > sample.len() as u64))
+}
+
+#[tauri::command]
+pub async fn detect_file_type(app: AppHandle, path: String) -> Result<FileType, LatticeError> {
+    log_command_async("detect_file_type", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || detect_file_type_sync(&path))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures_win_over_misleading_extensions() {
+        let png = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', 0, 0, 0, 0x0d];
+        let detected = classify(Path::new("notes.md"), &png, false);
+        assert_eq!((detected.mime.as_str(), detected.category), ("image/png", FileCategory::Image));
+        assert_eq!(detected.detected_by, DetectedBy::Magic);
+
+        let markdown = classify(Path::new("notes.md"), "# Über".as_bytes(), false);
+        assert_eq!((markdown.mime.as_str(), markdown.is_utf8), ("text/markdown", Some(true)));
+        let latin1 = classify(Path::new("notes"), b"caf\xe9 au lait", false);
+        assert_eq!((latin1.category, latin1.is_utf8), (FileCategory::Text, Some(false)));
+        let blob = classify(Path::new("data.md"), &[1, 0, 2, 0], false);
+        assert_eq!((blob.category, blob.detected_by), (FileCategory::Binary, DetectedBy::Content));
+        assert_eq!(classify(Path::new("empty"), &[], false).category, FileCategory::Unknown);
+    }
+
+    #[test]
+    fn a_character_split_by_the_sample_limit_is_still_utf8() {
+        let text = "é".as_bytes();
+        assert!(is_utf8(&text[..1], true));
+        assert!(!is_utf8(&text[..1], false));
+        assert!(!is_utf8(b"\xff rest", true));
+    }
+}
@@ -0,0 +1,938 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::error::LatticeError;
+use crate::file_style::{apply_line_ending, LineEndingStyle};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::workspace_settings::WORKSPACE_SETTINGS_DIR;
+use crate::{is_path_within_root, DesktopFsState, DesktopPreviewState};
+
+pub(crate) const PATH_RENAMED_EVENT: &str = "path-renamed";
+pub(crate) const TEMPLATES_DIR: &str = "templates";
+const MAX_NAME_SUFFIX: u32 = 10_000;
+/// The common per-name limit (ext4, APFS, NTFS in UTF-16 units, which bytes never undercount).
+const MAX_NAME_BYTES: usize = 255;
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1",
+    "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Describes the path itself; a symlink reports the link, not its target.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathMetadata {
+    pub size: u64,
+    /// Unix timestamps in milliseconds, `None` where the platform doesn't record them.
+    pub created: Option<u64>,
+    pub modified: Option<u64>,
+    pub accessed: Option<u64>,
+    pub is_dir: bool,
+    pub is_file: bool,
+    pub is_symlink: bool,
+    pub symlink_target: Option<String>,
+    pub readonly: bool,
+    /// Permission bits; always `None` on Windows.
+    pub mode: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PathRenamedPayload {
+    pub(crate) from: String,
+    pub(crate) to: String,
+}
+
+pub(crate) fn atomic_temp_path(target: &Path) -> Result<PathBuf, LatticeError> {
+    let parent = target
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| format!("Write target has no file name: {}", target.display()))?
+        .to_string_lossy();
+    Ok(parent.join(format!(".{file_name}.lattice-tmp-{}", Uuid::new_v4())))
+}
+
+fn write_temp_file(temp_path: &Path, bytes: &[u8], original: Option<&fs::Metadata>) -> std::io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp_path)?;
+    file.write_all(bytes)?;
+    if let Some(metadata) = original {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.sync_all()
+}
+
+#[cfg(unix)]
+fn sync_parent_directory(target: &Path) {
+    // Persist the rename itself; failure here doesn't invalidate the written data.
+    if let Some(parent) = target.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        if let Ok(directory) = fs::File::open(parent) {
+            let _ = directory.sync_all();
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_directory(_target: &Path) {}
+
+/// Writes through a sibling temp file and renames it over `target`, so readers
+/// only ever see the old or the new contents.
+pub(crate) fn write_bytes_atomic(target: &Path, bytes: &[u8]) -> Result<(), LatticeError> {
+    let original = fs::metadata(target).ok();
+    if original.as_ref().is_some_and(|metadata| metadata.is_dir()) {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Write target is a directory: {}", target.display()),
+        });
+    }
+
+    let temp_path = atomic_temp_path(target)?;
+    let result = write_temp_file(&temp_path, bytes, original.as_ref())
+        .and_then(|()| fs::rename(&temp_path, target));
+
+    match result {
+        Ok(()) => {
+            sync_parent_directory(target);
+            Ok(())
+        }
+        Err(error) => {
+            let _ = fs::remove_file(&temp_path);
+            Err(error.into())
+        }
+    }
+}
+
+pub(crate) fn timestamp_ms(time: std::io::Result<SystemTime>) -> Option<u64> {
+    time.ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+}
+
+#[cfg(unix)]
+fn mode_bits(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode())
+}
+
+#[cfg(not(unix))]
+fn mode_bits(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+fn stat_path_sync(path: &Path) -> Result<PathMetadata, LatticeError> {
+    // `symlink_metadata` never follows links, so broken links still stat fine.
+    let metadata = fs::symlink_metadata(path)?;
+    let is_symlink = metadata.file_type().is_symlink();
+    let symlink_target = if is_symlink {
+        fs::read_link(path)
+            .ok()
+            .map(|target| target.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    Ok(PathMetadata {
+        size: metadata.len(),
+        created: timestamp_ms(metadata.created()),
+        modified: timestamp_ms(metadata.modified()),
+        accessed: timestamp_ms(metadata.accessed()),
+        is_dir: metadata.is_dir(),
+        is_file: metadata.is_file(),
+        is_symlink,
+        symlink_target,
+        readonly: metadata.permissions().readonly(),
+        mode: mode_bits(&metadata),
+    })
+}
+
+#[tauri::command]
+pub async fn stat_path(app: AppHandle, path: String) -> Result<PathMetadata, LatticeError> {
+    log_command_async("stat_path", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || stat_path_sync(&path))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Execute permission is granted to each class that can already read the file, the
+/// way `chmod +x` is normally used; clearing it removes all three execute bits.
+#[cfg(unix)]
+fn with_executable(mode: u32, executable: bool) -> u32 {
+    if executable {
+        mode | ((mode & 0o444) >> 2)
+    } else {
+        mode & !0o111
+    }
+}
+
+/// Making a file writable again only restores the owner's write bit, never group or
+/// other write access it may never have had.
+#[cfg(unix)]
+fn with_readonly(mode: u32, readonly: bool) -> u32 {
+    if readonly {
+        mode & !0o222
+    } else {
+        mode | 0o200
+    }
+}
+
+fn existing_metadata(path: &Path) -> Result<fs::Metadata, LatticeError> {
+    fs::metadata(path).map_err(|error| LatticeError::at_path(path, error))
+}
+
+#[cfg(unix)]
+fn set_executable_sync(path: &Path, executable: bool) -> Result<(), LatticeError> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = existing_metadata(path)?.permissions().mode();
+    fs::set_permissions(path, fs::Permissions::from_mode(with_executable(mode, executable)))
+        .map_err(|error| LatticeError::at_path(path, error))
+}
+
+#[cfg(not(unix))]
+fn set_executable_sync(path: &Path, _executable: bool) -> Result<(), LatticeError> {
+    existing_metadata(path)?;
+    Err(LatticeError::Unsupported {
+        message: "Windows has no executable permission bit.".to_string(),
+    })
+}
+
+#[cfg(unix)]
+fn set_readonly_sync(path: &Path, readonly: bool) -> Result<(), LatticeError> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = existing_metadata(path)?.permissions().mode();
+    fs::set_permissions(path, fs::Permissions::from_mode(with_readonly(mode, readonly)))
+        .map_err(|error| LatticeError::at_path(path, error))
+}
+
+#[cfg(not(unix))]
+fn set_readonly_sync(path: &Path, readonly: bool) -> Result<(), LatticeError> {
+    let mut permissions = existing_metadata(path)?.permissions();
+    // On Windows this only toggles the read-only attribute.
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(readonly);
+    fs::set_permissions(path, permissions).map_err(|error| LatticeError::at_path(path, error))
+}
+
+#[tauri::command]
+pub async fn set_executable(app: AppHandle, path: String, executable: bool) -> Result<(), LatticeError> {
+    log_command_async("set_executable", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || set_executable_sync(&path, executable))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_readonly(app: AppHandle, path: String, readonly: bool) -> Result<(), LatticeError> {
+    log_command_async("set_readonly", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || set_readonly_sync(&path, readonly))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// `line_ending` re-emits every line break in that style, e.g. the one `detect_file_style` found.
+#[tauri::command]
+pub async fn write_file_atomic(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+    contents: String,
+    line_ending: Option<LineEndingStyle>,
+) -> Result<(), LatticeError> {
+    log_command_async("write_file_atomic", async move {
+        let path = resolve_command_path(&app, &path)?;
+        let contents = match line_ending {
+            Some(line_ending) => apply_line_ending(&contents, line_ending),
+            None => contents,
+        };
+        // `write_file_checked` relies on no other mutation running between its check and write.
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let label = window.label().to_string();
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            write_bytes_atomic(&path, contents.as_bytes())?;
+            crate::auto_reload::note_saved(&app, &label, &path, contents.as_bytes());
+            Ok(())
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+fn write_checked_sync(target: &Path, contents: &[u8], expected_hash: Option<&str>) -> Result<(), LatticeError> {
+    if let Some(expected_hash) = expected_hash {
+        let current = match fs::read(target) {
+            Ok(bytes) => Some(bytes),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(error) => return Err(LatticeError::at_path(target, error)),
+        };
+        let current_hash = current
+            .as_deref()
+            .map(|bytes| blake3::hash(bytes).to_hex().to_string());
+        if current_hash.as_deref() != Some(expected_hash.trim()) {
+            return Err(LatticeError::ConflictDetected {
+                current_hash,
+                current_contents: current.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+                message: format!("File changed on disk since it was opened: {}", target.display()),
+            });
+        }
+    }
+
+    write_bytes_atomic(target, contents)
+}
+
+#[tauri::command]
+pub async fn write_file_checked(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+    contents: String,
+    expected_hash: Option<String>,
+) -> Result<(), LatticeError> {
+    log_command_async("write_file_checked", async move {
+        let path = resolve_command_path(&app, &path)?;
+        // Serialize with other mutations so nothing in the app slips in between the check and the write.
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let label = window.label().to_string();
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            write_checked_sync(&path, contents.as_bytes(), expected_hash.as_deref())?;
+            crate::auto_reload::note_saved(&app, &label, &path, contents.as_bytes());
+            Ok(())
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn hash_file(app: AppHandle, path: String) -> Result<String, LatticeError> {
+    log_command_async("hash_file", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || crate::duplicates::hash_file(&path).map_err(LatticeError::from))
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Compares device and inode (the file index on Windows) rather than spellings, so hard
+/// links, symlinks and bind mounts of one file are the same file.
+pub(crate) fn same_file_sync(a: &Path, b: &Path) -> Result<bool, LatticeError> {
+    for path in [a, b] {
+        fs::metadata(path).map_err(|error| LatticeError::at_path(path, error))?;
+    }
+    Ok(same_file::is_same_file(a, b)?)
+}
+
+#[tauri::command]
+pub async fn same_file(app: AppHandle, a: String, b: String) -> Result<bool, LatticeError> {
+    log_command_async("same_file", async move {
+        let (a, b) = (resolve_command_path(&app, &a)?, resolve_command_path(&app, &b)?);
+        tokio::task::spawn_blocking(move || same_file_sync(&a, &b))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Resolves `path` through its parent so a symlink is judged by where it lives,
+/// not by what it points at.
+pub(crate) fn canonical_location(path: &Path) -> std::io::Result<PathBuf> {
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let canonical_parent = fs::canonicalize(parent)?;
+    Ok(match path.file_name() {
+        Some(name) => canonical_parent.join(name),
+        None => canonical_parent,
+    })
+}
+
+fn is_case_only_rename(from: &Path, to: &Path) -> bool {
+    let (Some(from_name), Some(to_name)) = (from.file_name(), to.file_name()) else {
+        return false;
+    };
+    let from_name = from_name.to_string_lossy();
+    let to_name = to_name.to_string_lossy();
+    from_name != to_name
+        && from_name.to_lowercase() == to_name.to_lowercase()
+        && from.parent() == to.parent()
+}
+
+fn is_same_entry(from: &Path, to: &Path) -> bool {
+    match (fs::canonicalize(from), fs::canonicalize(to)) {
+        (Ok(from), Ok(to)) => from == to,
+        _ => false,
+    }
+}
+
+/// Case-insensitive filesystems treat `Readme.md -> README.md` as a rename onto
+/// itself, so hop through a temporary name to make the new casing stick.
+fn rename_via_temp(from: &Path, to: &Path) -> std::io::Result<()> {
+    let file_name = from.file_name().unwrap_or_default().to_string_lossy();
+    let temp = from.with_file_name(format!(".{file_name}.lattice-rename-{}", Uuid::new_v4()));
+    fs::rename(from, &temp)?;
+    fs::rename(&temp, to).inspect_err(|_| {
+        let _ = fs::rename(&temp, from);
+    })
+}
+
+fn rename_path_sync(from: &Path, to: &Path, overwrite: bool, root: Option<&Path>) -> Result<PathBuf, LatticeError> {
+    if fs::symlink_metadata(from).is_err() {
+        return Err(LatticeError::NotFound {
+            message: format!("Path not found: {}", from.display()),
+        });
+    }
+    if to.file_name().is_none() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Rename target has no file name: {}", to.display()),
+        });
+    }
+
+    let from_location = canonical_location(from).map_err(|error| LatticeError::at_path(from, error))?;
+    let to_location = canonical_location(to).map_err(|error| LatticeError::at_path(to, error))?;
+    if let Some(root) = root {
+        for location in [&from_location, &to_location] {
+            if !is_path_within_root(location, root) {
+                return Err(LatticeError::OutsideScope {
+                    message: format!("Path is outside the current workspace: {}", location.display()),
+                });
+            }
+        }
+    }
+
+    if from_location == to_location {
+        return Ok(to.to_path_buf());
+    }
+    if is_case_only_rename(&from_location, &to_location) && is_same_entry(from, to) {
+        rename_via_temp(from, to).map_err(|error| LatticeError::at_path(from, error))?;
+        return Ok(to.to_path_buf());
+    }
+
+    if !overwrite && fs::symlink_metadata(to).is_ok() {
+        return Err(LatticeError::TargetExists {
+            message: format!("Target already exists: {}", to.display()),
+        });
+    }
+
+    // Plain `rename` only; a cross-device move fails loudly instead of becoming a copy.
+    fs::rename(from, to).map_err(|error| LatticeError::at_path(from, error))?;
+    Ok(to.to_path_buf())
+}
+
+#[tauri::command]
+pub async fn rename_path(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    preview_state: State<'_, DesktopPreviewState>,
+    from: String,
+    to: String,
+    overwrite: bool,
+) -> Result<String, LatticeError> {
+    log_command_async("rename_path", async move {
+        let root = preview_state
+            .workspace_root
+            .lock()
+            .map_err(|error| error.to_string())?
+            .clone();
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let from = resolve_command_path(&app, &from)?;
+        let to = resolve_command_path(&app, &to)?;
+        let from_path = from.clone();
+        let renamed = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            rename_path_sync(&from_path, &to, overwrite, root.as_deref())
+        })
+        .await
+        .map_err(|error| error.to_string())??;
+
+        crate::tags::follow_rename(&app, &from, &renamed);
+        let renamed = renamed.to_string_lossy().to_string();
+        let _ = app.emit(
+            PATH_RENAMED_EVENT,
+            PathRenamedPayload {
+                from: from.to_string_lossy().to_string(),
+                to: renamed.clone(),
+            },
+        );
+        Ok(renamed)
+    })
+    .await
+}
+
+fn validate_entry_name_for(name: &str, windows_rules: bool) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Name cannot be empty.".to_string());
+    }
+    if name == "." || name == ".." {
+        return Err(format!("\"{name}\" is not a valid name."));
+    }
+    if let Some(invalid) = name.chars().find(|character| matches!(character, '/' | '\\' | '\0')) {
+        return Err(format!("Name cannot contain {invalid:?}."));
+    }
+    if name.len() > MAX_NAME_BYTES {
+        return Err(format!("Name is longer than {MAX_NAME_BYTES} bytes."));
+    }
+    if !windows_rules {
+        return Ok(());
+    }
+
+    if let Some(invalid) = name
+        .chars()
+        .find(|character| matches!(character, '<' | '>' | ':' | '"' | '|' | '?' | '*') || character.is_control())
+    {
+        return Err(format!("Name cannot contain {invalid:?} on Windows."));
+    }
+    if name.ends_with([' ', '.']) {
+        return Err("Name cannot end with a space or a period on Windows.".to_string());
+    }
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        return Err(format!("\"{name}\" is a reserved name on Windows."));
+    }
+    Ok(())
+}
+
+/// Like `fs::rename`, but fails with `AlreadyExists` rather than replacing `to`. Files are
+/// hard-linked into place so nothing can appear at `to` between a check and the rename;
+/// where hard links aren't supported it falls back to checking first.
+pub(crate) fn rename_no_replace(from: &Path, to: &Path) -> std::io::Result<()> {
+    match fs::hard_link(from, to) {
+        Ok(()) => fs::remove_file(from),
+        Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => Err(error),
+        Err(_) if fs::symlink_metadata(to).is_ok() => Err(std::io::ErrorKind::AlreadyExists.into()),
+        Err(_) => fs::rename(from, to),
+    }
+}
+
+pub(crate) fn validate_entry_name(name: &str) -> Result<(), String> {
+    validate_entry_name_for(name, cfg!(windows))
+}
+
+/// `note.md` becomes `note (2).md`; dotfiles and extensionless names get the suffix at the end.
+pub(crate) fn suffixed_name(name: &str, attempt: u32) -> String {
+    if attempt <= 1 {
+        return name.to_string();
+    }
+    match name.rfind('.').filter(|index| *index > 0) {
+        Some(index) => format!("{} ({attempt}){}", &name[..index], &name[index..]),
+        None => format!("{name} ({attempt})"),
+    }
+}
+
+pub(crate) fn create_unique_entry<F>(dir: &Path, name: &str, mut create: F) -> Result<PathBuf, LatticeError>
+where
+    F: FnMut(&Path) -> std::io::Result<()>,
+{
+    for attempt in 1..=MAX_NAME_SUFFIX {
+        let candidate = dir.join(suffixed_name(name, attempt));
+        match create(&candidate) {
+            Ok(()) => return Ok(candidate),
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+    Err(LatticeError::AlreadyExists {
+        message: format!("Could not find a free name for {name} in {}", dir.display()),
+    })
+}
+
+fn require_parent_directory(parent: &Path) -> Result<(), LatticeError> {
+    if !parent.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Parent is not a directory: {}", parent.display()),
+        });
+    }
+    Ok(())
+}
+
+/// The nearest `.lattice/templates/<template>` at or above `dir`.
+fn find_template(dir: &Path, template: &str) -> Result<PathBuf, LatticeError> {
+    validate_entry_name(template).map_err(|message| LatticeError::InvalidInput { message })?;
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(WORKSPACE_SETTINGS_DIR).join(TEMPLATES_DIR).join(template))
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| LatticeError::NotFound {
+            message: format!("Template not found: {template}"),
+        })
+}
+
+/// Formats a Unix timestamp as a `YYYY-MM-DD` UTC date.
+fn format_utc_date(seconds: u64) -> String {
+    // Howard Hinnant's days-to-civil conversion.
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn render_template(template: &str, title: &str, now: SystemTime) -> String {
+    let seconds = now.duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0);
+    template
+        .replace("{{date}}", &format_utc_date(seconds))
+        .replace("{{title}}", title)
+}
+
+fn create_file_sync(dir: &Path, name: &str, template: Option<&str>) -> Result<PathBuf, LatticeError> {
+    validate_entry_name(name).map_err(|message| LatticeError::InvalidInput { message })?;
+    let template_contents = match template.map(str::trim).filter(|template| !template.is_empty()) {
+        Some(template) => Some(fs::read_to_string(find_template(dir, template)?)?),
+        None => None,
+    };
+
+    let mut created_title = String::new();
+    let path = create_unique_entry(dir, name, |candidate| {
+        created_title = candidate
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        fs::OpenOptions::new().write(true).create_new(true).open(candidate).map(|_| ())
+    })?;
+
+    if let Some(contents) = template_contents {
+        let rendered = render_template(&contents, &created_title, SystemTime::now());
+        fs::write(&path, rendered)?;
+    }
+    Ok(path)
+}
+
+fn create_folder_sync(dir: &Path, name: &str) -> Result<PathBuf, LatticeError> {
+    validate_entry_name(name).map_err(|message| LatticeError::InvalidInput { message })?;
+    create_unique_entry(dir, name, |candidate| fs::create_dir(candidate))
+}
+
+#[tauri::command]
+pub async fn create_file(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    dir: String,
+    name: String,
+    template: Option<String>,
+) -> Result<String, LatticeError> {
+    log_command_async("create_file", async move {
+        let parent = resolve_command_path(&app, &dir)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            require_parent_directory(&parent)?;
+            create_file_sync(&parent, name.trim(), template.as_deref())
+                .map(|path| path.to_string_lossy().to_string())
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn create_folder(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    dir: String,
+    name: String,
+) -> Result<String, LatticeError> {
+    log_command_async("create_folder", async move {
+        let parent = resolve_command_path(&app, &dir)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            require_parent_directory(&parent)?;
+            create_folder_sync(&parent, name.trim()).map(|path| path.to_string_lossy().to_string())
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_fixture_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("lattice-fileops-{}", Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn atomic_write_replaces_contents_without_leaving_temp_files() {
+        let root = create_fixture_root();
+        let target = root.join("note.md");
+
+        write_bytes_atomic(&target, b"first").unwrap();
+        write_bytes_atomic(&target, b"second").unwrap();
+
+        assert_eq!(fs::read_to_string(&target).unwrap(), "second");
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn atomic_write_refuses_directories_and_leaves_them_untouched() {
+        let root = create_fixture_root();
+
+        assert!(write_bytes_atomic(&root, b"data").is_err());
+        assert!(root.is_dir());
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_write_preserves_existing_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = create_fixture_root();
+        let target = root.join("script.sh");
+        fs::write(&target, "echo old").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o750)).unwrap();
+
+        write_bytes_atomic(&target, b"echo new").unwrap();
+
+        let mode = fs::metadata(&target).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o750);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn checked_writes_refuse_to_clobber_external_changes() {
+        let root = create_fixture_root();
+        let target = root.join("note.md");
+        fs::write(&target, "loaded").unwrap();
+        let loaded_hash = crate::duplicates::hash_file(&target).unwrap();
+
+        write_checked_sync(&target, b"mine", Some(&loaded_hash)).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "mine");
+
+        fs::write(&target, "theirs").unwrap();
+        let error = write_checked_sync(&target, b"mine again", Some(&loaded_hash)).unwrap_err();
+        let LatticeError::ConflictDetected {
+            current_hash,
+            current_contents,
+            ..
+        } = error
+        else {
+            panic!("expected a conflict");
+        };
+        assert_eq!(current_contents.as_deref(), Some("theirs"));
+        assert_eq!(current_hash, Some(crate::duplicates::hash_file(&target).unwrap()));
+        assert_eq!(fs::read_to_string(&target).unwrap(), "theirs");
+
+        write_checked_sync(&target, b"forced", None).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "forced");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn same_file_sees_through_links_and_spellings() {
+        let root = create_fixture_root();
+        let note = root.join("note.md");
+        fs::write(&note, "note").unwrap();
+        fs::write(root.join("other.md"), "other").unwrap();
+        fs::hard_link(&note, root.join("hard.md")).unwrap();
+        std::os::unix::fs::symlink(&note, root.join("soft.md")).unwrap();
+
+        assert!(same_file_sync(&note, &root.join("hard.md")).unwrap());
+        assert!(same_file_sync(&note, &root.join("soft.md")).unwrap());
+        assert!(same_file_sync(&note, &root.join(".").join("note.md")).unwrap());
+        assert!(!same_file_sync(&note, &root.join("other.md")).unwrap());
+        let missing = same_file_sync(&note, &root.join("missing.md")).unwrap_err();
+        assert!(matches!(missing, LatticeError::NotFound { .. }));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rename_refuses_existing_targets_unless_overwriting() {
+        let root = create_fixture_root();
+        let from = root.join("draft.md");
+        let to = root.join("final.md");
+        fs::write(&from, "draft").unwrap();
+        fs::write(&to, "final").unwrap();
+
+        let error = rename_path_sync(&from, &to, false, None).unwrap_err();
+        assert!(matches!(error, LatticeError::TargetExists { .. }));
+        assert_eq!(fs::read_to_string(&to).unwrap(), "final");
+
+        rename_path_sync(&from, &to, true, None).unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(&to).unwrap(), "draft");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rename_stays_inside_the_workspace_and_handles_case_only_changes() {
+        let root = create_fixture_root();
+        let workspace = fs::canonicalize(&root).unwrap().join("workspace");
+        fs::create_dir_all(&workspace).unwrap();
+        let from = workspace.join("Readme.md");
+        fs::write(&from, "hello").unwrap();
+
+        let error = rename_path_sync(&from, &root.join("escaped.md"), false, Some(&workspace)).unwrap_err();
+        assert!(matches!(error, LatticeError::OutsideScope { .. }));
+
+        let to = workspace.join("README.md");
+        rename_via_temp(&from, &to).unwrap();
+        let names: Vec<String> = fs::read_dir(&workspace)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["README.md".to_string()]);
+        assert!(is_case_only_rename(&from, &to));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn create_file_suffixes_collisions_and_renders_templates() {
+        let root = create_fixture_root();
+        let templates = root.join(".lattice/templates");
+        fs::create_dir_all(&templates).unwrap();
+        fs::write(templates.join("daily.md"), "# {{title}}\n").unwrap();
+        let notes = root.join("notes");
+        fs::create_dir_all(&notes).unwrap();
+
+        let first = create_file_sync(&notes, "today.md", None).unwrap();
+        let second = create_file_sync(&notes, "today.md", Some("daily.md")).unwrap();
+        assert_eq!(first, notes.join("today.md"));
+        assert_eq!(second, notes.join("today (2).md"));
+        assert_eq!(fs::read_to_string(&second).unwrap(), "# today (2)\n");
+
+        assert_eq!(create_folder_sync(&notes, "archive").unwrap(), notes.join("archive"));
+        assert_eq!(create_folder_sync(&notes, "archive").unwrap(), notes.join("archive (2)"));
+        assert!(create_file_sync(&notes, "x.md", Some("../escape.md")).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn entry_names_are_validated_per_platform() {
+        assert!(validate_entry_name_for("notes: draft.md", false).is_ok());
+        assert!(validate_entry_name_for("notes: draft.md", true).is_err());
+        assert!(validate_entry_name_for("con.txt", true).is_err());
+        assert!(validate_entry_name_for("trailing.", true).is_err());
+        assert!(validate_entry_name_for("a/b", false).is_err());
+        assert!(validate_entry_name_for("..", false).is_err());
+
+        assert_eq!(format_utc_date(0), "1970-01-01");
+        assert_eq!(format_utc_date(1_709_164_800), "2024-02-29");
+        assert_eq!(suffixed_name(".env", 3), ".env (3)");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stat_reports_broken_symlinks_instead_of_failing() {
+        let root = create_fixture_root();
+        let link = root.join("dangling");
+        std::os::unix::fs::symlink(root.join("missing.md"), &link).unwrap();
+        fs::write(root.join("note.md"), "hello").unwrap();
+
+        let stat = stat_path_sync(&link).unwrap();
+        assert!(stat.is_symlink && !stat.is_file && !stat.is_dir);
+        assert_eq!(stat.symlink_target, Some(root.join("missing.md").to_string_lossy().to_string()));
+
+        let note = stat_path_sync(&root.join("note.md")).unwrap();
+        assert!(note.is_file);
+        assert_eq!(note.size, 5);
+        assert!(note.modified.is_some_and(|modified| modified > 0));
+        assert!(note.mode.is_some());
+        assert!(stat_path_sync(&root.join("missing.md")).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn toggling_one_permission_flag_keeps_the_other_bits() {
+        use std::os::unix::fs::PermissionsExt;
+        let root = create_fixture_root();
+        let script = root.join("run.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o4640)).unwrap();
+
+        set_executable_sync(&script, true).unwrap();
+        assert_eq!(mode(&script), 0o4750);
+        set_readonly_sync(&script, true).unwrap();
+        assert_eq!(mode(&script), 0o4550);
+        set_readonly_sync(&script, false).unwrap();
+        assert_eq!(mode(&script), 0o4750);
+        set_executable_sync(&script, false).unwrap();
+        assert_eq!(mode(&script), 0o4640);
+
+        assert!(matches!(
+            set_executable_sync(&root.join("missing.sh"), true),
+            Err(LatticeError::NotFound { .. })
+        ));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
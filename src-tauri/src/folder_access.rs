@@ -0,0 +1,128 @@
+use std::path::Path;
+
+use tauri::AppHandle;
+use tauri_plugin_fs::FsExt;
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::paths::{resolve_app_path, ResolvedPath};
+use crate::{build_app_settings_from_store, save_app_settings};
+
+/// Trims and de-duplicates while keeping grant order.
+pub(crate) fn normalize_granted_folders(paths: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(paths.len());
+    for path in paths {
+        let trimmed = path.trim();
+        if !trimmed.is_empty() && !normalized.iter().any(|existing| existing == trimmed) {
+            normalized.push(trimmed.to_string());
+        }
+    }
+    normalized
+}
+
+/// Grants whose folder is gone would widen the scope for no reason, and could match a
+/// different folder created later at the same path.
+fn existing_grants(paths: Vec<String>) -> Vec<String> {
+    paths.into_iter().filter(|path| Path::new(path).is_dir()).collect()
+}
+
+/// The scope matches canonical paths, so grants are stored that way too.
+fn canonical_folder(resolved: ResolvedPath) -> Result<String, String> {
+    if !resolved.exists || !Path::new(&resolved.path).is_dir() {
+        return Err(format!("Not a folder: {}", resolved.display));
+    }
+    Ok(resolved.path)
+}
+
+fn allow_folder(app: &AppHandle, folder: &str) -> Result<(), String> {
+    app.fs_scope()
+        .allow_directory(folder, true)
+        .map_err(|error| error.to_string())
+}
+
+/// Re-applies saved grants during `setup`, dropping any whose folder no longer exists.
+pub(crate) fn restore_folder_access(app: &AppHandle) -> Result<(), String> {
+    let mut settings = build_app_settings_from_store(app)?;
+    let granted = existing_grants(settings.granted_folders.clone());
+    if granted.len() != settings.granted_folders.len() {
+        settings.granted_folders = granted.clone();
+        save_app_settings(app, settings)?;
+    }
+    for folder in &granted {
+        allow_folder(app, folder)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn grant_folder_access(app: AppHandle, folder: String) -> Result<Vec<String>, LatticeError> {
+    log_command("grant_folder_access", || {
+        let folder = canonical_folder(resolve_app_path(&app, &folder, None)?)?;
+        // The scope has no way to lift a forbid, so a revoked folder stays blocked until restart.
+        if app.fs_scope().is_forbidden(&folder) {
+            return Err(LatticeError::PermissionDenied {
+                message: format!("Access to {folder} was revoked this session; restart Lattice to grant it again."),
+            });
+        }
+        allow_folder(&app, &folder)?;
+
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.granted_folders.push(folder);
+        Ok(save_app_settings(&app, settings)?.granted_folders)
+    })
+}
+
+#[tauri::command]
+pub fn list_granted_folders(app: AppHandle) -> Result<Vec<String>, LatticeError> {
+    log_command("list_granted_folders", || Ok(build_app_settings_from_store(&app)?.granted_folders))
+}
+
+/// Forbids the folder for the rest of the session, since an allow can't be withdrawn.
+#[tauri::command]
+pub fn revoke_folder_access(app: AppHandle, folder: String) -> Result<Vec<String>, LatticeError> {
+    log_command("revoke_folder_access", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        // Match the stored form even when the folder itself has since been deleted.
+        let folder = resolve_app_path(&app, &folder, None)
+            .map(|resolved| resolved.path)
+            .unwrap_or_else(|_| folder.trim().to_string());
+        if !settings.granted_folders.contains(&folder) {
+            return Err(LatticeError::NotFound {
+                message: format!("Folder access was not granted: {folder}"),
+            });
+        }
+        app.fs_scope()
+            .forbid_directory(&folder, true)
+            .map_err(|error| error.to_string())?;
+
+        settings.granted_folders.retain(|existing| existing != &folder);
+        Ok(save_app_settings(&app, settings)?.granted_folders)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::resolve_user_path;
+    use std::fs;
+    use uuid::Uuid;
+
+    #[test]
+    fn stale_grants_are_dropped_and_duplicates_collapsed() {
+        let folder = std::env::temp_dir().join(format!("lattice-folder-access-{}", Uuid::new_v4()));
+        fs::create_dir_all(&folder).unwrap();
+        let folder_path = canonical_folder(resolve_user_path(&folder.to_string_lossy(), None, None).unwrap()).unwrap();
+        let missing = folder.join("gone").to_string_lossy().to_string();
+
+        let granted = normalize_granted_folders(vec![
+            format!(" {folder_path} "),
+            missing.clone(),
+            folder_path.clone(),
+        ]);
+        assert_eq!(granted, vec![folder_path.clone(), missing.clone()]);
+        assert_eq!(existing_grants(granted), vec![folder_path]);
+        assert!(canonical_folder(resolve_user_path(&missing, None, None).unwrap()).is_err());
+
+        fs::remove_dir_all(folder).unwrap();
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, FileDialogBuilder, FilePath};
+use tokio::sync::oneshot;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::{
+    build_app_settings_from_store, push_recent_folder_path, recent_folders_limit, resolve_existing_directory_path,
+    save_app_settings, AppSettings,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickerFilter {
+    pub name: String,
+    /// Without the leading dot; a leading dot is dropped.
+    pub extensions: Vec<String>,
+}
+
+/// The last opened folder, else the default folder, as long as it still exists.
+fn start_directory(settings: &AppSettings) -> Option<String> {
+    settings
+        .last_opened_folder
+        .iter()
+        .chain(&settings.default_folder)
+        .find_map(|folder| resolve_existing_directory_path(folder))
+}
+
+fn seeded_dialog(
+    app: &AppHandle,
+    window: &tauri::WebviewWindow,
+) -> Result<FileDialogBuilder<tauri::Wry>, LatticeError> {
+    let mut dialog = app.dialog().file().set_parent(window);
+    if let Some(directory) = start_directory(&build_app_settings_from_store(app)?) {
+        dialog = dialog.set_directory(directory);
+    }
+    Ok(dialog)
+}
+
+fn selected_path(path: FilePath) -> Result<String, LatticeError> {
+    let path: PathBuf = path.into_path().map_err(|error| error.to_string())?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Opens in the last used folder; the pick becomes the new last opened folder and joins the
+/// recent folders. `None` when the dialog is cancelled.
+#[tauri::command]
+pub async fn pick_folder(app: AppHandle, window: tauri::WebviewWindow) -> Result<Option<String>, LatticeError> {
+    log_command_async("pick_folder", async move {
+        let (sender, receiver) = oneshot::channel();
+        seeded_dialog(&app, &window)?.pick_folder(move |picked| {
+            let _ = sender.send(picked);
+        });
+        let Some(picked) = receiver.await.map_err(|error| error.to_string())? else {
+            return Ok(None);
+        };
+
+        let folder = selected_path(picked)?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        let limit = recent_folders_limit(&settings);
+        settings.recent_folders = push_recent_folder_path(&settings.recent_folders, &folder, limit);
+        settings.last_opened_folder = Some(folder.clone());
+        save_app_settings(&app, settings)?;
+        Ok(Some(folder))
+    })
+    .await
+}
+
+/// Starts where `pick_folder` does. Picking files doesn't move the last opened folder.
+#[tauri::command]
+pub async fn pick_files(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    filters: Vec<PickerFilter>,
+) -> Result<Option<Vec<String>>, LatticeError> {
+    log_command_async("pick_files", async move {
+        let mut dialog = seeded_dialog(&app, &window)?;
+        for filter in &filters {
+            let extensions: Vec<&str> = filter
+                .extensions
+                .iter()
+                .map(|extension| extension.trim().trim_start_matches('.'))
+                .filter(|extension| !extension.is_empty())
+                .collect();
+            if !extensions.is_empty() {
+                dialog = dialog.add_filter(filter.name.trim(), &extensions);
+            }
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        dialog.pick_files(move |picked| {
+            let _ = sender.send(picked);
+        });
+        match receiver.await.map_err(|error| error.to_string())? {
+            Some(picked) => Ok(Some(picked.into_iter().map(selected_path).collect::<Result<_, _>>()?)),
+            None => Ok(None),
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_start_directory_falls_back_to_the_default_folder() {
+        let existing = std::env::temp_dir().to_string_lossy().to_string();
+        let missing = std::env::temp_dir().join(format!("lattice-picker-{}", uuid::Uuid::new_v4()));
+        let mut settings = AppSettings {
+            last_opened_folder: Some(missing.to_string_lossy().to_string()),
+            default_folder: Some(existing.clone()),
+            ..AppSettings::default()
+        };
+        assert_eq!(start_directory(&settings), Some(existing.clone()));
+
+        settings.last_opened_folder = Some(existing.clone());
+        settings.default_folder = None;
+        assert_eq!(start_directory(&settings), Some(existing));
+        assert_eq!(start_directory(&AppSettings::default()), None);
+    }
+}
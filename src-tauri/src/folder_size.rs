@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::LatticeError;
+use crate::logging::{log_command, log_command_async};
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+const FOLDER_SIZE_PROGRESS_EVENT: &str = "folder-size-progress";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Caller-chosen id that ties progress events and cancellation to one scan.
+pub type CancelToken = String;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSizeReport {
+    pub total_bytes: u64,
+    pub file_count: u64,
+    pub dir_count: u64,
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FolderSizeProgressPayload {
+    token: CancelToken,
+    #[serde(flatten)]
+    report: FolderSizeReport,
+}
+
+#[derive(Default)]
+pub struct FolderSizeState {
+    active: StdMutex<HashMap<CancelToken, Arc<AtomicBool>>>,
+}
+
+fn measure_folder<F>(root: &Path, cancelled: &AtomicBool, mut on_progress: F) -> FolderSizeReport
+where
+    F: FnMut(&FolderSizeReport),
+{
+    let mut report = FolderSizeReport::default();
+    let mut pending = vec![root.to_path_buf()];
+    let mut last_progress = Instant::now();
+
+    while let Some(directory) = pending.pop() {
+        // Unreadable directories are skipped; the totals are best effort.
+        let Ok(entries) = fs::read_dir(&directory) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            if cancelled.load(Ordering::Relaxed) {
+                report.cancelled = true;
+                return report;
+            }
+
+            // `DirEntry::metadata` doesn't follow symlinks, so linked directories
+            // count as the link itself and are never descended into.
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                report.dir_count += 1;
+                pending.push(entry.path());
+            } else {
+                report.file_count += 1;
+                report.total_bytes += metadata.len();
+            }
+
+            if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                on_progress(&report);
+                last_progress = Instant::now();
+            }
+        }
+    }
+
+    report
+}
+
+#[tauri::command]
+pub async fn folder_size(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    size_state: State<'_, FolderSizeState>,
+    path: String,
+    token: CancelToken,
+) -> Result<FolderSizeReport, LatticeError> {
+    log_command_async("folder_size", async move {
+        let root = resolve_command_path(&app, &path)?;
+        if !root.is_dir() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Path is not a directory: {}", root.display()),
+            });
+        }
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        size_state
+            .active
+            .lock()
+            .map_err(|error| error.to_string())?
+            .insert(token.clone(), cancelled.clone());
+
+        let permit = fs_state.read_dir_permits.clone().acquire_owned().await;
+        let token_for_scan = token.clone();
+        let result = match permit {
+            Ok(permit) => tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                measure_folder(&root, &cancelled, |report| {
+                    let _ = app.emit(
+                        FOLDER_SIZE_PROGRESS_EVENT,
+                        FolderSizeProgressPayload {
+                            token: token_for_scan.clone(),
+                            report: report.clone(),
+                        },
+                    );
+                })
+            })
+            .await
+            .map_err(|error| error.to_string().into()),
+            Err(error) => Err(error.to_string().into()),
+        };
+
+        if let Ok(mut active) = size_state.active.lock() {
+            active.remove(&token);
+        }
+        result
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn cancel_folder_size(size_state: State<'_, FolderSizeState>, token: CancelToken) -> Result<(), LatticeError> {
+    log_command("cancel_folder_size", || {
+        let active = size_state.active.lock().map_err(|error| error.to_string())?;
+        match active.get(&token) {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+            None => Err(LatticeError::NotFound {
+                message: format!("No folder size scan is running for token: {token}"),
+            }),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn create_fixture_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("lattice-folder-size-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes/archive")).unwrap();
+        fs::write(root.join("notes/a.md"), "12345").unwrap();
+        fs::write(root.join("notes/archive/b.md"), "123").unwrap();
+        fs::write(root.join("readme.md"), "12").unwrap();
+        root
+    }
+
+    #[test]
+    fn totals_bytes_files_and_directories() {
+        let root = create_fixture_root();
+
+        let report = measure_folder(&root, &AtomicBool::new(false), |_| {});
+        assert_eq!(report.total_bytes, 10);
+        assert_eq!(report.file_count, 3);
+        assert_eq!(report.dir_count, 2);
+        assert!(!report.cancelled);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn cancelled_scans_return_partial_totals() {
+        let root = create_fixture_root();
+
+        let report = measure_folder(&root, &AtomicBool::new(true), |_| {});
+        assert!(report.cancelled);
+        assert_eq!(report.file_count, 0);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_directories_are_not_followed() {
+        let root = create_fixture_root();
+        std::os::unix::fs::symlink(root.join("notes"), root.join("notes-link")).unwrap();
+
+        let report = measure_folder(&root, &AtomicBool::new(false), |_| {});
+        assert_eq!(report.file_count, 4);
+        assert_eq!(report.dir_count, 2);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,248 @@
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::file_tree::FileNode;
+use crate::logging::log_command;
+use crate::paths::resolve_command_path;
+use crate::workspace_settings::{read_workspace_value, write_workspace_value, WORKSPACE_SETTINGS_DIR};
+
+/// Workspace setting mapping folder paths, relative to the workspace and `/`-separated
+/// (`""` for the workspace itself), to their `SortSpec`.
+const FOLDER_SORT_KEY: &str = "folderSort";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    #[default]
+    Name,
+    Modified,
+    Size,
+    /// By extension, then by name.
+    Type,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SortSpec {
+    pub key: SortKey,
+    pub direction: SortDirection,
+    /// Keeps folders above files whichever way the rest is sorted.
+    pub dirs_first: bool,
+}
+
+impl Default for SortSpec {
+    fn default() -> Self {
+        Self {
+            key: SortKey::Name,
+            direction: SortDirection::Asc,
+            dirs_first: true,
+        }
+    }
+}
+
+/// Splits a name into runs of ASCII digits and runs of everything else.
+fn name_chunks(name: &str) -> impl Iterator<Item = &str> {
+    let mut rest = name;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let is_digit = first.is_ascii_digit();
+        let end = rest
+            .find(|character: char| character.is_ascii_digit() != is_digit)
+            .unwrap_or(rest.len());
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        Some(chunk)
+    })
+}
+
+/// Digit runs compare by value however long they are, so there is no overflow; with equal
+/// values, fewer leading zeros comes first.
+fn compare_numbers(left: &str, right: &str) -> Ordering {
+    let left_digits = left.trim_start_matches('0');
+    let right_digits = right.trim_start_matches('0');
+    left_digits
+        .len()
+        .cmp(&right_digits.len())
+        .then_with(|| left_digits.cmp(right_digits))
+        .then_with(|| left.len().cmp(&right.len()))
+}
+
+/// Orders `file2` before `file10` and ignores case, falling back to the exact bytes so
+/// distinct names never compare equal.
+pub(crate) fn natural_cmp(left: &str, right: &str) -> Ordering {
+    let mut left_chunks = name_chunks(left);
+    let mut right_chunks = name_chunks(right);
+    loop {
+        let ordering = match (left_chunks.next(), right_chunks.next()) {
+            (None, None) => return left.cmp(right),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(left_chunk), Some(right_chunk)) => {
+                let left_numeric = left_chunk.starts_with(|character: char| character.is_ascii_digit());
+                let right_numeric = right_chunk.starts_with(|character: char| character.is_ascii_digit());
+                match (left_numeric, right_numeric) {
+                    (true, true) => compare_numbers(left_chunk, right_chunk),
+                    // Numbers sort before words, as in `2024 notes` before `notes`.
+                    (true, false) => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    (false, false) => left_chunk.to_lowercase().cmp(&right_chunk.to_lowercase()),
+                }
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn extension(node: &FileNode) -> String {
+    if node.is_dir {
+        return String::new();
+    }
+    Path::new(&node.name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn compare_nodes(left: &FileNode, right: &FileNode, sort: &SortSpec) -> Ordering {
+    if sort.dirs_first && left.is_dir != right.is_dir {
+        return right.is_dir.cmp(&left.is_dir);
+    }
+    let ordering = match sort.key {
+        SortKey::Name => Ordering::Equal,
+        SortKey::Modified => left.modified.cmp(&right.modified),
+        SortKey::Size => left.size.cmp(&right.size),
+        SortKey::Type => extension(left).cmp(&extension(right)),
+    }
+    .then_with(|| natural_cmp(&left.name, &right.name));
+    match sort.direction {
+        SortDirection::Asc => ordering,
+        SortDirection::Desc => ordering.reverse(),
+    }
+}
+
+/// Sort orders live with the nearest workspace (the closest folder with a `.lattice`
+/// directory), or with the folder itself outside one.
+fn sort_location(folder: PathBuf) -> Result<(PathBuf, String), LatticeError> {
+    if !folder.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Path is not a directory: {}", folder.display()),
+        });
+    }
+    let root = folder
+        .ancestors()
+        .find(|directory| directory.join(WORKSPACE_SETTINGS_DIR).is_dir())
+        .unwrap_or(&folder)
+        .to_path_buf();
+    let key = folder
+        .strip_prefix(&root)
+        .unwrap_or(Path::new(""))
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    Ok((root, key))
+}
+
+fn read_folder_sorts(app: &AppHandle, root: &Path) -> Result<Map<String, Value>, LatticeError> {
+    Ok(match read_workspace_value(app, root, FOLDER_SORT_KEY)? {
+        Some(Value::Object(sorts)) => sorts,
+        _ => Map::new(),
+    })
+}
+
+#[tauri::command]
+pub fn set_folder_sort(app: AppHandle, folder: String, sort: SortSpec) -> Result<(), LatticeError> {
+    log_command("set_folder_sort", || {
+        let (root, key) = sort_location(resolve_command_path(&app, &folder)?)?;
+        let mut sorts = read_folder_sorts(&app, &root)?;
+        sorts.insert(key, serde_json::to_value(sort).map_err(|error| error.to_string())?);
+        write_workspace_value(&app, &root, FOLDER_SORT_KEY.to_string(), Value::Object(sorts))
+    })
+}
+
+/// The default order (by name, folders first) for folders never given one.
+#[tauri::command]
+pub fn get_folder_sort(app: AppHandle, folder: String) -> Result<SortSpec, LatticeError> {
+    log_command("get_folder_sort", || {
+        let (root, key) = sort_location(resolve_command_path(&app, &folder)?)?;
+        Ok(read_folder_sorts(&app, &root)?
+            .remove(&key)
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default())
+    })
+}
+
+#[tauri::command]
+pub fn sort_entries(mut entries: Vec<FileNode>, sort: SortSpec) -> Vec<FileNode> {
+    entries.sort_by(|left, right| compare_nodes(left, right, &sort));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, is_dir: bool, size: u64) -> FileNode {
+        FileNode {
+            name: name.to_string(),
+            path: format!("/notes/{name}"),
+            is_dir,
+            is_symlink: false,
+            size,
+            modified: None,
+            depth: 1,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn names_sort_naturally_and_specs_apply_in_order() {
+        let mut names = vec!["file10", "File2", "file2", "file02", "file1b", "file", "2024 notes", "notes", "file1"];
+        names.sort_by(|left, right| natural_cmp(left, right));
+        assert_eq!(
+            names,
+            vec!["2024 notes", "file", "file1", "file1b", "File2", "file2", "file02", "file10", "notes"]
+        );
+        let huge = "x123456789012345678901234567890";
+        assert_eq!(natural_cmp(huge, "x99"), Ordering::Greater);
+
+        let entries = vec![
+            node("b.txt", false, 5),
+            node("docs", true, 0),
+            node("a10.md", false, 1),
+            node("a9.md", false, 9),
+        ];
+        let names = |entries: Vec<FileNode>| entries.into_iter().map(|node| node.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(sort_entries(entries.clone(), SortSpec::default())),
+            vec!["docs", "a9.md", "a10.md", "b.txt"]
+        );
+        let by_size = SortSpec {
+            key: SortKey::Size,
+            direction: SortDirection::Desc,
+            dirs_first: true,
+        };
+        assert_eq!(names(sort_entries(entries.clone(), by_size)), vec!["docs", "a9.md", "b.txt", "a10.md"]);
+        let by_type = SortSpec {
+            key: SortKey::Type,
+            dirs_first: false,
+            ..SortSpec::default()
+        };
+        assert_eq!(names(sort_entries(entries, by_type)), vec!["docs", "a9.md", "a10.md", "b.txt"]);
+    }
+}
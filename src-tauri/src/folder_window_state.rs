@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+use tauri_plugin_window_state::{StateFlags, WindowExt};
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::paths::resolve_command_path;
+use crate::settings_recovery::save_settings_store;
+use crate::{settings_store_path, WindowStateSnapshot};
+
+pub(crate) const FOLDER_WINDOW_STATES_KEY: &str = "folder_window_states";
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+struct TrackedWindow {
+    folder: String,
+    generation: u64,
+}
+
+/// Which folder each window currently shows, so move/resize events know where to save.
+#[derive(Default)]
+pub struct FolderWindowState {
+    windows: StdMutex<HashMap<String, TrackedWindow>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ScreenArea {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+fn folder_key(folder: &str) -> String {
+    let normalized = folder.trim().replace('\\', "/");
+    let trimmed = normalized.trim_end_matches('/');
+    if trimmed.is_empty() {
+        normalized
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn read_folder_states(app: &AppHandle) -> HashMap<String, WindowStateSnapshot> {
+    app.store(settings_store_path(app))
+        .ok()
+        .and_then(|store| store.get(FOLDER_WINDOW_STATES_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn write_folder_state(app: &AppHandle, folder: &str, snapshot: WindowStateSnapshot) -> Result<(), LatticeError> {
+    let mut states = read_folder_states(app);
+    states.insert(folder_key(folder), snapshot);
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    store.set(
+        FOLDER_WINDOW_STATES_KEY,
+        serde_json::to_value(states).map_err(|error| error.to_string())?,
+    );
+    save_settings_store(app).map_err(Into::into)
+}
+
+/// Geometry is kept in physical pixels so positions stay unambiguous across mixed-DPI displays.
+pub(crate) fn capture_window_state(
+    window: &WebviewWindow,
+    previous: Option<&WindowStateSnapshot>,
+) -> Result<WindowStateSnapshot, String> {
+    let is_maximized = window.is_maximized().map_err(|error| error.to_string())?;
+    // A maximized window reports the screen size; keep the restored geometry underneath it.
+    if let (true, Some(previous)) = (is_maximized, previous) {
+        return Ok(WindowStateSnapshot {
+            is_maximized,
+            ..previous.clone()
+        });
+    }
+
+    let position = window.outer_position().map_err(|error| error.to_string())?;
+    let size = window.inner_size().map_err(|error| error.to_string())?;
+    Ok(WindowStateSnapshot {
+        width: f64::from(size.width),
+        height: f64::from(size.height),
+        x: f64::from(position.x),
+        y: f64::from(position.y),
+        is_maximized,
+    })
+}
+
+fn overlap(snapshot: &WindowStateSnapshot, area: &ScreenArea) -> f64 {
+    let width = (snapshot.x + snapshot.width).min(area.x + area.width) - snapshot.x.max(area.x);
+    let height = (snapshot.y + snapshot.height).min(area.y + area.height) - snapshot.y.max(area.y);
+    width.max(0.0) * height.max(0.0)
+}
+
+/// Moves the window onto the display it overlaps most, or the first (primary) display
+/// when it is entirely off-screen, shrinking it if it no longer fits.
+fn clamp_to_screens(snapshot: &WindowStateSnapshot, areas: &[ScreenArea]) -> WindowStateSnapshot {
+    let Some(first) = areas.first() else {
+        return snapshot.clone();
+    };
+    let target = areas
+        .iter()
+        .filter(|area| overlap(snapshot, area) > 0.0)
+        .max_by(|left, right| overlap(snapshot, left).total_cmp(&overlap(snapshot, right)))
+        .unwrap_or(first);
+
+    let width = snapshot.width.min(target.width);
+    let height = snapshot.height.min(target.height);
+    WindowStateSnapshot {
+        width,
+        height,
+        x: snapshot.x.clamp(target.x, target.x + target.width - width),
+        y: snapshot.y.clamp(target.y, target.y + target.height - height),
+        is_maximized: snapshot.is_maximized,
+    }
+}
+
+fn connected_screen_areas(window: &WebviewWindow) -> Vec<ScreenArea> {
+    let primary = window.primary_monitor().ok().flatten();
+    let mut monitors = window.available_monitors().unwrap_or_default();
+    if let Some(primary) = primary {
+        if let Some(index) = monitors
+            .iter()
+            .position(|monitor| monitor.position() == primary.position())
+        {
+            monitors.swap(0, index);
+        }
+    }
+
+    monitors
+        .iter()
+        .map(|monitor| {
+            let area = monitor.work_area();
+            ScreenArea {
+                x: f64::from(area.position.x),
+                y: f64::from(area.position.y),
+                width: f64::from(area.size.width),
+                height: f64::from(area.size.height),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn apply_window_state(window: &WebviewWindow, snapshot: &WindowStateSnapshot) -> Result<(), LatticeError> {
+    let snapshot = clamp_to_screens(snapshot, &connected_screen_areas(window));
+    if window.is_maximized().unwrap_or(false) {
+        window.unmaximize().map_err(|error| error.to_string())?;
+    }
+    window
+        .set_size(PhysicalSize::new(snapshot.width.round() as u32, snapshot.height.round() as u32))
+        .map_err(|error| error.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(snapshot.x.round() as i32, snapshot.y.round() as i32))
+        .map_err(|error| error.to_string())?;
+    if snapshot.is_maximized {
+        window.maximize().map_err(|error| error.to_string())?;
+    }
+    Ok(())
+}
+
+fn save_window_state(app: &AppHandle, window: &WebviewWindow, folder: &str) -> Result<(), LatticeError> {
+    let previous = read_folder_states(app).remove(&folder_key(folder));
+    let snapshot = capture_window_state(window, previous.as_ref())?;
+    write_folder_state(app, folder, snapshot)
+}
+
+/// Applies the folder's saved geometry, or the global window-state when it has none.
+/// Returns whether per-folder geometry was found.
+pub(crate) fn restore_for_folder(app: &AppHandle, window: &WebviewWindow, folder: &str) -> Result<bool, LatticeError> {
+    let key = folder_key(folder);
+    {
+        let state = app.state::<FolderWindowState>();
+        let mut windows = state.windows.lock().map_err(|error| error.to_string())?;
+        let tracked = windows.entry(window.label().to_string()).or_insert(TrackedWindow {
+            folder: String::new(),
+            generation: 0,
+        });
+        // Re-opening the folder a window already shows must not snap it back.
+        if tracked.folder == key {
+            return Ok(false);
+        }
+        tracked.folder = key.clone();
+        tracked.generation += 1;
+    }
+
+    match read_folder_states(app).remove(&key) {
+        Some(snapshot) => apply_window_state(window, &snapshot).map(|_| true),
+        None => window
+            .restore_state(StateFlags::SIZE | StateFlags::POSITION | StateFlags::MAXIMIZED)
+            .map(|_| false)
+            .map_err(|error| error.to_string().into()),
+    }
+}
+
+/// Called for every move/resize; only the last event in a burst is written to the store.
+pub(crate) fn schedule_save(app: &AppHandle, label: &str) {
+    let generation = {
+        let state = app.state::<FolderWindowState>();
+        let Ok(mut windows) = state.windows.lock() else {
+            return;
+        };
+        let Some(tracked) = windows.get_mut(label) else {
+            return;
+        };
+        tracked.generation += 1;
+        tracked.generation
+    };
+
+    let app = app.clone();
+    let label = label.to_string();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DEBOUNCE).await;
+        let folder = {
+            let state = app.state::<FolderWindowState>();
+            let Ok(windows) = state.windows.lock() else {
+                return;
+            };
+            match windows.get(&label) {
+                Some(tracked) if tracked.generation == generation => tracked.folder.clone(),
+                _ => return,
+            }
+        };
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = save_window_state(&app, &window, &folder);
+        }
+    });
+}
+
+/// The folder a window last restored geometry for, in its normalized form.
+pub(crate) fn tracked_folder(app: &AppHandle, label: &str) -> Option<String> {
+    let state = app.state::<FolderWindowState>();
+    let windows = state.windows.lock().ok()?;
+    windows
+        .get(label)
+        .map(|tracked| tracked.folder.clone())
+        .filter(|folder| !folder.is_empty())
+}
+
+/// Records that a window shows `folder` without touching its geometry, so the
+/// frontend's follow-up `restore_window_state_for_folder` leaves it where it was put.
+pub(crate) fn track_folder(app: &AppHandle, label: &str, folder: &str) {
+    if let Ok(mut windows) = app.state::<FolderWindowState>().windows.lock() {
+        let tracked = windows.entry(label.to_string()).or_insert(TrackedWindow {
+            folder: String::new(),
+            generation: 0,
+        });
+        tracked.folder = folder_key(folder);
+        tracked.generation += 1;
+    }
+}
+
+pub(crate) fn forget_window(app: &AppHandle, label: &str) {
+    if let Ok(mut windows) = app.state::<FolderWindowState>().windows.lock() {
+        windows.remove(label);
+    }
+}
+
+#[tauri::command]
+pub fn save_window_state_for_folder(app: AppHandle, window: WebviewWindow, folder: String) -> Result<(), LatticeError> {
+    log_command("save_window_state_for_folder", || {
+        if folder.trim().is_empty() {
+            return Err(LatticeError::InvalidInput {
+                message: "Folder cannot be empty.".to_string(),
+            });
+        }
+        let folder = resolve_command_path(&app, &folder)?;
+        save_window_state(&app, &window, &folder.to_string_lossy())
+    })
+}
+
+#[tauri::command]
+pub fn restore_window_state_for_folder(app: AppHandle, window: WebviewWindow, folder: String) -> Result<bool, LatticeError> {
+    log_command("restore_window_state_for_folder", || {
+        if folder.trim().is_empty() {
+            return Err(LatticeError::InvalidInput {
+                message: "Folder cannot be empty.".to_string(),
+            });
+        }
+        let folder = resolve_command_path(&app, &folder)?;
+        restore_for_folder(&app, &window, &folder.to_string_lossy())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(x: f64, y: f64, width: f64, height: f64) -> WindowStateSnapshot {
+        WindowStateSnapshot {
+            width,
+            height,
+            x,
+            y,
+            is_maximized: false,
+        }
+    }
+
+    #[test]
+    fn restored_geometry_is_clamped_onto_a_connected_display() {
+        let primary = ScreenArea {
+            x: 0.0,
+            y: 0.0,
+            width: 1920.0,
+            height: 1040.0,
+        };
+        let right = ScreenArea {
+            x: 1920.0,
+            y: 0.0,
+            width: 1280.0,
+            height: 1000.0,
+        };
+
+        let on_right = snapshot(2000.0, 100.0, 800.0, 600.0);
+        assert_eq!(clamp_to_screens(&on_right, &[primary, right]), on_right);
+
+        // The right display was unplugged: fall back to the primary one.
+        let moved = clamp_to_screens(&on_right, &[primary]);
+        assert_eq!(moved, snapshot(1120.0, 100.0, 800.0, 600.0));
+
+        let oversized = clamp_to_screens(&snapshot(1800.0, -50.0, 1600.0, 1200.0), &[primary, right]);
+        assert_eq!(oversized, snapshot(1920.0, 0.0, 1280.0, 1000.0));
+
+        assert_eq!(clamp_to_screens(&on_right, &[]), on_right);
+    }
+
+    #[test]
+    fn folder_keys_ignore_separators_and_trailing_slashes() {
+        assert_eq!(folder_key(" C:\\notes\\ "), "C:/notes");
+        assert_eq!(folder_key("/home/me/code/"), "/home/me/code");
+        assert_eq!(folder_key("/"), "/");
+    }
+}
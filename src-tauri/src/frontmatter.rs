@@ -0,0 +1,162 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+
+/// The closing fence has to turn up within this much of the file; past it the block
+/// is treated as unterminated rather than read to the end.
+const MAX_FRONTMATTER_BYTES: u64 = 64 * 1024;
+const BOM: &str = "\u{feff}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontmatterFormat {
+    /// Fenced by `---`.
+    Yaml,
+    /// Fenced by `+++`.
+    Toml,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Frontmatter {
+    pub format: FrontmatterFormat,
+    /// Always a mapping; TOML tables are converted so callers handle one shape.
+    pub data: serde_yaml::Value,
+    /// Byte offset in the file where the body after the closing fence starts.
+    pub body_offset: usize,
+}
+
+fn fence_format(line: &str) -> Option<FrontmatterFormat> {
+    match line.trim_end() {
+        "---" => Some(FrontmatterFormat::Yaml),
+        "+++" => Some(FrontmatterFormat::Toml),
+        _ => None,
+    }
+}
+
+fn is_closing_fence(line: &str, format: FrontmatterFormat) -> bool {
+    let line = line.trim_end();
+    match format {
+        // YAML documents may also end with `...`.
+        FrontmatterFormat::Yaml => line == "---" || line == "...",
+        FrontmatterFormat::Toml => line == "+++",
+    }
+}
+
+fn parse_block(block: &str, format: FrontmatterFormat) -> Result<serde_yaml::Value, LatticeError> {
+    let invalid = |error: &dyn std::fmt::Display| LatticeError::InvalidInput {
+        message: format!("Invalid frontmatter: {error}"),
+    };
+    let data = match format {
+        FrontmatterFormat::Yaml => serde_yaml::from_str(block).map_err(|error| invalid(&error))?,
+        FrontmatterFormat::Toml => {
+            let table: toml::Table = toml::from_str(block).map_err(|error| invalid(&error))?;
+            serde_yaml::to_value(table).map_err(|error| invalid(&error))?
+        }
+    };
+    match data {
+        // An empty block is still frontmatter, just with nothing in it.
+        serde_yaml::Value::Null => Ok(serde_yaml::Value::Mapping(serde_yaml::Mapping::new())),
+        serde_yaml::Value::Mapping(_) => Ok(data),
+        _ => Err(LatticeError::InvalidInput {
+            message: "Frontmatter must be a mapping of keys to values".to_string(),
+        }),
+    }
+}
+
+/// Byte offset just past the closing fence, without parsing the block in between.
+pub(crate) fn frontmatter_end(text: &str) -> Option<usize> {
+    let start = if text.starts_with(BOM) { BOM.len() } else { 0 };
+    let mut lines = text[start..].split_inclusive('\n');
+    let opening = lines.next()?;
+    let format = fence_format(opening)?;
+    let mut offset = start + opening.len();
+    for line in lines {
+        offset += line.len();
+        if is_closing_fence(line, format) {
+            return Some(offset);
+        }
+    }
+    None
+}
+
+/// `None` when the text doesn't open with a fence or the fence is never closed.
+fn parse_frontmatter(text: &str) -> Result<Option<Frontmatter>, LatticeError> {
+    let start = if text.starts_with(BOM) { BOM.len() } else { 0 };
+    let mut lines = text[start..].split_inclusive('\n');
+    let Some((opening, format)) = lines.next().and_then(|line| Some((line, fence_format(line)?))) else {
+        return Ok(None);
+    };
+    let block_start = start + opening.len();
+
+    let mut offset = block_start;
+    for line in lines {
+        let line_end = offset + line.len();
+        if is_closing_fence(line, format) {
+            return Ok(Some(Frontmatter {
+                format,
+                data: parse_block(&text[block_start..offset], format)?,
+                body_offset: line_end,
+            }));
+        }
+        offset = line_end;
+    }
+    Ok(None)
+}
+
+fn read_frontmatter_sync(path: &Path) -> Result<Option<Frontmatter>, LatticeError> {
+    let file = fs::File::open(path).map_err(|error| LatticeError::at_path(path, error))?;
+    let mut head = Vec::new();
+    file.take(MAX_FRONTMATTER_BYTES).read_to_end(&mut head)?;
+    // Offsets must stay byte-exact, so only the valid UTF-8 prefix is looked at, e.g. when
+    // the cap cuts a character in half.
+    let text = match std::str::from_utf8(&head) {
+        Ok(text) => text,
+        Err(error) => std::str::from_utf8(&head[..error.valid_up_to()]).unwrap_or_default(),
+    };
+    parse_frontmatter(text)
+}
+
+#[tauri::command]
+pub async fn read_frontmatter(app: AppHandle, path: String) -> Result<Option<Frontmatter>, LatticeError> {
+    log_command_async("read_frontmatter", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || read_frontmatter_sync(&path))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yaml_and_toml_blocks_are_parsed_and_anything_else_is_none() {
+        let yaml = "---\ntitle: Notes\ntags: [physics, lab]\n---\n# Body\n";
+        let parsed = parse_frontmatter(yaml).unwrap().unwrap();
+        assert_eq!(parsed.format, FrontmatterFormat::Yaml);
+        assert_eq!(parsed.data["title"].as_str(), Some("Notes"));
+        assert_eq!(parsed.data["tags"][1].as_str(), Some("lab"));
+        assert_eq!(&yaml[parsed.body_offset..], "# Body\n");
+
+        let toml = "\u{feff}+++\r\ntitle = \"Draft\"\r\n+++\r\nBody";
+        let parsed = parse_frontmatter(toml).unwrap().unwrap();
+        assert_eq!(parsed.format, FrontmatterFormat::Toml);
+        assert_eq!(parsed.data["title"].as_str(), Some("Draft"));
+        assert_eq!(&toml[parsed.body_offset..], "Body");
+
+        assert_eq!(parse_frontmatter("---\n---").unwrap().unwrap().body_offset, 7);
+        assert!(parse_frontmatter("# No frontmatter\n---\n").unwrap().is_none());
+        assert!(parse_frontmatter("---\ntitle: never closed\n").unwrap().is_none());
+        assert!(parse_frontmatter("---\n- a list\n---\n").is_err());
+    }
+}
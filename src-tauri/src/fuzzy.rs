@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::UNIX_EPOCH;
+
+use ignore::WalkBuilder;
+use notify::RecursiveMode;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::watcher::{
+    create_event_watcher, register_watch, spawn_debounced_event_loop, WatchHandle, WatchKind, WatchOwner,
+};
+use crate::ignore_rules::{matcher_for, IgnoreMatcher};
+use crate::DesktopFsState;
+
+const MAX_INDEXED_FILES: usize = 200_000;
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_CONSECUTIVE: i64 = 24;
+const BONUS_SEGMENT_START: i64 = 32;
+const BONUS_FILE_NAME: i64 = 8;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuzzyMatch {
+    pub path: String,
+    pub relative_path: String,
+    pub score: i64,
+    /// UTF-16 offsets into `relative_path` of each matched character.
+    pub indices: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct IndexedFile {
+    path: String,
+    relative_path: String,
+    modified: Option<u64>,
+}
+
+struct CachedFileList {
+    files: Arc<Vec<IndexedFile>>,
+    _watch: WatchHandle,
+}
+
+#[derive(Default)]
+pub struct FuzzyIndexState {
+    roots: StdMutex<HashMap<PathBuf, CachedFileList>>,
+}
+
+fn collect_indexed_files(root: &Path, ignore: Arc<IgnoreMatcher>) -> Vec<IndexedFile> {
+    let mut files = Vec::new();
+    let walker = WalkBuilder::new(root)
+        .require_git(false)
+        .filter_entry(move |entry| {
+            !ignore.is_ignored(entry.path(), entry.file_type().is_some_and(|file_type| file_type.is_dir()))
+        })
+        .build();
+    for entry in walker.flatten() {
+        if files.len() >= MAX_INDEXED_FILES {
+            break;
+        }
+        if !entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+            continue;
+        }
+
+        let path = entry.path();
+        let relative_path = path.strip_prefix(root).unwrap_or(path).to_string_lossy().to_string();
+        let modified = entry
+            .metadata()
+            .ok()
+            .and_then(|metadata| metadata.modified().ok())
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as u64);
+        files.push(IndexedFile {
+            path: path.to_string_lossy().to_string(),
+            relative_path,
+            modified,
+        });
+    }
+    files
+}
+
+fn is_segment_start(chars: &[char], index: usize) -> bool {
+    let Some(previous) = index.checked_sub(1).map(|previous| chars[previous]) else {
+        return true;
+    };
+    matches!(previous, '/' | '\\' | '_' | '-' | '.' | ' ')
+        || (previous.is_lowercase() && chars[index].is_uppercase())
+}
+
+/// Scores `candidate` against an already-lowercased query. Returns `None`
+/// unless every query character appears in order.
+fn fuzzy_score(candidate: &str, query: &[char]) -> Option<(i64, Vec<usize>)> {
+    let chars: Vec<char> = candidate.chars().collect();
+    let lowered: Vec<char> = chars
+        .iter()
+        .map(|character| character.to_lowercase().next().unwrap_or(*character))
+        .collect();
+
+    let mut cursor = 0usize;
+    for needle in query {
+        cursor += lowered[cursor..].iter().position(|character| character == needle)? + 1;
+    }
+
+    let file_name_start = chars
+        .iter()
+        .rposition(|character| matches!(character, '/' | '\\'))
+        .map_or(0, |separator| separator + 1);
+    let position_bonus = |index: usize| {
+        let mut bonus = 0;
+        if is_segment_start(&chars, index) {
+            bonus += BONUS_SEGMENT_START;
+        }
+        if index >= file_name_start {
+            bonus += BONUS_FILE_NAME;
+        }
+        bonus
+    };
+
+    // best[i][j]: best score with query[i] matched at candidate position j.
+    let width = chars.len();
+    let mut best = vec![vec![None::<i64>; width]; query.len()];
+    let mut previous_position = vec![vec![0usize; width]; query.len()];
+
+    for (j, character) in lowered.iter().enumerate() {
+        if *character == query[0] {
+            best[0][j] = Some(SCORE_MATCH + position_bonus(j));
+        }
+    }
+
+    for i in 1..query.len() {
+        // Gapped predecessors pay one point per skipped character; tracking
+        // `score + k` lets the best gapped predecessor be kept as a running max.
+        let mut running_gap: Option<(i64, usize)> = None;
+        for j in 1..width {
+            if j >= 2 {
+                if let Some(score) = best[i - 1][j - 2] {
+                    let candidate_gap = score + (j - 2) as i64;
+                    if running_gap.is_none_or(|(current, _)| candidate_gap > current) {
+                        running_gap = Some((candidate_gap, j - 2));
+                    }
+                }
+            }
+            if lowered[j] != query[i] {
+                continue;
+            }
+
+            let consecutive = best[i - 1][j - 1].map(|score| (score + BONUS_CONSECUTIVE, j - 1));
+            let gapped = running_gap.map(|(score, k)| (score - (j as i64 - 1), k));
+            let chosen = match (consecutive, gapped) {
+                (Some(left), Some(right)) => Some(if right.0 > left.0 { right } else { left }),
+                (left, right) => left.or(right),
+            };
+            if let Some((score, k)) = chosen {
+                best[i][j] = Some(score + SCORE_MATCH + position_bonus(j));
+                previous_position[i][j] = k;
+            }
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut position, score) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .max_by_key(|(_, score)| *score)?;
+
+    let mut char_indices = vec![0usize; query.len()];
+    for i in (0..query.len()).rev() {
+        char_indices[i] = position;
+        position = previous_position[i][position];
+    }
+
+    let mut utf16_offsets = Vec::with_capacity(chars.len());
+    let mut offset = 0usize;
+    for character in &chars {
+        utf16_offsets.push(offset);
+        offset += character.len_utf16();
+    }
+    Some((score, char_indices.into_iter().map(|index| utf16_offsets[index]).collect()))
+}
+
+fn rank_files(files: &[IndexedFile], query: &str, limit: usize) -> Vec<FuzzyMatch> {
+    let needle: Vec<char> = query
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .map(|character| character.to_lowercase().next().unwrap_or(character))
+        .collect();
+
+    if needle.is_empty() {
+        let mut recent: Vec<&IndexedFile> = files.iter().collect();
+        recent.sort_by_key(|file| std::cmp::Reverse(file.modified));
+        return recent
+            .into_iter()
+            .take(limit)
+            .map(|file| FuzzyMatch {
+                path: file.path.clone(),
+                relative_path: file.relative_path.clone(),
+                score: 0,
+                indices: Vec::new(),
+            })
+            .collect();
+    }
+
+    let mut matches: Vec<FuzzyMatch> = files
+        .iter()
+        .filter_map(|file| {
+            fuzzy_score(&file.relative_path, &needle).map(|(score, indices)| FuzzyMatch {
+                path: file.path.clone(),
+                relative_path: file.relative_path.clone(),
+                score,
+                indices,
+            })
+        })
+        .collect();
+    matches.sort_by(|left, right| {
+        right
+            .score
+            .cmp(&left.score)
+            .then_with(|| left.relative_path.len().cmp(&right.relative_path.len()))
+            .then_with(|| left.relative_path.cmp(&right.relative_path))
+    });
+    matches.truncate(limit);
+    matches
+}
+
+fn invalidate_root(app: &AppHandle, root: &Path) {
+    let state = app.state::<FuzzyIndexState>();
+    let removed = state.roots.lock().ok().and_then(|mut roots| roots.remove(root));
+    drop(removed);
+}
+
+pub(crate) fn invalidate_roots_under(app: &AppHandle, folder: &Path) {
+    let state = app.state::<FuzzyIndexState>();
+    let removed: Vec<CachedFileList> = match state.roots.lock() {
+        Ok(mut roots) => {
+            let stale: Vec<PathBuf> = roots.keys().filter(|root| root.starts_with(folder)).cloned().collect();
+            stale.iter().filter_map(|root| roots.remove(root)).collect()
+        }
+        Err(_) => Vec::new(),
+    };
+    // Watchers are dropped outside the lock.
+    drop(removed);
+}
+
+/// Drops every cached file list; the next query for a root walks it again.
+pub(crate) fn clear_fuzzy_cache(app: &AppHandle) {
+    let state = app.state::<FuzzyIndexState>();
+    let removed = state.roots.lock().map(|mut roots| std::mem::take(&mut *roots)).unwrap_or_default();
+    drop(removed);
+}
+
+fn cached_file_list(app: &AppHandle, root: &Path) -> Option<Arc<Vec<IndexedFile>>> {
+    let state = app.state::<FuzzyIndexState>();
+    let roots = state.roots.lock().ok()?;
+    roots.get(root).map(|cached| cached.files.clone())
+}
+
+fn cache_file_list(app: &AppHandle, root: PathBuf, files: Arc<Vec<IndexedFile>>) {
+    // Without a watcher the list would go stale silently, so skip caching instead.
+    let Ok((watcher, receiver)) = create_event_watcher(&root, RecursiveMode::Recursive) else {
+        return;
+    };
+
+    let app_for_events = app.clone();
+    let root_for_events = root.clone();
+    spawn_debounced_event_loop(receiver, move |_batch| {
+        invalidate_root(&app_for_events, &root_for_events);
+        false
+    });
+
+    // Torn down from outside, the list can't be trusted any more.
+    let app_for_release = app.clone();
+    let root_for_release = root.clone();
+    let watch = register_watch(app, WatchOwner::FuzzyCache, WatchKind::Folder, root.clone(), None, watcher, move || {
+        invalidate_root(&app_for_release, &root_for_release)
+    });
+
+    let state = app.state::<FuzzyIndexState>();
+    if let Ok(mut roots) = state.roots.lock() {
+        roots.insert(root, CachedFileList { files, _watch: watch });
+    };
+}
+
+#[tauri::command]
+pub async fn fuzzy_find(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+    query: String,
+    limit: usize,
+) -> Result<Vec<FuzzyMatch>, LatticeError> {
+    log_command_async("fuzzy_find", async move {
+        let root = fs::canonicalize(resolve_command_path(&app, &root)?)?;
+        if !root.is_dir() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Search root is not a directory: {}", root.display()),
+            });
+        }
+
+        let files = match cached_file_list(&app, &root) {
+            Some(files) => files,
+            None => {
+                let permit = fs_state
+                    .read_dir_permits
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .map_err(|error| error.to_string())?;
+                let root_for_walk = root.clone();
+                let ignore = matcher_for(&app, &root);
+                let files = tokio::task::spawn_blocking(move || {
+                    let _permit = permit;
+                    Arc::new(collect_indexed_files(&root_for_walk, ignore))
+                })
+                .await
+                .map_err(|error| error.to_string())?;
+                cache_file_list(&app, root, files.clone());
+                files
+            }
+        };
+
+        tokio::task::spawn_blocking(move || rank_files(&files, &query, limit))
+            .await
+            .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indexed(relative_path: &str, modified: u64) -> IndexedFile {
+        IndexedFile {
+            path: format!("/root/{relative_path}"),
+            relative_path: relative_path.to_string(),
+            modified: Some(modified),
+        }
+    }
+
+    #[test]
+    fn favors_consecutive_and_segment_start_matches() {
+        let files = vec![
+            indexed("docs/random_thoughts.md", 1),
+            indexed("src/main.rs", 2),
+            indexed("notes/my_archive.md", 3),
+        ];
+
+        let matches = rank_files(&files, "main", 10);
+        assert_eq!(matches[0].relative_path, "src/main.rs");
+        assert_eq!(matches[0].indices, vec![4, 5, 6, 7]);
+
+        // Two segment starts outrank a consecutive run in the middle of nowhere.
+        let segment = rank_files(&files, "ma", 10);
+        assert_eq!(segment[0].relative_path, "notes/my_archive.md");
+        assert_eq!(segment[0].indices, vec![6, 9]);
+        assert!(rank_files(&files, "zzz", 10).is_empty());
+    }
+
+    #[test]
+    fn reports_utf16_indices_and_empty_query_returns_recent_files() {
+        let files = vec![indexed("格致/lattice.md", 5), indexed("old.md", 1), indexed("new.md", 9)];
+
+        let matches = rank_files(&files, "lat", 10);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].indices, vec![3, 4, 5]);
+
+        let recent: Vec<String> = rank_files(&files, "", 2)
+            .into_iter()
+            .map(|found| found.relative_path)
+            .collect();
+        assert_eq!(recent, vec!["new.md".to_string(), "格致/lattice.md".to_string()]);
+    }
+}
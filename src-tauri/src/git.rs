@@ -0,0 +1,259 @@
+use std::path::Path;
+
+use git2::{BranchType, ErrorCode, Repository, Status, StatusOptions};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileStatus {
+    pub path: String,
+    /// Relative to the repository work tree, always `/`-separated.
+    pub relative_path: String,
+    pub modified: bool,
+    pub staged: bool,
+    pub untracked: bool,
+    pub ignored: bool,
+    pub conflicted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranchInfo {
+    /// `None` when HEAD is detached.
+    pub branch: Option<String>,
+    pub head_commit: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+const STAGED_FLAGS: Status = Status::INDEX_NEW
+    .union(Status::INDEX_MODIFIED)
+    .union(Status::INDEX_DELETED)
+    .union(Status::INDEX_RENAMED)
+    .union(Status::INDEX_TYPECHANGE);
+const MODIFIED_FLAGS: Status = Status::WT_MODIFIED
+    .union(Status::WT_DELETED)
+    .union(Status::WT_RENAMED)
+    .union(Status::WT_TYPECHANGE);
+
+fn open_repository(folder: &Path) -> Result<Repository, LatticeError> {
+    Repository::discover(folder).map_err(|error| match error.code() {
+        ErrorCode::NotFound => LatticeError::NotARepository {
+            message: format!("Not inside a git repository: {}", folder.display()),
+        },
+        _ => error.to_string().into(),
+    })
+}
+
+fn collect_status(folder: &Path) -> Result<Vec<GitFileStatus>, LatticeError> {
+    let repository = open_repository(folder)?;
+    let Some(workdir) = repository.workdir().map(Path::to_path_buf) else {
+        return Err("Bare repositories have no working tree.".into());
+    };
+
+    // Ignored directories are reported once instead of being walked.
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(true)
+        .recurse_ignored_dirs(false)
+        .exclude_submodules(true);
+    let statuses = repository.statuses(Some(&mut options)).map_err(|error| error.to_string())?;
+
+    let mut files = statuses
+        .iter()
+        .filter_map(|entry| {
+            let relative_path = entry.path()?.trim_end_matches('/').to_string();
+            let status = entry.status();
+            Some(GitFileStatus {
+                path: workdir.join(&relative_path).to_string_lossy().to_string(),
+                relative_path,
+                modified: status.intersects(MODIFIED_FLAGS),
+                staged: status.intersects(STAGED_FLAGS),
+                untracked: status.contains(Status::WT_NEW),
+                ignored: status.contains(Status::IGNORED),
+                conflicted: status.contains(Status::CONFLICTED),
+            })
+        })
+        .collect::<Vec<_>>();
+    files.sort_by(|left, right| left.relative_path.cmp(&right.relative_path));
+    Ok(files)
+}
+
+fn collect_branch_info(folder: &Path) -> Result<GitBranchInfo, LatticeError> {
+    let repository = open_repository(folder)?;
+    let head = match repository.head() {
+        Ok(head) => head,
+        // A fresh repository has a HEAD pointing at a branch with no commits yet.
+        Err(error) if error.code() == ErrorCode::UnbornBranch => {
+            let branch = repository
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|head| head.symbolic_target().map(str::to_string))
+                .map(|target| target.trim_start_matches("refs/heads/").to_string());
+            return Ok(GitBranchInfo {
+                branch,
+                head_commit: None,
+                upstream: None,
+                ahead: 0,
+                behind: 0,
+            });
+        }
+        Err(error) => return Err(error.to_string().into()),
+    };
+
+    let head_oid = head.target();
+    let mut info = GitBranchInfo {
+        branch: head.is_branch().then(|| head.shorthand().map(str::to_string)).flatten(),
+        head_commit: head_oid.map(|oid| oid.to_string()),
+        upstream: None,
+        ahead: 0,
+        behind: 0,
+    };
+
+    let Some(branch_name) = info.branch.as_deref() else {
+        return Ok(info);
+    };
+    let Ok(upstream) = repository
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|branch| branch.upstream())
+    else {
+        return Ok(info);
+    };
+    info.upstream = upstream.name().ok().flatten().map(str::to_string);
+    if let (Some(local), Some(remote)) = (head_oid, upstream.get().target()) {
+        let (ahead, behind) = repository.graph_ahead_behind(local, remote).map_err(|error| error.to_string())?;
+        info.ahead = ahead;
+        info.behind = behind;
+    }
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn git_status(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    repo: String,
+) -> Result<Vec<GitFileStatus>, LatticeError> {
+    log_command_async("git_status", async move {
+        let repo = resolve_command_path(&app, &repo)?;
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            collect_status(&repo)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn git_branch_info(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    repo: String,
+) -> Result<GitBranchInfo, LatticeError> {
+    log_command_async("git_branch_info", async move {
+        let repo = resolve_command_path(&app, &repo)?;
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            collect_branch_info(&repo)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    use git2::{RepositoryInitOptions, Signature};
+
+    fn commit_all(repository: &Repository, message: &str) {
+        let mut index = repository.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+        let tree = repository.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Lattice", "lattice@example.com").unwrap();
+        let parent = repository.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        repository
+            .commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+            .unwrap();
+    }
+
+    #[test]
+    fn reports_status_flags_and_branch_without_walking_ignored_folders() {
+        let root = std::env::temp_dir().join(format!("lattice-git-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes")).unwrap();
+        let mut init = RepositoryInitOptions::new();
+        init.initial_head("main");
+        let repository = Repository::init_opts(&root, &init).unwrap();
+
+        assert_eq!(collect_branch_info(&root).unwrap().branch.as_deref(), Some("main"));
+
+        fs::write(root.join(".gitignore"), "build/\n").unwrap();
+        fs::write(root.join("notes/a.md"), "alpha").unwrap();
+        fs::write(root.join("notes/b.md"), "beta").unwrap();
+        commit_all(&repository, "initial");
+
+        fs::write(root.join("notes/a.md"), "alpha, edited").unwrap();
+        fs::write(root.join("notes/b.md"), "beta, staged").unwrap();
+        let mut index = repository.index().unwrap();
+        index.add_path(Path::new("notes/b.md")).unwrap();
+        index.write().unwrap();
+        fs::write(root.join("notes/c.md"), "gamma").unwrap();
+        fs::create_dir_all(root.join("build/deep")).unwrap();
+        fs::write(root.join("build/deep/out.txt"), "ignored").unwrap();
+
+        let files = collect_status(&root.join("notes")).unwrap();
+        let by_path = |path: &str| files.iter().find(|file| file.relative_path == path).unwrap();
+        assert_eq!(files.len(), 4);
+        assert!(by_path("notes/a.md").modified && !by_path("notes/a.md").staged);
+        assert!(by_path("notes/b.md").staged && !by_path("notes/b.md").modified);
+        assert!(by_path("notes/c.md").untracked);
+        assert!(by_path("build").ignored);
+
+        let info = collect_branch_info(&root).unwrap();
+        assert_eq!(info.branch.as_deref(), Some("main"));
+        assert!(info.head_commit.is_some() && info.upstream.is_none());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn folders_outside_a_repository_are_reported_as_such() {
+        let root = std::env::temp_dir().join(format!("lattice-git-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+
+        assert!(matches!(collect_status(&root), Err(LatticeError::NotARepository { .. })));
+        assert!(matches!(collect_branch_info(&root), Err(LatticeError::NotARepository { .. })));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
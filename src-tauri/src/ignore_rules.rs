@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::SystemTime;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use tauri::{AppHandle, Manager};
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::{resolve_command_path, resolve_command_path_in};
+use crate::workspace_settings::read_ignore_patterns;
+use crate::DesktopPreviewState;
+
+/// Files whose modification invalidates a cached matcher for their root.
+const STAMP_FILES: [&str; 3] = [".gitignore", ".git/info/exclude", ".lattice/workspace.json"];
+
+/// Combines `.gitignore` files with the workspace's `ignorePatterns` setting.
+/// User patterns are checked first, so `!keep.tmp` can re-include a file they hid.
+pub(crate) struct IgnoreMatcher {
+    root: PathBuf,
+    user: Gitignore,
+    /// Per-directory `.gitignore` files, loaded the first time a path below them is checked.
+    gitignores: StdMutex<HashMap<PathBuf, Option<Arc<Gitignore>>>>,
+}
+
+struct CachedMatcher {
+    stamp: Vec<Option<SystemTime>>,
+    matcher: Arc<IgnoreMatcher>,
+}
+
+#[derive(Default)]
+pub struct IgnoreMatcherState {
+    matchers: StdMutex<HashMap<PathBuf, CachedMatcher>>,
+}
+
+fn decide(matched: Match<&ignore::gitignore::Glob>) -> Option<bool> {
+    match matched {
+        Match::Ignore(_) => Some(true),
+        Match::Whitelist(_) => Some(false),
+        Match::None => None,
+    }
+}
+
+impl IgnoreMatcher {
+    pub(crate) fn new(root: &Path, patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns.iter().map(|pattern| pattern.trim()).filter(|pattern| !pattern.is_empty()) {
+            // One bad glob shouldn't disable the rest of the list.
+            let _ = builder.add_line(None, pattern);
+        }
+        Self {
+            root: root.to_path_buf(),
+            user: builder.build().unwrap_or_else(|_| Gitignore::empty()),
+            gitignores: StdMutex::new(HashMap::new()),
+        }
+    }
+
+    fn gitignore_for(&self, directory: &Path) -> Option<Arc<Gitignore>> {
+        let mut gitignores = self.gitignores.lock().ok()?;
+        gitignores
+            .entry(directory.to_path_buf())
+            .or_insert_with(|| {
+                let mut builder = GitignoreBuilder::new(directory);
+                let mut found = false;
+                let mut candidates = vec![directory.join(".gitignore")];
+                if directory == self.root {
+                    candidates.push(directory.join(".git/info/exclude"));
+                }
+                for candidate in candidates.into_iter().filter(|candidate| candidate.is_file()) {
+                    found |= builder.add(candidate).is_none();
+                }
+                found
+                    .then(|| builder.build().ok())
+                    .flatten()
+                    .filter(|gitignore| !gitignore.is_empty())
+                    .map(Arc::new)
+            })
+            .clone()
+    }
+
+    /// `path` must be expressed under the matcher's (canonical) root; anything else is kept.
+    pub(crate) fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+        if let Some(ignored) = decide(self.user.matched_path_or_any_parents(relative, is_dir)) {
+            return ignored;
+        }
+
+        // Like git, a deeper `.gitignore` overrides the ones above it.
+        let mut directory = path.parent();
+        while let Some(current) = directory.filter(|current| current.starts_with(&self.root)) {
+            if let Some(gitignore) = self.gitignore_for(current) {
+                let below = path.strip_prefix(current).unwrap_or(relative);
+                if let Some(ignored) = decide(gitignore.matched_path_or_any_parents(below, is_dir)) {
+                    return ignored;
+                }
+            }
+            directory = current.parent();
+        }
+        false
+    }
+}
+
+fn stamp_for(root: &Path) -> Vec<Option<SystemTime>> {
+    STAMP_FILES
+        .iter()
+        .map(|file| fs::metadata(root.join(file)).and_then(|metadata| metadata.modified()).ok())
+        .collect()
+}
+
+/// User patterns live with the open workspace, so walks of a subfolder use its rules.
+fn ignore_root_for(app: &AppHandle, path: &Path) -> PathBuf {
+    let workspace_root = app
+        .state::<DesktopPreviewState>()
+        .workspace_root
+        .lock()
+        .ok()
+        .and_then(|root| root.clone());
+    match workspace_root {
+        Some(root) if path.starts_with(&root) => root,
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Returns the cached matcher covering `path`, which must be canonical.
+pub(crate) fn matcher_for(app: &AppHandle, path: &Path) -> Arc<IgnoreMatcher> {
+    let root = ignore_root_for(app, path);
+    let stamp = stamp_for(&root);
+    let state = app.state::<IgnoreMatcherState>();
+    if let Ok(matchers) = state.matchers.lock() {
+        if let Some(cached) = matchers.get(&root).filter(|cached| cached.stamp == stamp) {
+            return cached.matcher.clone();
+        }
+    }
+
+    let matcher = Arc::new(IgnoreMatcher::new(&root, &read_ignore_patterns(app, &root)));
+    if let Ok(mut matchers) = state.matchers.lock() {
+        matchers.insert(
+            root,
+            CachedMatcher {
+                stamp,
+                matcher: matcher.clone(),
+            },
+        );
+    }
+    matcher
+}
+
+/// Drops cached rules (and the fuzzy file lists built with them) at or below `folder`.
+pub(crate) fn invalidate_ignore_matchers(app: &AppHandle, folder: &Path) {
+    let folder = fs::canonicalize(folder).unwrap_or_else(|_| folder.to_path_buf());
+    if let Ok(mut matchers) = app.state::<IgnoreMatcherState>().matchers.lock() {
+        matchers.retain(|root, _| !root.starts_with(&folder));
+    }
+    crate::fuzzy::invalidate_roots_under(app, &folder);
+}
+
+#[tauri::command]
+pub async fn test_ignore(app: AppHandle, root: String, path: String) -> Result<bool, LatticeError> {
+    log_command_async("test_ignore", async move {
+        let root = fs::canonicalize(resolve_command_path(&app, &root)?)?;
+        let path = resolve_command_path_in(&app, &path, &root)?;
+        // The path may already be hidden because it no longer exists; judge it by name then.
+        let path = fs::canonicalize(&path).unwrap_or(path);
+
+        tokio::task::spawn_blocking(move || {
+            let is_dir = path.is_dir();
+            matcher_for(&app, &root).is_ignored(&path, is_dir)
+        })
+        .await
+        .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_patterns_layer_over_nested_gitignores_with_negation() {
+        let root = std::env::temp_dir().join(format!("lattice-ignore-rules-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("build/out")).unwrap();
+        fs::create_dir_all(root.join("notes/drafts")).unwrap();
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(root.join("notes/.gitignore"), "drafts/\n!keep.log\n").unwrap();
+
+        let patterns = vec!["build/".to_string(), "*.tmp".to_string(), "!keep.tmp".to_string()];
+        let matcher = IgnoreMatcher::new(&root, &patterns);
+
+        assert!(matcher.is_ignored(&root.join("build"), true));
+        assert!(matcher.is_ignored(&root.join("build/out/app.js"), false));
+        assert!(matcher.is_ignored(&root.join("scratch.tmp"), false));
+        assert!(!matcher.is_ignored(&root.join("keep.tmp"), false));
+        assert!(matcher.is_ignored(&root.join("debug.log"), false));
+        assert!(!matcher.is_ignored(&root.join("notes/keep.log"), false));
+        assert!(matcher.is_ignored(&root.join("notes/drafts/idea.md"), false));
+        assert!(!matcher.is_ignored(&root.join("notes/readme.md"), false));
+        assert!(!matcher.is_ignored(&root, true));
+        assert!(!matcher.is_ignored(Path::new("/elsewhere/scratch.tmp"), false));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
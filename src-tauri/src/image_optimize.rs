@@ -0,0 +1,188 @@
+use std::fs;
+use std::path::Path;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ImageDecoder, ImageError, ImageFormat, ImageReader};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::error::LatticeError;
+use crate::fileops::write_bytes_atomic;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+/// GIF is left out on purpose: re-encoding would drop every frame after the first.
+const OPTIMIZABLE_FORMATS: [ImageFormat; 3] = [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizeReport {
+    pub path: String,
+    pub bytes_before: u64,
+    /// Size of the re-encoded image, whether or not it was written.
+    pub bytes_after: u64,
+    pub width: u32,
+    pub height: u32,
+    pub written: bool,
+}
+
+fn decode_failed(path: &Path, error: ImageError) -> LatticeError {
+    match error {
+        ImageError::Unsupported(_) => LatticeError::Unsupported {
+            message: format!("Not a supported image: {}", path.display()),
+        },
+        error => LatticeError::Failed {
+            message: format!("Failed to decode {}: {error}", path.display()),
+        },
+    }
+}
+
+/// Applies the EXIF rotation while decoding, since none of the encoders write EXIF back.
+fn decode_oriented(path: &Path) -> Result<(DynamicImage, ImageFormat), LatticeError> {
+    let reader = ImageReader::open(path)?.with_guessed_format()?;
+    let Some(format) = reader.format().filter(|format| OPTIMIZABLE_FORMATS.contains(format)) else {
+        return Err(LatticeError::Unsupported {
+            message: format!("Only JPEG, PNG and WebP images can be optimized: {}", path.display()),
+        });
+    };
+
+    let mut decoder = reader.into_decoder().map_err(|error| decode_failed(path, error))?;
+    let orientation = decoder.orientation().map_err(|error| decode_failed(path, error))?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|error| decode_failed(path, error))?;
+    image.apply_orientation(orientation);
+    Ok((image, format))
+}
+
+/// `quality` only affects JPEG; PNG gets maximum compression and WebP is encoded losslessly,
+/// the only WebP encoding the `image` crate offers.
+fn encode(image: &DynamicImage, format: ImageFormat, quality: u8) -> Result<Vec<u8>, LatticeError> {
+    let mut encoded = Vec::new();
+    let result = match format {
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut encoded, quality)),
+        ImageFormat::Png => image.write_with_encoder(PngEncoder::new_with_quality(
+            &mut encoded,
+            CompressionType::Best,
+            FilterType::Adaptive,
+        )),
+        _ if image.color().has_alpha() => {
+            DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(WebPEncoder::new_lossless(&mut encoded))
+        }
+        _ => DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(WebPEncoder::new_lossless(&mut encoded)),
+    };
+    result.map_err(|error| error.to_string())?;
+    Ok(encoded)
+}
+
+fn optimize_image_sync(
+    path: &Path,
+    quality: u8,
+    max_dim: Option<u32>,
+    force: bool,
+) -> Result<OptimizeReport, LatticeError> {
+    let path = fs::canonicalize(path)?;
+    let bytes_before = fs::metadata(&path)?.len();
+    let (image, format) = decode_oriented(&path)?;
+    let image = match max_dim {
+        Some(max_dim) if image.width() > max_dim || image.height() > max_dim => image.thumbnail(max_dim, max_dim),
+        _ => image,
+    };
+
+    let encoded = encode(&image, format, quality)?;
+    let bytes_after = encoded.len() as u64;
+    // Re-encoding an already optimized file often grows it; keep the original then.
+    let written = force || bytes_after < bytes_before;
+    if written {
+        write_bytes_atomic(&path, &encoded)?;
+    }
+
+    Ok(OptimizeReport {
+        path: path.to_string_lossy().to_string(),
+        bytes_before,
+        bytes_after,
+        width: image.width(),
+        height: image.height(),
+        written,
+    })
+}
+
+/// Shrinks a JPEG, PNG or WebP image in place, optionally downscaling it to fit `max_dim`.
+/// The file is only replaced when the result is smaller, unless `force` is set.
+#[tauri::command]
+pub async fn optimize_image(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+    quality: u8,
+    max_dim: Option<u32>,
+    force: Option<bool>,
+) -> Result<OptimizeReport, LatticeError> {
+    log_command_async("optimize_image", async move {
+        let path = resolve_command_path(&app, &path)?;
+        if !(1..=100).contains(&quality) {
+            return Err(LatticeError::InvalidInput {
+                message: "quality must be between 1 and 100".to_string(),
+            });
+        }
+        if max_dim == Some(0) {
+            return Err(LatticeError::InvalidInput {
+                message: "max_dim must be greater than zero".to_string(),
+            });
+        }
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            optimize_image_sync(&path, quality, max_dim, force.unwrap_or(false))
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn images_shrink_in_place_only_when_smaller() {
+        let root = std::env::temp_dir().join(format!("lattice-optimize-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let photo = root.join("photo.jpg");
+        let noisy = RgbImage::from_fn(400, 200, |x, y| {
+            Rgb([(x * 7 % 256) as u8, (y * 13 % 256) as u8, ((x ^ y) % 256) as u8])
+        });
+        DynamicImage::ImageRgb8(noisy).save_with_format(&photo, ImageFormat::Jpeg).unwrap();
+
+        let report = optimize_image_sync(&photo, 40, Some(100), false).unwrap();
+        assert!(report.written);
+        assert_eq!((report.width, report.height), (100, 50));
+        assert!(report.bytes_after < report.bytes_before);
+        assert_eq!(fs::metadata(&photo).unwrap().len(), report.bytes_after);
+
+        let optimized = fs::read(&photo).unwrap();
+        let report = optimize_image_sync(&photo, 100, None, false).unwrap();
+        assert!(!report.written);
+        assert_eq!(fs::read(&photo).unwrap(), optimized);
+        assert!(optimize_image_sync(&photo, 100, None, true).unwrap().written);
+
+        let gif = root.join("anim.gif");
+        DynamicImage::ImageRgb8(RgbImage::new(4, 4)).save_with_format(&gif, ImageFormat::Gif).unwrap();
+        assert!(matches!(
+            optimize_image_sync(&gif, 80, None, false),
+            Err(LatticeError::Unsupported { .. })
+        ));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,593 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Instant, UNIX_EPOCH};
+
+use ignore::WalkBuilder;
+use notify::RecursiveMode;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::LatticeError;
+use crate::ignore_rules::{matcher_for, IgnoreMatcher};
+use crate::logging::{log_command, log_command_async};
+use crate::paths::resolve_command_path;
+use crate::search::{looks_binary, SearchHit, SearchMatchRange};
+use crate::watcher::{
+    create_event_watcher, register_watch, spawn_debounced_event_loop, WatchHandle, WatchKind, WatchOwner,
+};
+
+const INDEX_PROGRESS_EVENT: &str = "index-progress";
+const INDEX_READY_EVENT: &str = "index-ready";
+const INDEX_CACHE_PATH: &str = ".lattice/index.bin";
+/// Bumped whenever the persisted layout or tokenizer changes, so old caches are ignored.
+const INDEX_FORMAT_VERSION: u32 = 1;
+const MAX_INDEXED_FILES: usize = 200_000;
+const MAX_INDEXED_FILE_BYTES: u64 = 2 * 1024 * 1024;
+const MAX_TOKEN_CHARS: usize = 64;
+const MAX_QUERY_HITS: usize = 1000;
+const PROGRESS_EVERY_FILES: usize = 500;
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+type FileId = u32;
+
+struct IndexedDoc {
+    path: PathBuf,
+    modified: Option<u64>,
+    tokens: Vec<String>,
+}
+
+/// Postings map each lowercased word to the files and 1-based lines it appears on.
+/// Removed files leave an empty slot so the ids of the others stay valid.
+#[derive(Default)]
+struct InvertedIndex {
+    docs: Vec<Option<IndexedDoc>>,
+    ids: HashMap<PathBuf, FileId>,
+    postings: HashMap<String, BTreeMap<FileId, Vec<u32>>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedDoc {
+    relative_path: String,
+    modified: Option<u64>,
+    postings: Vec<(String, Vec<u32>)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedIndex {
+    version: u32,
+    docs: Vec<PersistedDoc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexProgressPayload {
+    root: String,
+    indexed_files: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexReadyPayload {
+    root: String,
+    files: usize,
+    terms: usize,
+    duration_ms: u64,
+}
+
+struct ActiveIndex {
+    root: PathBuf,
+    index: Arc<StdMutex<InvertedIndex>>,
+    _watch: Option<WatchHandle>,
+}
+
+/// One index at a time, for the most recently opened folder. Each build bumps the
+/// generation; a build or watcher that is no longer current stops on its own.
+#[derive(Default)]
+pub struct IndexState {
+    active: StdMutex<Option<ActiveIndex>>,
+    generation: AtomicU64,
+}
+
+fn tokenize(line: &str) -> impl Iterator<Item = String> + '_ {
+    line.split(|character: char| !character.is_alphanumeric() && character != '_')
+        .filter(|word| !word.is_empty() && word.chars().count() <= MAX_TOKEN_CHARS)
+        .map(str::to_lowercase)
+}
+
+fn line_postings(text: &str) -> BTreeMap<String, Vec<u32>> {
+    let mut postings: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index as u32 + 1;
+        for token in tokenize(line) {
+            let lines = postings.entry(token).or_default();
+            if lines.last() != Some(&line_number) {
+                lines.push(line_number);
+            }
+        }
+    }
+    postings
+}
+
+impl InvertedIndex {
+    fn insert(&mut self, path: PathBuf, modified: Option<u64>, postings: BTreeMap<String, Vec<u32>>) {
+        // Re-indexing a file keeps its id so saves don't grow `docs`.
+        let reused = self.ids.get(&path).copied();
+        self.remove(&path);
+        let id = reused.unwrap_or(self.docs.len() as FileId);
+        for (token, lines) in &postings {
+            self.postings.entry(token.clone()).or_default().insert(id, lines.clone());
+        }
+        self.ids.insert(path.clone(), id);
+        let doc = Some(IndexedDoc {
+            path,
+            modified,
+            tokens: postings.into_keys().collect(),
+        });
+        match reused {
+            Some(id) => self.docs[id as usize] = doc,
+            None => self.docs.push(doc),
+        }
+    }
+
+    fn remove(&mut self, path: &Path) {
+        let Some(id) = self.ids.remove(path) else {
+            return;
+        };
+        let Some(doc) = self.docs[id as usize].take() else {
+            return;
+        };
+        for token in doc.tokens {
+            if let Some(files) = self.postings.get_mut(&token) {
+                files.remove(&id);
+                if files.is_empty() {
+                    self.postings.remove(&token);
+                }
+            }
+        }
+    }
+
+    /// Everything at or below `path`, for folders that were deleted or renamed away.
+    fn remove_under(&mut self, path: &Path) {
+        let stale: Vec<PathBuf> = self.ids.keys().filter(|indexed| indexed.starts_with(path)).cloned().collect();
+        for indexed in stale {
+            self.remove(&indexed);
+        }
+    }
+
+    fn modified(&self, path: &Path) -> Option<Option<u64>> {
+        let id = self.ids.get(path)?;
+        self.docs[*id as usize].as_ref().map(|doc| doc.modified)
+    }
+
+    fn file_count(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Lines containing every token, ordered by path and line.
+    fn matching_lines(&self, tokens: &[String], limit: usize) -> Vec<(PathBuf, u32)> {
+        let mut lists = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            match self.postings.get(token) {
+                Some(files) => lists.push(files),
+                None => return Vec::new(),
+            }
+        }
+        // Walking the rarest token's postings keeps the intersection cheap.
+        lists.sort_by_key(|files| files.len());
+        let Some((rarest, others)) = lists.split_first() else {
+            return Vec::new();
+        };
+
+        let mut found = Vec::new();
+        for (id, lines) in rarest.iter() {
+            let Some(doc) = self.docs[*id as usize].as_ref() else {
+                continue;
+            };
+            for line in lines {
+                if others.iter().all(|files| files.get(id).is_some_and(|lines| lines.binary_search(line).is_ok())) {
+                    found.push((doc.path.clone(), *line));
+                }
+            }
+        }
+        found.sort();
+        found.truncate(limit);
+        found
+    }
+
+    fn to_persisted(&self, root: &Path) -> PersistedIndex {
+        let mut by_doc: HashMap<FileId, Vec<(String, Vec<u32>)>> = HashMap::new();
+        for (token, files) in &self.postings {
+            for (id, lines) in files {
+                by_doc.entry(*id).or_default().push((token.clone(), lines.clone()));
+            }
+        }
+        let docs = self
+            .ids
+            .iter()
+            .filter_map(|(path, id)| {
+                let doc = self.docs[*id as usize].as_ref()?;
+                Some(PersistedDoc {
+                    relative_path: path.strip_prefix(root).ok()?.to_string_lossy().to_string(),
+                    modified: doc.modified,
+                    postings: by_doc.remove(id).unwrap_or_default(),
+                })
+            })
+            .collect();
+        PersistedIndex {
+            version: INDEX_FORMAT_VERSION,
+            docs,
+        }
+    }
+
+    fn from_persisted(root: &Path, persisted: PersistedIndex) -> Self {
+        let mut index = Self::default();
+        for doc in persisted.docs {
+            index.insert(root.join(doc.relative_path), doc.modified, doc.postings.into_iter().collect());
+        }
+        index
+    }
+}
+
+fn cache_path(root: &Path) -> PathBuf {
+    root.join(INDEX_CACHE_PATH)
+}
+
+fn load_cached_index(root: &Path) -> Option<InvertedIndex> {
+    let bytes = fs::read(cache_path(root)).ok()?;
+    let persisted: PersistedIndex = bincode::deserialize(&bytes).ok()?;
+    (persisted.version == INDEX_FORMAT_VERSION).then(|| InvertedIndex::from_persisted(root, persisted))
+}
+
+fn persist_index(root: &Path, index: &InvertedIndex) -> Result<(), LatticeError> {
+    let bytes = bincode::serialize(&index.to_persisted(root)).map_err(|error| error.to_string())?;
+    let path = cache_path(root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::fileops::write_bytes_atomic(&path, &bytes)
+}
+
+fn modified_ms(metadata: &fs::Metadata) -> Option<u64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+}
+
+/// Large and binary files are left out; `None` means "not indexable".
+fn read_indexable_text(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    if looks_binary(&bytes[..bytes.len().min(BINARY_SNIFF_BYTES)]) {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Hidden entries are skipped like the walker does, which also keeps the cache file
+/// itself from triggering re-indexing.
+fn is_hidden_below(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root).is_ok_and(|relative| {
+        relative
+            .components()
+            .any(|component| matches!(component, Component::Normal(name) if name.to_string_lossy().starts_with('.')))
+    })
+}
+
+/// Brings one path in line with the disk: re-reads changed files and drops gone ones.
+fn refresh_path(index: &StdMutex<InvertedIndex>, root: &Path, ignore: &IgnoreMatcher, path: &Path) {
+    if is_hidden_below(root, path) {
+        return;
+    }
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => {
+            if let Ok(mut index) = index.lock() {
+                index.remove_under(path);
+            }
+            return;
+        }
+    };
+    if !metadata.is_file() {
+        return;
+    }
+    if ignore.is_ignored(path, false) || metadata.len() > MAX_INDEXED_FILE_BYTES {
+        if let Ok(mut index) = index.lock() {
+            index.remove(path);
+        }
+        return;
+    }
+
+    let modified = modified_ms(&metadata);
+    let unchanged = index
+        .lock()
+        .ok()
+        .is_some_and(|index| index.modified(path) == Some(modified));
+    if unchanged {
+        return;
+    }
+    let Some(text) = read_indexable_text(path) else {
+        return;
+    };
+    let postings = line_postings(&text);
+    if let Ok(mut index) = index.lock() {
+        index.insert(path.to_path_buf(), modified, postings);
+    }
+}
+
+fn is_current(app: &AppHandle, generation: u64) -> bool {
+    app.state::<IndexState>().generation.load(Ordering::SeqCst) == generation
+}
+
+fn build_index(app: &AppHandle, root: &Path, index: &StdMutex<InvertedIndex>, generation: u64, use_cache: bool) {
+    let started = Instant::now();
+    // The cache seeds the index so unchanged files are not re-read; the walk below then
+    // corrects whatever changed while the app was closed.
+    if let Some(cached) = use_cache.then(|| load_cached_index(root)).flatten() {
+        if let Ok(mut index) = index.lock() {
+            if index.file_count() == 0 {
+                *index = cached;
+            }
+        }
+    }
+    let ignore = matcher_for(app, root);
+    let cached_paths: HashSet<PathBuf> = index
+        .lock()
+        .map(|index| index.ids.keys().cloned().collect())
+        .unwrap_or_default();
+
+    let ignore_for_walk = ignore.clone();
+    let walker = WalkBuilder::new(root)
+        .require_git(false)
+        .filter_entry(move |entry| {
+            !ignore_for_walk.is_ignored(entry.path(), entry.file_type().is_some_and(|file_type| file_type.is_dir()))
+        })
+        .build();
+
+    let mut seen = HashSet::new();
+    for entry in walker.flatten() {
+        if !entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+            continue;
+        }
+        if seen.len() >= MAX_INDEXED_FILES || !is_current(app, generation) {
+            break;
+        }
+        refresh_path(index, root, &ignore, entry.path());
+        seen.insert(entry.path().to_path_buf());
+        if seen.len() % PROGRESS_EVERY_FILES == 0 {
+            let _ = app.emit(
+                INDEX_PROGRESS_EVENT,
+                IndexProgressPayload {
+                    root: root.to_string_lossy().to_string(),
+                    indexed_files: seen.len(),
+                },
+            );
+        }
+    }
+    if !is_current(app, generation) {
+        return;
+    }
+
+    // Cached entries the walk didn't reach are stale, unless the watcher added them since.
+    for stale in cached_paths.difference(&seen) {
+        refresh_path(index, root, &ignore, stale);
+    }
+
+    let Ok(index) = index.lock() else {
+        return;
+    };
+    if let Err(error) = persist_index(root, &index) {
+        log::warn!("Failed to save search index for {}: {error}", root.display());
+    }
+    let _ = app.emit(
+        INDEX_READY_EVENT,
+        IndexReadyPayload {
+            root: root.to_string_lossy().to_string(),
+            files: index.file_count(),
+            terms: index.postings.len(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        },
+    );
+}
+
+fn watch_index(app: &AppHandle, root: &Path, index: Arc<StdMutex<InvertedIndex>>, generation: u64) -> Option<WatchHandle> {
+    let (watcher, receiver) = create_event_watcher(root, RecursiveMode::Recursive).ok()?;
+    let app_for_events = app.clone();
+    let root_for_events = root.to_path_buf();
+    spawn_debounced_event_loop(receiver, move |batch| {
+        if !is_current(&app_for_events, generation) {
+            return false;
+        }
+        let ignore = matcher_for(&app_for_events, &root_for_events);
+        let paths: HashSet<PathBuf> = batch.into_iter().flat_map(|change| change.paths).map(PathBuf::from).collect();
+        for path in paths {
+            refresh_path(&index, &root_for_events, &ignore, &path);
+        }
+        true
+    });
+    // An index nothing keeps current would answer from stale contents, so it goes too.
+    let app_for_release = app.clone();
+    let on_release = move || {
+        if is_current(&app_for_release, generation) {
+            release_index(&app_for_release);
+        }
+    };
+    let owner = WatchOwner::SearchIndex;
+    Some(register_watch(app, owner, WatchKind::Folder, root.to_path_buf(), None, watcher, on_release))
+}
+
+/// Starts indexing `folder` in the background, replacing the previous folder's index.
+/// Reopening the folder that is already indexed is a no-op unless `force` is set.
+pub(crate) fn index_folder(app: &AppHandle, folder: &str, force: bool) -> Result<(), LatticeError> {
+    let root = fs::canonicalize(resolve_command_path(app, folder)?)?;
+    if !root.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Index root is not a directory: {}", root.display()),
+        });
+    }
+
+    let state = app.state::<IndexState>();
+    let mut active = state.active.lock().map_err(|error| error.to_string())?;
+    if !force && active.as_ref().is_some_and(|active| active.root == root) {
+        return Ok(());
+    }
+
+    let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let index = Arc::new(StdMutex::new(InvertedIndex::default()));
+    let watch = watch_index(app, &root, index.clone(), generation);
+    let previous = active.replace(ActiveIndex {
+        root: root.clone(),
+        index: index.clone(),
+        _watch: watch,
+    });
+    drop(active);
+    drop(previous);
+
+    let app = app.clone();
+    // A forced rebuild skips the cache so a suspect one is really gone.
+    tauri::async_runtime::spawn_blocking(move || build_index(&app, &root, &index, generation, !force));
+    Ok(())
+}
+
+/// Frees the in-memory index; reopening the folder loads it back from the on-disk cache.
+pub(crate) fn release_index(app: &AppHandle) {
+    let state = app.state::<IndexState>();
+    // Stops a build that is still running along with the watcher.
+    state.generation.fetch_add(1, Ordering::SeqCst);
+    let released = state.active.lock().ok().and_then(|mut active| active.take());
+    drop(released);
+}
+
+fn query_terms(terms: &[String]) -> Vec<String> {
+    let mut tokens: Vec<String> = terms.iter().flat_map(|term| tokenize(term).collect::<Vec<_>>()).collect();
+    tokens.sort();
+    tokens.dedup();
+    tokens
+}
+
+/// Re-reads the matched lines so hits carry current text and UTF-16 match ranges,
+/// the same shape `search_contents` returns.
+fn hits_for_lines(lines: Vec<(PathBuf, u32)>, tokens: &[String]) -> Vec<SearchHit> {
+    let alternatives: Vec<String> = tokens.iter().map(|token| regex::escape(token)).collect();
+    let Ok(pattern) = RegexBuilder::new(&format!(r"\b(?:{})\b", alternatives.join("|")))
+        .case_insensitive(true)
+        .build()
+    else {
+        return Vec::new();
+    };
+
+    let mut hits = Vec::new();
+    let mut current: Option<(PathBuf, Vec<String>)> = None;
+    for (path, line_number) in lines {
+        if current.as_ref().is_none_or(|(open, _)| open != &path) {
+            let text = read_indexable_text(&path).unwrap_or_default();
+            current = Some((path.clone(), text.lines().map(str::to_string).collect()));
+        }
+        let Some(line) = current.as_ref().and_then(|(_, file_lines)| file_lines.get(line_number as usize - 1)) else {
+            continue;
+        };
+        let matches: Vec<SearchMatchRange> = pattern
+            .find_iter(line)
+            .map(|found| SearchMatchRange {
+                start: line[..found.start()].encode_utf16().count(),
+                end: line[..found.end()].encode_utf16().count(),
+            })
+            .collect();
+        // The file changed after it was indexed; the watcher will catch up shortly.
+        if matches.is_empty() {
+            continue;
+        }
+        hits.push(SearchHit {
+            path: path.to_string_lossy().to_string(),
+            line_number: line_number as usize,
+            line_text: line.clone(),
+            matches,
+        });
+    }
+    hits
+}
+
+/// Answers from the index of the most recently opened folder: lines containing every
+/// word of `terms`, matched case-insensitively as whole words.
+#[tauri::command]
+pub async fn query_index(state: State<'_, IndexState>, terms: Vec<String>) -> Result<Vec<SearchHit>, LatticeError> {
+    log_command_async("query_index", async move {
+        let tokens = query_terms(&terms);
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+        let index = match state.active.lock().map_err(|error| error.to_string())?.as_ref() {
+            Some(active) => active.index.clone(),
+            None => return Err(LatticeError::NotFound {
+                message: "No folder has been indexed yet.".to_string(),
+            }),
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let lines = index
+                .lock()
+                .map_err(|error| error.to_string())?
+                .matching_lines(&tokens, MAX_QUERY_HITS);
+            Ok(hits_for_lines(lines, &tokens))
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Discards the cached index for `root` and builds it again from scratch.
+#[tauri::command]
+pub fn rebuild_index(app: AppHandle, root: String) -> Result<(), LatticeError> {
+    log_command("rebuild_index", || index_folder(&app, &root, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn answers_multi_word_queries_and_forgets_removed_files() {
+        let mut index = InvertedIndex::default();
+        index.insert(PathBuf::from("/notes/a.md"), Some(1), line_postings("Lattice notes\nother lattice-notes line\nnotes"));
+        index.insert(PathBuf::from("/notes/b.md"), Some(1), line_postings("lattice only"));
+
+        let tokens = query_terms(&["LATTICE".to_string(), "notes".to_string()]);
+        assert_eq!(
+            index.matching_lines(&tokens, 10),
+            vec![(PathBuf::from("/notes/a.md"), 1), (PathBuf::from("/notes/a.md"), 2)]
+        );
+        assert_eq!(index.matching_lines(&query_terms(&["lattice".to_string()]), 10).len(), 3);
+
+        // Re-inserting replaces the old postings rather than adding to them.
+        index.insert(PathBuf::from("/notes/a.md"), Some(2), line_postings("fresh text"));
+        assert!(index.matching_lines(&tokens, 10).is_empty());
+        index.remove_under(Path::new("/notes"));
+        assert_eq!(index.file_count(), 0);
+        assert!(index.postings.is_empty());
+    }
+
+    #[test]
+    fn persisted_index_round_trips_relative_to_the_root() {
+        let root = std::env::temp_dir().join(format!("lattice-index-{}", Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let mut index = InvertedIndex::default();
+        index.insert(root.join("a.md"), Some(7), line_postings("hello world"));
+        persist_index(&root, &index).unwrap();
+
+        let loaded = load_cached_index(&root).unwrap();
+        assert_eq!(loaded.modified(&root.join("a.md")), Some(Some(7)));
+        assert_eq!(
+            loaded.matching_lines(&query_terms(&["world".to_string()]), 10),
+            vec![(root.join("a.md"), 1)]
+        );
+        assert!(is_hidden_below(&root, &cache_path(&root)));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Semaphore;
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::paths::resolve_command_path;
+use crate::recursive_delete::{delete_recursive_sync, protected_folders, DeleteReport};
+use crate::transfer::{transfer_path, ConflictPolicy, TransferReport};
+use crate::{build_app_settings_from_store, save_app_settings, DesktopFsState};
+
+const JOB_PROGRESS_EVENT: &str = "job-progress";
+const JOB_DONE_EVENT: &str = "job-done";
+const JOB_ERROR_EVENT: &str = "job-error";
+const DEFAULT_PARALLELISM: u32 = 2;
+const MAX_PARALLELISM: u32 = 16;
+/// Finished jobs stay queryable through `job_status` until this many newer ones finish.
+const MAX_FINISHED_JOBS: usize = 100;
+
+pub type JobId = String;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum JobReport {
+    Transfer(TransferReport),
+    Delete(DeleteReport),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum JobStatus {
+    /// Waiting for a free worker.
+    Queued,
+    /// `done` and `total` are bytes for transfers and entries for deletes.
+    Running { done: u64, total: u64 },
+    /// Cancelled jobs end here too; their report says how far they got.
+    Done { report: JobReport },
+    /// `error` is what the matching one-shot command would have returned.
+    Failed { error: Value },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JobProgressPayload {
+    id: JobId,
+    done: u64,
+    total: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JobDonePayload {
+    id: JobId,
+    report: JobReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JobErrorPayload {
+    id: JobId,
+    error: Value,
+}
+
+struct JobEntry {
+    status: JobStatus,
+    cancelled: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+struct Jobs {
+    entries: HashMap<JobId, JobEntry>,
+    finished: VecDeque<JobId>,
+}
+
+pub struct JobsState {
+    jobs: StdMutex<Jobs>,
+    workers: Arc<Semaphore>,
+    parallelism: StdMutex<u32>,
+}
+
+impl Default for JobsState {
+    fn default() -> Self {
+        Self {
+            jobs: StdMutex::default(),
+            workers: Arc::new(Semaphore::new(DEFAULT_PARALLELISM as usize)),
+            parallelism: StdMutex::new(DEFAULT_PARALLELISM),
+        }
+    }
+}
+
+impl JobsState {
+    fn set_status(&self, id: &str, status: JobStatus) {
+        let Ok(mut jobs) = self.jobs.lock() else {
+            return;
+        };
+        if let Some(entry) = jobs.entries.get_mut(id) {
+            entry.status = status;
+        }
+    }
+
+    fn finish(&self, id: &str, status: JobStatus) {
+        let Ok(mut jobs) = self.jobs.lock() else {
+            return;
+        };
+        let Some(entry) = jobs.entries.get_mut(id) else {
+            return;
+        };
+        entry.status = status;
+        jobs.finished.push_back(id.to_string());
+        while jobs.finished.len() > MAX_FINISHED_JOBS {
+            if let Some(oldest) = jobs.finished.pop_front() {
+                jobs.entries.remove(&oldest);
+            }
+        }
+    }
+
+    /// Running jobs combined, or `None` when nothing is running.
+    fn overall_progress(&self) -> Option<f64> {
+        let jobs = self.jobs.lock().ok()?;
+        let (mut running, mut done, mut total) = (false, 0u64, 0u64);
+        for entry in jobs.entries.values() {
+            if let JobStatus::Running { done: job_done, total: job_total } = entry.status {
+                running = true;
+                done += job_done;
+                total += job_total;
+            }
+        }
+        running.then(|| if total == 0 { 0.0 } else { done as f64 / total as f64 })
+    }
+}
+
+/// Mirrors the running jobs on the taskbar or dock icon, clearing it once they're all done.
+fn show_job_progress(app: &AppHandle) {
+    let progress = app.state::<JobsState>().overall_progress();
+    if let Err(error) = crate::taskbar::apply_taskbar_progress(app, progress) {
+        log::debug!("Failed to show job progress on the taskbar: {error}");
+    }
+}
+
+pub(crate) fn normalize_job_parallelism(workers: Option<u32>) -> Option<u32> {
+    workers.filter(|workers| *workers > 0).map(|workers| workers.min(MAX_PARALLELISM))
+}
+
+/// Queued and running jobs keep their permits; lowering the cap takes effect as they finish.
+fn apply_parallelism(app: &AppHandle, workers: Option<u32>) -> Result<(), LatticeError> {
+    let state = app.state::<JobsState>();
+    let target = workers.unwrap_or(DEFAULT_PARALLELISM);
+    let mut current = state.parallelism.lock().map_err(|error| error.to_string())?;
+    if target > *current {
+        state.workers.add_permits((target - *current) as usize);
+    } else if target < *current {
+        let workers = state.workers.clone();
+        let surplus = *current - target;
+        tauri::async_runtime::spawn(async move {
+            if let Ok(permits) = workers.acquire_many_owned(surplus).await {
+                permits.forget();
+            }
+        });
+    }
+    *current = target;
+    Ok(())
+}
+
+/// Applies the saved parallelism cap during `setup`.
+pub(crate) fn restore_job_parallelism(app: &AppHandle) -> Result<(), String> {
+    let workers = build_app_settings_from_store(app)?.job_parallelism;
+    apply_parallelism(app, workers).map_err(|error| error.to_string())
+}
+
+fn job_error(error: impl Serialize) -> Value {
+    serde_json::to_value(error).unwrap_or_else(|error| Value::String(error.to_string()))
+}
+
+type JobWork = Box<dyn FnOnce(&AtomicBool, &mut dyn FnMut(u64, u64)) -> Result<JobReport, Value> + Send>;
+
+/// Returns as soon as the job is queued; it runs once one of the workers is free.
+fn spawn_job(app: &AppHandle, jobs_state: &JobsState, work: JobWork) -> Result<JobId, LatticeError> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    jobs_state.jobs.lock().map_err(|error| error.to_string())?.entries.insert(
+        id.clone(),
+        JobEntry {
+            status: JobStatus::Queued,
+            cancelled: cancelled.clone(),
+        },
+    );
+
+    let app = app.clone();
+    let job_id = id.clone();
+    tauri::async_runtime::spawn(async move {
+        let workers = app.state::<JobsState>().workers.clone();
+        let Ok(permit) = workers.acquire_owned().await else {
+            return;
+        };
+        // Every job copies, moves or deletes, so it also counts against the cap shared with
+        // the one-shot mutating commands for as long as it runs.
+        let mutate_permits = app.state::<DesktopFsState>().mutate_path_permits.clone();
+        let Ok(mutate_permit) = mutate_permits.acquire_owned().await else {
+            return;
+        };
+        app.state::<JobsState>()
+            .set_status(&job_id, JobStatus::Running { done: 0, total: 0 });
+        show_job_progress(&app);
+
+        let app_for_work = app.clone();
+        let id_for_work = job_id.clone();
+        let outcome = tokio::task::spawn_blocking(move || {
+            let _permits = (permit, mutate_permit);
+            let mut on_progress = |done: u64, total: u64| {
+                app_for_work
+                    .state::<JobsState>()
+                    .set_status(&id_for_work, JobStatus::Running { done, total });
+                show_job_progress(&app_for_work);
+                let _ = app_for_work.emit(
+                    JOB_PROGRESS_EVENT,
+                    JobProgressPayload {
+                        id: id_for_work.clone(),
+                        done,
+                        total,
+                    },
+                );
+            };
+            work(&cancelled, &mut on_progress)
+        })
+        .await
+        .unwrap_or_else(|error| Err(Value::String(error.to_string())));
+
+        let jobs_state = app.state::<JobsState>();
+        match outcome {
+            Ok(report) => {
+                jobs_state.finish(&job_id, JobStatus::Done { report: report.clone() });
+                let _ = app.emit(JOB_DONE_EVENT, JobDonePayload { id: job_id, report });
+            }
+            Err(error) => {
+                jobs_state.finish(&job_id, JobStatus::Failed { error: error.clone() });
+                let _ = app.emit(JOB_ERROR_EVENT, JobErrorPayload { id: job_id, error });
+            }
+        }
+        show_job_progress(&app);
+    });
+    Ok(id)
+}
+
+fn transfer_job(
+    app: &AppHandle,
+    src: &str,
+    dst: &str,
+    on_conflict: ConflictPolicy,
+    delete_source: bool,
+) -> Result<JobWork, LatticeError> {
+    let source = resolve_command_path(app, src)?;
+    let target = resolve_command_path(app, dst)?;
+    let app = app.clone();
+    Ok(Box::new(move |cancelled, on_progress| {
+        let report =
+            transfer_path(&source, &target, on_conflict, delete_source, cancelled, on_progress).map_err(job_error)?;
+        // Tags only follow a move that fully completed, as with `move_path`.
+        if let (true, true, false, Some(destination)) =
+            (delete_source, report.failed.is_empty(), report.cancelled, &report.destination)
+        {
+            crate::tags::follow_rename(&app, &source, Path::new(destination));
+        }
+        Ok(JobReport::Transfer(report))
+    }))
+}
+
+#[tauri::command]
+pub fn start_copy_job(
+    app: AppHandle,
+    jobs_state: State<'_, JobsState>,
+    src: String,
+    dst: String,
+    on_conflict: ConflictPolicy,
+) -> Result<JobId, LatticeError> {
+    log_command("start_copy_job", || {
+        spawn_job(&app, &jobs_state, transfer_job(&app, &src, &dst, on_conflict, false)?)
+    })
+}
+
+#[tauri::command]
+pub fn start_move_job(
+    app: AppHandle,
+    jobs_state: State<'_, JobsState>,
+    src: String,
+    dst: String,
+    on_conflict: ConflictPolicy,
+) -> Result<JobId, LatticeError> {
+    log_command("start_move_job", || {
+        spawn_job(&app, &jobs_state, transfer_job(&app, &src, &dst, on_conflict, true)?)
+    })
+}
+
+/// Like `delete_recursive` without a dry run; a trash move can't be stopped once it starts.
+#[tauri::command]
+pub fn start_delete_job(
+    app: AppHandle,
+    jobs_state: State<'_, JobsState>,
+    path: String,
+    permanent: bool,
+) -> Result<JobId, LatticeError> {
+    log_command("start_delete_job", || {
+        let target = resolve_command_path(&app, &path)?;
+        let protected = protected_folders(&app);
+        let work: JobWork = Box::new(move |cancelled, on_progress| {
+            delete_recursive_sync(&target, &protected, false, permanent, cancelled, on_progress)
+                .map(JobReport::Delete)
+                .map_err(job_error)
+        });
+        spawn_job(&app, &jobs_state, work)
+    })
+}
+
+#[tauri::command]
+pub fn job_status(jobs_state: State<'_, JobsState>, id: JobId) -> Result<JobStatus, LatticeError> {
+    log_command("job_status", || {
+        let jobs = jobs_state.jobs.lock().map_err(|error| error.to_string())?;
+        jobs.entries
+            .get(&id)
+            .map(|entry| entry.status.clone())
+            .ok_or_else(|| LatticeError::NotFound {
+                message: format!("No job with id: {id}"),
+            })
+    })
+}
+
+/// Cancellation is cooperative: the job stops at its next entry and reports what it did.
+#[tauri::command]
+pub fn cancel_job(jobs_state: State<'_, JobsState>, id: JobId) -> Result<(), LatticeError> {
+    log_command("cancel_job", || {
+        let jobs = jobs_state.jobs.lock().map_err(|error| error.to_string())?;
+        match jobs.entries.get(&id) {
+            Some(entry) => {
+                entry.cancelled.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+            None => Err(LatticeError::NotFound {
+                message: format!("No job with id: {id}"),
+            }),
+        }
+    })
+}
+
+#[tauri::command]
+pub fn get_job_parallelism(app: AppHandle) -> Result<u32, LatticeError> {
+    log_command("get_job_parallelism", || {
+        Ok(build_app_settings_from_store(&app)?
+            .job_parallelism
+            .unwrap_or(DEFAULT_PARALLELISM))
+    })
+}
+
+/// `None` goes back to the default. Returns the cap actually applied.
+#[tauri::command]
+pub fn set_job_parallelism(app: AppHandle, workers: Option<u32>) -> Result<u32, LatticeError> {
+    log_command("set_job_parallelism", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.job_parallelism = workers;
+        let workers = save_app_settings(&app, settings)?.job_parallelism;
+        apply_parallelism(&app, workers)?;
+        Ok(workers.unwrap_or(DEFAULT_PARALLELISM))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelled_copies_leave_no_half_written_files() {
+        let root = std::env::temp_dir().join(format!("lattice-jobs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("notes")).unwrap();
+        std::fs::write(root.join("notes/a.md"), "alpha").unwrap();
+        std::fs::write(root.join("notes/b.md"), "beta").unwrap();
+
+        let cancelled = AtomicBool::new(true);
+        let report = transfer_path(
+            &root.join("notes"),
+            &root.join("copy"),
+            ConflictPolicy::Skip,
+            false,
+            &cancelled,
+            &mut |_, _| {},
+        )
+        .unwrap();
+        assert!(report.cancelled);
+        assert!(!root.join("copy").exists());
+        assert!(root.join("notes/a.md").is_file());
+
+        let report = delete_recursive_sync(&root.join("notes"), &[], false, true, &cancelled, &mut |_, _| {}).unwrap();
+        assert!(report.cancelled);
+        assert_eq!(report.deleted, 0);
+        assert!(root.join("notes/b.md").is_file());
+
+        assert_eq!(normalize_job_parallelism(Some(0)), None);
+        assert_eq!(normalize_job_parallelism(Some(64)), Some(MAX_PARALLELISM));
+        let status = serde_json::to_value(JobStatus::Done {
+            report: JobReport::Delete(report),
+        })
+        .unwrap();
+        assert_eq!((status["state"].as_str(), status["report"]["kind"].as_str()), (Some("done"), Some("delete")));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,223 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use std::sync::Mutex as StdMutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::{build_app_settings_from_store, save_app_settings};
+
+const OPEN_FOLDER_EVENT: &str = "open-folder";
+const INITIAL_OPEN_EVENT: &str = "initial-open";
+const MAIN_WINDOW_LABEL: &str = "main";
+/// Set on processes we relaunch ourselves so they skip the single-instance check.
+const MULTI_INSTANCE_ENV: &str = "LATTICE_MULTI_INSTANCE";
+
+#[derive(Default)]
+struct LaunchQueue {
+    ready: bool,
+    /// A launch target that arrived before any webview called `frontend_ready`.
+    pending: Option<LaunchTarget>,
+}
+
+#[derive(Default)]
+pub struct LaunchState {
+    queue: StdMutex<LaunchQueue>,
+}
+
+/// A folder to open, plus the file inside it when the launch named a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LaunchTarget {
+    folder: String,
+    file: Option<String>,
+}
+
+/// Picks the first argument naming an existing path. `args` excludes the executable;
+/// flags (including macOS's `-psn_…`) are skipped.
+pub(crate) fn launch_target<I, S>(args: I, cwd: &Path) -> Option<LaunchTarget>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    args.into_iter().find_map(|arg| {
+        let arg = arg.as_ref().trim();
+        if arg.is_empty() || arg.starts_with('-') {
+            return None;
+        }
+        let path = PathBuf::from(arg);
+        let path = if path.is_absolute() { path } else { cwd.join(path) };
+        let path = fs::canonicalize(path).ok()?;
+        let metadata = fs::metadata(&path).ok()?;
+        if metadata.is_dir() {
+            return Some(LaunchTarget {
+                folder: path.to_string_lossy().to_string(),
+                file: None,
+            });
+        }
+        let folder = path.parent()?;
+        Some(LaunchTarget {
+            folder: folder.to_string_lossy().to_string(),
+            file: Some(path.to_string_lossy().to_string()),
+        })
+    })
+}
+
+/// Relaunched instances must not forward straight back to the one that spawned them.
+pub(crate) fn single_instance_enabled() -> bool {
+    std::env::var_os(MULTI_INSTANCE_ENV).is_none()
+}
+
+fn relaunch_as_new_instance(args: &[String], cwd: &str) -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|error| error.to_string())?;
+    StdCommand::new(exe)
+        .args(args.iter().skip(1))
+        .current_dir(cwd)
+        .env(MULTI_INSTANCE_ENV, "1")
+        .spawn()
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+fn focus_main_window(app: &AppHandle) -> Option<WebviewWindow> {
+    let window = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .or_else(|| app.webview_windows().into_values().next())?;
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+    Some(window)
+}
+
+fn remember_last_opened_folder(app: &AppHandle, folder: &str) {
+    let result = build_app_settings_from_store(app).and_then(|mut settings| {
+        settings.last_opened_folder = Some(folder.to_string());
+        save_app_settings(app, settings)
+    });
+    if let Err(error) = result {
+        log::error!("Failed to remember launch folder: {error}");
+    }
+}
+
+/// Holds the target until the frontend is listening, or sends it straight to the
+/// main window as `open-folder` once it is.
+fn deliver_launch_target(app: &AppHandle, target: LaunchTarget) {
+    remember_last_opened_folder(app, &target.folder);
+    {
+        let state = app.state::<LaunchState>();
+        let Ok(mut queue) = state.queue.lock() else {
+            return;
+        };
+        if !queue.ready {
+            queue.pending = Some(target);
+            return;
+        }
+    }
+    if let Some(window) = focus_main_window(app) {
+        let _ = app.emit_to(window.label(), OPEN_FOLDER_EVENT, target);
+    }
+}
+
+pub(crate) fn send_folder_to_window(app: &AppHandle, label: &str, folder: &str) -> Result<(), String> {
+    let target = LaunchTarget {
+        folder: folder.to_string(),
+        file: None,
+    };
+    app.emit_to(label, OPEN_FOLDER_EVENT, target)
+        .map_err(|error| error.to_string())
+}
+
+/// Reads this process's own arguments during `setup`.
+pub(crate) fn capture_launch_args(app: &AppHandle) {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    if let Some(target) = launch_target(std::env::args().skip(1), &cwd) {
+        deliver_launch_target(app, target);
+    }
+}
+
+/// macOS hands "Open With" files and folders to a running app as `RunEvent::Opened`
+/// instead of arguments, including on the launch that starts it.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub(crate) fn handle_opened_urls(app: &AppHandle, urls: &[tauri::Url]) {
+    let paths = urls
+        .iter()
+        .filter_map(|url| url.to_file_path().ok())
+        .map(|path| path.to_string_lossy().to_string());
+    if let Some(target) = launch_target(paths, Path::new("/")) {
+        deliver_launch_target(app, target);
+    }
+}
+
+/// `tauri-plugin-single-instance` callback, run in the first instance with the second's argv.
+pub(crate) fn handle_second_instance(app: &AppHandle, args: Vec<String>, cwd: String) {
+    let allow_multiple = build_app_settings_from_store(app)
+        .map(|settings| settings.allow_multiple_instances)
+        .unwrap_or(false);
+    if allow_multiple {
+        if let Err(error) = relaunch_as_new_instance(&args, &cwd) {
+            log::error!("Failed to start another instance: {error}");
+        }
+        return;
+    }
+
+    match launch_target(args.iter().skip(1), Path::new(&cwd)) {
+        Some(target) => deliver_launch_target(app, target),
+        None => {
+            focus_main_window(app);
+        }
+    }
+}
+
+/// Called by the frontend once its `initial-open` listener is registered, so a launch
+/// path is never emitted before anything can receive it.
+#[tauri::command]
+pub fn frontend_ready(app: AppHandle, window: WebviewWindow) -> Result<(), LatticeError> {
+    log_command("frontend_ready", || {
+        let pending = {
+            let state = app.state::<LaunchState>();
+            let mut queue = state.queue.lock().map_err(|error| error.to_string())?;
+            queue.ready = true;
+            queue.pending.take()
+        };
+        if let Some(target) = pending {
+            app.emit_to(window.label(), INITIAL_OPEN_EVENT, target)
+                .map_err(|error| error.to_string())?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launch_targets_skip_flags_and_missing_paths_and_resolve_files_to_their_folder() {
+        let root = std::env::temp_dir().join(format!("lattice-launch-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::write(root.join("notes/a.md"), "alpha").unwrap();
+        let canonical = fs::canonicalize(&root).unwrap();
+        let display = |path: PathBuf| path.to_string_lossy().to_string();
+
+        assert_eq!(
+            launch_target(["--flag", "-psn_0_12345", "missing", "notes"], &root),
+            Some(LaunchTarget {
+                folder: display(canonical.join("notes")),
+                file: None,
+            })
+        );
+        assert_eq!(
+            launch_target([display(root.join("notes/a.md"))], Path::new("/")),
+            Some(LaunchTarget {
+                folder: display(canonical.join("notes")),
+                file: Some(display(canonical.join("notes/a.md"))),
+            })
+        );
+        assert_eq!(launch_target(Vec::<String>::new(), &root), None);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
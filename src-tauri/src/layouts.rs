@@ -0,0 +1,337 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+
+use crate::error::LatticeError;
+use crate::fileops::timestamp_ms;
+use crate::folder_window_state::{apply_window_state, capture_window_state, track_folder, tracked_folder};
+use crate::launch::send_folder_to_window;
+use crate::logging::{log_command, log_command_async};
+use crate::settings_recovery::save_settings_store;
+use crate::workspace_windows::{
+    open_workspace_window, read_open_windows, write_open_windows, OpenWindowRecord, WORKSPACE_WINDOW_LABEL_PREFIX,
+};
+use crate::{build_app_settings_from_store, settings_store_path, WindowStateSnapshot};
+
+pub(crate) const LAYOUTS_KEY: &str = "layouts";
+const MAIN_WINDOW_LABEL: &str = "main";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LayoutWindow {
+    /// The label at save time; restored workspace windows get fresh labels.
+    label: String,
+    #[serde(default)]
+    folder: Option<String>,
+    geometry: WindowStateSnapshot,
+    #[serde(default)]
+    monitor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredLayout {
+    /// Milliseconds since the Unix epoch.
+    #[serde(default)]
+    saved_at: u64,
+    #[serde(default)]
+    windows: Vec<LayoutWindow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutSummary {
+    pub name: String,
+    pub saved_at: u64,
+    pub window_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedWindow {
+    pub label: String,
+    pub folder: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredLayout {
+    /// Labels of the windows now open, main window first.
+    pub windows: Vec<String>,
+    /// Windows left out because their folder no longer exists. The main window always
+    /// stays open, so for it only the folder is skipped.
+    pub skipped: Vec<SkippedWindow>,
+}
+
+fn layout_name(name: &str) -> Result<&str, LatticeError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err(LatticeError::InvalidInput {
+            message: "Layout name is required.".to_string(),
+        });
+    }
+    Ok(trimmed)
+}
+
+fn read_layouts(app: &AppHandle) -> Result<Map<String, Value>, LatticeError> {
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    Ok(match store.get(LAYOUTS_KEY) {
+        Some(Value::Object(layouts)) => layouts,
+        _ => Map::new(),
+    })
+}
+
+fn write_layouts(app: &AppHandle, layouts: Map<String, Value>) -> Result<(), LatticeError> {
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    store.set(LAYOUTS_KEY, Value::Object(layouts));
+    save_settings_store(app).map_err(Into::into)
+}
+
+fn parse_layout(value: &Value) -> StoredLayout {
+    serde_json::from_value(value.clone()).unwrap_or_default()
+}
+
+fn is_layout_window(label: &str) -> bool {
+    label == MAIN_WINDOW_LABEL || label.starts_with(WORKSPACE_WINDOW_LABEL_PREFIX)
+}
+
+/// Keeps the first main-window entry (the main window is never recreated, only moved)
+/// and drops workspace windows whose folder is gone. Main comes first in the result.
+fn restorable_layout(
+    windows: Vec<LayoutWindow>,
+    exists: impl Fn(&str) -> bool,
+) -> (Option<LayoutWindow>, Vec<LayoutWindow>, Vec<SkippedWindow>) {
+    let mut main = None;
+    let mut workspaces = Vec::new();
+    let mut skipped = Vec::new();
+    let mut seen_labels = HashSet::new();
+    for mut window in windows {
+        if !is_layout_window(&window.label) || !seen_labels.insert(window.label.clone()) {
+            continue;
+        }
+        let folder_exists = window.folder.as_deref().is_some_and(&exists);
+        if window.label == MAIN_WINDOW_LABEL {
+            if window.folder.is_some() && !folder_exists {
+                skipped.push(SkippedWindow {
+                    label: window.label.clone(),
+                    folder: window.folder.take(),
+                });
+            }
+            main = Some(window);
+        } else if folder_exists {
+            workspaces.push(window);
+        } else {
+            skipped.push(SkippedWindow {
+                label: window.label,
+                folder: window.folder,
+            });
+        }
+    }
+    (main, workspaces, skipped)
+}
+
+fn window_folder(app: &AppHandle, window: &WebviewWindow, records: &[OpenWindowRecord]) -> Option<String> {
+    let label = window.label();
+    records
+        .iter()
+        .find(|record| record.label == label)
+        .map(|record| record.folder.clone())
+        .or_else(|| tracked_folder(app, label))
+        .or_else(|| {
+            (label == MAIN_WINDOW_LABEL)
+                .then(|| build_app_settings_from_store(app).ok()?.last_opened_folder)
+                .flatten()
+        })
+}
+
+fn capture_layout(app: &AppHandle) -> Result<Vec<LayoutWindow>, LatticeError> {
+    let records = read_open_windows(app);
+    let mut windows: Vec<WebviewWindow> = app
+        .webview_windows()
+        .into_values()
+        .filter(|window| is_layout_window(window.label()))
+        .collect();
+    windows.sort_by_key(|window| (window.label() != MAIN_WINDOW_LABEL, window.label().to_string()));
+
+    windows
+        .iter()
+        .map(|window| {
+            Ok(LayoutWindow {
+                label: window.label().to_string(),
+                folder: window_folder(app, window, &records),
+                geometry: capture_window_state(window, None)?,
+                monitor: window
+                    .current_monitor()
+                    .ok()
+                    .flatten()
+                    .and_then(|monitor| monitor.name().cloned()),
+            })
+        })
+        .collect()
+}
+
+/// Geometry is clamped onto a connected display, so a layout saved on an unplugged
+/// monitor lands on the primary one.
+fn place_window(app: &AppHandle, window: &WebviewWindow, saved: &LayoutWindow) {
+    if let Some(folder) = saved.folder.as_deref() {
+        track_folder(app, window.label(), folder);
+    }
+    if let Err(error) = apply_window_state(window, &saved.geometry) {
+        log::warn!("Failed to place window {}: {}", window.label(), error.message());
+    }
+}
+
+/// Replaces any layout with the same name.
+#[tauri::command]
+pub async fn save_layout(app: AppHandle, name: String) -> Result<LayoutSummary, LatticeError> {
+    log_command_async("save_layout", async move {
+        let name = layout_name(&name)?.to_string();
+        let stored = StoredLayout {
+            saved_at: timestamp_ms(Ok(SystemTime::now())).unwrap_or_default(),
+            windows: capture_layout(&app)?,
+        };
+        let summary = LayoutSummary {
+            name: name.clone(),
+            saved_at: stored.saved_at,
+            window_count: stored.windows.len(),
+        };
+        let mut layouts = read_layouts(&app)?;
+        layouts.insert(name, serde_json::to_value(stored).map_err(|error| error.to_string())?);
+        write_layouts(&app, layouts)?;
+        Ok(summary)
+    })
+    .await
+}
+
+/// Closes every workspace window, then reopens the saved set and moves the main window
+/// into place.
+#[tauri::command]
+pub async fn restore_layout(app: AppHandle, name: String) -> Result<RestoredLayout, LatticeError> {
+    log_command_async("restore_layout", async move {
+        let name = layout_name(&name)?;
+        let stored = read_layouts(&app)?
+            .get(name)
+            .map(parse_layout)
+            .ok_or_else(|| LatticeError::NotFound {
+                message: format!("No layout named \"{name}\"."),
+            })?;
+        let (main, workspaces, skipped) = restorable_layout(stored.windows, |folder| Path::new(folder).is_dir());
+
+        for (label, window) in app.webview_windows() {
+            if label.starts_with(WORKSPACE_WINDOW_LABEL_PREFIX) {
+                let _ = window.close();
+            }
+        }
+
+        let mut opened = Vec::new();
+        if let (Some(saved), Some(window)) = (main, app.get_webview_window(MAIN_WINDOW_LABEL)) {
+            place_window(&app, &window, &saved);
+            if let Some(folder) = saved.folder.as_deref() {
+                send_folder_to_window(&app, window.label(), folder)?;
+            }
+            opened.push(window.label().to_string());
+        }
+
+        let mut records = Vec::new();
+        for saved in workspaces {
+            let Some(folder) = saved.folder.clone() else {
+                continue;
+            };
+            let window = open_workspace_window(&app, &folder)?;
+            place_window(&app, &window, &saved);
+            records.push(OpenWindowRecord {
+                label: window.label().to_string(),
+                folder,
+            });
+            opened.push(window.label().to_string());
+        }
+        write_open_windows(&app, &records)?;
+
+        Ok(RestoredLayout {
+            windows: opened,
+            skipped,
+        })
+    })
+    .await
+}
+
+/// Most recently saved first.
+#[tauri::command]
+pub fn list_layouts(app: AppHandle) -> Result<Vec<LayoutSummary>, LatticeError> {
+    log_command("list_layouts", || {
+        let mut summaries: Vec<LayoutSummary> = read_layouts(&app)?
+            .iter()
+            .map(|(name, value)| {
+                let stored = parse_layout(value);
+                LayoutSummary {
+                    name: name.clone(),
+                    saved_at: stored.saved_at,
+                    window_count: stored.windows.len(),
+                }
+            })
+            .collect();
+        summaries.sort_by(|left, right| right.saved_at.cmp(&left.saved_at).then(left.name.cmp(&right.name)));
+        Ok(summaries)
+    })
+}
+
+#[tauri::command]
+pub fn delete_layout(app: AppHandle, name: String) -> Result<(), LatticeError> {
+    log_command("delete_layout", || {
+        let name = layout_name(&name)?;
+        let mut layouts = read_layouts(&app)?;
+        if layouts.remove(name).is_none() {
+            return Err(LatticeError::NotFound {
+                message: format!("No layout named \"{name}\"."),
+            });
+        }
+        write_layouts(&app, layouts)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(label: &str, folder: Option<&str>) -> LayoutWindow {
+        LayoutWindow {
+            label: label.to_string(),
+            folder: folder.map(str::to_string),
+            geometry: WindowStateSnapshot {
+                width: 800.0,
+                height: 600.0,
+                x: 10.0,
+                y: 20.0,
+                is_maximized: false,
+            },
+            monitor: None,
+        }
+    }
+
+    #[test]
+    fn windows_with_missing_folders_are_skipped_and_main_is_kept() {
+        let exists = |folder: &str| folder != "/gone";
+        let (main, workspaces, skipped) = restorable_layout(
+            vec![
+                window("workspace-a", Some("/notes")),
+                window("main", Some("/gone")),
+                window("workspace-b", Some("/gone")),
+                window("workspace-a", Some("/notes")),
+                window("workspace-c", None),
+                window("preview", Some("/notes")),
+            ],
+            exists,
+        );
+
+        assert_eq!(main, Some(window("main", None)));
+        assert_eq!(workspaces, vec![window("workspace-a", Some("/notes"))]);
+        let skipped_labels: Vec<&str> = skipped.iter().map(|window| window.label.as_str()).collect();
+        assert_eq!(skipped_labels, vec!["main", "workspace-b", "workspace-c"]);
+        assert_eq!(skipped[0].folder.as_deref(), Some("/gone"));
+    }
+}
@@ -0,0 +1,190 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::fileops::atomic_temp_path;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkKind {
+    Symbolic,
+    Hard,
+}
+
+#[cfg(windows)]
+fn requires_privilege(error: &std::io::Error) -> bool {
+    const ERROR_PRIVILEGE_NOT_HELD: i32 = 1314;
+    error.raw_os_error() == Some(ERROR_PRIVILEGE_NOT_HELD)
+}
+
+#[cfg(not(windows))]
+fn requires_privilege(_error: &std::io::Error) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn same_volume(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev(),
+        // Let the link call itself report whatever is wrong.
+        _ => true,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_volume(a: &Path, b: &Path) -> bool {
+    use crate::disk_space::volume_mount_point;
+    match (volume_mount_point(a), volume_mount_point(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
+
+/// The directory a new link at `link_path` goes in.
+fn link_parent(link_path: &Path) -> PathBuf {
+    link_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."))
+        .to_path_buf()
+}
+
+#[cfg(unix)]
+fn make_symlink(target: &Path, link_path: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link_path)
+}
+
+/// Windows has separate file and directory symlinks; a dangling target gets a file link.
+#[cfg(windows)]
+fn make_symlink(target: &Path, link_path: &Path) -> std::io::Result<()> {
+    if link_parent(link_path).join(target).is_dir() {
+        std::os::windows::fs::symlink_dir(target, link_path)
+    } else {
+        std::os::windows::fs::symlink_file(target, link_path)
+    }
+}
+
+fn make_link(target: &Path, link_path: &Path, kind: LinkKind) -> Result<(), LatticeError> {
+    let result = match kind {
+        LinkKind::Symbolic => make_symlink(target, link_path),
+        LinkKind::Hard => fs::hard_link(target, link_path),
+    };
+    result.map_err(|error| match error.kind() {
+        _ if requires_privilege(&error) => LatticeError::RequiresPrivilege {
+            message: "Creating symbolic links needs administrator rights or Developer Mode.".to_string(),
+        },
+        ErrorKind::CrossesDevices => LatticeError::CrossDevice {
+            message: format!("{} is on a different volume than {}.", target.display(), link_path.display()),
+        },
+        ErrorKind::AlreadyExists => LatticeError::AlreadyExists {
+            message: format!("Something already exists at {}.", link_path.display()),
+        },
+        _ => LatticeError::at_path(link_path, error),
+    })
+}
+
+/// Symlink targets are stored as given, so a relative one resolves from the link's folder.
+/// With `overwrite`, the new link is built beside the old entry and renamed over it; a real
+/// directory is never replaced.
+fn create_link_sync(target: &Path, link_path: &Path, kind: LinkKind, overwrite: bool) -> Result<(), LatticeError> {
+    let parent = link_parent(link_path);
+    if !parent.is_dir() {
+        return Err(LatticeError::NotFound {
+            message: format!("Folder not found: {}", parent.display()),
+        });
+    }
+    if kind == LinkKind::Hard {
+        let metadata = fs::metadata(target).map_err(|_| LatticeError::NotFound {
+            message: format!("Link target not found: {}", target.display()),
+        })?;
+        if metadata.is_dir() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Can't hard link a folder: {}", target.display()),
+            });
+        }
+        if !same_volume(target, &parent) {
+            return Err(LatticeError::CrossDevice {
+                message: format!("{} is on a different volume than {}.", target.display(), parent.display()),
+            });
+        }
+    }
+
+    let existing = match fs::symlink_metadata(link_path) {
+        Ok(metadata) => metadata,
+        Err(_) => return make_link(target, link_path, kind),
+    };
+    if !overwrite {
+        return Err(LatticeError::AlreadyExists {
+            message: format!("Something already exists at {}.", link_path.display()),
+        });
+    }
+    if existing.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Refusing to replace the folder {} with a link.", link_path.display()),
+        });
+    }
+
+    let temp_path = atomic_temp_path(link_path)?;
+    make_link(target, &temp_path, kind)?;
+    fs::rename(&temp_path, link_path).map_err(|error| {
+        let _ = fs::remove_file(&temp_path);
+        LatticeError::at_path(link_path, error)
+    })
+}
+
+#[tauri::command]
+pub async fn create_link(
+    app: AppHandle,
+    target: String,
+    link_path: String,
+    kind: LinkKind,
+    overwrite: bool,
+) -> Result<(), LatticeError> {
+    log_command_async("create_link", async move {
+        let target = resolve_command_path(&app, &target)?;
+        let link_path = resolve_command_path(&app, &link_path)?;
+        tokio::task::spawn_blocking(move || create_link_sync(&target, &link_path, kind, overwrite))
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn links_refuse_existing_paths_unless_overwriting() {
+        let root = std::env::temp_dir().join(format!("lattice-links-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("dotfiles")).unwrap();
+        let target = root.join("dotfiles/vimrc");
+        fs::write(&target, "set number").unwrap();
+        let link = root.join(".vimrc");
+
+        create_link_sync(Path::new("dotfiles/vimrc"), &link, LinkKind::Symbolic, false).unwrap();
+        assert_eq!(fs::read_link(&link).unwrap(), Path::new("dotfiles/vimrc"));
+        assert_eq!(fs::read_to_string(&link).unwrap(), "set number");
+
+        let again = create_link_sync(&target, &link, LinkKind::Hard, false);
+        assert!(matches!(again, Err(LatticeError::AlreadyExists { .. })));
+        create_link_sync(&target, &link, LinkKind::Hard, true).unwrap();
+        assert!(!fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert!(crate::fileops::same_file_sync(&target, &link).unwrap());
+
+        let folder = create_link_sync(&target, &root.join("dotfiles"), LinkKind::Symbolic, true);
+        assert!(matches!(folder, Err(LatticeError::InvalidInput { .. })));
+        let missing = create_link_sync(&root.join("missing"), &root.join("hard"), LinkKind::Hard, false);
+        assert!(matches!(missing, Err(LatticeError::NotFound { .. })));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
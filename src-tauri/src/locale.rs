@@ -0,0 +1,150 @@
+use std::sync::Mutex as StdMutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::{build_app_settings_from_store, save_app_settings};
+
+const LOCALE_CHANGED_EVENT: &str = "locale-changed";
+/// Used when the OS doesn't report a locale at all.
+const FALLBACK_LOCALE: &str = "en-US";
+
+/// The system locale last seen, so only actual OS changes are announced.
+pub struct LocaleState {
+    system: StdMutex<String>,
+}
+
+impl Default for LocaleState {
+    fn default() -> Self {
+        Self {
+            system: StdMutex::new(current_system_locale()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LocaleChangedPayload {
+    locale: String,
+    follows_system: bool,
+}
+
+/// Canonicalizes a BCP 47 tag's separators and case (`en_us` becomes `en-US`); `None` for
+/// anything that isn't shaped like one.
+pub(crate) fn normalize_locale(locale: &str) -> Option<String> {
+    let subtags: Vec<&str> = locale.trim().split(['-', '_']).collect();
+    let valid = |subtag: &&str| (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric());
+    let language = subtags.first()?;
+    let valid_language = language.len() >= 2 && language.chars().all(|c| c.is_ascii_alphabetic());
+    if !valid_language || !subtags.iter().all(valid) {
+        return None;
+    }
+    let canonical: Vec<String> = subtags
+        .iter()
+        .enumerate()
+        .map(|(index, subtag)| match subtag.len() {
+            _ if index == 0 => subtag.to_ascii_lowercase(),
+            // Regions are upper case and scripts title case, e.g. `zh-Hant-TW`.
+            2 => subtag.to_ascii_uppercase(),
+            4 if subtag.chars().all(|c| c.is_ascii_alphabetic()) => {
+                subtag[..1].to_ascii_uppercase() + &subtag[1..].to_ascii_lowercase()
+            }
+            _ => subtag.to_ascii_lowercase(),
+        })
+        .collect();
+    Some(canonical.join("-"))
+}
+
+fn current_system_locale() -> String {
+    sys_locale::get_locale()
+        .and_then(|locale| normalize_locale(&locale))
+        .unwrap_or_else(|| FALLBACK_LOCALE.to_string())
+}
+
+/// The saved locale, or the system one when there is none.
+fn effective_locale(app: &AppHandle) -> Result<(String, bool), LatticeError> {
+    Ok(match build_app_settings_from_store(app)?.locale {
+        Some(locale) => (locale, false),
+        None => (current_system_locale(), true),
+    })
+}
+
+fn emit_locale_changed(app: &AppHandle, locale: String, follows_system: bool) {
+    let _ = app.emit(LOCALE_CHANGED_EVENT, LocaleChangedPayload { locale, follows_system });
+}
+
+/// The OS has no locale-change event we can hook, so this runs when a window regains focus,
+/// which is when someone comes back from changing it. Only matters while following the system.
+pub(crate) fn check_system_locale(app: &AppHandle) {
+    let system = current_system_locale();
+    let state = app.state::<LocaleState>();
+    let Ok(mut last) = state.system.lock() else {
+        return;
+    };
+    if *last == system {
+        return;
+    }
+    *last = system.clone();
+    drop(last);
+
+    let follows_system = build_app_settings_from_store(app).is_ok_and(|settings| settings.locale.is_none());
+    if follows_system {
+        emit_locale_changed(app, system, true);
+    }
+}
+
+#[tauri::command]
+pub fn system_locale() -> String {
+    current_system_locale()
+}
+
+/// The locale the UI should use, whether chosen or inherited from the system.
+#[tauri::command]
+pub fn get_locale(app: AppHandle) -> Result<String, LatticeError> {
+    log_command("get_locale", || Ok(effective_locale(&app)?.0))
+}
+
+/// `None` goes back to following the system locale. Returns the locale now in effect.
+#[tauri::command]
+pub fn set_locale(
+    app: AppHandle,
+    state: State<'_, LocaleState>,
+    locale: Option<String>,
+) -> Result<String, LatticeError> {
+    log_command("set_locale", || {
+        let locale = match locale.filter(|locale| !locale.trim().is_empty()) {
+            Some(locale) => Some(normalize_locale(&locale).ok_or_else(|| LatticeError::InvalidInput {
+                message: format!("Not a locale tag: {:?}", locale.trim()),
+            })?),
+            None => None,
+        };
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.locale = locale;
+        save_app_settings(&app, settings)?;
+
+        let (locale, follows_system) = effective_locale(&app)?;
+        if follows_system {
+            *state.system.lock().map_err(|error| error.to_string())? = locale.clone();
+        }
+        emit_locale_changed(&app, locale.clone(), follows_system);
+        Ok(locale)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_tags_are_canonicalized_or_rejected() {
+        assert_eq!(normalize_locale("en_us").as_deref(), Some("en-US"));
+        assert_eq!(normalize_locale(" ZH-hant-tw ").as_deref(), Some("zh-Hant-TW"));
+        assert_eq!(normalize_locale("de").as_deref(), Some("de"));
+        assert_eq!(normalize_locale("es-419").as_deref(), Some("es-419"));
+        assert_eq!(normalize_locale("en US"), None);
+        assert_eq!(normalize_locale("1x-US"), None);
+        assert_eq!(normalize_locale(""), None);
+    }
+}
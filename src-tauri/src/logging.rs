@@ -0,0 +1,265 @@
+use std::fmt::Display;
+use std::fs::{self, OpenOptions};
+use std::future::Future;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use log::LevelFilter;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_log::{Target, TargetKind};
+use tauri_plugin_opener::OpenerExt;
+use time::{Date, OffsetDateTime};
+
+use crate::data_dir::redirected_data_dir;
+use crate::error::LatticeError;
+use crate::{build_app_settings_from_store, save_app_settings};
+
+const LOGS_DIR: &str = "logs";
+const LOG_FILE_PREFIX: &str = "lattice-";
+const LOG_FILE_EXTENSION: &str = "log";
+/// Once a day's log passes this it is moved aside and a fresh file started, so a
+/// runaway error loop holds at most twice this per day.
+const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024;
+const KEEP_LOGS_FOR: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Info;
+
+/// Logs a failed result with `context` and hands it back unchanged.
+pub(crate) trait LogError {
+    fn log_error(self, context: impl Display) -> Self;
+}
+
+impl<T, E: Display> LogError for Result<T, E> {
+    fn log_error(self, context: impl Display) -> Self {
+        if let Err(error) = &self {
+            log::error!("{context}: {error}");
+        }
+        self
+    }
+}
+
+/// Every `Result` command's body runs through this (or `log_command_async`), so a failure
+/// is logged under the command's name before the webview gets it.
+pub(crate) fn log_command<T>(
+    command: &str,
+    body: impl FnOnce() -> Result<T, LatticeError>,
+) -> Result<T, LatticeError> {
+    body().log_error(format_args!("Command {command} failed"))
+}
+
+pub(crate) async fn log_command_async<T>(
+    command: &str,
+    body: impl Future<Output = Result<T, LatticeError>>,
+) -> Result<T, LatticeError> {
+    body.await.log_error(format_args!("Command {command} failed"))
+}
+
+/// Accepts `log` level names in any case; empty means the default (`info`).
+pub(crate) fn normalize_log_level(level: &str) -> Option<String> {
+    let level = level.trim().to_ascii_lowercase();
+    if level.is_empty() {
+        return Some(String::new());
+    }
+    LevelFilter::from_str(&level).ok().map(|_| level)
+}
+
+fn level_filter(level: &str) -> LevelFilter {
+    LevelFilter::from_str(level).unwrap_or(DEFAULT_LOG_LEVEL)
+}
+
+/// Days roll over at local midnight, falling back to UTC when the offset is unknown.
+fn today() -> Date {
+    OffsetDateTime::now_local()
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
+        .date()
+}
+
+fn log_file_path(dir: &Path, day: Date) -> PathBuf {
+    dir.join(format!("{LOG_FILE_PREFIX}{day}.{LOG_FILE_EXTENSION}"))
+}
+
+fn overflow_log_file_path(dir: &Path, day: Date) -> PathBuf {
+    dir.join(format!("{LOG_FILE_PREFIX}{day}.1.{LOG_FILE_EXTENSION}"))
+}
+
+/// One file per day, plus a single overflow file when that day hits the size cap.
+struct DailyLogFile {
+    dir: PathBuf,
+    max_bytes: u64,
+    day: Option<Date>,
+    file: Option<fs::File>,
+    written: u64,
+}
+
+impl DailyLogFile {
+    fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            dir,
+            max_bytes,
+            day: None,
+            file: None,
+            written: 0,
+        }
+    }
+
+    fn roll(&mut self, today: Date) -> io::Result<()> {
+        if self.day == Some(today) && self.written < self.max_bytes {
+            return Ok(());
+        }
+
+        let path = log_file_path(&self.dir, today);
+        // Closed first: Windows can't rename a file that is still open.
+        self.file = None;
+        if self.day == Some(today) {
+            fs::rename(&path, overflow_log_file_path(&self.dir, today))?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        self.written = file.metadata()?.len();
+        self.file = Some(file);
+        self.day = Some(today);
+        Ok(())
+    }
+}
+
+impl Write for DailyLogFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.roll(today())?;
+        let file = self.file.as_mut().ok_or_else(|| io::Error::other("Log file is not open"))?;
+        let written = file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+fn is_log_file(path: &Path) -> bool {
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    name.starts_with(LOG_FILE_PREFIX) && path.extension().is_some_and(|extension| extension == LOG_FILE_EXTENSION)
+}
+
+fn prune_old_logs(dir: &Path, now: SystemTime) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()).filter(|path| is_log_file(path)) {
+        let expired = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| now.duration_since(modified).unwrap_or_default() > KEEP_LOGS_FOR);
+        if expired {
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
+
+/// Portable and relocated installs keep their logs with the rest of their data.
+fn log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match redirected_data_dir(app) {
+        Some(dir) => Ok(dir.join(LOGS_DIR)),
+        None => app.path().app_log_dir().map_err(|error| error.to_string()),
+    }
+}
+
+/// Installs the logger at the default level; `restore_log_level` applies the saved one
+/// once settings are readable.
+pub(crate) fn init_logging(app: &AppHandle) -> Result<(), String> {
+    let dir = log_dir(app)?;
+    fs::create_dir_all(&dir).map_err(|error| error.to_string())?;
+    prune_old_logs(&dir, SystemTime::now());
+
+    let file: Box<dyn Write + Send> = Box::new(DailyLogFile::new(dir, MAX_LOG_FILE_BYTES));
+    // Everything reaches the dispatcher; the runtime level is enforced by `log`'s max level.
+    app.plugin(
+        tauri_plugin_log::Builder::new()
+            .clear_targets()
+            .target(Target::new(TargetKind::Stdout))
+            .target(Target::new(TargetKind::Dispatch(
+                tauri_plugin_log::fern::Dispatch::new().chain(file),
+            )))
+            .level(LevelFilter::Trace)
+            .build(),
+    )
+    .map_err(|error| error.to_string())?;
+    log::set_max_level(DEFAULT_LOG_LEVEL);
+    Ok(())
+}
+
+pub(crate) fn restore_log_level(app: &AppHandle) -> Result<(), String> {
+    log::set_max_level(level_filter(&build_app_settings_from_store(app)?.log_level));
+    Ok(())
+}
+
+/// Takes effect immediately; returns the normalized level name.
+#[tauri::command]
+pub fn set_log_level(app: AppHandle, level: String) -> Result<String, LatticeError> {
+    log_command("set_log_level", || {
+        let level = normalize_log_level(&level).ok_or_else(|| format!("Unknown log level: {}", level.trim()))?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.log_level = level;
+        let level = save_app_settings(&app, settings)?.log_level;
+        log::set_max_level(level_filter(&level));
+        Ok(level)
+    })
+}
+
+/// Today's log file, which may not exist yet if nothing has been logged.
+#[tauri::command]
+pub fn get_log_file_path(app: AppHandle) -> Result<String, LatticeError> {
+    log_command("get_log_file_path", || Ok(log_file_path(&log_dir(&app)?, today()).to_string_lossy().to_string()))
+}
+
+#[tauri::command]
+pub fn open_log_folder(app: AppHandle) -> Result<(), LatticeError> {
+    log_command("open_log_folder", || {
+        let dir = log_dir(&app)?;
+        fs::create_dir_all(&dir)?;
+        app.opener()
+            .open_path(dir.to_string_lossy(), None::<&str>)
+            .map_err(|error| error.to_string().into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Month;
+    use uuid::Uuid;
+
+    #[test]
+    fn log_levels_are_normalized_and_unknown_ones_rejected() {
+        assert_eq!(normalize_log_level(" DEBUG ").as_deref(), Some("debug"));
+        assert_eq!(normalize_log_level("").as_deref(), Some(""));
+        assert_eq!(normalize_log_level("verbose"), None);
+        assert_eq!(level_filter(""), DEFAULT_LOG_LEVEL);
+        assert_eq!(level_filter("off"), LevelFilter::Off);
+    }
+
+    #[test]
+    fn daily_log_moves_aside_when_over_the_cap_and_starts_fresh_each_day() {
+        let dir = std::env::temp_dir().join(format!("lattice-logging-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let monday = Date::from_calendar_date(2026, Month::March, 2).unwrap();
+        let tuesday = monday.next_day().unwrap();
+        let mut log = DailyLogFile::new(dir.clone(), 4);
+
+        for line in ["first\n", "second\n"] {
+            log.roll(monday).unwrap();
+            log.file.as_mut().unwrap().write_all(line.as_bytes()).unwrap();
+            log.written += line.len() as u64;
+        }
+        log.roll(tuesday).unwrap();
+
+        assert_eq!(fs::read_to_string(overflow_log_file_path(&dir, monday)).unwrap(), "first\n");
+        assert_eq!(fs::read_to_string(log_file_path(&dir, monday)).unwrap(), "second\n");
+        assert!(log_file_path(&dir, tuesday).exists());
+        assert!(is_log_file(&log_file_path(&dir, tuesday)));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
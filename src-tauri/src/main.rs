@@ -5,7 +5,84 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod app_info;
+mod archive;
+mod auto_reload;
+mod autosave;
+mod backup;
+mod case_collisions;
+mod batch_rename;
+mod broken_links;
+mod checksum;
+mod child_counts;
+mod clipboard;
+mod config_format;
+mod data_dir;
+mod diff;
+mod disk_space;
+mod duplicates;
+mod encoding;
+mod error;
+mod favorites;
+mod file_associations;
+mod file_location;
+mod file_preview;
+mod file_locks;
+mod file_protocol;
+mod file_range;
+mod file_style;
+mod file_tree;
+mod file_type;
+mod fileops;
+mod folder_access;
+mod folder_picker;
+mod folder_size;
+mod folder_sort;
+mod folder_window_state;
+mod frontmatter;
+mod fuzzy;
+mod git;
+mod ignore_rules;
+mod image_optimize;
+mod index;
+mod jobs;
+mod launch;
+mod layouts;
+mod links;
+mod locale;
+mod logging;
+mod memory_pressure;
+mod monitors;
+mod open_with;
+mod paths;
 mod pdf_native;
+mod recycle_bin;
+mod recent_files;
+mod recursive_delete;
+mod resource_usage;
+mod replace;
+mod reveal;
+mod search;
+mod sessions;
+mod transfer;
+mod tree_export;
+mod settings_migration;
+mod settings_recovery;
+mod settings_watch;
+mod shortcuts;
+mod snapshot;
+mod soft_delete;
+mod tags;
+mod taskbar;
+mod templates;
+mod terminal;
+mod text_stats;
+mod theme;
+mod thumbnails;
+mod watcher;
+mod window_flags;
+mod workspace_settings;
+mod workspace_windows;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -39,10 +116,110 @@ use tokio::{
 };
 use uuid::Uuid;
 
+use crate::app_info::get_app_info;
+use crate::archive::{create_zip, extract_zip, zip_selection};
+use crate::auto_reload::{
+    register_open_file, set_auto_reload, set_open_file_dirty, unregister_open_file, OpenFilesState,
+};
+use crate::autosave::{get_autosave_interval, set_autosave_interval, AutosaveState};
+use crate::backup::backup_folder;
+use crate::case_collisions::check_case_collisions;
+use crate::batch_rename::batch_rename;
+use crate::broken_links::{find_broken_symlinks, remove_broken_symlinks};
+use crate::checksum::{verify_checksum, verify_checksum_file};
+use crate::child_counts::{child_counts, ChildCountState};
+use crate::clipboard::{
+    clipboard_has_image, copy_files_to_clipboard, paste_files_from_clipboard, save_clipboard_image, save_clipboard_text,
+    ClipboardState,
+};
+use crate::config_format::format_file;
+use crate::data_dir::{get_data_dir, set_data_dir, DataDirState};
+use crate::diff::diff_files;
+use crate::disk_space::disk_space;
+use crate::duplicates::find_duplicates;
+use crate::encoding::{read_file_base64, read_file_smart, read_files};
+use crate::error::LatticeError;
+use crate::favorites::{add_favorite_folder, get_favorite_folders, remove_favorite_folder, reorder_favorite_folders};
+use crate::file_associations::{is_default_handler, request_default_handler};
+use crate::file_location::open_file_at;
+use crate::file_preview::{batch_preview, file_preview, FilePreviewState};
+use crate::file_locks::check_path_locked;
+use crate::file_range::{read_file_range, read_file_tail};
+use crate::file_style::detect_file_style;
+use crate::file_type::detect_file_type;
+use crate::file_tree::{list_directory, render_tree};
+use crate::fileops::{
+    create_file, create_folder, hash_file, rename_path, same_file, set_executable, set_readonly, stat_path,
+    write_bytes_atomic, write_file_atomic, write_file_checked,
+};
+use crate::folder_access::{grant_folder_access, list_granted_folders, revoke_folder_access};
+use crate::folder_picker::{pick_files, pick_folder};
+use crate::folder_size::{cancel_folder_size, folder_size, FolderSizeState};
+use crate::folder_sort::{get_folder_sort, set_folder_sort, sort_entries};
+use crate::folder_window_state::{
+    restore_window_state_for_folder, save_window_state_for_folder, FolderWindowState, FOLDER_WINDOW_STATES_KEY,
+};
+use crate::frontmatter::read_frontmatter;
+use crate::fuzzy::{fuzzy_find, FuzzyIndexState};
+use crate::git::{git_branch_info, git_status};
+use crate::ignore_rules::{test_ignore, IgnoreMatcherState};
+use crate::image_optimize::optimize_image;
+use crate::index::{query_index, rebuild_index, IndexState};
+use crate::jobs::{
+    cancel_job, get_job_parallelism, job_status, set_job_parallelism, start_copy_job, start_delete_job, start_move_job,
+    JobsState,
+};
+use crate::launch::{frontend_ready, LaunchState};
+use crate::links::create_link;
+use crate::locale::{get_locale, set_locale, system_locale, LocaleState};
+use crate::logging::{get_log_file_path, log_command, log_command_async, open_log_folder, set_log_level, LogError};
+use crate::memory_pressure::{
+    get_memory_stats, set_memory_poll_interval, set_memory_pressure_threshold, MemoryPressureState,
+};
+use crate::monitors::{list_monitors, move_to_monitor};
+use crate::open_with::{open_url, open_with_default_app};
+use crate::paths::{complete_path, resolve_app_path, resolve_path};
 use crate::pdf_native::{
     desktop_extract_pdf_page_text_layout,
     desktop_ocr_pdf_page_text_layout,
 };
+use crate::recent_files::{get_recent_files, push_recent_file, remove_recent_file, toggle_pin_file, RecentFile};
+use crate::recursive_delete::delete_recursive;
+use crate::recycle_bin::{
+    empty_trash, list_trashed, request_empty_trash_token, restore_trashed, trash_path, trash_paths, EmptyTrashTokenState,
+};
+use crate::resource_usage::{self_resource_usage, ResourceUsageState};
+use crate::replace::replace_in_files;
+use crate::reveal::reveal_in_file_manager;
+use crate::search::{export_search_results, search_contents};
+use crate::layouts::{delete_layout, list_layouts, restore_layout, save_layout, LAYOUTS_KEY};
+use crate::sessions::{delete_session, list_sessions, restore_session, save_session, SESSIONS_KEY};
+use crate::settings_migration::{migrate_settings, migrate_settings_document, SETTINGS_SCHEMA_VERSION};
+use crate::settings_recovery::{save_settings_store, settings_health, SettingsHealthState};
+use crate::settings_watch::{reload_settings, SettingsWatchState};
+use crate::shortcuts::{register_global_shortcut, unregister_global_shortcut};
+use crate::snapshot::{diff_snapshots, snapshot_folder, snapshot_folder_incremental};
+use crate::soft_delete::{commit_soft_delete, soft_delete, undo_soft_delete, SOFT_DELETES_KEY};
+use crate::tags::{add_tag, files_with_tag, get_tags, prune_tags, remove_tag, TagStoreState};
+use crate::taskbar::{set_badge_count, set_taskbar_progress};
+use crate::templates::{
+    delete_template, instantiate_template, list_templates, save_template, TEMPLATE_USAGE_KEY,
+};
+use crate::terminal::open_terminal;
+use crate::text_stats::text_stats;
+use crate::theme::{get_system_appearance, get_theme, set_theme, SystemAppearanceState, ThemePreference};
+use crate::thumbnails::{clear_thumbnail_cache, get_thumbnail};
+use crate::transfer::{copy_path, move_path};
+use crate::tree_export::{export_tree_json, export_tree_to_file};
+use crate::watcher::{
+    list_active_watches, unwatch, unwatch_all, unwatch_folder, update_watch_globs, watch_file, watch_folder, watch_glob,
+    WatcherState,
+};
+use crate::window_flags::{get_window_flags, set_always_on_top, set_window_opacity};
+use crate::workspace_settings::{
+    get_workspace_setting, list_workspace_keys, set_workspace_setting, WORKSPACE_SETTINGS_FALLBACK_KEY,
+};
+use crate::workspace_windows::{open_folder_in_new_window, OPEN_WINDOWS_KEY};
 
 const SETTINGS_STORE: &str = "settings.json";
 const RUNNER_EVENT_NAME: &str = "runner://event";
@@ -53,14 +230,34 @@ const LAST_OPENED_FOLDER_KEY: &str = "last_opened_folder";
 const LAST_WORKSPACE_PATH_KEY: &str = "last_workspace_path";
 const RECENT_WORKSPACE_PATHS_KEY: &str = "recent_workspace_paths";
 const WINDOW_STATE_KEY: &str = "window_state";
+const RECENT_FOLDERS_KEY: &str = "recent_folders";
+/// Keys the backend reads and writes itself; `compact_store` keeps them whatever the caller lists.
+const BACKEND_STORE_KEYS: &[&str] = &[
+    FRONTEND_SETTINGS_KEY,
+    DEFAULT_FOLDER_KEY,
+    LAST_OPENED_FOLDER_KEY,
+    LAST_WORKSPACE_PATH_KEY,
+    RECENT_WORKSPACE_PATHS_KEY,
+    WINDOW_STATE_KEY,
+    RECENT_FOLDERS_KEY,
+    FOLDER_WINDOW_STATES_KEY,
+    SESSIONS_KEY,
+    LAYOUTS_KEY,
+    TEMPLATE_USAGE_KEY,
+    SOFT_DELETES_KEY,
+    OPEN_WINDOWS_KEY,
+    WORKSPACE_SETTINGS_FALLBACK_KEY,
+];
 const MAX_RECENT_WORKSPACES: usize = 12;
+const DEFAULT_MAX_RECENT_FOLDERS: usize = 15;
+const MAX_PREVIEW_RANGE_BYTES: u64 = 8 * 1024 * 1024;
 const DESKTOP_NATIVE_WEBVIEW_NEW_WINDOW_EVENT: &str = "desktop-native-webview://new-window";
 const DESKTOP_NATIVE_WEBVIEW_DOWNLOAD_EVENT: &str = "desktop-native-webview://download";
 
 #[cfg(windows)]
 const CREATE_NO_WINDOW_FLAG: u32 = 0x08000000;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowStateSnapshot {
     pub width: f64,
@@ -73,12 +270,55 @@ pub struct WindowStateSnapshot {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AppSettings {
+    #[serde(default)]
+    pub version: u32,
     pub default_folder: Option<String>,
     pub last_opened_folder: Option<String>,
     pub last_workspace_path: Option<String>,
     #[serde(default)]
     pub recent_workspace_paths: Vec<String>,
     pub window_state: Option<WindowStateSnapshot>,
+    #[serde(default)]
+    pub recent_folders: Vec<String>,
+    pub max_recent_folders: Option<usize>,
+    #[serde(default)]
+    pub recent_files: Vec<RecentFile>,
+    #[serde(default)]
+    pub favorite_folders: Vec<String>,
+    #[serde(default)]
+    pub granted_folders: Vec<String>,
+    #[serde(default)]
+    pub restore_open_windows: bool,
+    #[serde(default)]
+    pub allow_multiple_instances: bool,
+    pub global_toggle_shortcut: Option<String>,
+    pub theme: Option<String>,
+    pub soft_delete_ttl_minutes: Option<u64>,
+    pub autosave_interval_secs: Option<u32>,
+    /// Empty means the default level.
+    #[serde(default)]
+    pub log_level: String,
+    /// Monitors are matched by name; indices shift as displays come and go.
+    pub monitor_name: Option<String>,
+    /// Overrides the platform terminal; `{dir}` is replaced with the folder to open.
+    pub terminal_command: Option<String>,
+    /// Extensions the user asked to open with Lattice by default.
+    #[serde(default)]
+    pub default_handler_extensions: Vec<String>,
+    /// Caches are dropped when available memory falls below this; `None` disables the monitor.
+    pub memory_pressure_threshold_mb: Option<u64>,
+    pub memory_poll_interval_secs: Option<u32>,
+    /// How many background jobs run at once; `None` means the default.
+    pub job_parallelism: Option<u32>,
+    /// A BCP 47 tag; `None` follows the system locale.
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub always_on_top: bool,
+    /// `None` means fully opaque.
+    pub window_opacity: Option<f64>,
+    /// Reloads clean open files when another program rewrites them.
+    #[serde(default)]
+    pub auto_reload: bool,
     #[serde(default, flatten)]
     pub extra: HashMap<String, Value>,
 }
@@ -98,6 +338,13 @@ struct StartupWorkspaceResolution {
     source: Option<StartupWorkspaceSource>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentFolderEntry {
+    path: String,
+    exists: bool,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct DesktopDirEntry {
@@ -350,7 +597,7 @@ fn set_desktop_native_webview_snapshot(
     title: Option<String>,
     status: Option<&str>,
     last_error: Option<Option<String>>,
-) -> Result<DesktopNativeWebviewSnapshot, String> {
+) -> Result<DesktopNativeWebviewSnapshot, LatticeError> {
     let state = app.state::<DesktopNativeWebviewState>();
     let mut snapshots = state.snapshots.lock().map_err(|error| error.to_string())?;
     let snapshot = snapshots
@@ -377,7 +624,7 @@ fn set_desktop_native_webview_snapshot(
     Ok(snapshot.clone())
 }
 
-fn remove_desktop_native_webview_snapshot(app: &AppHandle, label: &str) -> Result<(), String> {
+fn remove_desktop_native_webview_snapshot(app: &AppHandle, label: &str) -> Result<(), LatticeError> {
     let state = app.state::<DesktopNativeWebviewState>();
     let mut snapshots = state.snapshots.lock().map_err(|error| error.to_string())?;
     snapshots.remove(label);
@@ -387,7 +634,7 @@ fn remove_desktop_native_webview_snapshot(app: &AppHandle, label: &str) -> Resul
 fn get_desktop_native_webview_snapshot(
     app: &AppHandle,
     label: &str,
-) -> Result<Option<DesktopNativeWebviewSnapshot>, String> {
+) -> Result<Option<DesktopNativeWebviewSnapshot>, LatticeError> {
     let state = app.state::<DesktopNativeWebviewState>();
     let snapshots = state.snapshots.lock().map_err(|error| error.to_string())?;
     Ok(snapshots.get(label).cloned())
@@ -454,10 +701,12 @@ fn decode_preview_request_path(path: &str) -> Result<String, String> {
     Ok(decoded)
 }
 
-fn canonicalize_directory_path(path: &str) -> Result<PathBuf, String> {
-    let canonical = fs::canonicalize(path).map_err(|error| error.to_string())?;
+fn canonicalize_directory_path(path: &str) -> Result<PathBuf, LatticeError> {
+    let canonical = fs::canonicalize(path)?;
     if !canonical.is_dir() {
-        return Err(format!("Preview root is not a directory: {path}"));
+        return Err(LatticeError::InvalidInput {
+            message: format!("Preview root is not a directory: {path}"),
+        });
     }
     Ok(canonical)
 }
@@ -563,6 +812,7 @@ fn append_preview_cors_headers(response: &mut http::Response<Vec<u8>>) {
 
 fn build_preview_file_response(
     path: &Path,
+    content_type: &str,
     request: &http::Request<Vec<u8>>,
 ) -> Result<http::Response<Vec<u8>>, String> {
     if request.method() == http::Method::OPTIONS {
@@ -577,7 +827,6 @@ fn build_preview_file_response(
     let mut file = fs::File::open(path).map_err(|error| error.to_string())?;
     let metadata = file.metadata().map_err(|error| error.to_string())?;
     let file_len = metadata.len();
-    let content_type = get_preview_content_type(path);
 
     if let Some(range_header) = request
         .headers()
@@ -585,6 +834,8 @@ fn build_preview_file_response(
         .and_then(|value| value.to_str().ok())
     {
         if let Some((start, end)) = parse_range_header(range_header, file_len) {
+            // Media players ask for `bytes=0-` and read on from wherever the answer ends.
+            let end = end.min(start + MAX_PREVIEW_RANGE_BYTES - 1);
             let chunk_len = end - start + 1;
             let mut buffer = vec![0; chunk_len as usize];
             file.seek(SeekFrom::Start(start)).map_err(|error| error.to_string())?;
@@ -674,17 +925,81 @@ fn normalize_recent_workspace_paths(paths: Vec<String>) -> Vec<String> {
     deduped
 }
 
+fn recent_folders_limit(settings: &AppSettings) -> usize {
+    settings
+        .max_recent_folders
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_MAX_RECENT_FOLDERS)
+}
+
+fn normalize_recent_folders(paths: Vec<String>, limit: usize) -> Vec<String> {
+    let mut deduped: Vec<String> = Vec::new();
+
+    for path in paths {
+        let trimmed = path.trim();
+        if trimmed.is_empty() || deduped.iter().any(|existing| existing == trimmed) {
+            continue;
+        }
+
+        deduped.push(trimmed.to_string());
+        if deduped.len() >= limit {
+            break;
+        }
+    }
+
+    deduped
+}
+
+fn push_recent_folder_path(paths: &[String], folder: &str, limit: usize) -> Vec<String> {
+    let mut next = Vec::with_capacity(paths.len() + 1);
+    next.push(folder.trim().to_string());
+    next.extend(paths.iter().filter(|path| path.trim() != folder.trim()).cloned());
+    normalize_recent_folders(next, limit)
+}
+
 fn has_persisted_app_settings(settings: &AppSettings) -> bool {
     settings.default_folder.is_some()
         || settings.last_opened_folder.is_some()
         || settings.last_workspace_path.is_some()
         || !settings.recent_workspace_paths.is_empty()
         || settings.window_state.is_some()
+        || !settings.recent_folders.is_empty()
+        || settings.max_recent_folders.is_some()
+        || !settings.recent_files.is_empty()
+        || !settings.favorite_folders.is_empty()
+        || !settings.granted_folders.is_empty()
+        || settings.restore_open_windows
+        || settings.allow_multiple_instances
+        || settings.global_toggle_shortcut.is_some()
+        || settings.theme.is_some()
+        || settings.soft_delete_ttl_minutes.is_some()
+        || settings.autosave_interval_secs.is_some()
+        || !settings.log_level.is_empty()
+        || settings.monitor_name.is_some()
+        || settings.terminal_command.is_some()
+        || !settings.default_handler_extensions.is_empty()
+        || settings.memory_pressure_threshold_mb.is_some()
+        || settings.memory_poll_interval_secs.is_some()
+        || settings.job_parallelism.is_some()
+        || settings.locale.is_some()
+        || settings.always_on_top
+        || settings.window_opacity.is_some()
+        || settings.auto_reload
         || !settings.extra.is_empty()
 }
 
+/// Absolute, so the store follows a portable or user-chosen data directory.
+fn settings_store_path(app: &tauri::AppHandle) -> PathBuf {
+    data_dir::data_dir(app).join(SETTINGS_STORE)
+}
+
+/// Where the settings store actually lives on disk.
+fn settings_file_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    tauri_plugin_store::resolve_store_path(app, settings_store_path(app)).map_err(|error| error.to_string())
+}
+
 fn build_app_settings_from_store(app: &tauri::AppHandle) -> Result<AppSettings, String> {
-    let store = app.store(SETTINGS_STORE).map_err(|error| error.to_string())?;
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
 
     let mut settings = store
         .get(FRONTEND_SETTINGS_KEY)
@@ -722,23 +1037,68 @@ fn build_app_settings_from_store(app: &tauri::AppHandle) -> Result<AppSettings,
             .and_then(|value| serde_json::from_value::<WindowStateSnapshot>(value).ok());
     }
 
+    if settings.recent_folders.is_empty() {
+        settings.recent_folders = store
+            .get(RECENT_FOLDERS_KEY)
+            .and_then(|value| serde_json::from_value::<Vec<String>>(value).ok())
+            .unwrap_or_default();
+    }
+
     settings.default_folder = normalize_optional_path(settings.default_folder);
     settings.last_opened_folder = normalize_optional_path(settings.last_opened_folder);
     settings.last_workspace_path = normalize_optional_path(settings.last_workspace_path);
     settings.recent_workspace_paths = normalize_recent_workspace_paths(settings.recent_workspace_paths);
+    let recent_folder_limit = recent_folders_limit(&settings);
+    settings.recent_folders = normalize_recent_folders(settings.recent_folders, recent_folder_limit);
 
     Ok(settings)
 }
 
 fn save_app_settings(app: &tauri::AppHandle, settings: AppSettings) -> Result<AppSettings, String> {
-    let store = app.store(SETTINGS_STORE).map_err(|error| error.to_string())?;
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    let recent_folder_limit = recent_folders_limit(&settings);
 
     let normalized = AppSettings {
+        // Never stamp a newer schema down to ours; see `settings_migration`.
+        version: settings.version.max(SETTINGS_SCHEMA_VERSION),
         default_folder: normalize_optional_path(settings.default_folder),
         last_opened_folder: normalize_optional_path(settings.last_opened_folder),
         last_workspace_path: normalize_optional_path(settings.last_workspace_path),
         recent_workspace_paths: normalize_recent_workspace_paths(settings.recent_workspace_paths),
         window_state: settings.window_state,
+        recent_folders: normalize_recent_folders(settings.recent_folders, recent_folder_limit),
+        max_recent_folders: settings.max_recent_folders,
+        recent_files: recent_files::normalize_recent_files(settings.recent_files),
+        favorite_folders: favorites::normalize_favorite_folders(settings.favorite_folders),
+        granted_folders: folder_access::normalize_granted_folders(settings.granted_folders),
+        restore_open_windows: settings.restore_open_windows,
+        allow_multiple_instances: settings.allow_multiple_instances,
+        global_toggle_shortcut: settings
+            .global_toggle_shortcut
+            .filter(|shortcut| !shortcut.trim().is_empty()),
+        theme: settings
+            .theme
+            .and_then(|theme| ThemePreference::parse(&theme))
+            .map(|theme| theme.as_str().to_string()),
+        soft_delete_ttl_minutes: settings.soft_delete_ttl_minutes,
+        autosave_interval_secs: autosave::normalize_autosave_interval(settings.autosave_interval_secs),
+        log_level: logging::normalize_log_level(&settings.log_level).unwrap_or_default(),
+        monitor_name: settings.monitor_name.filter(|name| !name.trim().is_empty()),
+        terminal_command: settings
+            .terminal_command
+            .filter(|command| !command.trim().is_empty()),
+        default_handler_extensions: file_associations::normalize_default_handler_extensions(
+            settings.default_handler_extensions,
+        ),
+        memory_pressure_threshold_mb: memory_pressure::normalize_memory_threshold(
+            settings.memory_pressure_threshold_mb,
+        ),
+        memory_poll_interval_secs: memory_pressure::normalize_memory_poll_interval(settings.memory_poll_interval_secs),
+        job_parallelism: jobs::normalize_job_parallelism(settings.job_parallelism),
+        locale: settings.locale.and_then(|locale| locale::normalize_locale(&locale)),
+        always_on_top: settings.always_on_top,
+        window_opacity: window_flags::normalize_window_opacity(settings.window_opacity),
+        auto_reload: settings.auto_reload,
         extra: settings.extra,
     };
 
@@ -781,6 +1141,15 @@ fn save_app_settings(app: &tauri::AppHandle, settings: AppSettings) -> Result<Ap
         store.delete(WINDOW_STATE_KEY);
     }
 
+    if normalized.recent_folders.is_empty() {
+        store.delete(RECENT_FOLDERS_KEY);
+    } else {
+        store.set(
+            RECENT_FOLDERS_KEY,
+            serde_json::to_value(&normalized.recent_folders).map_err(|error| error.to_string())?,
+        );
+    }
+
     if has_persisted_app_settings(&normalized) {
         store.set(
             FRONTEND_SETTINGS_KEY,
@@ -790,315 +1159,637 @@ fn save_app_settings(app: &tauri::AppHandle, settings: AppSettings) -> Result<Ap
         store.delete(FRONTEND_SETTINGS_KEY);
     }
 
-    store.save().map_err(|error| error.to_string())?;
+    save_settings_store(app)?;
     Ok(normalized)
 }
 
-#[tauri::command]
-fn get_default_folder(app: tauri::AppHandle) -> Result<Option<String>, String> {
-    Ok(build_app_settings_from_store(&app)?.default_folder)
+fn preserve_backend_owned_settings(incoming: &Value, current: AppSettings, next: &mut AppSettings) {
+    let Some(fields) = incoming.as_object() else {
+        return;
+    };
+
+    // The frontend writes its whole settings object back; fields it doesn't know
+    // about yet must not be wiped just because they were omitted.
+    if !fields.contains_key("recentFolders") {
+        next.recent_folders = current.recent_folders;
+    }
+    if !fields.contains_key("maxRecentFolders") {
+        next.max_recent_folders = current.max_recent_folders;
+    }
+    if !fields.contains_key("recentFiles") {
+        next.recent_files = current.recent_files;
+    }
+    if !fields.contains_key("favoriteFolders") {
+        next.favorite_folders = current.favorite_folders;
+    }
+    if !fields.contains_key("grantedFolders") {
+        next.granted_folders = current.granted_folders;
+    }
+    if !fields.contains_key("restoreOpenWindows") {
+        next.restore_open_windows = current.restore_open_windows;
+    }
+    if !fields.contains_key("allowMultipleInstances") {
+        next.allow_multiple_instances = current.allow_multiple_instances;
+    }
+    if !fields.contains_key("globalToggleShortcut") {
+        next.global_toggle_shortcut = current.global_toggle_shortcut;
+    }
+    if !fields.contains_key("theme") {
+        next.theme = current.theme;
+    }
+    if !fields.contains_key("softDeleteTtlMinutes") {
+        next.soft_delete_ttl_minutes = current.soft_delete_ttl_minutes;
+    }
+    if !fields.contains_key("autosaveIntervalSecs") {
+        next.autosave_interval_secs = current.autosave_interval_secs;
+    }
+    if !fields.contains_key("logLevel") {
+        next.log_level = current.log_level;
+    }
+    if !fields.contains_key("monitorName") {
+        next.monitor_name = current.monitor_name;
+    }
+    if !fields.contains_key("terminalCommand") {
+        next.terminal_command = current.terminal_command;
+    }
+    if !fields.contains_key("defaultHandlerExtensions") {
+        next.default_handler_extensions = current.default_handler_extensions;
+    }
+    if !fields.contains_key("memoryPressureThresholdMb") {
+        next.memory_pressure_threshold_mb = current.memory_pressure_threshold_mb;
+    }
+    if !fields.contains_key("memoryPollIntervalSecs") {
+        next.memory_poll_interval_secs = current.memory_poll_interval_secs;
+    }
+    if !fields.contains_key("jobParallelism") {
+        next.job_parallelism = current.job_parallelism;
+    }
+    if !fields.contains_key("locale") {
+        next.locale = current.locale;
+    }
+    if !fields.contains_key("alwaysOnTop") {
+        next.always_on_top = current.always_on_top;
+    }
+    if !fields.contains_key("windowOpacity") {
+        next.window_opacity = current.window_opacity;
+    }
+    if !fields.contains_key("autoReload") {
+        next.auto_reload = current.auto_reload;
+    }
 }
 
 #[tauri::command]
-fn set_default_folder(app: tauri::AppHandle, folder: String) -> Result<(), String> {
-    let mut settings = build_app_settings_from_store(&app)?;
-    settings.default_folder = Some(folder);
-    save_app_settings(&app, settings)?;
-    Ok(())
+fn get_default_folder(app: tauri::AppHandle) -> Result<Option<String>, LatticeError> {
+    log_command("get_default_folder", || Ok(build_app_settings_from_store(&app)?.default_folder))
 }
 
 #[tauri::command]
-fn get_last_opened_folder(app: tauri::AppHandle) -> Result<Option<String>, String> {
-    Ok(build_app_settings_from_store(&app)?.last_opened_folder)
+fn set_default_folder(app: tauri::AppHandle, folder: String) -> Result<(), LatticeError> {
+    log_command("set_default_folder", || {
+        let folder = paths::resolve_command_path(&app, &folder)?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.default_folder = Some(folder.to_string_lossy().to_string());
+        save_app_settings(&app, settings)?;
+        Ok(())
+    })
 }
 
 #[tauri::command]
-fn set_last_opened_folder(app: tauri::AppHandle, folder: String) -> Result<(), String> {
-    let mut settings = build_app_settings_from_store(&app)?;
-    settings.last_opened_folder = Some(folder);
-    save_app_settings(&app, settings)?;
-    Ok(())
+fn get_last_opened_folder(app: tauri::AppHandle) -> Result<Option<String>, LatticeError> {
+    log_command("get_last_opened_folder", || Ok(build_app_settings_from_store(&app)?.last_opened_folder))
 }
 
 #[tauri::command]
-fn clear_default_folder(app: tauri::AppHandle) -> Result<(), String> {
-    let mut settings = build_app_settings_from_store(&app)?;
-    settings.default_folder = None;
-    save_app_settings(&app, settings)?;
-    Ok(())
+fn set_last_opened_folder(app: tauri::AppHandle, folder: String) -> Result<(), LatticeError> {
+    log_command("set_last_opened_folder", || {
+        let folder = paths::resolve_command_path(&app, &folder)?.to_string_lossy().to_string();
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.last_opened_folder = Some(folder.clone());
+        save_app_settings(&app, settings)?;
+        // Opening a folder starts its content index; a failure there shouldn't block the open.
+        let _ = index::index_folder(&app, &folder, false).log_error(format_args!("Failed to index {folder}"));
+        Ok(())
+    })
 }
 
 #[tauri::command]
-fn set_last_workspace_path(app: tauri::AppHandle, path: Option<String>) -> Result<(), String> {
-    let mut settings = build_app_settings_from_store(&app)?;
-    settings.last_workspace_path = path.clone();
-    settings.last_opened_folder = path;
-    save_app_settings(&app, settings)?;
-    Ok(())
+fn clear_default_folder(app: tauri::AppHandle) -> Result<(), LatticeError> {
+    log_command("clear_default_folder", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.default_folder = None;
+        save_app_settings(&app, settings)?;
+        Ok(())
+    })
 }
 
 #[tauri::command]
-fn resolve_startup_workspace(app: tauri::AppHandle) -> Result<StartupWorkspaceResolution, String> {
-    let settings = build_app_settings_from_store(&app)?;
-    let valid_recent_workspace_paths = normalize_recent_workspace_paths(
-        settings
-            .recent_workspace_paths
-            .iter()
-            .filter_map(|path| resolve_existing_directory_path(path))
-            .collect(),
-    );
+fn set_last_workspace_path(app: tauri::AppHandle, path: Option<String>) -> Result<(), LatticeError> {
+    log_command("set_last_workspace_path", || {
+        let path = match path.filter(|path| !path.trim().is_empty()) {
+            Some(path) => Some(paths::resolve_command_path(&app, &path)?.to_string_lossy().to_string()),
+            None => None,
+        };
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.last_workspace_path = path.clone();
+        settings.last_opened_folder = path;
+        save_app_settings(&app, settings)?;
+        Ok(())
+    })
+}
 
-    let mut next_settings = settings.clone();
-    next_settings.recent_workspace_paths = valid_recent_workspace_paths.clone();
+#[tauri::command]
+fn get_recent_folders(app: tauri::AppHandle) -> Result<Vec<String>, LatticeError> {
+    log_command("get_recent_folders", || Ok(build_app_settings_from_store(&app)?.recent_folders))
+}
 
-    let resolved = if let Some(valid_path) = settings
-        .last_workspace_path
-        .as_deref()
-        .and_then(resolve_existing_directory_path)
-    {
-        next_settings.last_workspace_path = Some(valid_path.clone());
-        next_settings.last_opened_folder = Some(valid_path.clone());
-        if !next_settings.recent_workspace_paths.iter().any(|path| path == &valid_path) {
-            next_settings.recent_workspace_paths.insert(0, valid_path.clone());
-            next_settings.recent_workspace_paths =
-                normalize_recent_workspace_paths(next_settings.recent_workspace_paths.clone());
+#[tauri::command]
+fn push_recent_folder(app: tauri::AppHandle, folder: String) -> Result<RecentFolderEntry, LatticeError> {
+    log_command("push_recent_folder", || {
+        if folder.trim().is_empty() {
+            return Err(LatticeError::InvalidInput {
+                message: "Folder path is required.".to_string(),
+            });
         }
+        let resolved = paths::resolve_command_path(&app, &folder)?.to_string_lossy().to_string();
 
-        StartupWorkspaceResolution {
-            path: Some(valid_path),
-            source: Some(StartupWorkspaceSource::LastWorkspacePath),
-        }
-    } else if let Some(valid_path) = settings
-        .last_opened_folder
-        .as_deref()
-        .and_then(resolve_existing_directory_path)
-    {
-        next_settings.last_workspace_path = Some(valid_path.clone());
-        next_settings.last_opened_folder = Some(valid_path.clone());
-        if !next_settings.recent_workspace_paths.iter().any(|path| path == &valid_path) {
-            next_settings.recent_workspace_paths.insert(0, valid_path.clone());
-            next_settings.recent_workspace_paths =
-                normalize_recent_workspace_paths(next_settings.recent_workspace_paths.clone());
-        }
+        let mut settings = build_app_settings_from_store(&app)?;
+        let limit = recent_folders_limit(&settings);
+        settings.recent_folders = push_recent_folder_path(&settings.recent_folders, &resolved, limit);
+        save_app_settings(&app, settings)?;
 
-        StartupWorkspaceResolution {
-            path: Some(valid_path),
-            source: Some(StartupWorkspaceSource::LastWorkspacePath),
-        }
-    } else if let Some(valid_path) = valid_recent_workspace_paths.first().cloned() {
-        next_settings.last_workspace_path = Some(valid_path.clone());
-        next_settings.last_opened_folder = Some(valid_path.clone());
+        Ok(RecentFolderEntry {
+            exists: resolve_existing_directory_path(&resolved).is_some(),
+            path: resolved,
+        })
+    })
+}
+
+#[tauri::command]
+fn set_max_recent_folders(app: tauri::AppHandle, max: Option<usize>) -> Result<Vec<String>, LatticeError> {
+    log_command("set_max_recent_folders", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.max_recent_folders = max.filter(|limit| *limit > 0);
+        Ok(save_app_settings(&app, settings)?.recent_folders)
+    })
+}
+
+#[tauri::command]
+fn clear_recent_folders(app: tauri::AppHandle) -> Result<(), LatticeError> {
+    log_command("clear_recent_folders", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.recent_folders.clear();
+        save_app_settings(&app, settings)?;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn prune_missing_recent_folders(app: tauri::AppHandle) -> Result<Vec<String>, LatticeError> {
+    log_command("prune_missing_recent_folders", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        let retained: Vec<String> = settings
+            .recent_folders
+            .iter()
+            .filter(|path| resolve_existing_directory_path(path).is_some())
+            .cloned()
+            .collect();
 
-        StartupWorkspaceResolution {
-            path: Some(valid_path),
-            source: Some(StartupWorkspaceSource::RecentWorkspacePaths),
+        if retained.len() == settings.recent_folders.len() {
+            return Ok(retained);
         }
-    } else if let Some(valid_path) = settings
-        .default_folder
-        .as_deref()
-        .and_then(resolve_existing_directory_path)
-    {
-        next_settings.default_folder = Some(valid_path.clone());
 
-        StartupWorkspaceResolution {
-            path: Some(valid_path),
-            source: Some(StartupWorkspaceSource::DefaultFolder),
+        settings.recent_folders = retained;
+        Ok(save_app_settings(&app, settings)?.recent_folders)
+    })
+}
+
+#[tauri::command]
+fn resolve_startup_workspace(app: tauri::AppHandle) -> Result<StartupWorkspaceResolution, LatticeError> {
+    log_command("resolve_startup_workspace", || {
+        let settings = build_app_settings_from_store(&app)?;
+        let valid_recent_workspace_paths = normalize_recent_workspace_paths(
+            settings
+                .recent_workspace_paths
+                .iter()
+                .filter_map(|path| resolve_existing_directory_path(path))
+                .collect(),
+        );
+
+        let mut next_settings = settings.clone();
+        next_settings.recent_workspace_paths = valid_recent_workspace_paths.clone();
+
+        let resolved = if let Some(valid_path) = settings
+            .last_workspace_path
+            .as_deref()
+            .and_then(resolve_existing_directory_path)
+        {
+            next_settings.last_workspace_path = Some(valid_path.clone());
+            next_settings.last_opened_folder = Some(valid_path.clone());
+            if !next_settings.recent_workspace_paths.iter().any(|path| path == &valid_path) {
+                next_settings.recent_workspace_paths.insert(0, valid_path.clone());
+                next_settings.recent_workspace_paths =
+                    normalize_recent_workspace_paths(next_settings.recent_workspace_paths.clone());
+            }
+
+            StartupWorkspaceResolution {
+                path: Some(valid_path),
+                source: Some(StartupWorkspaceSource::LastWorkspacePath),
+            }
+        } else if let Some(valid_path) = settings
+            .last_opened_folder
+            .as_deref()
+            .and_then(resolve_existing_directory_path)
+        {
+            next_settings.last_workspace_path = Some(valid_path.clone());
+            next_settings.last_opened_folder = Some(valid_path.clone());
+            if !next_settings.recent_workspace_paths.iter().any(|path| path == &valid_path) {
+                next_settings.recent_workspace_paths.insert(0, valid_path.clone());
+                next_settings.recent_workspace_paths =
+                    normalize_recent_workspace_paths(next_settings.recent_workspace_paths.clone());
+            }
+
+            StartupWorkspaceResolution {
+                path: Some(valid_path),
+                source: Some(StartupWorkspaceSource::LastWorkspacePath),
+            }
+        } else if let Some(valid_path) = valid_recent_workspace_paths.first().cloned() {
+            next_settings.last_workspace_path = Some(valid_path.clone());
+            next_settings.last_opened_folder = Some(valid_path.clone());
+
+            StartupWorkspaceResolution {
+                path: Some(valid_path),
+                source: Some(StartupWorkspaceSource::RecentWorkspacePaths),
+            }
+        } else if let Some(valid_path) = settings
+            .default_folder
+            .as_deref()
+            .and_then(resolve_existing_directory_path)
+        {
+            next_settings.default_folder = Some(valid_path.clone());
+
+            StartupWorkspaceResolution {
+                path: Some(valid_path),
+                source: Some(StartupWorkspaceSource::DefaultFolder),
+            }
+        } else {
+            next_settings.last_workspace_path = None;
+            next_settings.last_opened_folder = None;
+
+            StartupWorkspaceResolution {
+                path: None,
+                source: None,
+            }
+        };
+
+        if next_settings.default_folder != settings.default_folder
+            || next_settings.last_opened_folder != settings.last_opened_folder
+            || next_settings.last_workspace_path != settings.last_workspace_path
+            || next_settings.recent_workspace_paths != settings.recent_workspace_paths
+        {
+            save_app_settings(&app, next_settings)?;
         }
-    } else {
-        next_settings.last_workspace_path = None;
-        next_settings.last_opened_folder = None;
 
-        StartupWorkspaceResolution {
-            path: None,
-            source: None,
+        Ok(resolved)
+    })
+}
+
+#[tauri::command]
+fn get_setting(app: tauri::AppHandle, key: String) -> Result<Option<Value>, LatticeError> {
+    log_command("get_setting", || {
+        if key == FRONTEND_SETTINGS_KEY {
+            let settings = build_app_settings_from_store(&app)?;
+            if has_persisted_app_settings(&settings) {
+                return Ok(Some(
+                    serde_json::to_value(settings).map_err(|error| error.to_string())?,
+                ));
+            }
+            return Ok(None);
         }
-    };
 
-    if next_settings.default_folder != settings.default_folder
-        || next_settings.last_opened_folder != settings.last_opened_folder
-        || next_settings.last_workspace_path != settings.last_workspace_path
-        || next_settings.recent_workspace_paths != settings.recent_workspace_paths
-    {
-        save_app_settings(&app, next_settings)?;
-    }
+        let store = app.store(settings_store_path(&app)).map_err(|error| error.to_string())?;
+        Ok(store.get(&key))
+    })
+}
+
+#[tauri::command]
+fn set_setting(app: tauri::AppHandle, key: String, value: Value) -> Result<(), LatticeError> {
+    log_command("set_setting", || {
+        if key == FRONTEND_SETTINGS_KEY {
+            let mut settings =
+                serde_json::from_value::<AppSettings>(value.clone()).map_err(|error| error.to_string())?;
+            preserve_backend_owned_settings(&value, build_app_settings_from_store(&app)?, &mut settings);
+            save_app_settings(&app, settings)?;
+            return Ok(());
+        }
 
-    Ok(resolved)
+        let store = app.store(settings_store_path(&app)).map_err(|error| error.to_string())?;
+        store.set(&key, value);
+        save_settings_store(&app)?;
+        Ok(())
+    })
 }
 
 #[tauri::command]
-fn get_setting(app: tauri::AppHandle, key: String) -> Result<Option<Value>, String> {
-    if key == FRONTEND_SETTINGS_KEY {
-        let settings = build_app_settings_from_store(&app)?;
-        if has_persisted_app_settings(&settings) {
-            return Ok(Some(
-                serde_json::to_value(settings).map_err(|error| error.to_string())?,
-            ));
+fn remove_setting(app: tauri::AppHandle, key: String) -> Result<(), LatticeError> {
+    log_command("remove_setting", || {
+        if key == FRONTEND_SETTINGS_KEY {
+            save_app_settings(&app, AppSettings::default())?;
+            return Ok(());
         }
-        return Ok(None);
-    }
 
-    let store = app.store(SETTINGS_STORE).map_err(|error| error.to_string())?;
-    Ok(store.get(&key))
+        let store = app.store(settings_store_path(&app)).map_err(|error| error.to_string())?;
+        store.delete(&key);
+        save_settings_store(&app)?;
+        Ok(())
+    })
 }
 
 #[tauri::command]
-fn set_setting(app: tauri::AppHandle, key: String, value: Value) -> Result<(), String> {
-    if key == FRONTEND_SETTINGS_KEY {
-        let settings =
-            serde_json::from_value::<AppSettings>(value).map_err(|error| error.to_string())?;
-        save_app_settings(&app, settings)?;
-        return Ok(());
+fn clear_settings(app: tauri::AppHandle) -> Result<(), LatticeError> {
+    log_command("clear_settings", || {
+        let store = app.store(settings_store_path(&app)).map_err(|error| error.to_string())?;
+        store.clear();
+        save_settings_store(&app)?;
+        Ok(())
+    })
+}
+
+fn encode_settings_bundle(entries: Vec<(String, Value)>) -> Result<Vec<u8>, String> {
+    let bundle: serde_json::Map<String, Value> = entries.into_iter().collect();
+    serde_json::to_vec_pretty(&Value::Object(bundle)).map_err(|error| error.to_string())
+}
+
+/// The upgraded bundle and how many keys the file itself held, since the upgrade can
+/// add keys of its own.
+fn parse_settings_bundle(raw: &[u8]) -> Result<(serde_json::Map<String, Value>, usize), String> {
+    match serde_json::from_slice::<Value>(raw) {
+        Ok(Value::Object(mut bundle)) => {
+            let imported = bundle.len();
+            // Bundles from older builds go through the same upgrade as settings.json.
+            migrate_settings_document(&mut bundle).map_err(|error| error.to_string())?;
+            Ok((bundle, imported))
+        }
+        Ok(_) => Err("Settings bundle must be a JSON object.".to_string()),
+        Err(error) => Err(format!("Settings bundle is not valid JSON: {error}")),
     }
+}
 
-    let store = app.store(SETTINGS_STORE).map_err(|error| error.to_string())?;
-    store.set(&key, value);
-    store.save().map_err(|error| error.to_string())?;
-    Ok(())
+#[tauri::command]
+fn export_settings(app: tauri::AppHandle, dest: String) -> Result<(), LatticeError> {
+    log_command("export_settings", || {
+        let store = app.store(settings_store_path(&app)).map_err(|error| error.to_string())?;
+        let dest = resolve_app_path(&app, &dest, None)?;
+        write_bytes_atomic(Path::new(&dest.path), &encode_settings_bundle(store.entries())?)
+    })
 }
 
 #[tauri::command]
-fn remove_setting(app: tauri::AppHandle, key: String) -> Result<(), String> {
-    if key == FRONTEND_SETTINGS_KEY {
-        save_app_settings(&app, AppSettings::default())?;
-        return Ok(());
-    }
+fn import_settings(app: tauri::AppHandle, src: String, merge: bool) -> Result<usize, LatticeError> {
+    log_command("import_settings", || {
+        let src = resolve_app_path(&app, &src, None)?;
+        let raw = fs::read(&src.path).map_err(|error| LatticeError::at_path(Path::new(&src.display), error))?;
+        let (bundle, imported) = parse_settings_bundle(&raw)?;
+
+        let store = app.store(settings_store_path(&app)).map_err(|error| error.to_string())?;
+        let mut backup_name = settings_file_path(&app)?.into_os_string();
+        backup_name.push(".pre-import.bak");
+        write_bytes_atomic(Path::new(&backup_name), &encode_settings_bundle(store.entries())?)?;
+
+        if !merge {
+            store.clear();
+        }
+        for (key, value) in bundle {
+            store.set(key, value);
+        }
+        save_settings_store(&app)?;
+        Ok(imported)
+    })
+}
 
-    let store = app.store(SETTINGS_STORE).map_err(|error| error.to_string())?;
-    store.delete(&key);
-    store.save().map_err(|error| error.to_string())?;
-    Ok(())
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CompactReport {
+    removed: Vec<String>,
+    bytes_before: u64,
+    bytes_after: u64,
+    /// The store as it was before compacting.
+    backup_path: String,
+}
+
+fn orphaned_store_keys(keys: impl IntoIterator<Item = String>, known_keys: &[String]) -> Vec<String> {
+    let mut orphaned: Vec<String> = keys
+        .into_iter()
+        .filter(|key| !BACKEND_STORE_KEYS.contains(&key.as_str()) && !known_keys.contains(key))
+        .collect();
+    orphaned.sort();
+    orphaned
 }
 
+/// Drops every store key that is neither in `known_keys` nor owned by the backend, then
+/// saves the store, which rewrites the file pretty-printed.
 #[tauri::command]
-fn clear_settings(app: tauri::AppHandle) -> Result<(), String> {
-    let store = app.store(SETTINGS_STORE).map_err(|error| error.to_string())?;
-    store.clear();
-    store.save().map_err(|error| error.to_string())?;
-    Ok(())
+fn compact_store(app: tauri::AppHandle, known_keys: Vec<String>) -> Result<CompactReport, LatticeError> {
+    log_command("compact_store", || {
+        let store = app.store(settings_store_path(&app)).map_err(|error| error.to_string())?;
+        let file_path = settings_file_path(&app)?;
+        let bytes_before = fs::metadata(&file_path).map(|metadata| metadata.len()).unwrap_or(0);
+
+        let known_keys: Vec<String> = known_keys.iter().map(|key| key.trim().to_string()).collect();
+        let removed = orphaned_store_keys(store.keys(), &known_keys);
+        let mut backup_name = file_path.clone().into_os_string();
+        backup_name.push(".pre-compact.bak");
+        write_bytes_atomic(Path::new(&backup_name), &encode_settings_bundle(store.entries())?)?;
+
+        for key in &removed {
+            store.delete(key);
+        }
+        save_settings_store(&app)?;
+        if !removed.is_empty() {
+            log::info!("Compacted settings store, removing {}", removed.join(", "));
+        }
+
+        Ok(CompactReport {
+            removed,
+            bytes_before,
+            bytes_after: fs::metadata(&file_path).map(|metadata| metadata.len()).unwrap_or(0),
+            backup_path: backup_name.to_string_lossy().to_string(),
+        })
+    })
 }
 
 #[tauri::command]
 async fn desktop_read_dir(
+    app: tauri::AppHandle,
     fs_state: State<'_, DesktopFsState>,
     path: String,
-) -> Result<Vec<DesktopDirEntry>, String> {
-    let normalized = PathBuf::from(path);
-    let permit = fs_state
-        .read_dir_permits
-        .clone()
-        .acquire_owned()
-        .await
-        .map_err(|error| error.to_string())?;
+) -> Result<Vec<DesktopDirEntry>, LatticeError> {
+    log_command_async("desktop_read_dir", async move {
+        let normalized = paths::resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
 
-    tokio::task::spawn_blocking(move || {
-        let _permit = permit;
-        let entries = fs::read_dir(&normalized).map_err(|error| error.to_string())?;
-        let mut results = Vec::new();
-
-        for entry in entries {
-            let entry = entry.map_err(|error| error.to_string())?;
-            let file_type = entry.file_type().map_err(|error| error.to_string())?;
-            results.push(DesktopDirEntry {
-                name: entry.file_name().to_string_lossy().to_string(),
-                is_directory: file_type.is_dir(),
-                is_file: file_type.is_file(),
-                is_symlink: file_type.is_symlink(),
-            });
-        }
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let entries = fs::read_dir(&normalized)?;
+            let mut results = Vec::new();
+
+            for entry in entries {
+                let entry = entry?;
+                let file_type = entry.file_type()?;
+                results.push(DesktopDirEntry {
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    is_directory: file_type.is_dir(),
+                    is_file: file_type.is_file(),
+                    is_symlink: file_type.is_symlink(),
+                });
+            }
 
-        Ok(results)
+            Ok(results)
+        })
+        .await
+        .map_err(|error| error.to_string())?
     })
     .await
-    .map_err(|error| error.to_string())?
 }
 
 #[tauri::command]
 async fn desktop_read_file_bytes_raw(
+    app: tauri::AppHandle,
     fs_state: State<'_, DesktopFsState>,
     path: String,
-) -> Result<TauriResponse, String> {
-    let permit = fs_state
-        .read_file_permits
-        .clone()
-        .acquire_owned()
+) -> Result<TauriResponse, LatticeError> {
+    log_command_async("desktop_read_file_bytes_raw", async move {
+        let path = paths::resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let bytes = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            fs::read(path)
+        })
         .await
-        .map_err(|error| error.to_string())?;
+        .map_err(|error| error.to_string())??;
 
-    let bytes = tokio::task::spawn_blocking(move || {
-        let _permit = permit;
-        fs::read(PathBuf::from(path)).map_err(|error| error.to_string())
+        Ok(TauriResponse::new(bytes))
     })
     .await
-    .map_err(|error| error.to_string())??;
-
-    Ok(TauriResponse::new(bytes))
 }
 
 #[tauri::command]
 async fn desktop_read_text_file(
+    app: tauri::AppHandle,
     fs_state: State<'_, DesktopFsState>,
     path: String,
-) -> Result<String, String> {
-    let permit = fs_state
-        .read_file_permits
-        .clone()
-        .acquire_owned()
-        .await
-        .map_err(|error| error.to_string())?;
+) -> Result<String, LatticeError> {
+    log_command_async("desktop_read_text_file", async move {
+        let path = paths::resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
 
-    tokio::task::spawn_blocking(move || {
-        let _permit = permit;
-        fs::read_to_string(PathBuf::from(path)).map_err(|error| error.to_string())
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            fs::read_to_string(path).map_err(LatticeError::from)
+        })
+        .await
+        .map_err(|error| error.to_string())?
     })
     .await
-    .map_err(|error| error.to_string())?
 }
 
 #[tauri::command]
 async fn desktop_read_text_file_chunk(
+    app: tauri::AppHandle,
     fs_state: State<'_, DesktopFsState>,
     path: String,
     max_bytes: usize,
-) -> Result<DesktopTextChunk, String> {
-    let permit = fs_state
-        .read_file_permits
-        .clone()
-        .acquire_owned()
-        .await
-        .map_err(|error| error.to_string())?;
+) -> Result<DesktopTextChunk, LatticeError> {
+    log_command_async("desktop_read_text_file_chunk", async move {
+        let target = paths::resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
 
-    tokio::task::spawn_blocking(move || {
-        let _permit = permit;
-        let target = PathBuf::from(path);
-        let mut file = fs::File::open(&target).map_err(|error| error.to_string())?;
-        let total_bytes = file.metadata().map_err(|error| error.to_string())?.len();
-        let read_limit = max_bytes.max(1).min(1024 * 1024);
-        let mut buffer = vec![0; read_limit];
-        let bytes_read = file.read(&mut buffer).map_err(|error| error.to_string())?;
-        buffer.truncate(bytes_read);
-
-        Ok(DesktopTextChunk {
-            text: String::from_utf8_lossy(&buffer).to_string(),
-            bytes_read,
-            total_bytes,
-            has_more: (bytes_read as u64) < total_bytes,
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let mut file = fs::File::open(&target)?;
+            let total_bytes = file.metadata()?.len();
+            let read_limit = max_bytes.max(1).min(1024 * 1024);
+            let mut buffer = vec![0; read_limit];
+            let bytes_read = file.read(&mut buffer)?;
+            buffer.truncate(bytes_read);
+
+            Ok(DesktopTextChunk {
+                text: String::from_utf8_lossy(&buffer).to_string(),
+                bytes_read,
+                total_bytes,
+                has_more: (bytes_read as u64) < total_bytes,
+            })
         })
+        .await
+        .map_err(|error| error.to_string())?
     })
     .await
-    .map_err(|error| error.to_string())?
 }
 
 #[tauri::command]
-fn desktop_write_file_bytes(path: String, data: Vec<u8>) -> Result<(), String> {
-    fs::write(PathBuf::from(path), data).map_err(|error| error.to_string())
+fn desktop_write_file_bytes(app: tauri::AppHandle, path: String, data: Vec<u8>) -> Result<(), LatticeError> {
+    log_command("desktop_write_file_bytes", || {
+        let path = paths::resolve_command_path(&app, &path)?;
+        fs::write(&path, data).map_err(|error| LatticeError::at_path(&path, error))
+    })
 }
 
 #[tauri::command]
 async fn desktop_copy_path(
+    app: tauri::AppHandle,
     fs_state: State<'_, DesktopFsState>,
     source: String,
     target: String,
-) -> Result<(), String> {
+) -> Result<(), LatticeError> {
+    log_command_async("desktop_copy_path", async move {
+        let source = paths::resolve_command_path(&app, &source)?;
+        let target = paths::resolve_command_path(&app, &target)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            copy_desktop_path(&source, &target)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Shared by `desktop_move_path` and `desktop_rename_path`, which each log under their own name.
+async fn move_desktop_path(
+    app: &tauri::AppHandle,
+    fs_state: &DesktopFsState,
+    source: &str,
+    target: &str,
+) -> Result<(), LatticeError> {
+    let source_path = paths::resolve_command_path(app, source)?;
+    let target_path = paths::resolve_command_path(app, target)?;
     let permit = fs_state
         .mutate_path_permits
         .clone()
@@ -1108,7 +1799,11 @@ async fn desktop_copy_path(
 
     tokio::task::spawn_blocking(move || {
         let _permit = permit;
-        copy_desktop_path(&PathBuf::from(source), &PathBuf::from(target))
+        match fs::rename(&source_path, &target_path) {
+            Ok(()) => Ok(()),
+            Err(_) => copy_desktop_path(&source_path, &target_path)
+                .and_then(|_| remove_desktop_path_sync(&source_path, true)),
+        }
     })
     .await
     .map_err(|error| error.to_string())?
@@ -1116,136 +1811,140 @@ async fn desktop_copy_path(
 
 #[tauri::command]
 async fn desktop_move_path(
+    app: tauri::AppHandle,
     fs_state: State<'_, DesktopFsState>,
     source: String,
     target: String,
-) -> Result<(), String> {
-    let permit = fs_state
-        .mutate_path_permits
-        .clone()
-        .acquire_owned()
-        .await
-        .map_err(|error| error.to_string())?;
-
-    tokio::task::spawn_blocking(move || {
-        let _permit = permit;
-        let source_path = PathBuf::from(source);
-        let target_path = PathBuf::from(target);
-        match fs::rename(&source_path, &target_path) {
-            Ok(()) => Ok(()),
-            Err(_) => {
-                copy_desktop_path(&source_path, &target_path)?;
-                remove_desktop_path_sync(&source_path, true)
-            }
-        }
+) -> Result<(), LatticeError> {
+    log_command_async("desktop_move_path", async move {
+        move_desktop_path(&app, &fs_state, &source, &target).await
     })
     .await
-    .map_err(|error| error.to_string())?
 }
 
 #[tauri::command]
 async fn desktop_rename_path(
+    app: tauri::AppHandle,
     fs_state: State<'_, DesktopFsState>,
     source: String,
     target: String,
-) -> Result<(), String> {
-    desktop_move_path(fs_state, source, target).await
+) -> Result<(), LatticeError> {
+    log_command_async("desktop_rename_path", async move {
+        move_desktop_path(&app, &fs_state, &source, &target).await
+    })
+    .await
 }
 
 #[tauri::command]
-async fn desktop_exists_path(path: String) -> Result<bool, String> {
-    tokio::task::spawn_blocking(move || {
-        PathBuf::from(path)
-            .try_exists()
-            .map_err(|error| error.to_string())
+async fn desktop_exists_path(app: tauri::AppHandle, path: String) -> Result<bool, LatticeError> {
+    log_command_async("desktop_exists_path", async move {
+        let path = paths::resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || path.try_exists().map_err(LatticeError::from))
+        .await
+        .map_err(|error| error.to_string())?
     })
     .await
-    .map_err(|error| error.to_string())?
 }
 
 #[tauri::command]
-async fn desktop_file_metadata(path: String) -> Result<DesktopFileMetadata, String> {
-    tokio::task::spawn_blocking(move || {
-        let metadata = fs::metadata(PathBuf::from(path)).map_err(|error| error.to_string())?;
-        let modified_ms = metadata
-            .modified()
-            .ok()
-            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
-            .map(|duration| duration.as_millis());
-
-        Ok(DesktopFileMetadata {
-            size: metadata.len(),
-            modified_ms,
+async fn desktop_file_metadata(app: tauri::AppHandle, path: String) -> Result<DesktopFileMetadata, LatticeError> {
+    log_command_async("desktop_file_metadata", async move {
+        let path = paths::resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || {
+            let metadata = fs::metadata(path)?;
+            let modified_ms = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_millis());
+
+            Ok(DesktopFileMetadata {
+                size: metadata.len(),
+                modified_ms,
+            })
         })
+        .await
+        .map_err(|error| error.to_string())?
     })
     .await
-    .map_err(|error| error.to_string())?
 }
 
 #[tauri::command]
 async fn desktop_is_directory(
+    app: tauri::AppHandle,
     fs_state: State<'_, DesktopFsState>,
     path: String,
-) -> Result<bool, String> {
-    let permit = fs_state
-        .read_dir_permits
-        .clone()
-        .acquire_owned()
-        .await
-        .map_err(|error| error.to_string())?;
+) -> Result<bool, LatticeError> {
+    log_command_async("desktop_is_directory", async move {
+        let path = paths::resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
 
-    tokio::task::spawn_blocking(move || {
-        let _permit = permit;
-        Ok(PathBuf::from(path).is_dir())
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            Ok(path.is_dir())
+        })
+        .await
+        .map_err(|error| error.to_string())?
     })
     .await
-    .map_err(|error| error.to_string())?
 }
 
 #[tauri::command]
-async fn fetch_web_document(url: String) -> Result<WebDocumentSnapshot, String> {
-    let trimmed = url.trim();
-    if trimmed.is_empty() {
-        return Err("URL is required.".to_string());
-    }
-
-    let parsed = reqwest::Url::parse(trimmed).map_err(|error| error.to_string())?;
-    match parsed.scheme() {
-        "http" | "https" => {}
-        _ => return Err("Only HTTP and HTTPS URLs can be opened internally.".to_string()),
-    }
+async fn fetch_web_document(url: String) -> Result<WebDocumentSnapshot, LatticeError> {
+    log_command_async("fetch_web_document", async move {
+        let trimmed = url.trim();
+        if trimmed.is_empty() {
+            return Err(LatticeError::InvalidInput {
+                message: "URL is required.".to_string(),
+            });
+        }
 
-    let client = reqwest::Client::builder()
-        .redirect(reqwest::redirect::Policy::limited(10))
-        .timeout(Duration::from_secs(15))
-        .user_agent("Lattice/2.2.0 (+https://github.com/tryandaction/lattice)")
-        .build()
-        .map_err(|error| error.to_string())?;
+        let parsed = reqwest::Url::parse(trimmed).map_err(|error| error.to_string())?;
+        match parsed.scheme() {
+            "http" | "https" => {}
+            _ => return Err(LatticeError::Unsupported {
+                message: "Only HTTP and HTTPS URLs can be opened internally.".to_string(),
+            }),
+        }
 
-    let response = client
-        .get(parsed)
-        .send()
-        .await
-        .map_err(|error| error.to_string())?;
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::limited(10))
+            .timeout(Duration::from_secs(15))
+            .user_agent("Lattice/2.2.0 (+https://github.com/tryandaction/lattice)")
+            .build()
+            .map_err(|error| error.to_string())?;
 
-    let status = response.status();
-    if !status.is_success() {
-        return Err(format!("Failed to load webpage: HTTP {}", status.as_u16()));
-    }
+        let response = client
+            .get(parsed)
+            .send()
+            .await
+            .map_err(|error| error.to_string())?;
 
-    let final_url = response.url().to_string();
-    let content_type = response
-        .headers()
-        .get(reqwest::header::CONTENT_TYPE)
-        .and_then(|value| value.to_str().ok())
-        .map(|value| value.to_string());
-    let body = response.text().await.map_err(|error| error.to_string())?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("Failed to load webpage: HTTP {}", status.as_u16()).into());
+        }
 
-    Ok(WebDocumentSnapshot {
-        final_url,
-        content_type,
-        body,
+        let final_url = response.url().to_string();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+        let body = response.text().await.map_err(|error| error.to_string())?;
+
+        Ok(WebDocumentSnapshot {
+            final_url,
+            content_type,
+            body,
+        })
     })
+    .await
 }
 
 #[tauri::command]
@@ -1260,58 +1959,62 @@ async fn desktop_native_webview_mount(
     height: f64,
     visible: bool,
     focus: bool,
-) -> Result<DesktopNativeWebviewSnapshot, String> {
-    let target_url = reqwest::Url::parse(url.trim()).map_err(|error| error.to_string())?;
-    if let Some(existing) = app.get_webview(&label) {
-        let current_url = existing.url().map_err(|error| error.to_string())?;
-        if current_url != target_url {
+) -> Result<DesktopNativeWebviewSnapshot, LatticeError> {
+    log_command_async("desktop_native_webview_mount", async move {
+        let target_url = reqwest::Url::parse(url.trim()).map_err(|error| error.to_string())?;
+        if let Some(existing) = app.get_webview(&label) {
+            let current_url = existing.url().map_err(|error| error.to_string())?;
+            if current_url != target_url {
+                existing
+                    .navigate(target_url.clone())
+                    .map_err(|error| error.to_string())?;
+            }
             existing
-                .navigate(target_url.clone())
+                .set_position(LogicalPosition::new(x, y))
                 .map_err(|error| error.to_string())?;
-        }
-        existing
-            .set_position(LogicalPosition::new(x, y))
-            .map_err(|error| error.to_string())?;
-        existing
-            .set_size(LogicalSize::new(width, height))
-            .map_err(|error| error.to_string())?;
-        if visible {
-            existing.show().map_err(|error| error.to_string())?;
-            if focus {
-                existing.set_focus().map_err(|error| error.to_string())?;
+            existing
+                .set_size(LogicalSize::new(width, height))
+                .map_err(|error| error.to_string())?;
+            if visible {
+                existing.show().map_err(|error| error.to_string())?;
+                if focus {
+                    existing.set_focus().map_err(|error| error.to_string())?;
+                }
+            } else {
+                existing.hide().map_err(|error| error.to_string())?;
             }
-        } else {
-            existing.hide().map_err(|error| error.to_string())?;
+            return set_desktop_native_webview_snapshot(
+                &app,
+                &label,
+                Some(target_url.to_string()),
+                None,
+                Some("ready"),
+                Some(None),
+            );
         }
-        return set_desktop_native_webview_snapshot(
-            &app,
-            &label,
-            Some(target_url.to_string()),
-            None,
-            Some("ready"),
-            Some(None),
-        );
-    }
 
-    let app_for_navigation = app.clone();
-    let label_for_navigation = label.clone();
-    let app_for_page_load = app.clone();
-    let label_for_page_load = label.clone();
-    let app_for_title = app.clone();
-    let label_for_title = label.clone();
-    let app_for_new_window = app.clone();
-    let label_for_new_window = label.clone();
-    let app_for_download = app.clone();
-    let label_for_download = label.clone();
-
-    let webview_builder = tauri::webview::WebviewBuilder::new(
-        label.clone(),
-        WebviewUrl::External(target_url.clone()),
-    )
-    .user_agent("LatticeEmbeddedWebview/2.2.0")
-    .data_directory(build_desktop_native_webview_data_directory(&target_url))
-    .initialization_script(
-        r#"
+        let app_for_navigation = app.clone();
+        let label_for_navigation = label.clone();
+        let app_for_page_load = app.clone();
+        let label_for_page_load = label.clone();
+        let app_for_title = app.clone();
+        let label_for_title = label.clone();
+        let app_for_new_window = app.clone();
+        let label_for_new_window = label.clone();
+        let app_for_download = app.clone();
+        let label_for_download = label.clone();
+
+        let webview_builder = tauri::webview::WebviewBuilder::new(
+            label.clone(),
+            WebviewUrl::External(target_url.clone()),
+        )
+        .user_agent("LatticeEmbeddedWebview/2.2.0")
+        .data_directory(match data_dir::redirected_data_dir(&app) {
+            Some(root) => root.join(build_desktop_native_webview_data_directory(&target_url)),
+            None => build_desktop_native_webview_data_directory(&target_url),
+        })
+        .initialization_script(
+            r#"
           (() => {
             const patchHistory = (method) => {
               const original = history[method];
@@ -1332,173 +2035,175 @@ async fn desktop_native_webview_mount(
             });
           })();
         "#,
-    )
-    .on_navigation(move |next_url| {
-        let _ = set_desktop_native_webview_snapshot(
-            &app_for_navigation,
-            &label_for_navigation,
-            Some(next_url.to_string()),
-            None,
-            Some("ready"),
-            None,
-        );
-        true
-    })
-    .on_page_load(move |_webview, payload| {
-        let _ = set_desktop_native_webview_snapshot(
-            &app_for_page_load,
-            &label_for_page_load,
-            Some(payload.url().to_string()),
-            None,
-            Some("ready"),
-            Some(None),
-        );
-    })
-    .on_document_title_changed(move |_webview, title| {
-        let _ = set_desktop_native_webview_snapshot(
-            &app_for_title,
-            &label_for_title,
-            None,
-            Some(title),
-            None,
-            None,
-        );
-    })
-    .on_new_window(move |next_url, _features| {
-        let _ = emit_desktop_native_webview_new_window(
-            &app_for_new_window,
-            &label_for_new_window,
-            &next_url,
-        );
-        tauri::webview::NewWindowResponse::Deny
-    })
-    .on_download(move |_webview, event| {
-        match event {
-            tauri::webview::DownloadEvent::Requested { url, destination } => {
-                let _ = emit_desktop_native_webview_download_event(
-                    &app_for_download,
-                    DesktopNativeWebviewDownloadEventPayload {
-                        label: label_for_download.clone(),
-                        phase: "requested".to_string(),
-                        url: url.to_string(),
-                        path: Some(destination.display().to_string()),
-                        success: None,
-                    },
-                );
-            }
-            tauri::webview::DownloadEvent::Finished { url, path, success } => {
-                let _ = emit_desktop_native_webview_download_event(
-                    &app_for_download,
-                    DesktopNativeWebviewDownloadEventPayload {
-                        label: label_for_download.clone(),
-                        phase: "finished".to_string(),
-                        url: url.to_string(),
-                        path: path.map(|value| value.display().to_string()),
-                        success: Some(success),
-                    },
-                );
+        )
+        .on_navigation(move |next_url| {
+            let _ = set_desktop_native_webview_snapshot(
+                &app_for_navigation,
+                &label_for_navigation,
+                Some(next_url.to_string()),
+                None,
+                Some("ready"),
+                None,
+            );
+            true
+        })
+        .on_page_load(move |_webview, payload| {
+            let _ = set_desktop_native_webview_snapshot(
+                &app_for_page_load,
+                &label_for_page_load,
+                Some(payload.url().to_string()),
+                None,
+                Some("ready"),
+                Some(None),
+            );
+        })
+        .on_document_title_changed(move |_webview, title| {
+            let _ = set_desktop_native_webview_snapshot(
+                &app_for_title,
+                &label_for_title,
+                None,
+                Some(title),
+                None,
+                None,
+            );
+        })
+        .on_new_window(move |next_url, _features| {
+            let _ = emit_desktop_native_webview_new_window(
+                &app_for_new_window,
+                &label_for_new_window,
+                &next_url,
+            );
+            tauri::webview::NewWindowResponse::Deny
+        })
+        .on_download(move |_webview, event| {
+            match event {
+                tauri::webview::DownloadEvent::Requested { url, destination } => {
+                    let _ = emit_desktop_native_webview_download_event(
+                        &app_for_download,
+                        DesktopNativeWebviewDownloadEventPayload {
+                            label: label_for_download.clone(),
+                            phase: "requested".to_string(),
+                            url: url.to_string(),
+                            path: Some(destination.display().to_string()),
+                            success: None,
+                        },
+                    );
+                }
+                tauri::webview::DownloadEvent::Finished { url, path, success } => {
+                    let _ = emit_desktop_native_webview_download_event(
+                        &app_for_download,
+                        DesktopNativeWebviewDownloadEventPayload {
+                            label: label_for_download.clone(),
+                            phase: "finished".to_string(),
+                            url: url.to_string(),
+                            path: path.map(|value| value.display().to_string()),
+                            success: Some(success),
+                        },
+                    );
+                }
+                _ => {}
             }
-            _ => {}
-        }
-        true
-    });
-
-    let initial_snapshot = set_desktop_native_webview_snapshot(
-        &app,
-        &label,
-        Some(target_url.to_string()),
-        None,
-        Some("mounting"),
-        Some(None),
-    )?;
-
-    let app_for_mount = app.clone();
-    let label_for_mount = label.clone();
-    let target_url_for_mount = target_url.clone();
-    let window_label_for_mount = window_label.clone();
-    std::thread::spawn(move || {
-        let app_for_thread = app_for_mount.clone();
-        let label_for_thread = label_for_mount.clone();
-        let target_url_for_thread = target_url_for_mount.clone();
-        let window_label_for_thread = window_label_for_mount.clone();
-        let mount_result = app_for_mount.run_on_main_thread(move || {
-            let Some(window) = app_for_thread.get_window(&window_label_for_thread) else {
-                let _ = set_desktop_native_webview_snapshot(
-                    &app_for_thread,
-                    &label_for_thread,
-                    Some(target_url_for_thread.to_string()),
-                    None,
-                    Some("error"),
-                    Some(Some(format!("Window not found: {}", window_label_for_thread))),
-                );
-                return;
-            };
+            true
+        });
 
-            let child_result = window.add_child(
-                webview_builder,
-                LogicalPosition::new(x, y),
-                LogicalSize::new(width, height),
-            );
+        let initial_snapshot = set_desktop_native_webview_snapshot(
+            &app,
+            &label,
+            Some(target_url.to_string()),
+            None,
+            Some("mounting"),
+            Some(None),
+        )?;
 
-            match child_result {
-                Ok(webview) => {
-                    let visibility_result = if !visible {
-                        webview.hide().map_err(|error| error.to_string())
-                    } else if focus {
-                        webview.set_focus().map_err(|error| error.to_string())
-                    } else {
-                        Ok(())
-                    };
-
-                    match visibility_result {
-                        Ok(()) => {
-                            let _ = set_desktop_native_webview_snapshot(
-                                &app_for_thread,
-                                &label_for_thread,
-                                Some(target_url_for_thread.to_string()),
-                                None,
-                                Some("ready"),
-                                Some(None),
-                            );
-                        }
-                        Err(error) => {
-                            let _ = set_desktop_native_webview_snapshot(
-                                &app_for_thread,
-                                &label_for_thread,
-                                Some(target_url_for_thread.to_string()),
-                                None,
-                                Some("error"),
-                                Some(Some(error)),
-                            );
-                        }
-                    }
-                }
-                Err(error) => {
+        let app_for_mount = app.clone();
+        let label_for_mount = label.clone();
+        let target_url_for_mount = target_url.clone();
+        let window_label_for_mount = window_label.clone();
+        std::thread::spawn(move || {
+            let app_for_thread = app_for_mount.clone();
+            let label_for_thread = label_for_mount.clone();
+            let target_url_for_thread = target_url_for_mount.clone();
+            let window_label_for_thread = window_label_for_mount.clone();
+            let mount_result = app_for_mount.run_on_main_thread(move || {
+                let Some(window) = app_for_thread.get_window(&window_label_for_thread) else {
                     let _ = set_desktop_native_webview_snapshot(
                         &app_for_thread,
                         &label_for_thread,
                         Some(target_url_for_thread.to_string()),
                         None,
                         Some("error"),
-                        Some(Some(error.to_string())),
+                        Some(Some(format!("Window not found: {}", window_label_for_thread))),
                     );
+                    return;
+                };
+
+                let child_result = window.add_child(
+                    webview_builder,
+                    LogicalPosition::new(x, y),
+                    LogicalSize::new(width, height),
+                );
+
+                match child_result {
+                    Ok(webview) => {
+                        let visibility_result = if !visible {
+                            webview.hide().map_err(|error| error.to_string())
+                        } else if focus {
+                            webview.set_focus().map_err(|error| error.to_string())
+                        } else {
+                            Ok(())
+                        };
+
+                        match visibility_result {
+                            Ok(()) => {
+                                let _ = set_desktop_native_webview_snapshot(
+                                    &app_for_thread,
+                                    &label_for_thread,
+                                    Some(target_url_for_thread.to_string()),
+                                    None,
+                                    Some("ready"),
+                                    Some(None),
+                                );
+                            }
+                            Err(error) => {
+                                let _ = set_desktop_native_webview_snapshot(
+                                    &app_for_thread,
+                                    &label_for_thread,
+                                    Some(target_url_for_thread.to_string()),
+                                    None,
+                                    Some("error"),
+                                    Some(Some(error)),
+                                );
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        let _ = set_desktop_native_webview_snapshot(
+                            &app_for_thread,
+                            &label_for_thread,
+                            Some(target_url_for_thread.to_string()),
+                            None,
+                            Some("error"),
+                            Some(Some(error.to_string())),
+                        );
+                    }
                 }
+            });
+
+            if let Err(error) = mount_result {
+                let _ = set_desktop_native_webview_snapshot(
+                    &app_for_mount,
+                    &label_for_mount,
+                    Some(target_url_for_mount.to_string()),
+                    None,
+                    Some("error"),
+                    Some(Some(error.to_string())),
+                );
             }
         });
 
-        if let Err(error) = mount_result {
-            let _ = set_desktop_native_webview_snapshot(
-                &app_for_mount,
-                &label_for_mount,
-                Some(target_url_for_mount.to_string()),
-                None,
-                Some("error"),
-                Some(Some(error.to_string())),
-            );
-        }
-    });
-
-    Ok(initial_snapshot)
+        Ok(initial_snapshot)
+    })
+    .await
 }
 
 #[tauri::command]
@@ -1509,16 +2214,18 @@ fn desktop_native_webview_update_bounds(
     y: f64,
     width: f64,
     height: f64,
-) -> Result<(), String> {
-    let webview = app
-        .get_webview(&label)
-        .ok_or_else(|| format!("Webview not found: {}", label))?;
-    webview
-        .set_position(LogicalPosition::new(x, y))
-        .map_err(|error| error.to_string())?;
-    webview
-        .set_size(LogicalSize::new(width, height))
-        .map_err(|error| error.to_string())
+) -> Result<(), LatticeError> {
+    log_command("desktop_native_webview_update_bounds", || {
+        let webview = app
+            .get_webview(&label)
+            .ok_or_else(|| format!("Webview not found: {}", label))?;
+        webview
+            .set_position(LogicalPosition::new(x, y))
+            .map_err(|error| error.to_string())?;
+        webview
+            .set_size(LogicalSize::new(width, height))
+            .map_err(|error| error.to_string().into())
+    })
 }
 
 #[tauri::command]
@@ -1527,48 +2234,54 @@ fn desktop_native_webview_set_visibility(
     label: String,
     visible: bool,
     focus: bool,
-) -> Result<(), String> {
-    let webview = app
-        .get_webview(&label)
-        .ok_or_else(|| format!("Webview not found: {}", label))?;
-    if visible {
-        webview.show().map_err(|error| error.to_string())?;
-        if focus {
-            webview.set_focus().map_err(|error| error.to_string())?;
+) -> Result<(), LatticeError> {
+    log_command("desktop_native_webview_set_visibility", || {
+        let webview = app
+            .get_webview(&label)
+            .ok_or_else(|| format!("Webview not found: {}", label))?;
+        if visible {
+            webview.show().map_err(|error| error.to_string())?;
+            if focus {
+                webview.set_focus().map_err(|error| error.to_string())?;
+            }
+        } else {
+            webview.hide().map_err(|error| error.to_string())?;
         }
-    } else {
-        webview.hide().map_err(|error| error.to_string())?;
-    }
-    Ok(())
+        Ok(())
+    })
 }
 
 #[tauri::command]
-fn desktop_native_webview_close(app: AppHandle, label: String) -> Result<(), String> {
-    if let Some(webview) = app.get_webview(&label) {
-        webview.close().map_err(|error| error.to_string())?;
-    }
-    remove_desktop_native_webview_snapshot(&app, &label)
+fn desktop_native_webview_close(app: AppHandle, label: String) -> Result<(), LatticeError> {
+    log_command("desktop_native_webview_close", || {
+        if let Some(webview) = app.get_webview(&label) {
+            webview.close().map_err(|error| error.to_string())?;
+        }
+        remove_desktop_native_webview_snapshot(&app, &label)
+    })
 }
 
 #[tauri::command]
 fn desktop_native_webview_get_state(
     app: AppHandle,
     label: String,
-) -> Result<Option<DesktopNativeWebviewSnapshot>, String> {
-    if let Some(webview) = app.get_webview(&label) {
-        let current_url = webview.url().map_err(|error| error.to_string())?.to_string();
-        let snapshot = set_desktop_native_webview_snapshot(
-            &app,
-            &label,
-            Some(current_url),
-            None,
-            Some("ready"),
-            None,
-        )?;
-        return Ok(Some(snapshot));
-    }
+) -> Result<Option<DesktopNativeWebviewSnapshot>, LatticeError> {
+    log_command("desktop_native_webview_get_state", || {
+        if let Some(webview) = app.get_webview(&label) {
+            let current_url = webview.url().map_err(|error| error.to_string())?.to_string();
+            let snapshot = set_desktop_native_webview_snapshot(
+                &app,
+                &label,
+                Some(current_url),
+                None,
+                Some("ready"),
+                None,
+            )?;
+            return Ok(Some(snapshot));
+        }
 
-    get_desktop_native_webview_snapshot(&app, &label)
+        get_desktop_native_webview_snapshot(&app, &label)
+    })
 }
 
 #[tauri::command]
@@ -1576,189 +2289,229 @@ fn desktop_native_webview_navigate(
     app: AppHandle,
     label: String,
     url: String,
-) -> Result<DesktopNativeWebviewSnapshot, String> {
-    let webview = app
-        .get_webview(&label)
-        .ok_or_else(|| format!("Webview not found: {}", label))?;
-    let target_url = reqwest::Url::parse(url.trim()).map_err(|error| error.to_string())?;
-    webview
-        .navigate(target_url.clone())
-        .map_err(|error| error.to_string())?;
-    set_desktop_native_webview_snapshot(
-        &app,
-        &label,
-        Some(target_url.to_string()),
-        None,
-        Some("ready"),
-        Some(None),
-    )
+) -> Result<DesktopNativeWebviewSnapshot, LatticeError> {
+    log_command("desktop_native_webview_navigate", || {
+        let webview = app
+            .get_webview(&label)
+            .ok_or_else(|| format!("Webview not found: {}", label))?;
+        let target_url = reqwest::Url::parse(url.trim()).map_err(|error| error.to_string())?;
+        webview
+            .navigate(target_url.clone())
+            .map_err(|error| error.to_string())?;
+        set_desktop_native_webview_snapshot(
+            &app,
+            &label,
+            Some(target_url.to_string()),
+            None,
+            Some("ready"),
+            Some(None),
+        )
+    })
 }
 
 #[tauri::command]
-fn desktop_native_webview_reload(app: AppHandle, label: String) -> Result<(), String> {
-    let webview = app
-        .get_webview(&label)
-        .ok_or_else(|| format!("Webview not found: {}", label))?;
-    webview.reload().map_err(|error| error.to_string())
+fn desktop_native_webview_reload(app: AppHandle, label: String) -> Result<(), LatticeError> {
+    log_command("desktop_native_webview_reload", || {
+        let webview = app
+            .get_webview(&label)
+            .ok_or_else(|| format!("Webview not found: {}", label))?;
+        webview.reload().map_err(|error| error.to_string().into())
+    })
 }
 
 #[tauri::command]
-fn desktop_native_webview_go_back(app: AppHandle, label: String) -> Result<(), String> {
-    let webview = app
-        .get_webview(&label)
-        .ok_or_else(|| format!("Webview not found: {}", label))?;
-    webview
-        .eval("window.history.back();")
-        .map_err(|error| error.to_string())
+fn desktop_native_webview_go_back(app: AppHandle, label: String) -> Result<(), LatticeError> {
+    log_command("desktop_native_webview_go_back", || {
+        let webview = app
+            .get_webview(&label)
+            .ok_or_else(|| format!("Webview not found: {}", label))?;
+        webview
+            .eval("window.history.back();")
+            .map_err(|error| error.to_string().into())
+    })
 }
 
 #[tauri::command]
-fn desktop_native_webview_go_forward(app: AppHandle, label: String) -> Result<(), String> {
-    let webview = app
-        .get_webview(&label)
-        .ok_or_else(|| format!("Webview not found: {}", label))?;
-    webview
-        .eval("window.history.forward();")
-        .map_err(|error| error.to_string())
+fn desktop_native_webview_go_forward(app: AppHandle, label: String) -> Result<(), LatticeError> {
+    log_command("desktop_native_webview_go_forward", || {
+        let webview = app
+            .get_webview(&label)
+            .ok_or_else(|| format!("Webview not found: {}", label))?;
+        webview
+            .eval("window.history.forward();")
+            .map_err(|error| error.to_string().into())
+    })
 }
 
 #[tauri::command]
 async fn desktop_create_dir(
+    app: tauri::AppHandle,
     fs_state: State<'_, DesktopFsState>,
     path: String,
     recursive: bool,
-) -> Result<(), String> {
-    let permit = fs_state
-        .mutate_path_permits
-        .clone()
-        .acquire_owned()
-        .await
-        .map_err(|error| error.to_string())?;
+) -> Result<(), LatticeError> {
+    log_command_async("desktop_create_dir", async move {
+        let target = paths::resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
 
-    tokio::task::spawn_blocking(move || {
-        let _permit = permit;
-        let target = PathBuf::from(path);
-        if recursive {
-            fs::create_dir_all(target).map_err(|error| error.to_string())?;
-        } else {
-            fs::create_dir(target).map_err(|error| error.to_string())?;
-        }
-        Ok(())
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let created = if recursive {
+                fs::create_dir_all(&target)
+            } else {
+                fs::create_dir(&target)
+            };
+            created.map_err(|error| LatticeError::at_path(&target, error))
+        })
+        .await
+        .map_err(|error| error.to_string())?
     })
     .await
-    .map_err(|error| error.to_string())?
 }
 
 #[tauri::command]
 async fn desktop_remove_path(
+    app: tauri::AppHandle,
     fs_state: State<'_, DesktopFsState>,
     path: String,
     recursive: bool,
-) -> Result<(), String> {
-    let permit = fs_state
-        .mutate_path_permits
-        .clone()
-        .acquire_owned()
-        .await
-        .map_err(|error| error.to_string())?;
+) -> Result<(), LatticeError> {
+    log_command_async("desktop_remove_path", async move {
+        let path = paths::resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
 
-    tokio::task::spawn_blocking(move || {
-        let _permit = permit;
-        remove_desktop_path_sync(&PathBuf::from(path), recursive)
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            remove_desktop_path_sync(&path, recursive)
+        })
+        .await
+        .map_err(|error| error.to_string())?
     })
     .await
-    .map_err(|error| error.to_string())?
 }
 
-fn copy_desktop_path(source: &Path, target: &Path) -> Result<(), String> {
-    let metadata = fs::symlink_metadata(source).map_err(|error| error.to_string())?;
+fn copy_desktop_path(source: &Path, target: &Path) -> Result<(), LatticeError> {
+    let at_source = |error| LatticeError::at_path(source, error);
+    let at_target = |error| LatticeError::at_path(target, error);
+    let metadata = fs::symlink_metadata(source).map_err(at_source)?;
+    if fs::symlink_metadata(target).is_ok() && fileops::same_file_sync(source, target)? {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Source and destination are the same: {}", source.display()),
+        });
+    }
     if metadata.is_dir() {
-        fs::create_dir_all(target).map_err(|error| error.to_string())?;
-        for entry in fs::read_dir(source).map_err(|error| error.to_string())? {
-            let entry = entry.map_err(|error| error.to_string())?;
+        fs::create_dir_all(target).map_err(at_target)?;
+        for entry in fs::read_dir(source).map_err(at_source)? {
+            let entry = entry.map_err(at_source)?;
             copy_desktop_path(&entry.path(), &target.join(entry.file_name()))?;
         }
         return Ok(());
     }
 
     if let Some(parent) = target.parent() {
-        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+        fs::create_dir_all(parent).map_err(at_target)?;
     }
-    fs::copy(source, target).map(|_| ()).map_err(|error| error.to_string())
+    fs::copy(source, target).map_err(at_target)?;
+    Ok(())
 }
 
-fn remove_desktop_path_sync(target: &Path, recursive: bool) -> Result<(), String> {
-    let metadata = fs::symlink_metadata(target).map_err(|error| error.to_string())?;
+fn remove_desktop_path_sync(target: &Path, recursive: bool) -> Result<(), LatticeError> {
+    let at_target = |error| LatticeError::at_path(target, error);
+    let metadata = fs::symlink_metadata(target).map_err(at_target)?;
     if metadata.is_dir() {
         if recursive {
-            fs::remove_dir_all(target).map_err(|error| error.to_string())?;
+            fs::remove_dir_all(target).map_err(at_target)?;
         } else {
-            fs::remove_dir(target).map_err(|error| error.to_string())?;
+            fs::remove_dir(target).map_err(at_target)?;
         }
     } else {
-        fs::remove_file(target).map_err(|error| error.to_string())?;
+        fs::remove_file(target).map_err(at_target)?;
     }
     Ok(())
 }
 
 #[tauri::command]
 fn desktop_set_preview_root(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
     preview_state: State<'_, DesktopPreviewState>,
     path: Option<String>,
-) -> Result<(), String> {
-    let normalized_root = match path {
-        Some(path) if !path.trim().is_empty() => Some(canonicalize_directory_path(&path)?),
-        _ => None,
-    };
+) -> Result<(), LatticeError> {
+    log_command("desktop_set_preview_root", || {
+        let normalized_root = match path {
+            Some(path) if !path.trim().is_empty() => {
+                Some(canonicalize_directory_path(&resolve_app_path(&app, &path, None)?.path)?)
+            }
+            _ => None,
+        };
+        // The frontend sets the preview root whenever a folder is opened in this window.
+        if let Some(root) = &normalized_root {
+            if let Err(error) = folder_window_state::restore_for_folder(&app, &window, &root.to_string_lossy()) {
+                log::error!("Failed to restore window state for folder: {error}");
+            }
+        }
 
-    let mut workspace_root = preview_state
-        .workspace_root
-        .lock()
-        .map_err(|error| error.to_string())?;
-    *workspace_root = normalized_root;
-    Ok(())
+        let mut workspace_root = preview_state
+            .workspace_root
+            .lock()
+            .map_err(|error| error.to_string())?;
+        *workspace_root = normalized_root;
+        Ok(())
+    })
 }
 
 #[tauri::command]
-fn desktop_window_minimize(window: tauri::WebviewWindow) -> Result<(), String> {
-    window.minimize().map_err(|error| error.to_string())
+fn desktop_window_minimize(window: tauri::WebviewWindow) -> Result<(), LatticeError> {
+    log_command("desktop_window_minimize", || window.minimize().map_err(|error| error.to_string().into()))
 }
 
 #[tauri::command]
-fn desktop_window_start_dragging(window: tauri::WebviewWindow) -> Result<(), String> {
-    window.start_dragging().map_err(|error| error.to_string())
+fn desktop_window_start_dragging(window: tauri::WebviewWindow) -> Result<(), LatticeError> {
+    log_command("desktop_window_start_dragging", || window.start_dragging().map_err(|error| error.to_string().into()))
 }
 
 #[tauri::command]
-fn desktop_window_toggle_maximize(window: tauri::WebviewWindow) -> Result<bool, String> {
-    let is_maximized = window.is_maximized().map_err(|error| error.to_string())?;
-    if is_maximized {
-        window.unmaximize().map_err(|error| error.to_string())?;
-        Ok(false)
-    } else {
-        window.maximize().map_err(|error| error.to_string())?;
-        Ok(true)
-    }
+fn desktop_window_toggle_maximize(window: tauri::WebviewWindow) -> Result<bool, LatticeError> {
+    log_command("desktop_window_toggle_maximize", || {
+        let is_maximized = window.is_maximized().map_err(|error| error.to_string())?;
+        if is_maximized {
+            window.unmaximize().map_err(|error| error.to_string())?;
+            Ok(false)
+        } else {
+            window.maximize().map_err(|error| error.to_string())?;
+            Ok(true)
+        }
+    })
 }
 
 #[tauri::command]
-fn desktop_window_is_maximized(window: tauri::WebviewWindow) -> Result<bool, String> {
-    window.is_maximized().map_err(|error| error.to_string())
+fn desktop_window_is_maximized(window: tauri::WebviewWindow) -> Result<bool, LatticeError> {
+    log_command("desktop_window_is_maximized", || window.is_maximized().map_err(|error| error.to_string().into()))
 }
 
 #[tauri::command]
-fn desktop_window_close(window: tauri::WebviewWindow) -> Result<(), String> {
-    window.close().map_err(|error| error.to_string())
+fn desktop_window_close(window: tauri::WebviewWindow) -> Result<(), LatticeError> {
+    log_command("desktop_window_close", || window.close().map_err(|error| error.to_string().into()))
 }
 
 #[tauri::command]
-fn detect_python_environments(cwd: Option<String>) -> Result<Vec<PythonEnvironmentInfo>, String> {
-    discover_python_environments(cwd.as_deref())
+fn detect_python_environments(cwd: Option<String>) -> Result<Vec<PythonEnvironmentInfo>, LatticeError> {
+    log_command("detect_python_environments", || discover_python_environments(cwd.as_deref()))
 }
 
 #[tauri::command]
-fn probe_command_availability(command: String) -> Result<CommandAvailability, String> {
-    Ok(probe_command(&command))
+fn probe_command_availability(command: String) -> Result<CommandAvailability, LatticeError> {
+    log_command("probe_command_availability", || Ok(probe_command(&command)))
 }
 
 fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
@@ -1805,71 +2558,79 @@ fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
 }
 
 #[tauri::command]
-fn formula_ocr_pix2tex(request: FormulaOcrPix2texRequest) -> Result<FormulaOcrPix2texResponse, String> {
-    let command_name = request
-        .command
-        .as_deref()
-        .map(str::trim)
-        .filter(|value| !value.is_empty())
-        .unwrap_or("pix2tex")
-        .to_string();
-    let availability = probe_command(&command_name);
-    if !availability.available {
-        return Err(availability.error.unwrap_or_else(|| {
-            format!("pix2tex is not available. Install LaTeX-OCR and ensure `{command_name}` is on PATH.")
-        }));
-    }
+fn formula_ocr_pix2tex(request: FormulaOcrPix2texRequest) -> Result<FormulaOcrPix2texResponse, LatticeError> {
+    log_command("formula_ocr_pix2tex", || {
+        let command_name = request
+            .command
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or("pix2tex")
+            .to_string();
+        let availability = probe_command(&command_name);
+        if !availability.available {
+            return Err(LatticeError::Unsupported {
+                message: availability.error.unwrap_or_else(|| {
+                    format!("pix2tex is not available. Install LaTeX-OCR and ensure `{command_name}` is on PATH.")
+                }),
+            });
+        }
 
-    let data_url = request.image_data_url.trim();
-    let base64_payload = data_url
-        .strip_prefix("data:image/png;base64,")
-        .ok_or_else(|| "Expected a PNG data URL".to_string())?;
-    let image_bytes = decode_base64(base64_payload)?;
-    if image_bytes.is_empty() {
-        return Err("OCR image is empty".to_string());
-    }
+        let data_url = request.image_data_url.trim();
+        let base64_payload = data_url
+            .strip_prefix("data:image/png;base64,")
+            .ok_or_else(|| "Expected a PNG data URL".to_string())?;
+        let image_bytes = decode_base64(base64_payload)?;
+        if image_bytes.is_empty() {
+            return Err(LatticeError::InvalidInput {
+                message: "OCR image is empty".to_string(),
+            });
+        }
 
-    let image_path = env::temp_dir().join(format!("lattice-formula-ocr-{}.png", Uuid::new_v4()));
-    fs::write(&image_path, image_bytes).map_err(|error| error.to_string())?;
+        let image_path = env::temp_dir().join(format!("lattice-formula-ocr-{}.png", Uuid::new_v4()));
+        fs::write(&image_path, image_bytes)?;
 
-    let output_result = (|| {
-        let mut command = StdCommand::new(&command_name);
-        configure_std_command(&mut command);
-        command.arg(&image_path);
-        command.stdout(Stdio::piped());
-        command.stderr(Stdio::piped());
-        command.output().map_err(|error| error.to_string())
-    })();
+        let output_result = (|| {
+            let mut command = StdCommand::new(&command_name);
+            configure_std_command(&mut command);
+            command.arg(&image_path);
+            command.stdout(Stdio::piped());
+            command.stderr(Stdio::piped());
+            command.output().map_err(|error| error.to_string())
+        })();
 
-    let _ = fs::remove_file(&image_path);
+        let _ = fs::remove_file(&image_path);
 
-    let output = output_result?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        return Err(if stderr.is_empty() {
-            format!("pix2tex exited with status {}", output.status)
-        } else {
-            stderr
-        });
-    }
+        let output = output_result?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(LatticeError::Failed {
+                message: if stderr.is_empty() {
+                    format!("pix2tex exited with status {}", output.status)
+                } else {
+                    stderr
+                },
+            });
+        }
 
-    let latex = String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(str::trim)
-        .filter(|line| !line.is_empty())
-        .last()
-        .unwrap_or("")
-        .trim_matches('`')
-        .trim()
-        .to_string();
-    if latex.is_empty() {
-        return Err("pix2tex returned an empty result".to_string());
-    }
+        let latex = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .last()
+            .unwrap_or("")
+            .trim_matches('`')
+            .trim()
+            .to_string();
+        if latex.is_empty() {
+            return Err("pix2tex returned an empty result".into());
+        }
 
-    Ok(FormulaOcrPix2texResponse {
-        latex,
-        backend: "pix2tex".to_string(),
-        command: command_name,
+        Ok(FormulaOcrPix2texResponse {
+            latex,
+            backend: "pix2tex".to_string(),
+            command: command_name,
+        })
     })
 }
 
@@ -1878,154 +2639,160 @@ async fn start_local_execution(
     app: AppHandle,
     state: State<'_, ExecutionSessions>,
     request: LocalExecutionRequest,
-) -> Result<ExecutionStartResponse, String> {
-    if matches!(request.runner_type, RunnerType::PythonPyodide) {
-        return Err("python-pyodide is not handled by the desktop backend".to_string());
-    }
-
-    if matches!(request.runner_type, RunnerType::CompiledNative) {
-        return start_compiled_native_execution(app, state, request).await;
-    }
-
-    let session_id = request
-        .session_id
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| Uuid::new_v4().to_string());
-    let runner_type = request.runner_type.clone();
-    let mode = request.mode.clone();
-    let request_cwd = request.cwd.clone();
-    let request_file_path = request.file_path.clone();
+) -> Result<ExecutionStartResponse, LatticeError> {
+    log_command_async("start_local_execution", async move {
+        if matches!(request.runner_type, RunnerType::PythonPyodide) {
+            return Err(LatticeError::Unsupported {
+                message: "python-pyodide is not handled by the desktop backend".to_string(),
+            });
+        }
 
-    let cwd_path = request
-        .cwd
-        .as_deref()
-        .filter(|value| !value.trim().is_empty())
-        .map(PathBuf::from);
+        if matches!(request.runner_type, RunnerType::CompiledNative) {
+            return start_compiled_native_execution(app, state, request).await;
+        }
 
-    let (mut command, cleanup_artifacts) = build_execution_command(&request, cwd_path.as_deref())?;
+        let session_id = request
+            .session_id
+            .clone()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let runner_type = request.runner_type.clone();
+        let mode = request.mode.clone();
+        let request_cwd = request.cwd.clone();
+        let request_file_path = request.file_path.clone();
+
+        let cwd_path = request
+            .cwd
+            .as_deref()
+            .filter(|value| !value.trim().is_empty())
+            .map(|cwd| paths::resolve_command_path(&app, cwd))
+            .transpose()?;
+
+        let (mut command, cleanup_artifacts) = build_execution_command(&request, cwd_path.as_deref())?;
+
+        if let Some(cwd) = &cwd_path {
+            command.current_dir(cwd);
+        }
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
 
-    if let Some(cwd) = &cwd_path {
-        command.current_dir(cwd);
-    }
-    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        if let Some(environment) = &request.env {
+            command.envs(environment);
+        }
+        command.env("PYTHONUTF8", "1");
+        command.env("PYTHONIOENCODING", "utf-8");
 
-    if let Some(environment) = &request.env {
-        command.envs(environment);
-    }
-    command.env("PYTHONUTF8", "1");
-    command.env("PYTHONIOENCODING", "utf-8");
+        let mut child = command.spawn()?;
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let child = Arc::new(Mutex::new(child));
+        let terminated = Arc::new(AtomicBool::new(false));
 
-    let mut child = command.spawn().map_err(|error| error.to_string())?;
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
-    let child = Arc::new(Mutex::new(child));
-    let terminated = Arc::new(AtomicBool::new(false));
+        {
+            let mut sessions = state.sessions.lock().await;
+            sessions.insert(
+                session_id.clone(),
+                ManagedExecution {
+                    child: Arc::clone(&child),
+                    terminated: Arc::clone(&terminated),
+                },
+            );
+        }
 
-    {
-        let mut sessions = state.sessions.lock().await;
-        sessions.insert(
-            session_id.clone(),
-            ManagedExecution {
-                child: Arc::clone(&child),
-                terminated: Arc::clone(&terminated),
-            },
+        emit_runner_event(
+            &app,
+            &session_id,
+            "started",
+            json!({
+                "cwd": request_cwd,
+                "filePath": request_file_path,
+                "mode": mode,
+                "runnerType": runner_type,
+            }),
         );
-    }
 
-    emit_runner_event(
-        &app,
-        &session_id,
-        "started",
-        json!({
-            "cwd": request_cwd,
-            "filePath": request_file_path,
-            "mode": mode,
-            "runnerType": runner_type,
-        }),
-    );
+        if let Some(stdout_pipe) = stdout {
+            let app_handle = app.clone();
+            let stdout_session_id = session_id.clone();
+            let stdout_runner_type = runner_type.clone();
+            tokio::spawn(async move {
+                stream_output(
+                    app_handle,
+                    stdout_session_id,
+                    stdout_pipe,
+                    "stdout",
+                    matches!(stdout_runner_type, RunnerType::PythonLocal),
+                )
+                .await;
+            });
+        }
 
-    if let Some(stdout_pipe) = stdout {
-        let app_handle = app.clone();
-        let stdout_session_id = session_id.clone();
-        let stdout_runner_type = runner_type.clone();
-        tokio::spawn(async move {
-            stream_output(
-                app_handle,
-                stdout_session_id,
-                stdout_pipe,
-                "stdout",
-                matches!(stdout_runner_type, RunnerType::PythonLocal),
-            )
-            .await;
-        });
-    }
+        if let Some(stderr_pipe) = stderr {
+            let app_handle = app.clone();
+            let stderr_session_id = session_id.clone();
+            tokio::spawn(async move {
+                stream_output(app_handle, stderr_session_id, stderr_pipe, "stderr", false).await;
+            });
+        }
 
-    if let Some(stderr_pipe) = stderr {
-        let app_handle = app.clone();
-        let stderr_session_id = session_id.clone();
+        let wait_app = app.clone();
+        let wait_session_id = session_id.clone();
+        let cleanup = cleanup_artifacts.clone();
         tokio::spawn(async move {
-            stream_output(app_handle, stderr_session_id, stderr_pipe, "stderr", false).await;
-        });
-    }
-
-    let wait_app = app.clone();
-    let wait_session_id = session_id.clone();
-    let cleanup = cleanup_artifacts.clone();
-    tokio::spawn(async move {
-        let exit_result = {
-            let mut guard = child.lock().await;
-            guard.wait().await
-        };
+            let exit_result = {
+                let mut guard = child.lock().await;
+                guard.wait().await
+            };
 
-        match exit_result {
-            Ok(status) => {
-                let success = status.success();
-                let exit_code = status.code();
-                let was_terminated = terminated.load(Ordering::SeqCst);
-                emit_runner_event(
-                    &wait_app,
-                    &wait_session_id,
-                    if was_terminated { "terminated" } else { "completed" },
-                    json!({
-                        "success": success && !was_terminated,
-                        "exitCode": exit_code,
-                        "terminated": was_terminated,
-                    }),
-                );
-            }
-            Err(error) => {
-                emit_runner_event(
-                    &wait_app,
-                    &wait_session_id,
-                    "error",
-                    json!({
-                        "message": error.to_string(),
-                    }),
-                );
+            match exit_result {
+                Ok(status) => {
+                    let success = status.success();
+                    let exit_code = status.code();
+                    let was_terminated = terminated.load(Ordering::SeqCst);
+                    emit_runner_event(
+                        &wait_app,
+                        &wait_session_id,
+                        if was_terminated { "terminated" } else { "completed" },
+                        json!({
+                            "success": success && !was_terminated,
+                            "exitCode": exit_code,
+                            "terminated": was_terminated,
+                        }),
+                    );
+                }
+                Err(error) => {
+                    emit_runner_event(
+                        &wait_app,
+                        &wait_session_id,
+                        "error",
+                        json!({
+                            "message": error.to_string(),
+                        }),
+                    );
+                }
             }
-        }
 
-        {
-            let sessions = wait_app.state::<ExecutionSessions>();
-            let mut guard = sessions.sessions.lock().await;
-            guard.remove(&wait_session_id);
-        }
+            {
+                let sessions = wait_app.state::<ExecutionSessions>();
+                let mut guard = sessions.sessions.lock().await;
+                guard.remove(&wait_session_id);
+            }
 
-        cleanup_execution_files(cleanup);
-    });
+            cleanup_execution_files(cleanup);
+        });
 
-    Ok(ExecutionStartResponse {
-        session_id,
-        runner_type,
+        Ok(ExecutionStartResponse {
+            session_id,
+            runner_type,
+        })
     })
+    .await
 }
 
 async fn start_compiled_native_execution(
     app: AppHandle,
     state: State<'_, ExecutionSessions>,
     request: LocalExecutionRequest,
-) -> Result<ExecutionStartResponse, String> {
+) -> Result<ExecutionStartResponse, LatticeError> {
     let session_id = request
         .session_id
         .clone()
@@ -2039,7 +2806,8 @@ async fn start_compiled_native_execution(
         .cwd
         .as_deref()
         .filter(|value| !value.trim().is_empty())
-        .map(PathBuf::from);
+        .map(|cwd| paths::resolve_command_path(&app, cwd))
+        .transpose()?;
 
     let compiler = request
         .command
@@ -2054,11 +2822,11 @@ async fn start_compiled_native_execution(
 
     let compiler_availability = probe_command(&compiler);
     if !compiler_availability.available {
-        return Err(
-            compiler_availability
+        return Err(LatticeError::Unsupported {
+            message: compiler_availability
                 .error
                 .unwrap_or_else(|| format!("Command not available: {compiler}")),
-        );
+        });
     }
 
     let output_path = compiled_native_output_path(&session_id)?;
@@ -2098,7 +2866,7 @@ async fn start_compiled_native_execution(
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    let mut compile_child = compile_command.spawn().map_err(|error| error.to_string())?;
+    let mut compile_child = compile_command.spawn()?;
     let compile_stdout = compile_child.stdout.take();
     let compile_stderr = compile_child.stderr.take();
     let compile_child = Arc::new(Mutex::new(compile_child));
@@ -2306,19 +3074,24 @@ async fn remove_execution_session(app: &AppHandle, session_id: &str) {
 async fn terminate_local_execution(
     state: State<'_, ExecutionSessions>,
     session_id: String,
-) -> Result<(), String> {
-    let managed = {
-        let sessions = state.sessions.lock().await;
-        sessions.get(&session_id).cloned()
-    };
+) -> Result<(), LatticeError> {
+    log_command_async("terminate_local_execution", async move {
+        let managed = {
+            let sessions = state.sessions.lock().await;
+            sessions.get(&session_id).cloned()
+        };
 
-    let Some(managed) = managed else {
-        return Err(format!("Execution session not found: {session_id}"));
-    };
+        let Some(managed) = managed else {
+            return Err(LatticeError::NotFound {
+                message: format!("Execution session not found: {session_id}"),
+            });
+        };
 
-    managed.terminated.store(true, Ordering::SeqCst);
-    let mut child = managed.child.lock().await;
-    child.kill().await.map_err(|error| error.to_string())
+        managed.terminated.store(true, Ordering::SeqCst);
+        let mut child = managed.child.lock().await;
+        child.kill().await.map_err(LatticeError::from)
+    })
+    .await
 }
 
 #[tauri::command]
@@ -2327,152 +3100,156 @@ async fn start_python_session(
     execution_state: State<'_, ExecutionSessions>,
     session_state: State<'_, PythonSessions>,
     request: PythonSessionStartRequest,
-) -> Result<ExecutionStartResponse, String> {
-    let session_id = request
-        .session_id
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| Uuid::new_v4().to_string());
-
-    let cwd_path = request
-        .cwd
-        .as_deref()
-        .filter(|value| !value.trim().is_empty())
-        .map(PathBuf::from);
+) -> Result<ExecutionStartResponse, LatticeError> {
+    log_command_async("start_python_session", async move {
+        let session_id = request
+            .session_id
+            .clone()
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        let cwd_path = request
+            .cwd
+            .as_deref()
+            .filter(|value| !value.trim().is_empty())
+            .map(|cwd| paths::resolve_command_path(&app, cwd))
+            .transpose()?;
+
+        let python = if let Some(command) = request.command.clone().filter(|value| !value.trim().is_empty()) {
+            command
+        } else {
+            let environments = discover_python_environments(cwd_path.as_deref().and_then(Path::to_str))?;
+            environments
+                .first()
+                .map(|env| env.path.clone())
+                .ok_or_else(|| "No local Python interpreter detected".to_string())?
+        };
 
-    let python = if let Some(command) = request.command.clone().filter(|value| !value.trim().is_empty()) {
-        command
-    } else {
-        let environments = discover_python_environments(cwd_path.as_deref().and_then(Path::to_str))?;
-        environments
-            .first()
-            .map(|env| env.path.clone())
-            .ok_or_else(|| "No local Python interpreter detected".to_string())?
-    };
+        let cleanup = create_python_session_artifacts()?;
+        let mut command = Command::new(&python);
+        configure_python_tokio_command(&mut command);
+        command.arg("-u").arg(&cleanup.bootstrap_path);
 
-    let cleanup = create_python_session_artifacts()?;
-    let mut command = Command::new(&python);
-    configure_python_tokio_command(&mut command);
-    command.arg("-u").arg(&cleanup.bootstrap_path);
+        if let Some(cwd) = &cwd_path {
+            command.current_dir(cwd);
+        }
+        command.stdout(Stdio::piped()).stderr(Stdio::piped()).stdin(Stdio::piped());
 
-    if let Some(cwd) = &cwd_path {
-        command.current_dir(cwd);
-    }
-    command.stdout(Stdio::piped()).stderr(Stdio::piped()).stdin(Stdio::piped());
+        if let Some(environment) = &request.env {
+            command.envs(environment);
+        }
+        command.env("PYTHONUTF8", "1");
+        command.env("PYTHONIOENCODING", "utf-8");
 
-    if let Some(environment) = &request.env {
-        command.envs(environment);
-    }
-    command.env("PYTHONUTF8", "1");
-    command.env("PYTHONIOENCODING", "utf-8");
+        let mut child = command.spawn()?;
+        let stdout = child.stdout.take().ok_or_else(|| "Failed to capture Python session stdout".to_string())?;
+        let stderr = child.stderr.take().ok_or_else(|| "Failed to capture Python session stderr".to_string())?;
+        let stdin = child.stdin.take().ok_or_else(|| "Failed to capture Python session stdin".to_string())?;
+        let child = Arc::new(Mutex::new(child));
+        let stdin = Arc::new(Mutex::new(stdin));
+        let terminated = Arc::new(AtomicBool::new(false));
 
-    let mut child = command.spawn().map_err(|error| error.to_string())?;
-    let stdout = child.stdout.take().ok_or_else(|| "Failed to capture Python session stdout".to_string())?;
-    let stderr = child.stderr.take().ok_or_else(|| "Failed to capture Python session stderr".to_string())?;
-    let stdin = child.stdin.take().ok_or_else(|| "Failed to capture Python session stdin".to_string())?;
-    let child = Arc::new(Mutex::new(child));
-    let stdin = Arc::new(Mutex::new(stdin));
-    let terminated = Arc::new(AtomicBool::new(false));
+        {
+            let mut sessions = session_state.sessions.lock().await;
+            sessions.insert(
+                session_id.clone(),
+                ManagedPythonSession {
+                    child: Arc::clone(&child),
+                    stdin: Arc::clone(&stdin),
+                    terminated: Arc::clone(&terminated),
+                    cleanup: cleanup.clone(),
+                },
+            );
+        }
 
-    {
-        let mut sessions = session_state.sessions.lock().await;
-        sessions.insert(
-            session_id.clone(),
-            ManagedPythonSession {
-                child: Arc::clone(&child),
-                stdin: Arc::clone(&stdin),
-                terminated: Arc::clone(&terminated),
-                cleanup: cleanup.clone(),
-            },
-        );
-    }
+        {
+            let mut executions = execution_state.sessions.lock().await;
+            executions.insert(
+                session_id.clone(),
+                ManagedExecution {
+                    child: Arc::clone(&child),
+                    terminated: Arc::clone(&terminated),
+                },
+            );
+        }
 
-    {
-        let mut executions = execution_state.sessions.lock().await;
-        executions.insert(
-            session_id.clone(),
-            ManagedExecution {
-                child: Arc::clone(&child),
-                terminated: Arc::clone(&terminated),
-            },
+        emit_runner_event(
+            &app,
+            &session_id,
+            "started",
+            json!({
+                "cwd": request.cwd,
+                "mode": ExecutionMode::Cell,
+                "runnerType": RunnerType::PythonLocal,
+                "persistent": true,
+            }),
         );
-    }
-
-    emit_runner_event(
-        &app,
-        &session_id,
-        "started",
-        json!({
-            "cwd": request.cwd,
-            "mode": ExecutionMode::Cell,
-            "runnerType": RunnerType::PythonLocal,
-            "persistent": true,
-        }),
-    );
 
-    let stdout_app = app.clone();
-    let stdout_session_id = session_id.clone();
-    tokio::spawn(async move {
-        stream_output(stdout_app, stdout_session_id, stdout, "stdout", true).await;
-    });
+        let stdout_app = app.clone();
+        let stdout_session_id = session_id.clone();
+        tokio::spawn(async move {
+            stream_output(stdout_app, stdout_session_id, stdout, "stdout", true).await;
+        });
 
-    let stderr_app = app.clone();
-    let stderr_session_id = session_id.clone();
-    tokio::spawn(async move {
-        stream_output(stderr_app, stderr_session_id, stderr, "stderr", false).await;
-    });
+        let stderr_app = app.clone();
+        let stderr_session_id = session_id.clone();
+        tokio::spawn(async move {
+            stream_output(stderr_app, stderr_session_id, stderr, "stderr", false).await;
+        });
 
-    let wait_app = app.clone();
-    let wait_session_id = session_id.clone();
-    tokio::spawn(async move {
-        let exit_result = {
-            let mut guard = child.lock().await;
-            guard.wait().await
-        };
+        let wait_app = app.clone();
+        let wait_session_id = session_id.clone();
+        tokio::spawn(async move {
+            let exit_result = {
+                let mut guard = child.lock().await;
+                guard.wait().await
+            };
 
-        match exit_result {
-            Ok(status) => {
-                let was_terminated = terminated.load(Ordering::SeqCst);
-                emit_runner_event(
-                    &wait_app,
-                    &wait_session_id,
-                    if was_terminated { "terminated" } else { "completed" },
-                    json!({
-                        "success": status.success() && !was_terminated,
-                        "exitCode": status.code(),
-                        "terminated": was_terminated,
-                        "persistent": true,
-                    }),
-                );
-            }
-            Err(error) => {
-                emit_runner_event(
-                    &wait_app,
-                    &wait_session_id,
-                    "error",
-                    json!({ "message": error.to_string() }),
-                );
+            match exit_result {
+                Ok(status) => {
+                    let was_terminated = terminated.load(Ordering::SeqCst);
+                    emit_runner_event(
+                        &wait_app,
+                        &wait_session_id,
+                        if was_terminated { "terminated" } else { "completed" },
+                        json!({
+                            "success": status.success() && !was_terminated,
+                            "exitCode": status.code(),
+                            "terminated": was_terminated,
+                            "persistent": true,
+                        }),
+                    );
+                }
+                Err(error) => {
+                    emit_runner_event(
+                        &wait_app,
+                        &wait_session_id,
+                        "error",
+                        json!({ "message": error.to_string() }),
+                    );
+                }
             }
-        }
 
-        {
-            let python_sessions = wait_app.state::<PythonSessions>();
-            let mut guard = python_sessions.sessions.lock().await;
-            if let Some(session) = guard.remove(&wait_session_id) {
-                cleanup_execution_files(Some(session.cleanup));
+            {
+                let python_sessions = wait_app.state::<PythonSessions>();
+                let mut guard = python_sessions.sessions.lock().await;
+                if let Some(session) = guard.remove(&wait_session_id) {
+                    cleanup_execution_files(Some(session.cleanup));
+                }
             }
-        }
-        {
-            let executions = wait_app.state::<ExecutionSessions>();
-            let mut guard = executions.sessions.lock().await;
-            guard.remove(&wait_session_id);
-        }
-    });
+            {
+                let executions = wait_app.state::<ExecutionSessions>();
+                let mut guard = executions.sessions.lock().await;
+                guard.remove(&wait_session_id);
+            }
+        });
 
-    Ok(ExecutionStartResponse {
-        session_id,
-        runner_type: RunnerType::PythonLocal,
+        Ok(ExecutionStartResponse {
+            session_id,
+            runner_type: RunnerType::PythonLocal,
+        })
     })
+    .await
 }
 
 #[tauri::command]
@@ -2480,132 +3257,176 @@ async fn execute_python_session(
     app: AppHandle,
     session_state: State<'_, PythonSessions>,
     request: PythonSessionExecuteRequest,
-) -> Result<(), String> {
-    let managed = {
-        let sessions = session_state.sessions.lock().await;
-        sessions.get(&request.session_id).cloned()
-    };
+) -> Result<(), LatticeError> {
+    log_command_async("execute_python_session", async move {
+        let managed = {
+            let sessions = session_state.sessions.lock().await;
+            sessions.get(&request.session_id).cloned()
+        };
 
-    let Some(managed) = managed else {
-        return Err(format!("Python session not found: {}", request.session_id));
-    };
+        let Some(managed) = managed else {
+            return Err(LatticeError::NotFound {
+                message: format!("Python session not found: {}", request.session_id),
+            });
+        };
 
-    emit_runner_event(
-        &app,
-        &request.session_id,
-        "started",
-        json!({
-            "mode": ExecutionMode::Cell,
-            "runnerType": RunnerType::PythonLocal,
-            "persistent": true,
-        }),
-    );
+        emit_runner_event(
+            &app,
+            &request.session_id,
+            "started",
+            json!({
+                "mode": ExecutionMode::Cell,
+                "runnerType": RunnerType::PythonLocal,
+                "persistent": true,
+            }),
+        );
 
-    let payload = serde_json::to_string(&json!({ "code": request.code })).map_err(|error| error.to_string())?;
-    let mut stdin = managed.stdin.lock().await;
-    stdin.write_all(payload.as_bytes()).await.map_err(|error| error.to_string())?;
-    stdin.write_all(b"\n").await.map_err(|error| error.to_string())?;
-    stdin.flush().await.map_err(|error| error.to_string())
+        let payload = serde_json::to_string(&json!({ "code": request.code })).map_err(|error| error.to_string())?;
+        let mut stdin = managed.stdin.lock().await;
+        stdin.write_all(payload.as_bytes()).await?;
+        stdin.write_all(b"\n").await?;
+        stdin.flush().await.map_err(LatticeError::from)
+    })
+    .await
 }
 
 #[tauri::command]
 async fn stop_python_session(
     session_state: State<'_, PythonSessions>,
     session_id: String,
-) -> Result<(), String> {
-    let managed = {
-        let sessions = session_state.sessions.lock().await;
-        sessions.get(&session_id).cloned()
-    };
+) -> Result<(), LatticeError> {
+    log_command_async("stop_python_session", async move {
+        let managed = {
+            let sessions = session_state.sessions.lock().await;
+            sessions.get(&session_id).cloned()
+        };
 
-    let Some(managed) = managed else {
-        return Err(format!("Python session not found: {session_id}"));
-    };
+        let Some(managed) = managed else {
+            return Err(LatticeError::NotFound {
+                message: format!("Python session not found: {session_id}"),
+            });
+        };
 
-    managed.terminated.store(true, Ordering::SeqCst);
-    let mut child = managed.child.lock().await;
-    child.kill().await.map_err(|error| error.to_string())
+        managed.terminated.store(true, Ordering::SeqCst);
+        let mut child = managed.child.lock().await;
+        child.kill().await.map_err(LatticeError::from)
+    })
+    .await
 }
 
 #[tauri::command]
-fn desktop_open_terminal_at_path(path: String) -> Result<(), String> {
-    let cwd = PathBuf::from(path);
-    if !cwd.is_dir() {
-        return Err("Terminal path must be an existing directory".to_string());
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        StdCommand::new("cmd")
-            .args(["/C", "start", "", "cmd"])
-            .current_dir(&cwd)
-            .spawn()
-            .map(|_| ())
-            .map_err(|error| error.to_string())
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        StdCommand::new("open")
-            .args(["-a", "Terminal", "."])
-            .current_dir(&cwd)
-            .spawn()
-            .map(|_| ())
-            .map_err(|error| error.to_string())
-    }
-
-    #[cfg(all(unix, not(target_os = "macos")))]
-    {
-        let candidates = [
-            ("x-terminal-emulator", Vec::<&str>::new()),
-            ("gnome-terminal", Vec::<&str>::new()),
-            ("konsole", vec!["--workdir", "."]),
-            ("xfce4-terminal", vec!["--working-directory", "."]),
-            ("xterm", Vec::<&str>::new()),
-        ];
-
-        for (command, args) in candidates {
-            if StdCommand::new(command)
-                .args(args)
-                .current_dir(&cwd)
-                .spawn()
-                .is_ok()
-            {
-                return Ok(());
-            }
-        }
-
-        Err("No supported terminal application was found".to_string())
-    }
+fn desktop_open_terminal_at_path(app: tauri::AppHandle, path: String) -> Result<(), LatticeError> {
+    log_command("desktop_open_terminal_at_path", || {
+        let dir = resolve_app_path(&app, &path, None)?;
+        terminal::launch_terminal(&app, Path::new(&dir.path))
+    })
 }
 
 fn main() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+    // Must be the first plugin so a second launch exits before anything else starts.
+    let builder = if launch::single_instance_enabled() {
+        builder.plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            launch::handle_second_instance(app, args, cwd)
+        }))
+    } else {
+        builder
+    };
+
+    builder
         .register_uri_scheme_protocol("lattice-preview", |ctx, request| {
             let preview_state = ctx.app_handle().state::<DesktopPreviewState>();
             let result = resolve_preview_path(&preview_state, &request)
-                .and_then(|path| build_preview_file_response(&path, &request));
+                .and_then(|path| build_preview_file_response(&path, get_preview_content_type(&path), &request));
 
             match result {
                 Ok(response) => response,
                 Err(message) => preview_error_response(http::StatusCode::FORBIDDEN, message),
             }
         })
+        .register_uri_scheme_protocol(file_protocol::FILE_PROTOCOL, |ctx, request| {
+            file_protocol::handle_file_request(ctx.app_handle(), &request)
+        })
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(settings_recovery::exit_save_plugin())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_window_state::Builder::default().build())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(shortcuts::handle_global_shortcut)
+                .build(),
+        )
         .manage(DesktopPreviewState::default())
         .manage(DesktopNativeWebviewState::default())
         .manage(DesktopFsState::default())
         .manage(ExecutionSessions::default())
         .manage(PythonSessions::default())
+        .manage(WatcherState::default())
+        .manage(OpenFilesState::default())
+        .manage(FuzzyIndexState::default())
+        .manage(IndexState::default())
+        .manage(IgnoreMatcherState::default())
+        .manage(TagStoreState::default())
+        .manage(FolderSizeState::default())
+        .manage(ChildCountState::default())
+        .manage(FilePreviewState::default())
+        .manage(ClipboardState::default())
+        .manage(EmptyTrashTokenState::default())
+        .manage(ResourceUsageState::default())
+        .manage(SettingsWatchState::default())
+        .manage(FolderWindowState::default())
+        .manage(DataDirState::default())
+        .manage(LaunchState::default())
+        .manage(AutosaveState::default())
+        .manage(MemoryPressureState::default())
+        .manage(JobsState::default())
+        .manage(LocaleState::default())
+        .manage(SettingsHealthState::default())
+        .manage(SystemAppearanceState::default())
         .invoke_handler(tauri::generate_handler![
             get_setting,
             set_setting,
             remove_setting,
             clear_settings,
+            register_global_shortcut,
+            unregister_global_shortcut,
+            get_theme,
+            set_theme,
+            get_system_appearance,
+            get_window_flags,
+            set_always_on_top,
+            set_auto_reload,
+            register_open_file,
+            set_open_file_dirty,
+            unregister_open_file,
+            set_window_opacity,
+            get_locale,
+            set_locale,
+            system_locale,
+            get_autosave_interval,
+            set_autosave_interval,
+            get_memory_stats,
+            set_memory_pressure_threshold,
+            set_memory_poll_interval,
+            list_monitors,
+            move_to_monitor,
+            export_settings,
+            import_settings,
+            compact_store,
+            reload_settings,
+            settings_health,
+            get_app_info,
+            frontend_ready,
+            get_data_dir,
+            set_data_dir,
+            get_workspace_setting,
+            set_workspace_setting,
+            set_folder_sort,
+            get_folder_sort,
+            sort_entries,
+            list_workspace_keys,
             get_default_folder,
             set_default_folder,
             get_last_opened_folder,
@@ -2613,16 +3434,139 @@ fn main() {
             set_last_workspace_path,
             resolve_startup_workspace,
             clear_default_folder,
+            get_recent_folders,
+            push_recent_folder,
+            pick_folder,
+            pick_files,
+            set_max_recent_folders,
+            clear_recent_folders,
+            push_recent_file,
+            get_recent_files,
+            open_file_at,
+            file_preview,
+            batch_preview,
+            toggle_pin_file,
+            remove_recent_file,
+            get_favorite_folders,
+            add_favorite_folder,
+            remove_favorite_folder,
+            reorder_favorite_folders,
+            save_session,
+            restore_session,
+            list_sessions,
+            delete_session,
+            save_layout,
+            restore_layout,
+            list_layouts,
+            delete_layout,
+            list_templates,
+            save_template,
+            delete_template,
+            instantiate_template,
+            grant_folder_access,
+            list_granted_folders,
+            revoke_folder_access,
+            prune_missing_recent_folders,
+            open_folder_in_new_window,
+            save_window_state_for_folder,
+            restore_window_state_for_folder,
             desktop_read_dir,
+            list_directory,
+            render_tree,
+            export_tree_json,
+            export_tree_to_file,
+            child_counts,
+            folder_size,
+            cancel_folder_size,
+            search_contents,
+            replace_in_files,
+            export_search_results,
+            query_index,
+            rebuild_index,
+            fuzzy_find,
+            add_tag,
+            remove_tag,
+            get_tags,
+            files_with_tag,
+            prune_tags,
+            test_ignore,
+            find_duplicates,
+            verify_checksum,
+            verify_checksum_file,
+            git_status,
+            git_branch_info,
+            watch_folder,
+            watch_glob,
+            update_watch_globs,
+            unwatch_folder,
+            watch_file,
+            unwatch,
+            unwatch_all,
+            list_active_watches,
             desktop_read_file_bytes_raw,
             desktop_read_text_file,
             desktop_read_text_file_chunk,
+            read_file_range,
+            read_file_tail,
+            detect_file_style,
+            read_frontmatter,
+            text_stats,
+            format_file,
+            detect_file_type,
+            read_file_smart,
+            read_files,
+            read_file_base64,
+            get_thumbnail,
+            clear_thumbnail_cache,
+            optimize_image,
             desktop_write_file_bytes,
+            write_file_atomic,
+            write_file_checked,
+            hash_file,
+            snapshot_folder,
+            snapshot_folder_incremental,
+            diff_snapshots,
+            same_file,
+            create_link,
+            find_broken_symlinks,
+            remove_broken_symlinks,
+            check_path_locked,
+            diff_files,
+            disk_space,
+            self_resource_usage,
+            resolve_path,
+            complete_path,
             desktop_copy_path,
             desktop_move_path,
             desktop_rename_path,
+            copy_path,
+            move_path,
+            backup_folder,
+            check_case_collisions,
+            start_copy_job,
+            start_move_job,
+            start_delete_job,
+            job_status,
+            cancel_job,
+            get_job_parallelism,
+            set_job_parallelism,
+            set_taskbar_progress,
+            set_badge_count,
+            copy_files_to_clipboard,
+            paste_files_from_clipboard,
+            clipboard_has_image,
+            save_clipboard_image,
+            save_clipboard_text,
+            create_zip,
+            zip_selection,
+            extract_zip,
+            rename_path,
+            batch_rename,
             desktop_exists_path,
             desktop_file_metadata,
+            stat_path,
+            set_executable,
+            set_readonly,
             desktop_is_directory,
             fetch_web_document,
             desktop_native_webview_mount,
@@ -2635,7 +3579,19 @@ fn main() {
             desktop_native_webview_go_back,
             desktop_native_webview_go_forward,
             desktop_create_dir,
+            create_file,
+            create_folder,
             desktop_remove_path,
+            trash_path,
+            trash_paths,
+            delete_recursive,
+            list_trashed,
+            restore_trashed,
+            request_empty_trash_token,
+            empty_trash,
+            soft_delete,
+            undo_soft_delete,
+            commit_soft_delete,
             desktop_set_preview_root,
             desktop_window_minimize,
             desktop_window_start_dragging,
@@ -2651,10 +3607,67 @@ fn main() {
             execute_python_session,
             stop_python_session,
             desktop_open_terminal_at_path,
+            open_terminal,
+            is_default_handler,
+            request_default_handler,
+            reveal_in_file_manager,
+            open_with_default_app,
+            set_log_level,
+            get_log_file_path,
+            open_log_folder,
+            open_url,
             desktop_extract_pdf_page_text_layout,
             desktop_ocr_pdf_page_text_layout,
         ])
         .setup(|app| {
+            if let Err(error) = logging::init_logging(app.handle()) {
+                eprintln!("Failed to start logging: {error}");
+            }
+            if let Err(error) = data_dir::init_data_dir(app.handle()) {
+                log::error!("Failed to prepare data directory: {error}");
+            }
+            if let Err(error) = settings_recovery::recover_settings(app.handle()) {
+                log::error!("Failed to check settings file: {error}");
+            }
+            if let Err(error) = migrate_settings(app.handle()) {
+                log::error!("Failed to migrate settings: {error}");
+            }
+            if let Err(error) = settings_watch::start_settings_watch(app.handle()) {
+                log::error!("Failed to watch settings file: {error}");
+            }
+            if let Err(error) = logging::restore_log_level(app.handle()) {
+                log::error!("Failed to apply saved log level: {error}");
+            }
+            if let Err(error) = folder_access::restore_folder_access(app.handle()) {
+                log::error!("Failed to restore folder access: {error}");
+            }
+            launch::capture_launch_args(app.handle());
+            let app_for_soft_deletes = app.handle().clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                soft_delete::commit_expired_soft_deletes(&app_for_soft_deletes)
+            });
+            workspace_windows::restore_open_windows(app.handle());
+            if let Err(error) = shortcuts::restore_global_shortcut(app.handle()) {
+                log::error!("Failed to restore global shortcut: {error}");
+            }
+            if let Err(error) = theme::restore_theme(app.handle()) {
+                log::error!("Failed to apply saved theme: {error}");
+            }
+            if let Err(error) = autosave::restore_autosave(app.handle()) {
+                log::error!("Failed to start autosave: {error}");
+            }
+            if let Err(error) = memory_pressure::restore_memory_monitor(app.handle()) {
+                log::error!("Failed to start memory monitor: {error}");
+            }
+            if let Err(error) = jobs::restore_job_parallelism(app.handle()) {
+                log::error!("Failed to apply job parallelism: {error}");
+            }
+            if let Err(error) = monitors::restore_monitor(app.handle()) {
+                log::error!("Failed to restore window monitor: {error}");
+            }
+            if let Err(error) = window_flags::restore_window_flags(app.handle()) {
+                log::error!("Failed to restore window flags: {error}");
+            }
             if let Some(window) = app.get_webview_window("main") {
                 #[cfg(target_os = "windows")]
                 {
@@ -2664,8 +3677,35 @@ fn main() {
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("Failed to run Lattice application");
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                folder_window_state::schedule_save(window.app_handle(), window.label());
+            }
+            tauri::WindowEvent::ThemeChanged(system_theme) => {
+                theme::handle_system_theme_changed(window.app_handle(), *system_theme);
+            }
+            tauri::WindowEvent::Focused(true) => {
+                locale::check_system_locale(window.app_handle());
+                theme::check_system_appearance(window.app_handle(), None);
+            }
+            tauri::WindowEvent::Destroyed => {
+                watcher::release_window_watches(window.app_handle(), window.label());
+                auto_reload::release_window_files(window.app_handle(), window.label());
+                workspace_windows::forget_closed_window(window.app_handle(), window.label());
+                folder_window_state::forget_window(window.app_handle(), window.label());
+            }
+            _ => {}
+        })
+        .build(tauri::generate_context!())
+        .expect("Failed to build Lattice application")
+        .run(|_app, _event| {
+            // Launch paths come from argv in `setup`, from the single-instance plugin for
+            // later launches, and on macOS from `Opened`; no other run events are hooked.
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            if let tauri::RunEvent::Opened { urls } = &_event {
+                launch::handle_opened_urls(_app, urls);
+            }
+        });
 }
 
 async fn stream_output<R>(
@@ -2778,7 +3818,8 @@ fn build_python_command(
     let python = if let Some(command) = request.command.clone().filter(|value| !value.trim().is_empty()) {
         command
     } else {
-        let environments = discover_python_environments(cwd.and_then(Path::to_str))?;
+        let environments =
+            discover_python_environments(cwd.and_then(Path::to_str)).map_err(|error| error.to_string())?;
         environments
             .first()
             .map(|env| env.path.clone())
@@ -2863,7 +3904,7 @@ fn cleanup_execution_files(cleanup: Option<CleanupArtifacts>) {
     }
 }
 
-fn discover_python_environments(cwd: Option<&str>) -> Result<Vec<PythonEnvironmentInfo>, String> {
+fn discover_python_environments(cwd: Option<&str>) -> Result<Vec<PythonEnvironmentInfo>, LatticeError> {
     let mut results = Vec::new();
     let mut seen_paths = HashSet::new();
 
@@ -3365,6 +4406,28 @@ mod tests {
         assert_eq!(encoded["aiPanelWidth"], json!(32));
     }
 
+    #[test]
+    fn compacting_keeps_known_and_backend_owned_keys() {
+        let keys = ["zoomLevel", "legacyPanel", "sessions", FRONTEND_SETTINGS_KEY, "oldSidebar"].map(String::from);
+        let known = vec!["zoomLevel".to_string()];
+        assert_eq!(orphaned_store_keys(keys, &known), ["legacyPanel", "oldSidebar"]);
+        assert!(orphaned_store_keys([FRONTEND_SETTINGS_KEY.to_string()], &[]).is_empty());
+    }
+
+    #[test]
+    fn settings_bundles_must_be_json_objects_and_are_upgraded_on_import() {
+        assert!(parse_settings_bundle(b"[1, 2]").is_err());
+        assert!(parse_settings_bundle(b"{not json").is_err());
+
+        let (bundle, imported) = parse_settings_bundle(br#"{"last_opened_folder": "C:/notes", "custom": 1}"#).unwrap();
+        assert_eq!(imported, 2);
+        assert_eq!(bundle.get("custom"), Some(&json!(1)));
+        assert_eq!(bundle[FRONTEND_SETTINGS_KEY]["lastOpenedFolder"], json!("C:/notes"));
+
+        let encoded = encode_settings_bundle(bundle.into_iter().collect()).unwrap();
+        assert_eq!(parse_settings_bundle(&encoded).unwrap().0.len(), 3);
+    }
+
     #[test]
     fn persisted_settings_detection_counts_extra_frontend_fields() {
         let mut settings = AppSettings::default();
@@ -3389,6 +4452,24 @@ mod tests {
         assert_eq!(parse_range_header("bytes=-15", 100), Some((85, 99)));
         assert_eq!(parse_range_header("bytes=150-200", 100), None);
     }
+
+    #[test]
+    fn recent_folder_push_moves_existing_entry_to_front_and_caps_list() {
+        let existing = vec![
+            "C:/alpha".to_string(),
+            "C:/beta".to_string(),
+            "C:/gamma".to_string(),
+        ];
+
+        assert_eq!(
+            push_recent_folder_path(&existing, "C:/gamma", 15),
+            vec!["C:/gamma", "C:/alpha", "C:/beta"]
+        );
+        assert_eq!(
+            push_recent_folder_path(&existing, " C:/delta ", 3),
+            vec!["C:/delta", "C:/alpha", "C:/beta"]
+        );
+    }
 }
 
 fn python_session_bootstrap() -> &'static str {
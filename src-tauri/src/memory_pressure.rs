@@ -0,0 +1,174 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+use sysinfo::{MemoryRefreshKind, System};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::LatticeError;
+use crate::logging::{log_command, log_command_async};
+use crate::{build_app_settings_from_store, save_app_settings};
+
+const MEMORY_PRESSURE_EVENT: &str = "memory-pressure";
+const DEFAULT_POLL_SECS: u32 = 10;
+const MAX_POLL_SECS: u32 = 60 * 60;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Each (re)start bumps the generation; a monitor stops once it is no longer current.
+#[derive(Default)]
+pub struct MemoryPressureState {
+    generation: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStats {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    pub used_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryPressurePayload {
+    available_bytes: u64,
+    threshold_bytes: u64,
+}
+
+/// `0` turns the monitor off like `None`.
+pub(crate) fn normalize_memory_threshold(mb: Option<u64>) -> Option<u64> {
+    mb.filter(|mb| *mb > 0)
+}
+
+pub(crate) fn normalize_memory_poll_interval(secs: Option<u32>) -> Option<u32> {
+    secs.filter(|secs| *secs > 0).map(|secs| secs.min(MAX_POLL_SECS))
+}
+
+/// Only RAM is refreshed, which reads a single kernel counter set and stays cheap to poll.
+fn read_memory_stats(system: &mut System) -> MemoryStats {
+    system.refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram());
+    MemoryStats {
+        total_bytes: system.total_memory(),
+        available_bytes: system.available_memory(),
+        used_bytes: system.used_memory(),
+    }
+}
+
+/// Pressure is reported once when available memory falls below the threshold, and again
+/// only after it has recovered above it.
+fn crossed_below(was_under: bool, stats: &MemoryStats, threshold_bytes: u64) -> (bool, bool) {
+    let under = stats.available_bytes < threshold_bytes;
+    (under, under && !was_under)
+}
+
+/// Caches that can be rebuilt on demand. Thumbnails live on disk and hold no memory.
+fn shed_caches(app: &AppHandle) {
+    crate::index::release_index(app);
+    crate::fuzzy::clear_fuzzy_cache(app);
+    crate::child_counts::clear_child_counts(app);
+    crate::pdf_native::clear_pdf_layout_cache();
+}
+
+fn restart_monitor(app: &AppHandle, threshold_mb: Option<u64>, poll_secs: Option<u32>) {
+    let generation = app.state::<MemoryPressureState>().generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let Some(threshold_mb) = threshold_mb else {
+        return;
+    };
+    let threshold_bytes = threshold_mb.saturating_mul(BYTES_PER_MB);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let period = Duration::from_secs(u64::from(poll_secs.unwrap_or(DEFAULT_POLL_SECS)));
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut system = System::new();
+        let mut under = false;
+        loop {
+            ticker.tick().await;
+            if app.state::<MemoryPressureState>().generation.load(Ordering::SeqCst) != generation {
+                break;
+            }
+            let stats = read_memory_stats(&mut system);
+            let crossed;
+            (under, crossed) = crossed_below(under, &stats, threshold_bytes);
+            if crossed {
+                log::warn!(
+                    "Available memory is down to {} MB; clearing caches",
+                    stats.available_bytes / BYTES_PER_MB
+                );
+                shed_caches(&app);
+                let _ = app.emit(
+                    MEMORY_PRESSURE_EVENT,
+                    MemoryPressurePayload {
+                        available_bytes: stats.available_bytes,
+                        threshold_bytes,
+                    },
+                );
+            }
+        }
+    });
+}
+
+/// Starts the saved memory monitor during `setup`.
+pub(crate) fn restore_memory_monitor(app: &AppHandle) -> Result<(), String> {
+    let settings = build_app_settings_from_store(app)?;
+    restart_monitor(app, settings.memory_pressure_threshold_mb, settings.memory_poll_interval_secs);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_memory_stats() -> Result<MemoryStats, LatticeError> {
+    log_command_async("get_memory_stats", async move {
+        tokio::task::spawn_blocking(|| read_memory_stats(&mut System::new()))
+            .await
+            .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+/// `None` or `0` stops the monitor. Returns the threshold actually saved.
+#[tauri::command]
+pub fn set_memory_pressure_threshold(app: AppHandle, mb: Option<u64>) -> Result<Option<u64>, LatticeError> {
+    log_command("set_memory_pressure_threshold", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.memory_pressure_threshold_mb = mb;
+        let settings = save_app_settings(&app, settings)?;
+        restart_monitor(&app, settings.memory_pressure_threshold_mb, settings.memory_poll_interval_secs);
+        Ok(settings.memory_pressure_threshold_mb)
+    })
+}
+
+/// `None` goes back to the default of every ten seconds.
+#[tauri::command]
+pub fn set_memory_poll_interval(app: AppHandle, secs: Option<u32>) -> Result<Option<u32>, LatticeError> {
+    log_command("set_memory_poll_interval", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.memory_poll_interval_secs = secs;
+        let settings = save_app_settings(&app, settings)?;
+        restart_monitor(&app, settings.memory_pressure_threshold_mb, settings.memory_poll_interval_secs);
+        Ok(settings.memory_poll_interval_secs)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pressure_is_reported_once_per_drop_below_the_threshold() {
+        let stats = |available_bytes| MemoryStats {
+            total_bytes: 1000,
+            available_bytes,
+            used_bytes: 1000 - available_bytes,
+        };
+        assert_eq!(crossed_below(false, &stats(500), 100), (false, false));
+        assert_eq!(crossed_below(false, &stats(50), 100), (true, true));
+        assert_eq!(crossed_below(true, &stats(40), 100), (true, false));
+        assert_eq!(crossed_below(true, &stats(150), 100), (false, false));
+
+        assert_eq!(normalize_memory_threshold(Some(0)), None);
+        assert_eq!(normalize_memory_poll_interval(Some(u32::MAX)), Some(MAX_POLL_SECS));
+        let live = read_memory_stats(&mut System::new());
+        assert!(live.total_bytes > 0 && live.available_bytes <= live.total_bytes);
+    }
+}
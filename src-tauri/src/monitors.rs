@@ -0,0 +1,209 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::{build_app_settings_from_store, save_app_settings};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub index: usize,
+    pub name: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl Rect {
+    fn of_work_area(monitor: &Monitor) -> Self {
+        let area = monitor.work_area();
+        Self {
+            x: area.position.x,
+            y: area.position.y,
+            width: area.size.width,
+            height: area.size.height,
+        }
+    }
+
+    fn intersects(&self, other: &Rect) -> bool {
+        let right = |rect: &Rect| i64::from(rect.x) + i64::from(rect.width);
+        let bottom = |rect: &Rect| i64::from(rect.y) + i64::from(rect.height);
+        i64::from(self.x) < right(other)
+            && i64::from(other.x) < right(self)
+            && i64::from(self.y) < bottom(other)
+            && i64::from(other.y) < bottom(self)
+    }
+}
+
+/// Centers a window of the given size in `area`, shrinking it first if it doesn't fit.
+fn centered_in(area: Rect, width: u32, height: u32) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + ((area.width - width) / 2) as i32,
+        y: area.y + ((area.height - height) / 2) as i32,
+        width,
+        height,
+    }
+}
+
+fn main_window(app: &AppHandle) -> Result<WebviewWindow, String> {
+    app.get_webview_window("main")
+        .ok_or_else(|| "Main window is not available.".to_string())
+}
+
+fn is_same_monitor(left: &Monitor, right: &Monitor) -> bool {
+    left.name() == right.name() && left.position() == right.position()
+}
+
+/// Maximized windows are restored around the move so they maximize on the new monitor.
+fn center_on(window: &WebviewWindow, monitor: &Monitor) -> Result<(), String> {
+    let was_maximized = window.is_maximized().unwrap_or(false);
+    if was_maximized {
+        window.unmaximize().map_err(|error| error.to_string())?;
+    }
+    let size = window.outer_size().map_err(|error| error.to_string())?;
+    let target = centered_in(Rect::of_work_area(monitor), size.width, size.height);
+    window
+        .set_size(PhysicalSize::new(target.width, target.height))
+        .map_err(|error| error.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(target.x, target.y))
+        .map_err(|error| error.to_string())?;
+    if was_maximized {
+        window.maximize().map_err(|error| error.to_string())?;
+    }
+    Ok(())
+}
+
+fn window_rect(window: &WebviewWindow) -> Result<Rect, String> {
+    let position = window.outer_position().map_err(|error| error.to_string())?;
+    let size = window.outer_size().map_err(|error| error.to_string())?;
+    Ok(Rect {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+/// Runs during `setup`, before the main window is maximized. Falls back to the primary
+/// monitor when the saved one is no longer connected, and only moves the window then if
+/// it would otherwise sit off every connected screen.
+pub(crate) fn restore_monitor(app: &AppHandle) -> Result<(), String> {
+    let window = main_window(app)?;
+    let monitors = window.available_monitors().map_err(|error| error.to_string())?;
+    let saved_name = build_app_settings_from_store(app)?.monitor_name;
+    let saved = saved_name
+        .as_ref()
+        .and_then(|name| monitors.iter().find(|monitor| monitor.name() == Some(name)));
+
+    if let Some(saved) = saved {
+        let current = window.current_monitor().ok().flatten();
+        if !current.is_some_and(|current| is_same_monitor(&current, saved)) {
+            center_on(&window, saved)?;
+        }
+        return Ok(());
+    }
+
+    let rect = window_rect(&window)?;
+    if monitors
+        .iter()
+        .any(|monitor| Rect::of_work_area(monitor).intersects(&rect))
+    {
+        return Ok(());
+    }
+    match window.primary_monitor().ok().flatten().or_else(|| monitors.into_iter().next()) {
+        Some(fallback) => center_on(&window, &fallback),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, LatticeError> {
+    log_command("list_monitors", || {
+        let primary = app.primary_monitor().ok().flatten();
+        let monitors = app.available_monitors().map_err(|error| error.to_string())?;
+        Ok(monitors
+            .iter()
+            .enumerate()
+            .map(|(index, monitor)| MonitorInfo {
+                index,
+                name: monitor.name().cloned(),
+                width: monitor.size().width,
+                height: monitor.size().height,
+                x: monitor.position().x,
+                y: monitor.position().y,
+                scale_factor: monitor.scale_factor(),
+                is_primary: primary.as_ref().is_some_and(|primary| is_same_monitor(primary, monitor)),
+            })
+            .collect())
+    })
+}
+
+/// `index` is into `list_monitors`; a display unplugged since then clamps to the last one.
+#[tauri::command]
+pub fn move_to_monitor(app: AppHandle, index: usize) -> Result<(), LatticeError> {
+    log_command("move_to_monitor", || {
+        let window = main_window(&app)?;
+        let mut monitors = app.available_monitors().map_err(|error| error.to_string())?;
+        if monitors.is_empty() {
+            return Err(LatticeError::NotFound {
+                message: "No monitors are connected.".to_string(),
+            });
+        }
+        let monitor = monitors.swap_remove(index.min(monitors.len() - 1));
+        center_on(&window, &monitor)?;
+
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.monitor_name = monitor.name().cloned();
+        save_app_settings(&app, settings)?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_are_centered_and_shrunk_to_fit_the_work_area() {
+        let area = Rect {
+            x: -1920,
+            y: 40,
+            width: 1920,
+            height: 1040,
+        };
+        assert_eq!(
+            centered_in(area, 1200, 800),
+            Rect {
+                x: -1560,
+                y: 160,
+                width: 1200,
+                height: 800
+            }
+        );
+        assert_eq!(centered_in(area, 2560, 1440), area);
+
+        let elsewhere = Rect {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 600,
+        };
+        assert!(!area.intersects(&elsewhere));
+        assert!(area.intersects(&centered_in(area, 100, 100)));
+    }
+}
@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+
+/// Anything else (`file:`, `javascript:`, custom app protocols…) is refused.
+const ALLOWED_URL_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+/// `ERROR_NO_ASSOCIATION`, returned by `ShellExecute` for unregistered extensions.
+#[cfg(target_os = "windows")]
+const WINDOWS_NO_ASSOCIATION: i32 = 1155;
+
+fn is_missing_handler(error: &std::io::Error) -> bool {
+    #[cfg(target_os = "windows")]
+    if error.raw_os_error() == Some(WINDOWS_NO_ASSOCIATION) {
+        return true;
+    }
+    // `open`/`xdg-open` only exit unsuccessfully when nothing claims the file type.
+    #[cfg(not(target_os = "windows"))]
+    if error.kind() == std::io::ErrorKind::Other && error.to_string().contains("exit status") {
+        return true;
+    }
+    error.kind() == std::io::ErrorKind::NotFound
+}
+
+fn map_open_error(target: &str, error: tauri_plugin_opener::Error) -> LatticeError {
+    match error {
+        tauri_plugin_opener::Error::Io(error) if is_missing_handler(&error) => LatticeError::NoHandler {
+            message: format!("No application is registered to open {target}"),
+        },
+        error => error.to_string().into(),
+    }
+}
+
+fn validate_url(url: &str) -> Result<reqwest::Url, LatticeError> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|error| LatticeError::Failed {
+        message: format!("Invalid URL: {error}"),
+    })?;
+    if !ALLOWED_URL_SCHEMES.contains(&parsed.scheme()) {
+        return Err(LatticeError::DisallowedScheme {
+            message: format!("Refusing to open {}: links", parsed.scheme()),
+        });
+    }
+    Ok(parsed)
+}
+
+#[tauri::command]
+pub async fn open_with_default_app(app: AppHandle, path: String) -> Result<(), LatticeError> {
+    log_command_async("open_with_default_app", async move {
+        let path = resolve_command_path(&app, &path)?.to_string_lossy().to_string();
+        if !Path::new(&path).exists() {
+            return Err(LatticeError::NotFound {
+                message: format!("Path not found: {path}"),
+            });
+        }
+        // Some launchers wait for the handler to start, so keep them off the main thread.
+        tokio::task::spawn_blocking(move || {
+            app.opener()
+                .open_path(path.as_str(), None::<&str>)
+                .map_err(|error| map_open_error(&path, error))
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn open_url(app: AppHandle, url: String) -> Result<(), LatticeError> {
+    log_command_async("open_url", async move {
+        let url = validate_url(&url)?.to_string();
+        tokio::task::spawn_blocking(move || {
+            app.opener()
+                .open_url(url.as_str(), None::<&str>)
+                .map_err(|error| map_open_error(&url, error))
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_web_and_mail_links_are_opened() {
+        assert!(validate_url(" https://example.com/docs ").is_ok());
+        assert!(validate_url("mailto:someone@example.com").is_ok());
+        assert!(matches!(validate_url("file:///etc/passwd"), Err(LatticeError::DisallowedScheme { .. })));
+        assert!(matches!(validate_url("javascript:alert(1)"), Err(LatticeError::DisallowedScheme { .. })));
+        assert!(matches!(validate_url("not a url"), Err(LatticeError::Failed { .. })));
+    }
+}
@@ -0,0 +1,343 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::error::LatticeError;
+use crate::logging::{log_command, log_command_async};
+
+const MAX_PATH_COMPLETIONS: usize = 50;
+
+static ENV_VAR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$\{(\w+)\}|\$(\w+)|%(\w+)%").expect("environment variable pattern is valid"));
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedPath {
+    /// Absolute, with symlinks resolved as far as the path exists.
+    pub path: String,
+    /// Home shortened to `~` and without Windows' `\\?\` prefix, for showing to users.
+    pub display: String,
+    pub exists: bool,
+    /// `None` without a base; `false` flags input that climbs out of it.
+    pub within_base: Option<bool>,
+}
+
+fn expand_home(input: &str, home: Option<&Path>) -> PathBuf {
+    let Some(home) = home else {
+        return PathBuf::from(input);
+    };
+    match input.strip_prefix('~') {
+        Some("") => home.to_path_buf(),
+        Some(rest) if rest.starts_with(['/', '\\']) => home.join(&rest[1..]),
+        _ => PathBuf::from(input),
+    }
+}
+
+/// `$NAME` and `${NAME}`, plus `%NAME%` on Windows. Unset variables are left as typed, since
+/// `$` and `%` are also legal in file names.
+fn expand_env_vars(input: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    ENV_VAR
+        .replace_all(input, |captures: &Captures<'_>| {
+            let name = match (captures.get(1).or(captures.get(2)), captures.get(3)) {
+                (Some(name), _) => Some(name.as_str()),
+                (None, Some(name)) if cfg!(windows) => Some(name.as_str()),
+                _ => None,
+            };
+            name.and_then(&lookup).unwrap_or_else(|| captures[0].to_string())
+        })
+        .to_string()
+}
+
+/// Resolves `.` and `..` without touching the disk; `..` never climbs above the root.
+pub(crate) fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if matches!(normalized.components().next_back(), Some(Component::Normal(_))) {
+                    normalized.pop();
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Canonicalizes the deepest existing ancestor and re-attaches the missing tail, so
+/// paths that are about to be created still resolve.
+fn canonicalize_existing_prefix(path: &Path) -> (PathBuf, bool) {
+    if let Ok(canonical) = fs::canonicalize(path) {
+        return (canonical, true);
+    }
+    let mut missing = Vec::new();
+    let mut ancestor = path;
+    while let Some(parent) = ancestor.parent() {
+        if let Some(name) = ancestor.file_name() {
+            missing.push(name.to_os_string());
+        }
+        ancestor = parent;
+        if let Ok(canonical) = fs::canonicalize(ancestor) {
+            let resolved = missing.iter().rev().fold(canonical, |resolved, name| resolved.join(name));
+            return (resolved, false);
+        }
+    }
+    (path.to_path_buf(), false)
+}
+
+/// Windows canonical paths start with `\\?\` (or `\\?\UNC\` for shares), which most
+/// programs and users don't expect to see.
+pub(crate) fn without_verbatim_prefix(path: &Path) -> PathBuf {
+    let shown = path.to_string_lossy();
+    match shown.strip_prefix(r"\\?\") {
+        Some(share) if share.starts_with(r"UNC\") => PathBuf::from(format!(r"\\{}", &share[4..])),
+        Some(local) => PathBuf::from(local),
+        None => path.to_path_buf(),
+    }
+}
+
+fn display_path(path: &Path, home: Option<&Path>) -> String {
+    let shown = without_verbatim_prefix(path);
+    match home.and_then(|home| shown.strip_prefix(home).ok()) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~{}{}", std::path::MAIN_SEPARATOR, rest.display()),
+        None => shown.display().to_string(),
+    }
+}
+
+fn absolute_input(input: &str, base: Option<&Path>, home: Option<&Path>) -> Result<PathBuf, LatticeError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(LatticeError::InvalidInput {
+            message: "Path is required.".to_string(),
+        });
+    }
+    let expanded = expand_home(&expand_env_vars(trimmed, |name| std::env::var(name).ok()), home);
+    if expanded.is_absolute() {
+        return Ok(expanded);
+    }
+    match base {
+        Some(base) => Ok(base.join(expanded)),
+        None => Err(LatticeError::InvalidInput {
+            message: format!("Relative path needs a base folder: {trimmed}"),
+        }),
+    }
+}
+
+/// Shared by commands taking user-typed paths, so `~`, `..` and relative input behave
+/// the same everywhere.
+pub(crate) fn resolve_user_path(input: &str, base: Option<&str>, home: Option<&Path>) -> Result<ResolvedPath, LatticeError> {
+    let base = match base.map(str::trim).filter(|base| !base.is_empty()) {
+        Some(base) => {
+            let base = expand_home(base, home);
+            if !base.is_absolute() {
+                return Err(LatticeError::InvalidInput {
+                    message: format!("Base folder must be absolute: {}", base.display()),
+                });
+            }
+            Some(canonicalize_existing_prefix(&normalize_lexically(&base)).0)
+        }
+        None => None,
+    };
+
+    let absolute = absolute_input(input, base.as_deref(), home)?;
+    let (resolved, exists) = canonicalize_existing_prefix(&normalize_lexically(&absolute));
+    Ok(ResolvedPath {
+        display: display_path(&resolved, home),
+        within_base: base.as_ref().map(|base| resolved.starts_with(base)),
+        path: resolved.to_string_lossy().to_string(),
+        exists,
+    })
+}
+
+pub(crate) fn resolve_app_path(app: &AppHandle, input: &str, base: Option<&str>) -> Result<ResolvedPath, LatticeError> {
+    let home = app.path().home_dir().ok();
+    resolve_user_path(input, base, home.as_deref())
+}
+
+/// A command's path argument, resolved like `resolve_app_path` except that a symlink at the
+/// end is kept rather than followed, so commands act on the link itself.
+fn command_path(input: &str, base: Option<&Path>, home: Option<&Path>) -> Result<PathBuf, LatticeError> {
+    let absolute = normalize_lexically(&absolute_input(input, base, home)?);
+    let resolved = match (absolute.parent(), absolute.file_name()) {
+        (Some(parent), Some(name)) => canonicalize_existing_prefix(parent).0.join(name),
+        _ => absolute,
+    };
+    Ok(without_verbatim_prefix(&resolved))
+}
+
+/// Every command that takes a path from the frontend runs it through this first.
+pub(crate) fn resolve_command_path(app: &AppHandle, input: &str) -> Result<PathBuf, LatticeError> {
+    let home = app.path().home_dir().ok();
+    command_path(input, None, home.as_deref())
+}
+
+/// Like `resolve_command_path`, for commands that take paths relative to a folder.
+pub(crate) fn resolve_command_path_in(app: &AppHandle, input: &str, base: &Path) -> Result<PathBuf, LatticeError> {
+    let home = app.path().home_dir().ok();
+    command_path(input, Some(base), home.as_deref())
+}
+
+pub(crate) fn resolve_command_paths(app: &AppHandle, inputs: &[String]) -> Result<Vec<PathBuf>, LatticeError> {
+    inputs.iter().map(|input| resolve_command_path(app, input)).collect()
+}
+
+#[tauri::command]
+pub fn resolve_path(app: AppHandle, input: String, base: Option<String>) -> Result<ResolvedPath, LatticeError> {
+    log_command("resolve_path", || resolve_app_path(&app, &input, base.as_deref()))
+}
+
+/// Entries of the folder `partial` ends in whose names start with its last fragment,
+/// case-insensitively. Each suggestion keeps what was typed before the fragment (so `~`
+/// stays `~`), and folders end in the separator the input used.
+fn path_completions(partial: &str, dirs_only: bool, home: Option<&Path>, limit: usize) -> Vec<String> {
+    let partial = if partial == "~" { "~/" } else { partial };
+    let Some(split) = partial.rfind(['/', '\\']) else {
+        return Vec::new();
+    };
+    let (typed_parent, fragment) = partial.split_at(split + 1);
+    let separator = &typed_parent[split..];
+    let parent = expand_home(typed_parent, home);
+    if !parent.is_absolute() {
+        return Vec::new();
+    }
+    let Ok(entries) = fs::read_dir(&parent) else {
+        return Vec::new();
+    };
+
+    let fragment = fragment.to_lowercase();
+    let mut matches: Vec<(bool, String)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Dotfiles only show up once the fragment asks for them.
+            if (name.starts_with('.') && !fragment.starts_with('.')) || !name.to_lowercase().starts_with(&fragment) {
+                return None;
+            }
+            let is_dir = entry.path().is_dir();
+            (is_dir || !dirs_only).then_some((is_dir, name))
+        })
+        .collect();
+    matches.sort_by(|(left_dir, left), (right_dir, right)| {
+        right_dir
+            .cmp(left_dir)
+            .then_with(|| left.to_lowercase().cmp(&right.to_lowercase()))
+            .then_with(|| left.cmp(right))
+    });
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(is_dir, name)| format!("{typed_parent}{name}{}", if is_dir { separator } else { "" }))
+        .collect()
+}
+
+/// A missing or unreadable folder gives no suggestions rather than an error, since it's
+/// usually just a half-typed name.
+#[tauri::command]
+pub async fn complete_path(app: AppHandle, partial: String, dirs_only: bool) -> Result<Vec<String>, LatticeError> {
+    log_command_async("complete_path", async move {
+        let home = app.path().home_dir().ok();
+        tokio::task::spawn_blocking(move || {
+            path_completions(partial.trim_start(), dirs_only, home.as_deref(), MAX_PATH_COMPLETIONS)
+        })
+        .await
+        .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn temp_base() -> PathBuf {
+        let base = std::env::temp_dir().join(format!("lattice-paths-{}", Uuid::new_v4()));
+        fs::create_dir_all(base.join("notes")).unwrap();
+        fs::write(base.join("notes").join("a.md"), "a").unwrap();
+        fs::canonicalize(base).unwrap()
+    }
+
+    #[test]
+    fn traversal_out_of_the_base_is_flagged() {
+        let base = temp_base();
+        let base_str = base.to_string_lossy().to_string();
+
+        let inside = resolve_user_path("notes/../notes/./a.md", Some(&base_str), None).unwrap();
+        assert_eq!(inside.path, base.join("notes").join("a.md").to_string_lossy());
+        assert_eq!((inside.exists, inside.within_base), (true, Some(true)));
+
+        let escaped = resolve_user_path("notes/../../elsewhere", Some(&base_str), None).unwrap();
+        assert_eq!(escaped.within_base, Some(false));
+        let absolute = resolve_user_path(&std::env::temp_dir().to_string_lossy(), Some(&base_str), None).unwrap();
+        assert_eq!(absolute.within_base, Some(false));
+
+        assert!(resolve_user_path("notes", None, None).is_err());
+        assert!(resolve_user_path("  ", Some(&base_str), None).is_err());
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn missing_intermediate_components_still_resolve() {
+        let base = temp_base();
+        let base_str = base.to_string_lossy().to_string();
+
+        let missing = resolve_user_path("drafts/2026/../idea.md", Some(&base_str), None).unwrap();
+        assert_eq!(missing.path, base.join("drafts").join("idea.md").to_string_lossy());
+        assert_eq!((missing.exists, missing.within_base), (false, Some(true)));
+
+        let home = resolve_user_path("~/notes/a.md", None, Some(&base)).unwrap();
+        assert!(home.exists);
+        assert_eq!(home.display, format!("~{}notes{}a.md", std::path::MAIN_SEPARATOR, std::path::MAIN_SEPARATOR));
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn command_paths_expand_variables_and_keep_a_final_symlink() {
+        let base = temp_base();
+        let lookup = |name: &str| (name == "NOTES").then(|| "/srv/notes".to_string());
+        assert_eq!(expand_env_vars("$NOTES/a.md", lookup), "/srv/notes/a.md");
+        assert_eq!(expand_env_vars("${NOTES}/$UNSET/$5", lookup), "/srv/notes/$UNSET/$5");
+        let windows_style = if cfg!(windows) { "/srv/notes" } else { "%NOTES%" };
+        assert_eq!(expand_env_vars("%NOTES%", lookup), windows_style);
+
+        assert_eq!(command_path("~/notes/../notes/a.md", None, Some(&base)).unwrap(), base.join("notes").join("a.md"));
+        assert!(command_path("notes/a.md", None, Some(&base)).is_err());
+        assert_eq!(command_path("notes/a.md", Some(&base), None).unwrap(), base.join("notes").join("a.md"));
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(base.join("notes"), base.join("link")).unwrap();
+            assert_eq!(command_path("~/link", None, Some(&base)).unwrap(), base.join("link"));
+            assert_eq!(command_path("~/link/a.md", None, Some(&base)).unwrap(), base.join("notes").join("a.md"));
+        }
+        assert_eq!(
+            without_verbatim_prefix(Path::new(r"\\?\UNC\server\share")),
+            PathBuf::from(r"\\server\share")
+        );
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn completions_list_matching_entries_with_folders_first() {
+        let base = temp_base();
+        fs::create_dir_all(base.join("Nightly")).unwrap();
+        fs::write(base.join("nav.md"), "").unwrap();
+        fs::write(base.join(".notes-cache"), "").unwrap();
+        let home = Some(base.as_path());
+
+        assert_eq!(path_completions("~/n", false, home, 10), vec!["~/Nightly/", "~/notes/", "~/nav.md"]);
+        assert_eq!(path_completions("~\\NO", true, home, 10), vec!["~\\notes\\"]);
+        assert_eq!(path_completions("~/.n", false, home, 10), vec!["~/.notes-cache"]);
+        assert_eq!(path_completions("~/n", false, home, 1), vec!["~/Nightly/"]);
+        let typed = format!("{}/notes/", base.display());
+        assert_eq!(path_completions(&typed, false, home, 10), vec![format!("{typed}a.md")]);
+        assert!(path_completions("~/missing/n", false, home, 10).is_empty());
+        assert!(path_completions("notes/a", false, home, 10).is_empty());
+        fs::remove_dir_all(base).unwrap();
+    }
+}
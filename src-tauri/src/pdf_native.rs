@@ -6,6 +6,11 @@ use std::sync::{Mutex, OnceLock};
 use pdfium_auto::bind_bundled;
 use pdfium_render::prelude::*;
 use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -66,15 +71,16 @@ pub struct PdfOcrPageOptions {
 
 static PDF_PAGE_LAYOUT_CACHE: OnceLock<Mutex<HashMap<String, PdfNativePageTextLayout>>> = OnceLock::new();
 
-fn get_pdfium() -> Result<Pdfium, String> {
-    bind_bundled().map_err(|error| error.to_string())
+fn get_pdfium() -> Result<Pdfium, LatticeError> {
+    bind_bundled().map_err(|error| error.to_string().into())
 }
 
-fn normalize_pdf_path(path: &str) -> Result<PathBuf, String> {
-    let candidate = PathBuf::from(path);
-    let canonical = std::fs::canonicalize(&candidate).map_err(|error| error.to_string())?;
+fn normalize_pdf_path(app: &AppHandle, path: &str) -> Result<PathBuf, LatticeError> {
+    let canonical = std::fs::canonicalize(resolve_command_path(app, path)?)?;
     if !canonical.is_file() {
-        return Err(format!("PDF path is not a file: {}", canonical.display()));
+        return Err(LatticeError::InvalidInput {
+            message: format!("PDF path is not a file: {}", canonical.display()),
+        });
     }
     Ok(canonical)
 }
@@ -83,6 +89,12 @@ fn pdf_page_layout_cache() -> &'static Mutex<HashMap<String, PdfNativePageTextLa
     PDF_PAGE_LAYOUT_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+pub(crate) fn clear_pdf_layout_cache() {
+    if let Ok(mut cache) = pdf_page_layout_cache().lock() {
+        *cache = HashMap::new();
+    }
+}
+
 fn build_cache_key(path: &PathBuf, page_number: usize) -> String {
     format!("{}::{page_number}", path.to_string_lossy())
 }
@@ -325,114 +337,27 @@ fn parse_tesseract_tsv(
 
 #[tauri::command]
 pub async fn desktop_extract_pdf_page_text_layout(
+    app: AppHandle,
     path: String,
     page_number: usize,
-) -> Result<PdfNativePageTextLayout, String> {
-    if page_number < 1 {
-        return Err("pageNumber must be >= 1".to_string());
-    }
-
-    let normalized_path = normalize_pdf_path(&path)?;
-    let cache_key = build_cache_key(&normalized_path, page_number);
-    if let Some(cached) = pdf_page_layout_cache()
-        .lock()
-        .map_err(|error| error.to_string())?
-        .get(&cache_key)
-        .cloned()
-    {
-        return Ok(cached);
-    }
-
-    let pdfium = get_pdfium()?;
-    let document = pdfium
-        .load_pdf_from_file(&normalized_path, None)
-        .map_err(|error| error.to_string())?;
-    let page = document
-        .pages()
-        .get((page_number - 1) as u16)
-        .map_err(|error| error.to_string())?;
-
-    let page_width = pdf_points_value(page.width());
-    let page_height = pdf_points_value(page.height());
-    let text_page = page.text().map_err(|error| error.to_string())?;
-    let text = text_page.all();
-    let mut search_byte_start = 0usize;
-    let mut fallback_utf16_offset = 0usize;
-    let chars = text_page
-        .chars()
-        .iter()
-        .filter_map(|character| {
-            let glyph_text = character.unicode_string()?;
-            if glyph_text.is_empty() {
-                return None;
-            }
-            let (char_index, char_end_index) = resolve_glyph_utf16_offset(
-                &text,
-                &glyph_text,
-                &mut search_byte_start,
-                &mut fallback_utf16_offset,
-            );
-
-            let bounds = character
-                .tight_bounds()
-                .or_else(|_| character.loose_bounds())
-                .ok()?;
-            let (x1, y1, x2, y2) = pdf_rect_to_top_left(bounds, page_height);
-            if x2 <= x1 || y2 <= y1 {
-                return None;
-            }
-
-            Some(PdfNativeTextChar {
-                char_index,
-                char_end_index,
-                text: glyph_text,
-                x1,
-                y1,
-                x2,
-                y2,
-                font_size: character.scaled_font_size().value as f32,
-            })
-        })
-        .collect::<Vec<_>>();
-
-    let layout = PdfNativePageTextLayout {
-        source: "pdfium".to_string(),
-        page_number,
-        width: page_width,
-        height: page_height,
-        text,
-        chars,
-    };
-
-    pdf_page_layout_cache()
-        .lock()
-        .map_err(|error| error.to_string())?
-        .insert(cache_key, layout.clone());
-
-    Ok(layout)
-}
-
-#[tauri::command]
-pub async fn desktop_ocr_pdf_page_text_layout(
-    path: String,
-    page_number: usize,
-    options: Option<PdfOcrPageOptions>,
-) -> Result<PdfOcrPageTextLayout, String> {
-    if page_number < 1 {
-        return Err("pageNumber must be >= 1".to_string());
-    }
+) -> Result<PdfNativePageTextLayout, LatticeError> {
+    log_command_async("desktop_extract_pdf_page_text_layout", async move {
+        if page_number < 1 {
+            return Err(LatticeError::InvalidInput {
+                message: "pageNumber must be >= 1".to_string(),
+            });
+        }
 
-    let normalized_path = normalize_pdf_path(&path)?;
-    tokio::task::spawn_blocking(move || {
-        let options = options.unwrap_or(PdfOcrPageOptions {
-            dpi: None,
-            language: None,
-            psm: None,
-        });
-        let dpi = options.dpi.unwrap_or(220).clamp(120, 400);
-        let language = options.language.unwrap_or_else(|| "eng".to_string());
-        let psm = options.psm.unwrap_or(6).clamp(3, 13);
-        let tesseract = if cfg!(target_os = "windows") { "tesseract.exe" } else { "tesseract" };
+        let normalized_path = normalize_pdf_path(&app, &path)?;
+        let cache_key = build_cache_key(&normalized_path, page_number);
+        if let Some(cached) = pdf_page_layout_cache()
+            .lock()
+            .map_err(|error| error.to_string())?
+            .get(&cache_key)
+            .cloned()
+        {
+            return Ok(cached);
+        }
 
         let pdfium = get_pdfium()?;
         let document = pdfium
@@ -442,55 +367,158 @@ pub async fn desktop_ocr_pdf_page_text_layout(
             .pages()
             .get((page_number - 1) as u16)
             .map_err(|error| error.to_string())?;
+
         let page_width = pdf_points_value(page.width());
         let page_height = pdf_points_value(page.height());
-        let render_scale = dpi as f32 / 72.0;
-        let target_width = (page_width * render_scale).round().max(1.0) as i32;
-        let render_config = PdfRenderConfig::new()
-            .set_target_width(target_width)
-            .render_form_data(true);
-
-        let temp_path = std::env::temp_dir().join(format!(
-            "lattice-ocr-{}-{}.png",
-            std::process::id(),
-            uuid_like_suffix()
-        ));
-        page.render_with_config(&render_config)
+        let text_page = page.text().map_err(|error| error.to_string())?;
+        let text = text_page.all();
+        let mut search_byte_start = 0usize;
+        let mut fallback_utf16_offset = 0usize;
+        let chars = text_page
+            .chars()
+            .iter()
+            .filter_map(|character| {
+                let glyph_text = character.unicode_string()?;
+                if glyph_text.is_empty() {
+                    return None;
+                }
+                let (char_index, char_end_index) = resolve_glyph_utf16_offset(
+                    &text,
+                    &glyph_text,
+                    &mut search_byte_start,
+                    &mut fallback_utf16_offset,
+                );
+
+                let bounds = character
+                    .tight_bounds()
+                    .or_else(|_| character.loose_bounds())
+                    .ok()?;
+                let (x1, y1, x2, y2) = pdf_rect_to_top_left(bounds, page_height);
+                if x2 <= x1 || y2 <= y1 {
+                    return None;
+                }
+
+                Some(PdfNativeTextChar {
+                    char_index,
+                    char_end_index,
+                    text: glyph_text,
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    font_size: character.scaled_font_size().value as f32,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let layout = PdfNativePageTextLayout {
+            source: "pdfium".to_string(),
+            page_number,
+            width: page_width,
+            height: page_height,
+            text,
+            chars,
+        };
+
+        pdf_page_layout_cache()
+            .lock()
             .map_err(|error| error.to_string())?
-            .as_image()
-            .save(&temp_path)
-            .map_err(|error| error.to_string())?;
+            .insert(cache_key, layout.clone());
+
+        Ok(layout)
+    })
+    .await
+}
 
-        let mut command = Command::new(tesseract);
-        configure_hidden_command(&mut command);
-        let output = command
-            .arg(&temp_path)
-            .arg("stdout")
-            .arg("-l")
-            .arg(language)
-            .arg("--psm")
-            .arg(psm.to_string())
-            .arg("tsv")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|error| format!("Failed to run tesseract. Install Tesseract OCR and ensure it is on PATH. {error}"));
-        let _ = std::fs::remove_file(&temp_path);
-        let output = output?;
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-            return Err(if stderr.is_empty() {
-                "Tesseract OCR failed without stderr output".to_string()
-            } else {
-                stderr
+#[tauri::command]
+pub async fn desktop_ocr_pdf_page_text_layout(
+    app: AppHandle,
+    path: String,
+    page_number: usize,
+    options: Option<PdfOcrPageOptions>,
+) -> Result<PdfOcrPageTextLayout, LatticeError> {
+    log_command_async("desktop_ocr_pdf_page_text_layout", async move {
+        if page_number < 1 {
+            return Err(LatticeError::InvalidInput {
+                message: "pageNumber must be >= 1".to_string(),
             });
         }
 
-        let tsv = String::from_utf8_lossy(&output.stdout).to_string();
-        Ok(parse_tesseract_tsv(&tsv, page_number, page_width, page_height, render_scale))
+        let normalized_path = normalize_pdf_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || {
+            let options = options.unwrap_or(PdfOcrPageOptions {
+                dpi: None,
+                language: None,
+                psm: None,
+            });
+            let dpi = options.dpi.unwrap_or(220).clamp(120, 400);
+            let language = options.language.unwrap_or_else(|| "eng".to_string());
+            let psm = options.psm.unwrap_or(6).clamp(3, 13);
+            let tesseract = if cfg!(target_os = "windows") { "tesseract.exe" } else { "tesseract" };
+
+            let pdfium = get_pdfium()?;
+            let document = pdfium
+                .load_pdf_from_file(&normalized_path, None)
+                .map_err(|error| error.to_string())?;
+            let page = document
+                .pages()
+                .get((page_number - 1) as u16)
+                .map_err(|error| error.to_string())?;
+            let page_width = pdf_points_value(page.width());
+            let page_height = pdf_points_value(page.height());
+            let render_scale = dpi as f32 / 72.0;
+            let target_width = (page_width * render_scale).round().max(1.0) as i32;
+            let render_config = PdfRenderConfig::new()
+                .set_target_width(target_width)
+                .render_form_data(true);
+
+            let temp_path = std::env::temp_dir().join(format!(
+                "lattice-ocr-{}-{}.png",
+                std::process::id(),
+                uuid_like_suffix()
+            ));
+            page.render_with_config(&render_config)
+                .map_err(|error| error.to_string())?
+                .as_image()
+                .save(&temp_path)
+                .map_err(|error| error.to_string())?;
+
+            let mut command = Command::new(tesseract);
+            configure_hidden_command(&mut command);
+            let output = command
+                .arg(&temp_path)
+                .arg("stdout")
+                .arg("-l")
+                .arg(language)
+                .arg("--psm")
+                .arg(psm.to_string())
+                .arg("tsv")
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .output()
+                .map_err(|error| {
+                    format!("Failed to run tesseract. Install Tesseract OCR and ensure it is on PATH. {error}")
+                });
+            let _ = std::fs::remove_file(&temp_path);
+            let output = output?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                return Err(LatticeError::Failed {
+                    message: if stderr.is_empty() {
+                        "Tesseract OCR failed without stderr output".to_string()
+                    } else {
+                        stderr
+                    },
+                });
+            }
+
+            let tsv = String::from_utf8_lossy(&output.stdout).to_string();
+            Ok(parse_tesseract_tsv(&tsv, page_number, page_width, page_height, render_scale))
+        })
+        .await
+        .map_err(|error| error.to_string())?
     })
     .await
-    .map_err(|error| error.to_string())?
 }
 
 fn uuid_like_suffix() -> String {
@@ -0,0 +1,172 @@
+use std::path::Path;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::fileops::timestamp_ms;
+use crate::logging::log_command;
+use crate::paths::resolve_command_path;
+use crate::{build_app_settings_from_store, save_app_settings};
+
+/// Cap on unpinned entries; pinned files never roll off.
+const MAX_RECENT_FILES: usize = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFile {
+    pub path: String,
+    /// Milliseconds since the Unix epoch.
+    pub last_opened: u64,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Pinned first, then most recently opened; drops duplicates and unpinned overflow.
+pub(crate) fn normalize_recent_files(files: Vec<RecentFile>) -> Vec<RecentFile> {
+    let mut files: Vec<RecentFile> = files
+        .into_iter()
+        .filter_map(|file| {
+            let path = file.path.trim();
+            (!path.is_empty()).then(|| RecentFile {
+                path: path.to_string(),
+                ..file
+            })
+        })
+        .collect();
+    files.sort_by(|left, right| {
+        right
+            .pinned
+            .cmp(&left.pinned)
+            .then(right.last_opened.cmp(&left.last_opened))
+    });
+
+    let mut normalized: Vec<RecentFile> = Vec::with_capacity(files.len());
+    let mut unpinned = 0;
+    for file in files {
+        if normalized.iter().any(|existing| existing.path == file.path) {
+            continue;
+        }
+        if !file.pinned {
+            if unpinned >= MAX_RECENT_FILES {
+                continue;
+            }
+            unpinned += 1;
+        }
+        normalized.push(file);
+    }
+    normalized
+}
+
+fn push_recent_file_entry(files: &[RecentFile], path: &str, now: u64) -> Vec<RecentFile> {
+    let pinned = files.iter().any(|file| file.path == path && file.pinned);
+    let mut next = vec![RecentFile {
+        path: path.to_string(),
+        last_opened: now,
+        pinned,
+    }];
+    next.extend(files.iter().filter(|file| file.path != path).cloned());
+    normalize_recent_files(next)
+}
+
+fn required_path(path: &str) -> Result<&str, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("File path is required.".to_string());
+    }
+    Ok(trimmed)
+}
+
+pub(crate) fn record_recent_file(app: &AppHandle, path: &str) -> Result<Vec<RecentFile>, LatticeError> {
+    let path = resolve_command_path(app, required_path(path)?)?;
+    let now = timestamp_ms(Ok(SystemTime::now())).unwrap_or_default();
+    let mut settings = build_app_settings_from_store(app)?;
+    settings.recent_files = push_recent_file_entry(&settings.recent_files, &path.to_string_lossy(), now);
+    Ok(save_app_settings(app, settings)?.recent_files)
+}
+
+#[tauri::command]
+pub fn push_recent_file(app: AppHandle, path: String) -> Result<Vec<RecentFile>, LatticeError> {
+    log_command("push_recent_file", || record_recent_file(&app, &path))
+}
+
+/// Also forgets unpinned files that no longer exist.
+#[tauri::command]
+pub fn get_recent_files(app: AppHandle) -> Result<Vec<RecentFile>, LatticeError> {
+    log_command("get_recent_files", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        let before = settings.recent_files.len();
+        settings
+            .recent_files
+            .retain(|file| file.pinned || Path::new(&file.path).is_file());
+        if settings.recent_files.len() == before {
+            return Ok(settings.recent_files);
+        }
+        Ok(save_app_settings(&app, settings)?.recent_files)
+    })
+}
+
+#[tauri::command]
+pub fn toggle_pin_file(app: AppHandle, path: String) -> Result<Vec<RecentFile>, LatticeError> {
+    log_command("toggle_pin_file", || {
+        let path = required_path(&path)?;
+        let resolved = resolve_command_path(&app, path)?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        let file = settings
+            .recent_files
+            .iter_mut()
+            .find(|file| file.path == path || Path::new(&file.path) == resolved)
+            .ok_or_else(|| format!("Not a recent file: {path}"))?;
+        file.pinned = !file.pinned;
+        Ok(save_app_settings(&app, settings)?.recent_files)
+    })
+}
+
+#[tauri::command]
+pub fn remove_recent_file(app: AppHandle, path: String) -> Result<Vec<RecentFile>, LatticeError> {
+    log_command("remove_recent_file", || {
+        let path = required_path(&path)?;
+        let resolved = resolve_command_path(&app, path)?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings
+            .recent_files
+            .retain(|file| file.path != path && Path::new(&file.path) != resolved);
+        Ok(save_app_settings(&app, settings)?.recent_files)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recent(path: &str, last_opened: u64, pinned: bool) -> RecentFile {
+        RecentFile {
+            path: path.to_string(),
+            last_opened,
+            pinned,
+        }
+    }
+
+    #[test]
+    fn pinned_files_sort_first_and_never_roll_off() {
+        let mut files = vec![recent("/notes/pinned.md", 1, true)];
+        for index in 0..MAX_RECENT_FILES as u64 {
+            files = push_recent_file_entry(&files, &format!("/notes/{index}.md"), 10 + index);
+        }
+        assert_eq!(files.len(), MAX_RECENT_FILES + 1);
+        assert_eq!(files[0], recent("/notes/pinned.md", 1, true));
+        assert_eq!(files[1].path, format!("/notes/{}.md", MAX_RECENT_FILES - 1));
+
+        let files = push_recent_file_entry(&files, "/notes/new.md", 1_000);
+        assert_eq!(files.len(), MAX_RECENT_FILES + 1);
+        assert_eq!(files[0].path, "/notes/pinned.md");
+        assert_eq!(files[1].path, "/notes/new.md");
+        assert!(!files.iter().any(|file| file.path == "/notes/0.md"));
+
+        // Re-opening a pinned file refreshes its timestamp but keeps the pin.
+        let files = push_recent_file_entry(&files, "/notes/pinned.md", 2_000);
+        assert_eq!(files[0], recent("/notes/pinned.md", 2_000, true));
+        assert_eq!(files.iter().filter(|file| file.path == "/notes/pinned.md").count(), 1);
+    }
+}
@@ -0,0 +1,353 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::error::LatticeError;
+use crate::file_locks::{files_in_use, LockInfo};
+use crate::fileops::canonical_location;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::recycle_bin::trash_path_sync;
+use crate::{build_app_settings_from_store, DesktopFsState, DesktopPreviewState};
+
+const DELETE_PROGRESS_EVENT: &str = "delete-progress";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// Reports list at most this many read-only paths; `read_only_count` has the full number.
+const MAX_REPORTED_READ_ONLY: usize = 100;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteReport {
+    pub path: String,
+    pub dry_run: bool,
+    /// Whether the tree went to the OS trash rather than being removed outright.
+    pub trashed: bool,
+    pub files: u64,
+    pub directories: u64,
+    pub total_bytes: u64,
+    /// Entries whose read-only flag may stop a permanent delete, mainly on Windows.
+    pub read_only: Vec<String>,
+    pub read_only_count: u64,
+    /// Files another process has open or locked, which Windows won't let go of.
+    pub in_use: Vec<LockInfo>,
+    pub failed: Vec<DeleteFailure>,
+    /// Entries actually removed; short of `files + directories` when some failed or it was cancelled.
+    pub deleted: u64,
+    /// Stopped by `cancel_job`; everything not counted in `deleted` is still in place.
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeleteProgressPayload {
+    path: String,
+    entries_done: u64,
+    entries_total: u64,
+}
+
+/// Symlinks are counted as themselves and never followed.
+fn measure_tree(path: &Path, report: &mut DeleteReport) {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) => {
+            report.failed.push(DeleteFailure {
+                path: path.to_string_lossy().to_string(),
+                error: error.to_string(),
+            });
+            return;
+        }
+    };
+    if metadata.permissions().readonly() && !metadata.file_type().is_symlink() {
+        report.read_only_count += 1;
+        if report.read_only.len() < MAX_REPORTED_READ_ONLY {
+            report.read_only.push(path.to_string_lossy().to_string());
+        }
+    }
+    if !metadata.is_dir() {
+        report.files += 1;
+        report.total_bytes += metadata.len();
+        return;
+    }
+
+    report.directories += 1;
+    match fs::read_dir(path) {
+        Ok(entries) => {
+            for entry in entries {
+                match entry {
+                    Ok(entry) => measure_tree(&entry.path(), report),
+                    Err(error) => report.failed.push(DeleteFailure {
+                        path: path.to_string_lossy().to_string(),
+                        error: error.to_string(),
+                    }),
+                }
+            }
+        }
+        Err(error) => report.failed.push(DeleteFailure {
+            path: path.to_string_lossy().to_string(),
+            error: error.to_string(),
+        }),
+    }
+}
+
+/// `target` and `protected` are canonical locations.
+fn guard_protected(target: &Path, protected: &[PathBuf]) -> Result<(), LatticeError> {
+    match protected.iter().find(|protected| protected.starts_with(target)) {
+        Some(protected) if protected == target => Err(LatticeError::Guarded {
+            message: format!("Refusing to delete {}, which is open or a favorite.", target.display()),
+        }),
+        Some(protected) => Err(LatticeError::Guarded {
+            message: format!(
+                "Refusing to delete {}, which contains {}.",
+                target.display(),
+                protected.display()
+            ),
+        }),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn protected_folders(app: &AppHandle) -> Vec<PathBuf> {
+    let mut protected: Vec<PathBuf> = app
+        .state::<DesktopPreviewState>()
+        .workspace_root
+        .lock()
+        .ok()
+        .and_then(|root| root.clone())
+        .into_iter()
+        .collect();
+    if let Ok(settings) = build_app_settings_from_store(app) {
+        protected.extend(
+            settings
+                .last_opened_folder
+                .into_iter()
+                .chain(settings.favorite_folders)
+                .filter_map(|folder| fs::canonicalize(folder).ok()),
+        );
+    }
+    protected
+}
+
+struct PermanentDelete<'a> {
+    report: &'a mut DeleteReport,
+    entries_done: u64,
+    last_progress: Instant,
+    cancelled: &'a AtomicBool,
+    on_progress: &'a mut dyn FnMut(u64, u64),
+}
+
+impl PermanentDelete<'_> {
+    fn advance(&mut self) {
+        self.entries_done += 1;
+        if self.last_progress.elapsed() >= PROGRESS_INTERVAL {
+            (self.on_progress)(self.entries_done, self.report.files + self.report.directories);
+            self.last_progress = Instant::now();
+        }
+    }
+
+    /// Children first, carrying on past failures so one locked file doesn't stop the rest.
+    fn remove(&mut self, path: &Path) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            self.report.cancelled = true;
+            return false;
+        }
+        let is_dir = fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir());
+        let removed = if is_dir {
+            let mut complete = true;
+            match fs::read_dir(path) {
+                Ok(entries) => {
+                    for entry in entries.flatten() {
+                        complete &= self.remove(&entry.path());
+                    }
+                }
+                Err(error) => return self.fail(path, error),
+            }
+            if !complete {
+                return false;
+            }
+            fs::remove_dir(path)
+        } else {
+            fs::remove_file(path)
+        };
+        match removed {
+            Ok(()) => {
+                self.advance();
+                true
+            }
+            Err(error) => self.fail(path, error),
+        }
+    }
+
+    fn fail(&mut self, path: &Path, error: std::io::Error) -> bool {
+        self.report.failed.push(DeleteFailure {
+            path: path.to_string_lossy().to_string(),
+            error: error.to_string(),
+        });
+        false
+    }
+}
+
+pub(crate) fn delete_recursive_sync(
+    target: &Path,
+    protected: &[PathBuf],
+    dry_run: bool,
+    permanent: bool,
+    cancelled: &AtomicBool,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<DeleteReport, LatticeError> {
+    if fs::symlink_metadata(target).is_err() {
+        return Err(LatticeError::NotFound {
+            message: format!("Path not found: {}", target.display()),
+        });
+    }
+    let location = canonical_location(target).map_err(|error| LatticeError::at_path(target, error))?;
+    guard_protected(&location, protected)?;
+
+    let mut report = DeleteReport {
+        path: target.to_string_lossy().to_string(),
+        dry_run,
+        ..DeleteReport::default()
+    };
+    measure_tree(target, &mut report);
+    report.in_use = files_in_use(target);
+    if dry_run {
+        return Ok(report);
+    }
+
+    let entries_total = report.files + report.directories;
+    if !permanent {
+        trash_path_sync(Path::new(&report.path))?;
+        report.trashed = true;
+        report.deleted = entries_total;
+        on_progress(entries_total, entries_total);
+        return Ok(report);
+    }
+
+    // Measuring failures describe the tree, not the delete; start the list fresh.
+    report.failed.clear();
+    let mut delete = PermanentDelete {
+        report: &mut report,
+        entries_done: 0,
+        last_progress: Instant::now(),
+        cancelled,
+        on_progress,
+    };
+    delete.remove(target);
+    let entries_done = delete.entries_done;
+    report.deleted = entries_done;
+    on_progress(entries_done, entries_total);
+    Ok(report)
+}
+
+/// Moves `path` to the OS trash unless `permanent`; `dry_run` only measures it.
+#[tauri::command]
+pub async fn delete_recursive(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+    dry_run: bool,
+    permanent: bool,
+) -> Result<DeleteReport, LatticeError> {
+    log_command_async("delete_recursive", async move {
+        let target = resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+        let protected = protected_folders(&app);
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let mut emit_progress = |entries_done: u64, entries_total: u64| {
+                let _ = app.emit(
+                    DELETE_PROGRESS_EVENT,
+                    DeleteProgressPayload {
+                        path: target.to_string_lossy().to_string(),
+                        entries_done,
+                        entries_total,
+                    },
+                );
+            };
+            delete_recursive_sync(&target, &protected, dry_run, permanent, &AtomicBool::new(false), &mut emit_progress)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static NOT_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+    fn create_tree() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("lattice-recursive-delete-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes/archive")).unwrap();
+        fs::write(root.join("notes/a.md"), "alpha").unwrap();
+        fs::write(root.join("notes/archive/b.md"), "beta").unwrap();
+        set_readonly(&root.join("notes/a.md"), true);
+        fs::canonicalize(root).unwrap()
+    }
+
+    #[allow(clippy::permissions_set_readonly_false)]
+    fn set_readonly(path: &Path, readonly: bool) {
+        let mut permissions = fs::metadata(path).unwrap().permissions();
+        permissions.set_readonly(readonly);
+        fs::set_permissions(path, permissions).unwrap();
+    }
+
+    #[test]
+    fn dry_run_measures_without_deleting_and_permanent_delete_removes_the_tree() {
+        let root = create_tree();
+        let notes = root.join("notes");
+
+        let preview = delete_recursive_sync(&notes, &[], true, true, &NOT_CANCELLED, &mut |_, _| {}).unwrap();
+        assert_eq!((preview.files, preview.directories, preview.total_bytes), (2, 2, 9));
+        assert_eq!(preview.read_only, vec![notes.join("a.md").to_string_lossy().to_string()]);
+        assert!(notes.join("archive/b.md").is_file());
+
+        // Windows refuses to remove read-only files outright.
+        set_readonly(&notes.join("a.md"), false);
+        let mut last_progress = (0, 0);
+        let mut on_progress = |done, total| last_progress = (done, total);
+        let deleted = delete_recursive_sync(&notes, &[], false, true, &NOT_CANCELLED, &mut on_progress).unwrap();
+        assert!(deleted.failed.is_empty(), "{:?}", deleted.failed);
+        assert!(!deleted.trashed);
+        assert_eq!(deleted.deleted, 4);
+        assert!(!notes.exists());
+        assert_eq!(last_progress, (4, 4));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn the_open_folder_and_its_ancestors_are_guarded() {
+        let root = create_tree();
+        let protected = vec![root.join("notes/archive")];
+
+        for target in [root.join("notes/archive"), root.join("notes")] {
+            let result = delete_recursive_sync(&target, &protected, false, true, &NOT_CANCELLED, &mut |_, _| {});
+            assert!(matches!(result, Err(LatticeError::Guarded { .. })), "{target:?}");
+        }
+        assert!(root.join("notes/archive/b.md").is_file());
+        let note = root.join("notes/a.md");
+        assert!(delete_recursive_sync(&note, &protected, true, true, &NOT_CANCELLED, &mut |_, _| {}).is_ok());
+
+        set_readonly(&root.join("notes/a.md"), false);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
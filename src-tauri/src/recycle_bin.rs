@@ -0,0 +1,341 @@
+use std::path::Path;
+use std::sync::Mutex as StdMutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::error::LatticeError;
+use crate::file_locks::{check_path_locked_sync, describe_holders};
+use crate::logging::{log_command, log_command_async};
+use crate::paths::resolve_command_path;
+
+/// Long enough to read a confirmation dialog, short enough that a stray call later fails.
+const EMPTY_TRASH_TOKEN_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashFailure {
+    pub path: String,
+    pub error: LatticeError,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashBatchReport {
+    pub trashed: Vec<String>,
+    pub failed: Vec<TrashFailure>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmptyTrashReport {
+    pub items: usize,
+    /// Bytes in purged files; folders only report entry counts, so they aren't included.
+    pub bytes: u64,
+    /// Purged folders whose size couldn't be counted in `bytes`.
+    pub unmeasured: usize,
+}
+
+struct IssuedToken {
+    token: String,
+    issued_at: Instant,
+}
+
+/// The single outstanding token for `empty_trash`; requesting a new one replaces it.
+#[derive(Default)]
+pub struct EmptyTrashTokenState {
+    issued: StdMutex<Option<IssuedToken>>,
+}
+
+/// An item in the OS trash; `id` is opaque and only meaningful to `restore_trashed`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    pub id: String,
+    pub name: String,
+    pub original_path: String,
+    /// Unix timestamp in milliseconds.
+    pub deleted_at: i64,
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux", target_os = "freebsd"))]
+fn move_to_os_trash(path: &Path) -> Result<(), LatticeError> {
+    trash::delete(path).map_err(|error| error.to_string().into())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux", target_os = "freebsd")))]
+fn move_to_os_trash(_path: &Path) -> Result<(), LatticeError> {
+    Err(LatticeError::Unsupported {
+        message: "Moving files to the trash is not supported on this platform.".to_string(),
+    })
+}
+
+/// Listing and restoring rely on `trash::os_limited`, which macOS doesn't provide.
+#[cfg(any(
+    target_os = "windows",
+    all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))
+))]
+mod os_trash {
+    use super::{EmptyTrashReport, TrashItem};
+    use crate::error::LatticeError;
+
+    fn item_id(item: &trash::TrashItem) -> String {
+        item.id.to_string_lossy().to_string()
+    }
+
+    pub(super) fn list() -> Result<Vec<TrashItem>, LatticeError> {
+        Ok(trash::os_limited::list()
+            .map_err(|error| error.to_string())?
+            .iter()
+            .map(|item| TrashItem {
+                id: item_id(item),
+                name: item.name.to_string_lossy().to_string(),
+                original_path: item.original_path().to_string_lossy().to_string(),
+                deleted_at: item.time_deleted.saturating_mul(1000),
+            })
+            .collect())
+    }
+
+    pub(super) fn restore(ids: &[String]) -> Result<(), LatticeError> {
+        let items: Vec<trash::TrashItem> = trash::os_limited::list()
+            .map_err(|error| error.to_string())?
+            .into_iter()
+            .filter(|item| ids.contains(&item_id(item)))
+            .collect();
+        if let Some(missing) = ids.iter().find(|id| !items.iter().any(|item| &item_id(item) == *id)) {
+            return Err(LatticeError::NotFound {
+                message: format!("Trash item not found: {missing}"),
+            });
+        }
+        trash::os_limited::restore_all(items).map_err(|error| match error {
+            trash::Error::RestoreCollision { path, .. } => LatticeError::AlreadyExists {
+                message: format!("Cannot restore over an existing path: {}", path.display()),
+            },
+            error => error.to_string().into(),
+        })
+    }
+
+    pub(super) fn purge_all() -> Result<EmptyTrashReport, LatticeError> {
+        let items = trash::os_limited::list().map_err(|error| error.to_string())?;
+        let mut report = EmptyTrashReport {
+            items: items.len(),
+            ..EmptyTrashReport::default()
+        };
+        for item in &items {
+            match trash::os_limited::metadata(item).map(|metadata| metadata.size) {
+                Ok(trash::TrashItemSize::Bytes(bytes)) => report.bytes += bytes,
+                _ => report.unmeasured += 1,
+            }
+        }
+        trash::os_limited::purge_all(items).map_err(|error| error.to_string())?;
+        Ok(report)
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))
+)))]
+mod os_trash {
+    use super::{EmptyTrashReport, TrashItem};
+    use crate::error::LatticeError;
+
+    fn unsupported() -> LatticeError {
+        LatticeError::Unsupported {
+            message: "Browsing the trash is not supported on this platform.".to_string(),
+        }
+    }
+
+    pub(super) fn list() -> Result<Vec<TrashItem>, LatticeError> {
+        Err(unsupported())
+    }
+
+    pub(super) fn restore(_ids: &[String]) -> Result<(), LatticeError> {
+        Err(unsupported())
+    }
+
+    pub(super) fn purge_all() -> Result<EmptyTrashReport, LatticeError> {
+        Err(unsupported())
+    }
+}
+
+/// Consumes the issued token on success, so each token empties the trash at most once.
+fn redeem_token(issued: &mut Option<IssuedToken>, token: &str, now: Instant) -> Result<(), LatticeError> {
+    let Some(current) = issued.as_ref().filter(|current| current.token == token.trim()) else {
+        return Err(LatticeError::InvalidToken {
+            message: "That confirmation is not valid; request a new one.".to_string(),
+        });
+    };
+    if now.duration_since(current.issued_at) > EMPTY_TRASH_TOKEN_TTL {
+        *issued = None;
+        return Err(LatticeError::ExpiredToken {
+            message: "The confirmation has expired; request a new one.".to_string(),
+        });
+    }
+    *issued = None;
+    Ok(())
+}
+
+fn items_under_folder(items: Vec<TrashItem>, folder: Option<&Path>) -> Vec<TrashItem> {
+    let mut items: Vec<TrashItem> = match folder {
+        Some(folder) => items
+            .into_iter()
+            .filter(|item| Path::new(&item.original_path).starts_with(folder))
+            .collect(),
+        None => items,
+    };
+    items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+    items
+}
+
+pub(crate) fn trash_path_sync(target: &Path) -> Result<(), LatticeError> {
+    // A dangling symlink is still a valid trash target, so check the link itself.
+    if std::fs::symlink_metadata(target).is_err() {
+        return Err(LatticeError::NotFound {
+            message: format!("Path not found: {}", target.display()),
+        });
+    }
+    move_to_os_trash(target).map_err(|error| in_use_error(target).unwrap_or(error))
+}
+
+/// Trash failures are cryptic when a file is open elsewhere, so name who has it.
+fn in_use_error(target: &Path) -> Option<LatticeError> {
+    let info = check_path_locked_sync(target).ok().filter(|info| info.locked)?;
+    Some(LatticeError::InUse {
+        message: format!("{} is in use by {}", target.display(), describe_holders(&info.holders)),
+        holders: info.holders,
+    })
+}
+
+#[tauri::command]
+pub async fn trash_path(app: AppHandle, path: String) -> Result<(), LatticeError> {
+    log_command_async("trash_path", async move {
+        let target = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || trash_path_sync(&target))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn trash_paths(app: AppHandle, paths: Vec<String>) -> Result<TrashBatchReport, LatticeError> {
+    log_command_async("trash_paths", async move {
+        tokio::task::spawn_blocking(move || {
+            let mut report = TrashBatchReport::default();
+            for path in paths {
+                match resolve_command_path(&app, &path).and_then(|target| trash_path_sync(&target)) {
+                    Ok(()) => report.trashed.push(path),
+                    Err(error) => report.failed.push(TrashFailure { path, error }),
+                }
+            }
+            report
+        })
+        .await
+        .map_err(|error| error.to_string().into())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_trashed(app: AppHandle, folder: Option<String>) -> Result<Vec<TrashItem>, LatticeError> {
+    log_command_async("list_trashed", async move {
+        let folder = match folder.filter(|folder| !folder.trim().is_empty()) {
+            Some(folder) => Some(resolve_command_path(&app, &folder)?),
+            None => None,
+        };
+        tokio::task::spawn_blocking(move || {
+            Ok(items_under_folder(os_trash::list()?, folder.as_deref()))
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn restore_trashed(ids: Vec<String>) -> Result<(), LatticeError> {
+    log_command_async("restore_trashed", async move {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        tokio::task::spawn_blocking(move || os_trash::restore(&ids))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Returns a token `empty_trash` must be called with within a minute.
+#[tauri::command]
+pub fn request_empty_trash_token(state: State<'_, EmptyTrashTokenState>) -> Result<String, LatticeError> {
+    log_command("request_empty_trash_token", || {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        *state.issued.lock().map_err(|error| error.to_string())? = Some(IssuedToken {
+            token: token.clone(),
+            issued_at: Instant::now(),
+        });
+        Ok(token)
+    })
+}
+
+/// Permanently deletes everything in the OS trash.
+#[tauri::command]
+pub async fn empty_trash(
+    state: State<'_, EmptyTrashTokenState>,
+    confirm_token: String,
+) -> Result<EmptyTrashReport, LatticeError> {
+    log_command_async("empty_trash", async move {
+        redeem_token(&mut *state.issued.lock().map_err(|error| error.to_string())?, &confirm_token, Instant::now())?;
+        tokio::task::spawn_blocking(os_trash::purge_all)
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_is_limited_to_the_folder_and_newest_first() {
+        let item = |id: &str, original_path: &str, deleted_at: i64| TrashItem {
+            id: id.to_string(),
+            name: id.to_string(),
+            original_path: original_path.to_string(),
+            deleted_at,
+        };
+        let items = vec![
+            item("old", "/notes/old.md", 1_000),
+            item("other", "/notes-archive/other.md", 3_000),
+            item("new", "/notes/sub/new.md", 2_000),
+        ];
+
+        let listed = items_under_folder(items.clone(), Some(Path::new("/notes")));
+        assert_eq!(listed.iter().map(|item| item.id.as_str()).collect::<Vec<_>>(), vec!["new", "old"]);
+        assert_eq!(items_under_folder(items, None).len(), 3);
+    }
+
+    #[test]
+    fn empty_trash_tokens_are_single_use_and_expire() {
+        let issued_at = Instant::now();
+        let issue = || {
+            Some(IssuedToken {
+                token: "token".to_string(),
+                issued_at,
+            })
+        };
+
+        let mut issued = issue();
+        assert!(matches!(redeem_token(&mut issued, "other", issued_at), Err(LatticeError::InvalidToken { .. })));
+        assert!(redeem_token(&mut issued, "token", issued_at).is_ok());
+        assert!(matches!(redeem_token(&mut issued, "token", issued_at), Err(LatticeError::InvalidToken { .. })));
+
+        let mut issued = issue();
+        let later = issued_at + EMPTY_TRASH_TOKEN_TTL + Duration::from_secs(1);
+        assert!(matches!(redeem_token(&mut issued, "token", later), Err(LatticeError::ExpiredToken { .. })));
+        assert!(issued.is_none());
+    }
+}
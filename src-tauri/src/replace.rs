@@ -0,0 +1,292 @@
+use std::borrow::Cow;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ignore::WalkBuilder;
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::error::LatticeError;
+use crate::fileops::write_bytes_atomic;
+use crate::ignore_rules::{matcher_for, IgnoreMatcher};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::search::{looks_binary, matches_extension_allowlist, normalize_extensions, BINARY_SNIFF_BYTES};
+use crate::transfer::TransferFailure;
+use crate::DesktopFsState;
+
+/// Enough lines per file for a preview; the counts always cover every match.
+const MAX_PREVIEW_LINES_PER_FILE: usize = 20;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReplaceOpts {
+    /// Treats `find` as a regex; `replace` may then use `$1` or `${name}` groups.
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// Extensions to include, with or without the leading dot. Empty means all files.
+    pub extensions: Vec<String>,
+    pub dry_run: bool,
+    /// Copies each file to `<name>.bak` before rewriting it.
+    pub backup: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacePreview {
+    pub line_number: usize,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileReplacement {
+    pub path: String,
+    pub replacements: usize,
+    pub preview: Vec<ReplacePreview>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceReport {
+    pub dry_run: bool,
+    /// Files with at least one match; in a real run, the ones rewritten.
+    pub files: Vec<FileReplacement>,
+    pub total_replacements: usize,
+    /// Files that matched but could not be backed up or written; the rest are untouched by it.
+    pub failed: Vec<TransferFailure>,
+}
+
+struct Replacer {
+    pattern: Regex,
+    replace: String,
+    expand: bool,
+}
+
+impl Replacer {
+    fn new(find: &str, replace: &str, opts: &ReplaceOpts) -> Result<Self, LatticeError> {
+        if find.is_empty() {
+            return Err(LatticeError::InvalidInput {
+                message: "Text to find is required.".to_string(),
+            });
+        }
+        let source = if opts.regex { find.to_string() } else { regex::escape(find) };
+        let source = if opts.whole_word {
+            format!(r"\b(?:{source})\b")
+        } else {
+            source
+        };
+        let pattern = RegexBuilder::new(&source)
+            .case_insensitive(!opts.case_sensitive)
+            .build()
+            .map_err(|error| LatticeError::InvalidInput {
+                message: format!("Invalid pattern: {error}"),
+            })?;
+        Ok(Self {
+            pattern,
+            replace: replace.to_string(),
+            expand: opts.regex,
+        })
+    }
+
+    fn replace_line<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        if self.expand {
+            self.pattern.replace_all(line, self.replace.as_str())
+        } else {
+            self.pattern.replace_all(line, NoExpand(&self.replace))
+        }
+    }
+
+    /// Works a line at a time, leaving line endings alone, so patterns never span lines.
+    /// Returns the new text and the per-file result, or `None` when nothing matched.
+    fn apply(&self, path: &Path, text: &str) -> Option<(String, FileReplacement)> {
+        let mut output = String::with_capacity(text.len());
+        let mut replacements = 0;
+        let mut preview = Vec::new();
+        for (index, line) in text.split_inclusive('\n').enumerate() {
+            let body = line.trim_end_matches(['\n', '\r']);
+            let count = self.pattern.find_iter(body).count();
+            if count == 0 {
+                output.push_str(line);
+                continue;
+            }
+            let replaced = self.replace_line(body);
+            replacements += count;
+            if preview.len() < MAX_PREVIEW_LINES_PER_FILE {
+                preview.push(ReplacePreview {
+                    line_number: index + 1,
+                    before: body.to_string(),
+                    after: replaced.to_string(),
+                });
+            }
+            output.push_str(&replaced);
+            output.push_str(&line[body.len()..]);
+        }
+        (replacements > 0).then(|| {
+            let file = FileReplacement {
+                path: path.to_string_lossy().to_string(),
+                replacements,
+                preview,
+            };
+            (output, file)
+        })
+    }
+}
+
+/// `None` for binary files and ones that aren't valid UTF-8, which are never rewritten.
+fn read_text(path: &Path) -> std::io::Result<Option<String>> {
+    let mut bytes = Vec::new();
+    fs::File::open(path)?.read_to_end(&mut bytes)?;
+    if looks_binary(&bytes[..bytes.len().min(BINARY_SNIFF_BYTES)]) {
+        return Ok(None);
+    }
+    Ok(String::from_utf8(bytes).ok())
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+fn write_replacement(path: &Path, text: &str, backup: bool) -> Result<(), LatticeError> {
+    if backup {
+        fs::copy(path, backup_path(path)).map_err(|error| LatticeError::at_path(path, error))?;
+    }
+    write_bytes_atomic(path, text.as_bytes())
+}
+
+fn replace_in_directory(
+    root: &Path,
+    replacer: &Replacer,
+    opts: &ReplaceOpts,
+    ignore: Arc<IgnoreMatcher>,
+) -> Result<ReplaceReport, LatticeError> {
+    if !root.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Replace root is not a directory: {}", root.display()),
+        });
+    }
+
+    let allowlist = normalize_extensions(&opts.extensions);
+    let mut report = ReplaceReport {
+        dry_run: opts.dry_run,
+        ..ReplaceReport::default()
+    };
+    // Collected up front: the walk is lazy, so it would otherwise reach the `.bak` files
+    // written along the way and back those up too.
+    let paths: Vec<PathBuf> = WalkBuilder::new(root)
+        .require_git(false)
+        .filter_entry(move |entry| {
+            !ignore.is_ignored(entry.path(), entry.file_type().is_some_and(|file_type| file_type.is_dir()))
+        })
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|file_type| file_type.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| matches_extension_allowlist(path, &allowlist))
+        .collect();
+    for path in paths.iter().map(PathBuf::as_path) {
+        // Unreadable files are skipped, as in search.
+        let Ok(Some(text)) = read_text(path) else {
+            continue;
+        };
+        let Some((output, file)) = replacer.apply(path, &text) else {
+            continue;
+        };
+
+        if !opts.dry_run {
+            // Files already rewritten stay rewritten; a failure only costs this one.
+            if let Err(error) = write_replacement(path, &output, opts.backup) {
+                report.failed.push(TransferFailure {
+                    path: file.path,
+                    error: error.message().to_string(),
+                });
+                continue;
+            }
+        }
+        report.total_replacements += file.replacements;
+        report.files.push(file);
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn replace_in_files(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+    find: String,
+    replace: String,
+    opts: ReplaceOpts,
+) -> Result<ReplaceReport, LatticeError> {
+    log_command_async("replace_in_files", async move {
+        let root = resolve_command_path(&app, &root)?;
+        let replacer = Replacer::new(&find, &replace, &opts)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let root = fs::canonicalize(&root).unwrap_or(root);
+            let ignore = matcher_for(&app, &root);
+            replace_in_directory(&root, &replacer, &opts, ignore)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replacements_expand_groups_and_dry_runs_write_nothing() {
+        let root = std::env::temp_dir().join(format!("lattice-replace-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join(".gitignore"), "ignored.md\n").unwrap();
+        fs::write(root.join("ignored.md"), "v1.2\n").unwrap();
+        fs::write(root.join("a.md"), "v1.2 and v3.4\r\nnone\nv5.6").unwrap();
+        fs::write(root.join("c.bin"), b"v1.2\0").unwrap();
+        let ignore = Arc::new(IgnoreMatcher::new(&root, &[]));
+        let mut opts = ReplaceOpts {
+            regex: true,
+            dry_run: true,
+            ..ReplaceOpts::default()
+        };
+        let replacer = Replacer::new(r"v(\d)\.(\d)", "$2.$1", &opts).unwrap();
+
+        let preview = replace_in_directory(&root, &replacer, &opts, ignore.clone()).unwrap();
+        assert_eq!(preview.total_replacements, 3);
+        assert_eq!(preview.files.len(), 1);
+        assert_eq!(preview.files[0].preview[0].after, "2.1 and 4.3");
+        assert_eq!(fs::read_to_string(root.join("a.md")).unwrap(), "v1.2 and v3.4\r\nnone\nv5.6");
+
+        opts.dry_run = false;
+        opts.backup = true;
+        let report = replace_in_directory(&root, &replacer, &opts, ignore).unwrap();
+        assert_eq!(report.total_replacements, 3);
+        assert_eq!(fs::read_to_string(root.join("a.md")).unwrap(), "2.1 and 4.3\r\nnone\n6.5");
+        assert_eq!(fs::read_to_string(root.join("a.md.bak")).unwrap(), "v1.2 and v3.4\r\nnone\nv5.6");
+        assert_eq!(report.files.len(), 1);
+        assert!(!root.join("a.md.bak.bak").exists());
+        assert_eq!(fs::read_to_string(root.join("ignored.md")).unwrap(), "v1.2\n");
+
+        let literal = Replacer::new("a.md", "$1", &ReplaceOpts::default()).unwrap();
+        assert_eq!(literal.replace_line("A.MD a_md"), "$1 a_md");
+        assert!(Replacer::new("", "x", &ReplaceOpts::default()).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
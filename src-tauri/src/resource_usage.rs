@@ -0,0 +1,130 @@
+use std::sync::Mutex as StdMutex;
+use std::time::Instant;
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, MINIMUM_CPU_UPDATE_INTERVAL};
+use tauri::State;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsage {
+    /// Resident set size in bytes.
+    pub memory_bytes: Option<u64>,
+    /// Share of the whole machine, 0–100, averaged since the previous call.
+    pub cpu_percent: Option<f64>,
+    /// Only reported on Linux.
+    pub thread_count: Option<usize>,
+}
+
+struct Sampler {
+    system: System,
+    pid: Pid,
+    last_refresh: Option<Instant>,
+}
+
+/// One `System` kept for the app's lifetime; only our own process entry is ever refreshed,
+/// which keeps polling from the status bar cheap.
+#[derive(Default)]
+pub struct ResourceUsageState {
+    sampler: StdMutex<Option<Sampler>>,
+}
+
+/// `sysinfo` reports 100% per core; the status bar shows the share of the whole machine.
+fn machine_cpu_percent(per_core_percent: f32, cores: usize) -> f64 {
+    (f64::from(per_core_percent) / cores.max(1) as f64).clamp(0.0, 100.0)
+}
+
+impl Sampler {
+    fn new() -> Result<Self, String> {
+        Ok(Self {
+            system: System::new(),
+            pid: sysinfo::get_current_pid().map_err(str::to_string)?,
+            last_refresh: None,
+        })
+    }
+
+    fn refresh(&mut self) {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[self.pid]),
+            false,
+            ProcessRefreshKind::nothing().with_memory().with_cpu().with_tasks(),
+        );
+        self.last_refresh = Some(Instant::now());
+    }
+
+    /// CPU usage is a difference between two refreshes, so the first call (or one right
+    /// after another) waits out the minimum interval before sampling again.
+    fn sample(&mut self) -> ResourceUsage {
+        match self.last_refresh {
+            Some(last) if last.elapsed() >= MINIMUM_CPU_UPDATE_INTERVAL => {}
+            Some(last) => {
+                std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL.saturating_sub(last.elapsed()));
+            }
+            None => {
+                self.refresh();
+                std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+            }
+        }
+        self.refresh();
+
+        let Some(process) = self.system.process(self.pid) else {
+            return ResourceUsage {
+                memory_bytes: None,
+                cpu_percent: None,
+                thread_count: None,
+            };
+        };
+        let cores = std::thread::available_parallelism().map_or(1, usize::from);
+        ResourceUsage {
+            memory_bytes: Some(process.memory()).filter(|memory| *memory > 0),
+            cpu_percent: Some(machine_cpu_percent(process.cpu_usage(), cores)),
+            thread_count: process.tasks().map(|tasks| tasks.len()).filter(|count| *count > 0),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn self_resource_usage(state: State<'_, ResourceUsageState>) -> Result<ResourceUsage, LatticeError> {
+    log_command_async("self_resource_usage", async move {
+        let sampler = state.sampler.lock().map_err(|error| error.to_string())?.take();
+        let (sampler, usage) = tokio::task::spawn_blocking(move || {
+            let mut sampler = match sampler {
+                Some(sampler) => sampler,
+                None => Sampler::new()?,
+            };
+            let usage = sampler.sample();
+            Ok::<_, String>((sampler, usage))
+        })
+        .await
+        .map_err(|error| error.to_string())??;
+
+        if let Ok(mut slot) = state.sampler.lock() {
+            *slot = Some(sampler);
+        }
+        Ok(usage)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_usage_is_scaled_to_the_whole_machine() {
+        assert_eq!(machine_cpu_percent(200.0, 8), 25.0);
+        assert_eq!(machine_cpu_percent(850.0, 8), 100.0);
+        assert_eq!(machine_cpu_percent(-1.0, 4), 0.0);
+        assert_eq!(machine_cpu_percent(50.0, 0), 50.0);
+    }
+
+    #[test]
+    fn the_current_process_reports_memory() {
+        let usage = Sampler::new().unwrap().sample();
+        assert!(usage.memory_bytes.is_some_and(|memory| memory > 0));
+        assert!(usage.cpu_percent.is_some());
+    }
+}
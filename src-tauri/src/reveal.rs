@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+
+#[derive(Debug, PartialEq, Eq)]
+enum RevealTarget {
+    /// Opened as-is.
+    Directory(PathBuf),
+    /// Selected inside its parent folder.
+    File(PathBuf),
+}
+
+fn reveal_target(path: &Path) -> Result<RevealTarget, LatticeError> {
+    let metadata = std::fs::metadata(path).map_err(|error| LatticeError::at_path(path, error))?;
+    let path = std::fs::canonicalize(path).map_err(|error| LatticeError::at_path(path, error))?;
+    Ok(if metadata.is_dir() {
+        RevealTarget::Directory(path)
+    } else {
+        RevealTarget::File(path)
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn open_file_manager(target: &RevealTarget) -> Result<(), LatticeError> {
+    use std::os::windows::process::CommandExt;
+
+    let mut command = StdCommand::new("explorer");
+    match target {
+        RevealTarget::Directory(path) => command.arg(path),
+        // Explorer parses `/select,` itself, so the path must be quoted verbatim.
+        RevealTarget::File(path) => command.raw_arg(format!("/select,\"{}\"", path.display())),
+    };
+    // Explorer's exit code is meaningless, so a successful spawn is the best signal.
+    command.spawn().map(|_| ()).map_err(|error| error.to_string().into())
+}
+
+#[cfg(target_os = "macos")]
+fn open_file_manager(target: &RevealTarget) -> Result<(), LatticeError> {
+    let mut command = StdCommand::new("open");
+    match target {
+        RevealTarget::Directory(path) => command.arg(path),
+        RevealTarget::File(path) => command.arg("-R").arg(path),
+    };
+    command.spawn().map(|_| ()).map_err(|error| error.to_string().into())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn open_file_manager(target: &RevealTarget) -> Result<(), LatticeError> {
+    let folder = match target {
+        RevealTarget::Directory(path) => path.clone(),
+        RevealTarget::File(path) => {
+            // Most desktop file managers implement the freedesktop FileManager1 interface.
+            if let Ok(uri) = reqwest::Url::from_file_path(path) {
+                let shown = StdCommand::new("dbus-send")
+                    .args([
+                        "--session",
+                        "--print-reply",
+                        "--dest=org.freedesktop.FileManager1",
+                        "--type=method_call",
+                        "/org/freedesktop/FileManager1",
+                        "org.freedesktop.FileManager1.ShowItems",
+                    ])
+                    .arg(format!("array:string:{uri}"))
+                    .arg("string:")
+                    .output()
+                    .is_ok_and(|output| output.status.success());
+                if shown {
+                    return Ok(());
+                }
+            }
+            path.parent().map(Path::to_path_buf).unwrap_or_else(|| path.clone())
+        }
+    };
+
+    for opener in ["xdg-open", "gio", "nautilus", "dolphin", "thunar"] {
+        let mut command = StdCommand::new(opener);
+        if opener == "gio" {
+            command.arg("open");
+        }
+        if command.arg(&folder).spawn().is_ok() {
+            return Ok(());
+        }
+    }
+
+    Err(LatticeError::NoFileManager {
+        message: "No supported file manager was found".to_string(),
+    })
+}
+
+#[tauri::command]
+pub async fn reveal_in_file_manager(app: AppHandle, path: String) -> Result<(), LatticeError> {
+    log_command_async("reveal_in_file_manager", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || {
+            let target = reveal_target(&path)?;
+            open_file_manager(&target)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directories_are_opened_and_files_are_selected() {
+        let root = std::env::temp_dir().join(format!("lattice-reveal-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("note.md"), "note").unwrap();
+        let canonical = std::fs::canonicalize(&root).unwrap();
+
+        assert_eq!(reveal_target(&root).unwrap(), RevealTarget::Directory(canonical.clone()));
+        assert_eq!(
+            reveal_target(&root.join("note.md")).unwrap(),
+            RevealTarget::File(canonical.join("note.md"))
+        );
+        assert!(matches!(reveal_target(&root.join("missing.md")), Err(LatticeError::NotFound { .. })));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+
+use ignore::WalkBuilder;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::error::LatticeError;
+use crate::fileops::write_bytes_atomic;
+use crate::ignore_rules::{matcher_for, IgnoreMatcher};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+const DEFAULT_MAX_SEARCH_RESULTS: usize = 1000;
+pub(crate) const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchOpts {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub max_results: Option<usize>,
+    /// Extensions to include, with or without the leading dot. Empty means all files.
+    pub extensions: Vec<String>,
+}
+
+/// Match columns are UTF-16 offsets so they index directly into JS strings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMatchRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub path: String,
+    pub line_number: usize,
+    pub line_text: String,
+    pub matches: Vec<SearchMatchRange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    /// Hits grouped under a heading per file, each linking to its line.
+    Markdown,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchExport<'a> {
+    query: &'a str,
+    exported_at: &'a str,
+    hits: &'a [SearchHit],
+}
+
+fn build_search_pattern(query: &str, opts: &SearchOpts) -> Result<Regex, String> {
+    let escaped = regex::escape(query);
+    let pattern = if opts.whole_word {
+        format!(r"\b(?:{escaped})\b")
+    } else {
+        escaped
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!opts.case_sensitive)
+        .build()
+        .map_err(|error| error.to_string())
+}
+
+pub(crate) fn normalize_extensions(extensions: &[String]) -> Vec<String> {
+    extensions
+        .iter()
+        .map(|extension| extension.trim().trim_start_matches('.').to_ascii_lowercase())
+        .filter(|extension| !extension.is_empty())
+        .collect()
+}
+
+pub(crate) fn matches_extension_allowlist(path: &Path, allowlist: &[String]) -> bool {
+    if allowlist.is_empty() {
+        return true;
+    }
+
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase())
+        .is_some_and(|extension| allowlist.iter().any(|allowed| allowed == &extension))
+}
+
+pub(crate) fn looks_binary(prefix: &[u8]) -> bool {
+    prefix.contains(&0)
+}
+
+fn utf16_column(line: &str, byte_index: usize) -> usize {
+    line[..byte_index].encode_utf16().count()
+}
+
+fn search_file(path: &Path, pattern: &Regex, limit: usize, hits: &mut Vec<SearchHit>) -> std::io::Result<()> {
+    let file = fs::File::open(path)?;
+    let mut reader = BufReader::with_capacity(BINARY_SNIFF_BYTES, file);
+    if looks_binary(reader.fill_buf()?) {
+        return Ok(());
+    }
+
+    let mut buffer = Vec::new();
+    let mut line_number = 0usize;
+    while hits.len() < limit {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        let decoded = String::from_utf8_lossy(&buffer);
+        let line = decoded.trim_end_matches(['\n', '\r']);
+        let matches: Vec<SearchMatchRange> = pattern
+            .find_iter(line)
+            .map(|found| SearchMatchRange {
+                start: utf16_column(line, found.start()),
+                end: utf16_column(line, found.end()),
+            })
+            .collect();
+
+        if !matches.is_empty() {
+            hits.push(SearchHit {
+                path: path.to_string_lossy().to_string(),
+                line_number,
+                line_text: line.to_string(),
+                matches,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn search_directory_contents(
+    root: &Path,
+    query: &str,
+    opts: &SearchOpts,
+    ignore: Arc<IgnoreMatcher>,
+) -> Result<Vec<SearchHit>, LatticeError> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !root.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Search root is not a directory: {}", root.display()),
+        });
+    }
+
+    let pattern = build_search_pattern(query, opts)?;
+    let allowlist = normalize_extensions(&opts.extensions);
+    let limit = opts.max_results.unwrap_or(DEFAULT_MAX_SEARCH_RESULTS);
+    let mut hits = Vec::new();
+
+    let walker = WalkBuilder::new(root)
+        .require_git(false)
+        .filter_entry(move |entry| {
+            !ignore.is_ignored(entry.path(), entry.file_type().is_some_and(|file_type| file_type.is_dir()))
+        })
+        .build();
+    for entry in walker.flatten() {
+        if hits.len() >= limit {
+            break;
+        }
+        if !entry.file_type().is_some_and(|file_type| file_type.is_file()) {
+            continue;
+        }
+        if !matches_extension_allowlist(entry.path(), &allowlist) {
+            continue;
+        }
+
+        // Unreadable files are skipped rather than failing the whole search.
+        let _ = search_file(entry.path(), &pattern, limit, &mut hits);
+    }
+
+    Ok(hits)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Columns are 1-based UTF-16 offsets of the first match on the line, like the editor shows.
+fn hit_column(hit: &SearchHit) -> usize {
+    hit.matches.first().map_or(1, |range| range.start + 1)
+}
+
+/// The query and timestamp come first as key/value rows, then a blank row, then the hits.
+fn search_results_csv(query: &str, exported_at: &str, hits: &[SearchHit]) -> String {
+    let mut csv = format!("query,{}\r\nexportedAt,{exported_at}\r\n\r\npath,line,column,text\r\n", csv_field(query));
+    for hit in hits {
+        let _ = write!(
+            csv,
+            "{},{},{},{}\r\n",
+            csv_field(&hit.path),
+            hit.line_number,
+            hit_column(hit),
+            csv_field(&hit.line_text)
+        );
+    }
+    csv
+}
+
+/// Fences `text` with one more backtick than its longest run, so any line renders literally.
+fn inline_code(text: &str) -> String {
+    let longest_run = text.split(|character| character != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run + 1);
+    let padding = if text.starts_with('`') || text.ends_with('`') { " " } else { "" };
+    format!("{fence}{padding}{text}{padding}{fence}")
+}
+
+/// Files keep the order of their first hit.
+fn search_results_markdown(query: &str, exported_at: &str, hits: &[SearchHit]) -> String {
+    let mut groups: Vec<(&str, Vec<&SearchHit>)> = Vec::new();
+    let mut group_index: HashMap<&str, usize> = HashMap::new();
+    for hit in hits {
+        let index = *group_index.entry(&hit.path).or_insert_with(|| {
+            groups.push((&hit.path, Vec::new()));
+            groups.len() - 1
+        });
+        groups[index].1.push(hit);
+    }
+
+    let mut markdown = format!(
+        "# Search results for {}\n\nExported {exported_at}; {} hits in {} files.\n",
+        inline_code(query),
+        hits.len(),
+        groups.len()
+    );
+    for (path, hits) in groups {
+        let link_target = path.replace('\\', "/");
+        let _ = write!(markdown, "\n## {}\n\n", inline_code(path));
+        for hit in hits {
+            let _ = writeln!(
+                markdown,
+                "- [Line {}](<{link_target}#L{}>): {}",
+                hit.line_number,
+                hit.line_number,
+                inline_code(hit.line_text.trim())
+            );
+        }
+    }
+    markdown
+}
+
+fn render_search_export(
+    query: &str,
+    exported_at: &str,
+    hits: &[SearchHit],
+    format: ExportFormat,
+) -> Result<String, LatticeError> {
+    match format {
+        ExportFormat::Json => {
+            let export = SearchExport {
+                query,
+                exported_at,
+                hits,
+            };
+            serde_json::to_string_pretty(&export).map_err(|error| error.to_string().into())
+        }
+        ExportFormat::Csv => Ok(search_results_csv(query, exported_at, hits)),
+        ExportFormat::Markdown => Ok(search_results_markdown(query, exported_at, hits)),
+    }
+}
+
+#[tauri::command]
+pub async fn export_search_results(
+    app: AppHandle,
+    query: String,
+    hits: Vec<SearchHit>,
+    dest: String,
+    format: ExportFormat,
+) -> Result<(), LatticeError> {
+    log_command_async("export_search_results", async move {
+        let dest = resolve_command_path(&app, &dest)?;
+        tokio::task::spawn_blocking(move || {
+            let exported_at = OffsetDateTime::now_local()
+                .unwrap_or_else(|_| OffsetDateTime::now_utc())
+                .format(&Rfc3339)
+                .map_err(|error| error.to_string())?;
+            let contents = render_search_export(&query, &exported_at, &hits, format)?;
+            write_bytes_atomic(&dest, contents.as_bytes())
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn search_contents(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+    query: String,
+    opts: SearchOpts,
+) -> Result<Vec<SearchHit>, LatticeError> {
+    log_command_async("search_contents", async move {
+        let root = resolve_command_path(&app, &root)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            // Canonical so walked paths line up with the cached ignore rules.
+            let root = fs::canonicalize(&root).unwrap_or(root);
+            let ignore = matcher_for(&app, &root);
+            search_directory_contents(&root, &query, &opts, ignore)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn create_fixture_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("lattice-search-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::write(root.join(".gitignore"), "ignored.md\n").unwrap();
+        fs::write(root.join("ignored.md"), "Lattice lattice\n").unwrap();
+        fs::write(root.join("notes/a.md"), "intro\r\nLattice 格致 lattice\nlatticework\n").unwrap();
+        fs::write(root.join("notes/b.txt"), "lattice in text\n").unwrap();
+        fs::write(root.join("notes/c.bin"), b"lattice\0\x01\x02").unwrap();
+        root
+    }
+
+    fn no_rules(root: &Path) -> Arc<IgnoreMatcher> {
+        Arc::new(IgnoreMatcher::new(root, &[]))
+    }
+
+    #[test]
+    fn finds_line_hits_with_utf16_columns_and_skips_ignored_and_binary_files() {
+        let root = create_fixture_root();
+        let opts = SearchOpts {
+            whole_word: true,
+            extensions: vec![".MD".to_string()],
+            ..SearchOpts::default()
+        };
+
+        let hits = search_directory_contents(&root, "lattice", &opts, no_rules(&root)).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line_number, 2);
+        assert_eq!(hits[0].line_text, "Lattice 格致 lattice");
+        let columns: Vec<(usize, usize)> = hits[0].matches.iter().map(|range| (range.start, range.end)).collect();
+        assert_eq!(columns, vec![(0, 7), (11, 18)]);
+
+        let all_hits = search_directory_contents(&root, "lattice", &SearchOpts::default(), no_rules(&root)).unwrap();
+        assert!(all_hits.iter().all(|hit| !hit.path.ends_with("ignored.md") && !hit.path.ends_with("c.bin")));
+        assert_eq!(all_hits.len(), 3);
+
+        let user_rules = Arc::new(IgnoreMatcher::new(&root, &["*.txt".to_string()]));
+        let filtered = search_directory_contents(&root, "lattice", &SearchOpts::default(), user_rules).unwrap();
+        assert!(filtered.iter().all(|hit| !hit.path.ends_with("b.txt")));
+        assert_eq!(filtered.len(), 2);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn case_sensitive_search_respects_result_cap() {
+        let root = create_fixture_root();
+        let opts = SearchOpts {
+            case_sensitive: true,
+            max_results: Some(1),
+            ..SearchOpts::default()
+        };
+
+        let hits = search_directory_contents(&root, "Lattice", &opts, no_rules(&root)).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].matches.len(), 1);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn exports_escape_csv_fields_and_group_markdown_by_file() {
+        let hit = |path: &str, line_number, line_text: &str| SearchHit {
+            path: path.to_string(),
+            line_number,
+            line_text: line_text.to_string(),
+            matches: vec![SearchMatchRange { start: 4, end: 11 }],
+        };
+        let hits = vec![
+            hit("/notes/a, b.md", 2, "the \"lattice\" constant"),
+            hit("/notes/c.md", 7, "use `lattice` here"),
+            hit("/notes/a, b.md", 9, "lattice"),
+        ];
+        let at = "2026-10-14T09:30:00Z";
+
+        let csv = render_search_export("lattice", at, &hits, ExportFormat::Csv).unwrap();
+        assert!(csv.starts_with("query,lattice\r\nexportedAt,2026-10-14T09:30:00Z\r\n\r\npath,line,column,text\r\n"));
+        assert!(csv.contains("\"/notes/a, b.md\",2,5,\"the \"\"lattice\"\" constant\"\r\n"));
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+
+        let markdown = render_search_export("lattice", at, &hits, ExportFormat::Markdown).unwrap();
+        assert!(markdown.contains("3 hits in 2 files"));
+        assert_eq!(markdown.matches("\n## ").count(), 2);
+        assert!(markdown.contains("- [Line 9](</notes/a, b.md#L9>): `lattice`"));
+        assert!(markdown.contains("``use `lattice` here``"));
+        assert!(markdown.find("#L9").unwrap() < markdown.find("## `/notes/c.md`").unwrap());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_search_export("lattice", at, &hits, ExportFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["exportedAt"], at);
+        assert_eq!(json["hits"][1]["lineNumber"], 7);
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::error::LatticeError;
+use crate::fileops::timestamp_ms;
+use crate::logging::log_command;
+use crate::settings_recovery::save_settings_store;
+use crate::settings_store_path;
+
+pub(crate) const SESSIONS_KEY: &str = "sessions";
+
+/// Every field defaults and unknown ones are carried along, so sessions saved by newer
+/// or older versions still load.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionState {
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub open_files: Vec<String>,
+    #[serde(default)]
+    pub active_file: Option<String>,
+    /// Keyed by file path.
+    #[serde(default)]
+    pub scroll_positions: HashMap<String, f64>,
+    #[serde(default, flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredSession {
+    /// Milliseconds since the Unix epoch.
+    #[serde(default)]
+    saved_at: u64,
+    #[serde(default)]
+    state: SessionState,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub name: String,
+    pub saved_at: u64,
+    pub folder: Option<String>,
+    pub file_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredSession {
+    pub state: SessionState,
+    /// Paths dropped from the session because they no longer exist.
+    pub missing: Vec<String>,
+}
+
+fn session_name(name: &str) -> Result<&str, LatticeError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err(LatticeError::InvalidInput {
+            message: "Session name is required.".to_string(),
+        });
+    }
+    Ok(trimmed)
+}
+
+/// Kept as raw values so saving one session never rewrites the others in a lossy way.
+fn read_sessions(app: &AppHandle) -> Result<Map<String, Value>, LatticeError> {
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    Ok(match store.get(SESSIONS_KEY) {
+        Some(Value::Object(sessions)) => sessions,
+        _ => Map::new(),
+    })
+}
+
+fn write_sessions(app: &AppHandle, sessions: Map<String, Value>) -> Result<(), LatticeError> {
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    store.set(SESSIONS_KEY, Value::Object(sessions));
+    save_settings_store(app).map_err(Into::into)
+}
+
+/// A session that can't be parsed at all is treated as empty rather than failing.
+fn parse_session(value: &Value) -> StoredSession {
+    serde_json::from_value(value.clone()).unwrap_or_default()
+}
+
+/// Drops files and a folder that have since disappeared, returning what was dropped.
+fn without_missing_paths(mut state: SessionState, exists: impl Fn(&str) -> bool) -> RestoredSession {
+    let mut missing = Vec::new();
+    if let Some(folder) = state.folder.take() {
+        if exists(&folder) {
+            state.folder = Some(folder);
+        } else {
+            missing.push(folder);
+        }
+    }
+
+    let (open_files, gone): (Vec<String>, Vec<String>) = state.open_files.into_iter().partition(|path| exists(path));
+    missing.extend(gone);
+    state.open_files = open_files;
+    state.scroll_positions.retain(|path, _| state.open_files.contains(path));
+    if let Some(active) = state.active_file.take() {
+        if state.open_files.contains(&active) {
+            state.active_file = Some(active);
+        } else {
+            if !missing.contains(&active) {
+                missing.push(active);
+            }
+            state.active_file = state.open_files.first().cloned();
+        }
+    }
+    RestoredSession { state, missing }
+}
+
+/// Replaces any session with the same name.
+#[tauri::command]
+pub fn save_session(app: AppHandle, name: String, state: SessionState) -> Result<(), LatticeError> {
+    log_command("save_session", || {
+        let name = session_name(&name)?;
+        let stored = StoredSession {
+            saved_at: timestamp_ms(Ok(SystemTime::now())).unwrap_or_default(),
+            state,
+        };
+        let mut sessions = read_sessions(&app)?;
+        sessions.insert(
+            name.to_string(),
+            serde_json::to_value(stored).map_err(|error| error.to_string())?,
+        );
+        write_sessions(&app, sessions)
+    })
+}
+
+#[tauri::command]
+pub fn restore_session(app: AppHandle, name: String) -> Result<RestoredSession, LatticeError> {
+    log_command("restore_session", || {
+        let name = session_name(&name)?;
+        let sessions = read_sessions(&app)?;
+        let stored = sessions
+            .get(name)
+            .map(parse_session)
+            .ok_or_else(|| format!("No session named \"{name}\"."))?;
+        Ok(without_missing_paths(stored.state, |path| Path::new(path).exists()))
+    })
+}
+
+/// Most recently saved first.
+#[tauri::command]
+pub fn list_sessions(app: AppHandle) -> Result<Vec<SessionSummary>, LatticeError> {
+    log_command("list_sessions", || {
+        let mut summaries: Vec<SessionSummary> = read_sessions(&app)?
+            .iter()
+            .map(|(name, value)| {
+                let stored = parse_session(value);
+                SessionSummary {
+                    name: name.clone(),
+                    saved_at: stored.saved_at,
+                    folder: stored.state.folder,
+                    file_count: stored.state.open_files.len(),
+                }
+            })
+            .collect();
+        summaries.sort_by(|left, right| right.saved_at.cmp(&left.saved_at).then(left.name.cmp(&right.name)));
+        Ok(summaries)
+    })
+}
+
+#[tauri::command]
+pub fn delete_session(app: AppHandle, name: String) -> Result<(), LatticeError> {
+    log_command("delete_session", || {
+        let name = session_name(&name)?;
+        let mut sessions = read_sessions(&app)?;
+        if sessions.remove(name).is_none() {
+            return Err(LatticeError::NotFound {
+                message: format!("No session named \"{name}\"."),
+            });
+        }
+        write_sessions(&app, sessions)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sessions_from_other_versions_still_parse() {
+        let older = parse_session(&json!({ "savedAt": 5, "state": { "openFiles": ["/notes/a.md"] } }));
+        assert_eq!(older.state.open_files, vec!["/notes/a.md".to_string()]);
+        assert_eq!(older.state.active_file, None);
+
+        let newer = json!({ "state": { "folder": "/notes", "pinnedTabs": ["/notes/a.md"] } });
+        let round_trip = serde_json::to_value(parse_session(&newer)).unwrap();
+        assert_eq!(round_trip["state"]["pinnedTabs"], json!(["/notes/a.md"]));
+        assert_eq!(parse_session(&json!("garbage")).saved_at, 0);
+    }
+
+    #[test]
+    fn missing_files_are_dropped_and_reported() {
+        let state = SessionState {
+            folder: Some("/notes".to_string()),
+            open_files: vec!["/notes/a.md".to_string(), "/notes/gone.md".to_string()],
+            active_file: Some("/notes/gone.md".to_string()),
+            scroll_positions: HashMap::from([
+                ("/notes/a.md".to_string(), 120.0),
+                ("/notes/gone.md".to_string(), 40.0),
+            ]),
+            extra: HashMap::new(),
+        };
+
+        let restored = without_missing_paths(state, |path| path != "/notes/gone.md");
+        assert_eq!(restored.missing, vec!["/notes/gone.md".to_string()]);
+        assert_eq!(restored.state.open_files, vec!["/notes/a.md".to_string()]);
+        assert_eq!(restored.state.active_file.as_deref(), Some("/notes/a.md"));
+        assert_eq!(restored.state.scroll_positions.len(), 1);
+        assert_eq!(restored.state.folder.as_deref(), Some("/notes"));
+    }
+}
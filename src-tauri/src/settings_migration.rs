@@ -0,0 +1,243 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::fileops::write_bytes_atomic;
+use crate::{
+    settings_file_path, settings_store_path, WindowStateSnapshot, DEFAULT_FOLDER_KEY, FRONTEND_SETTINGS_KEY,
+    LAST_OPENED_FOLDER_KEY, LAST_WORKSPACE_PATH_KEY, RECENT_FOLDERS_KEY, RECENT_WORKSPACE_PATHS_KEY,
+    WINDOW_STATE_KEY,
+};
+
+pub(crate) const SETTINGS_SCHEMA_VERSION: u32 = 1;
+const SETTINGS_VERSION_FIELD: &str = "version";
+
+/// Legacy top-level store keys paired with the `AppSettings` field they feed.
+const LEGACY_SETTINGS_FIELDS: [(&str, &str); 6] = [
+    (DEFAULT_FOLDER_KEY, "defaultFolder"),
+    (LAST_OPENED_FOLDER_KEY, "lastOpenedFolder"),
+    (LAST_WORKSPACE_PATH_KEY, "lastWorkspacePath"),
+    (RECENT_WORKSPACE_PATHS_KEY, "recentWorkspacePaths"),
+    (WINDOW_STATE_KEY, "windowState"),
+    (RECENT_FOLDERS_KEY, "recentFolders"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SettingsMigrationError {
+    #[serde(rename_all = "camelCase")]
+    FutureVersion {
+        found: u64,
+        supported: u32,
+        message: String,
+    },
+    Failed { message: String },
+}
+
+impl std::fmt::Display for SettingsMigrationError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FutureVersion { message, .. } | Self::Failed { message } => formatter.write_str(message),
+        }
+    }
+}
+
+fn failed(error: impl ToString) -> SettingsMigrationError {
+    SettingsMigrationError::Failed {
+        message: error.to_string(),
+    }
+}
+
+fn settings_version(settings: &Map<String, Value>) -> u64 {
+    settings
+        .get(SETTINGS_VERSION_FIELD)
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+}
+
+fn take_settings_object(document: &mut Map<String, Value>) -> Map<String, Value> {
+    match document.remove(FRONTEND_SETTINGS_KEY) {
+        Some(Value::Object(settings)) => settings,
+        // Early builds stored the frontend settings as a serialized JSON string.
+        Some(Value::String(encoded)) => match serde_json::from_str::<Value>(&encoded) {
+            Ok(Value::Object(settings)) => settings,
+            _ => Map::new(),
+        },
+        _ => Map::new(),
+    }
+}
+
+fn coerce_string_list(value: Value) -> Option<Value> {
+    match value {
+        Value::String(path) => Some(Value::Array(vec![Value::String(path)])),
+        Value::Array(items) => Some(Value::Array(
+            items.into_iter().filter(Value::is_string).collect(),
+        )),
+        _ => None,
+    }
+}
+
+/// v0 had no version field, kept the canonical copy of each path in legacy
+/// top-level keys, and tolerated values of the wrong type.
+fn migrate_v0_to_v1(document: &Map<String, Value>, settings: &mut Map<String, Value>) {
+    for (legacy_key, field) in LEGACY_SETTINGS_FIELDS {
+        let missing = settings.get(field).is_none_or(Value::is_null);
+        if let (true, Some(value)) = (missing, document.get(legacy_key)) {
+            settings.insert(field.to_string(), value.clone());
+        }
+    }
+
+    for field in ["defaultFolder", "lastOpenedFolder", "lastWorkspacePath"] {
+        if settings.get(field).is_some_and(|value| !value.is_string()) {
+            settings.remove(field);
+        }
+    }
+
+    for field in ["recentWorkspacePaths", "recentFolders"] {
+        if let Some(value) = settings.remove(field) {
+            if let Some(list) = coerce_string_list(value) {
+                settings.insert(field.to_string(), list);
+            }
+        }
+    }
+
+    let window_state_is_valid = settings
+        .get("windowState")
+        .is_none_or(|value| serde_json::from_value::<WindowStateSnapshot>(value.clone()).is_ok());
+    if !window_state_is_valid {
+        settings.remove("windowState");
+    }
+
+    if settings.get("maxRecentFolders").is_some_and(|value| !value.is_u64()) {
+        settings.remove("maxRecentFolders");
+    }
+}
+
+/// Upgrades a raw `settings.json` document in place. Returns whether anything changed.
+pub(crate) fn migrate_settings_document(document: &mut Map<String, Value>) -> Result<bool, SettingsMigrationError> {
+    let mut settings = take_settings_object(document);
+    let found = settings_version(&settings);
+    if found > u64::from(SETTINGS_SCHEMA_VERSION) {
+        document.insert(FRONTEND_SETTINGS_KEY.to_string(), Value::Object(settings));
+        return Err(SettingsMigrationError::FutureVersion {
+            found,
+            supported: SETTINGS_SCHEMA_VERSION,
+            message: format!(
+                "Settings were written by a newer Lattice (schema v{found}); this build supports up to v{SETTINGS_SCHEMA_VERSION}."
+            ),
+        });
+    }
+
+    let mut version = found;
+    while version < u64::from(SETTINGS_SCHEMA_VERSION) {
+        match version {
+            0 => migrate_v0_to_v1(document, &mut settings),
+            _ => unreachable!("no migration registered for settings schema v{version}"),
+        }
+        version += 1;
+    }
+
+    let changed = found != version;
+    if changed {
+        settings.insert(SETTINGS_VERSION_FIELD.to_string(), Value::from(version));
+    }
+    document.insert(FRONTEND_SETTINGS_KEY.to_string(), Value::Object(settings));
+    Ok(changed)
+}
+
+pub(crate) fn settings_backup_path(settings_path: &Path) -> PathBuf {
+    let mut file_name = settings_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".bak");
+    settings_path.with_file_name(file_name)
+}
+
+fn migrate_settings_file(settings_path: &Path) -> Result<bool, SettingsMigrationError> {
+    let raw = match fs::read(settings_path) {
+        Ok(raw) => raw,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(error) => return Err(failed(error)),
+    };
+    let mut document = match serde_json::from_slice::<Value>(&raw).map_err(failed)? {
+        Value::Object(document) => document,
+        _ => return Err(failed("Settings file is not a JSON object.")),
+    };
+
+    if !migrate_settings_document(&mut document)? {
+        return Ok(false);
+    }
+
+    fs::copy(settings_path, settings_backup_path(settings_path)).map_err(failed)?;
+    let encoded = serde_json::to_vec_pretty(&Value::Object(document)).map_err(failed)?;
+    write_bytes_atomic(settings_path, &encoded).map_err(failed)?;
+    Ok(true)
+}
+
+/// Runs once during `setup`, before anything reads the settings store.
+pub(crate) fn migrate_settings(app: &AppHandle) -> Result<(), SettingsMigrationError> {
+    let settings_path = settings_file_path(app).map_err(failed)?;
+
+    if migrate_settings_file(&settings_path)? {
+        // Pick up the migrated file in case a plugin already opened the store.
+        let store = app.store(settings_store_path(app)).map_err(failed)?;
+        store.reload().map_err(failed)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::{AppSettings, SETTINGS_STORE};
+
+    #[test]
+    fn upgrades_v0_documents_and_keeps_last_opened_folder() {
+        let mut document = json!({
+            "last_opened_folder": "C:/notes",
+            "recent_workspace_paths": "C:/notes",
+            "lattice-settings": {
+                "theme": "dark",
+                "fontSize": 15,
+                "windowState": { "width": "wide" },
+                "maxRecentFolders": -3
+            }
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+
+        assert!(migrate_settings_document(&mut document).unwrap());
+
+        let settings = serde_json::from_value::<AppSettings>(document[FRONTEND_SETTINGS_KEY].clone())
+            .expect("migrated settings should deserialize");
+        assert_eq!(settings.version, SETTINGS_SCHEMA_VERSION);
+        assert_eq!(settings.last_opened_folder.as_deref(), Some("C:/notes"));
+        assert_eq!(settings.recent_workspace_paths, vec!["C:/notes".to_string()]);
+        assert!(settings.window_state.is_none());
+        assert_eq!(settings.theme.as_deref(), Some("dark"));
+        assert_eq!(settings.extra.get("fontSize"), Some(&json!(15)));
+
+        assert!(!migrate_settings_document(&mut document).unwrap());
+    }
+
+    #[test]
+    fn refuses_future_versions_and_leaves_the_file_untouched() {
+        let root = std::env::temp_dir().join(format!("lattice-settings-migration-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let settings_path = root.join(SETTINGS_STORE);
+        let original = r#"{"lattice-settings":{"version":99,"lastOpenedFolder":"C:/notes"}}"#;
+        fs::write(&settings_path, original).unwrap();
+
+        let error = migrate_settings_file(&settings_path).unwrap_err();
+        assert!(matches!(error, SettingsMigrationError::FutureVersion { found: 99, .. }));
+        assert_eq!(fs::read_to_string(&settings_path).unwrap(), original);
+        assert!(!settings_backup_path(&settings_path).exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,188 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex as StdMutex;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::plugin::{Builder as PluginBuilder, TauriPlugin};
+use tauri::{AppHandle, Emitter, Manager, RunEvent, State, Wry};
+use tauri_plugin_store::StoreExt;
+
+use crate::error::LatticeError;
+use crate::fileops::{timestamp_ms, write_bytes_atomic};
+use crate::logging::log_command;
+use crate::settings_migration::settings_backup_path;
+use crate::{settings_file_path, settings_store_path};
+
+const SETTINGS_RECOVERED_EVENT: &str = "settings-recovered";
+
+/// Serializes saves, so a slower save can never replace a newer snapshot on disk.
+static SETTINGS_SAVE_LOCK: StdMutex<()> = StdMutex::new(());
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum SettingsHealth {
+    #[default]
+    Intact,
+    /// The file didn't parse and was restored from `settings.json.bak`.
+    #[serde(rename_all = "camelCase")]
+    Recovered { corrupt_path: String },
+    /// Neither the file nor its backup parsed, so Lattice started with defaults.
+    #[serde(rename_all = "camelCase")]
+    Reset { corrupt_path: String },
+}
+
+/// How the settings file looked at startup.
+#[derive(Default)]
+pub struct SettingsHealthState {
+    health: StdMutex<SettingsHealth>,
+}
+
+fn parse_settings(bytes: &[u8]) -> Option<Map<String, Value>> {
+    match serde_json::from_slice(bytes).ok()? {
+        Value::Object(settings) => Some(settings),
+        _ => None,
+    }
+}
+
+fn corrupt_path(settings_path: &Path, now_ms: u64) -> PathBuf {
+    let mut file_name = settings_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".corrupt-{now_ms}"));
+    settings_path.with_file_name(file_name)
+}
+
+/// Moves an unparseable settings file aside and puts the backup in its place when that
+/// one still parses. A missing file is intact; it just hasn't been written yet.
+fn recover_settings_file(settings_path: &Path, now_ms: u64) -> Result<SettingsHealth, LatticeError> {
+    match fs::read(settings_path) {
+        Ok(raw) if parse_settings(&raw).is_some() => return Ok(SettingsHealth::Intact),
+        Ok(_) => {}
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(SettingsHealth::Intact),
+        Err(error) => return Err(LatticeError::at_path(settings_path, error)),
+    }
+
+    let corrupt = corrupt_path(settings_path, now_ms);
+    fs::rename(settings_path, &corrupt).map_err(|error| LatticeError::at_path(settings_path, error))?;
+    let corrupt_path = corrupt.to_string_lossy().to_string();
+
+    let backup = fs::read(settings_backup_path(settings_path)).ok();
+    match backup.filter(|backup| parse_settings(backup).is_some()) {
+        Some(backup) => {
+            write_bytes_atomic(settings_path, &backup)?;
+            Ok(SettingsHealth::Recovered { corrupt_path })
+        }
+        None => Ok(SettingsHealth::Reset { corrupt_path }),
+    }
+}
+
+/// Runs during `setup`, before migration or anything else reads the settings store.
+/// `settings-recovered` goes out for windows already listening; the rest can ask
+/// `settings_health`.
+pub(crate) fn recover_settings(app: &AppHandle) -> Result<(), LatticeError> {
+    let settings_path = settings_file_path(app)?;
+    let now_ms = timestamp_ms(Ok(SystemTime::now())).unwrap_or_default();
+    let health = recover_settings_file(&settings_path, now_ms)?;
+    match &health {
+        SettingsHealth::Intact => return Ok(()),
+        SettingsHealth::Recovered { corrupt_path } => {
+            log::warn!("Settings file was unreadable; restored the backup and kept the original at {corrupt_path}");
+            // Pick up the restored file in case a plugin already opened the store.
+            let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+            store.reload().map_err(|error| error.to_string())?;
+        }
+        SettingsHealth::Reset { corrupt_path } => {
+            log::error!("Settings file and its backup were unreadable; starting with defaults, kept {corrupt_path}");
+        }
+    }
+
+    if let Ok(mut current) = app.state::<SettingsHealthState>().health.lock() {
+        *current = health.clone();
+    }
+    let _ = app.emit(SETTINGS_RECOVERED_EVENT, health);
+    Ok(())
+}
+
+/// Saves the settings store atomically, first copying the file it replaces to
+/// `settings.json.bak` as long as that file still parses, so the backup is always the
+/// last good save. Use this instead of `Store::save`, which writes in place.
+pub(crate) fn save_settings_store(app: &AppHandle) -> Result<(), String> {
+    let _guard = SETTINGS_SAVE_LOCK.lock().map_err(|error| error.to_string())?;
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    let settings_path = settings_file_path(app)?;
+    if let Some(parent) = settings_path.parent() {
+        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+
+    if let Ok(current) = fs::read(&settings_path) {
+        if parse_settings(&current).is_some() {
+            write_bytes_atomic(&settings_backup_path(&settings_path), &current)
+                .map_err(|error| error.message().to_string())?;
+        }
+    }
+    let entries: Map<String, Value> = store.entries().into_iter().collect();
+    let encoded = serde_json::to_vec_pretty(&Value::Object(entries)).map_err(|error| error.to_string())?;
+    write_bytes_atomic(&settings_path, &encoded).map_err(|error| error.message().to_string())
+}
+
+/// Must be registered before the store plugin, whose own exit handler saves every open
+/// store in place. This one saves the settings atomically first and then closes the store,
+/// so the plugin finds nothing left to write.
+pub(crate) fn exit_save_plugin() -> TauriPlugin<Wry> {
+    PluginBuilder::new("settings-exit-save")
+        .on_event(|app, event| {
+            if !matches!(event, RunEvent::Exit) {
+                return;
+            }
+            let Some(store) = app.get_store(settings_store_path(app)) else {
+                return;
+            };
+            if let Err(error) = save_settings_store(app) {
+                log::error!("Failed to save settings on exit: {error}");
+            }
+            store.close_resource();
+        })
+        .build()
+}
+
+#[tauri::command]
+pub fn settings_health(state: State<'_, SettingsHealthState>) -> Result<SettingsHealth, LatticeError> {
+    log_command("settings_health", || Ok(state.health.lock().map_err(|error| error.to_string())?.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncated_settings_fall_back_to_the_backup_and_then_to_defaults() {
+        let root = std::env::temp_dir().join(format!("lattice-settings-recovery-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let settings_path = root.join("settings.json");
+        assert_eq!(recover_settings_file(&settings_path, 1).unwrap(), SettingsHealth::Intact);
+
+        fs::write(&settings_path, r#"{"theme":"dark"}"#).unwrap();
+        assert_eq!(recover_settings_file(&settings_path, 1).unwrap(), SettingsHealth::Intact);
+
+        fs::write(settings_backup_path(&settings_path), r#"{"theme":"light"}"#).unwrap();
+        fs::write(&settings_path, r#"{"theme":"da"#).unwrap();
+        let health = recover_settings_file(&settings_path, 2).unwrap();
+        let corrupt = root.join("settings.json.corrupt-2");
+        assert_eq!(
+            health,
+            SettingsHealth::Recovered {
+                corrupt_path: corrupt.to_string_lossy().to_string()
+            }
+        );
+        assert_eq!(fs::read_to_string(&settings_path).unwrap(), r#"{"theme":"light"}"#);
+        assert_eq!(fs::read_to_string(corrupt).unwrap(), r#"{"theme":"da"#);
+
+        fs::write(settings_backup_path(&settings_path), "").unwrap();
+        fs::write(&settings_path, "[]").unwrap();
+        assert!(matches!(recover_settings_file(&settings_path, 3).unwrap(), SettingsHealth::Reset { .. }));
+        assert!(!settings_path.exists());
+        assert!(root.join("settings.json.corrupt-3").is_file());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
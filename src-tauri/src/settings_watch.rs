@@ -0,0 +1,172 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex as StdMutex;
+
+use notify::RecursiveMode;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::{settings_file_path, settings_store_path};
+use crate::watcher::{
+    create_event_watcher, register_watch, spawn_debounced_event_loop, WatchHandle, WatchKind, WatchOwner,
+};
+
+const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsChangedPayload {
+    keys: Vec<String>,
+}
+
+/// What the file held the last time it and the in-memory store agreed.
+struct Synced {
+    hash: blake3::Hash,
+    contents: Map<String, Value>,
+}
+
+#[derive(Default)]
+pub struct SettingsWatchState {
+    watch: StdMutex<Option<WatchHandle>>,
+    synced: StdMutex<Option<Synced>>,
+}
+
+fn read_settings_file(path: &Path) -> Result<(blake3::Hash, Map<String, Value>), LatticeError> {
+    let bytes = fs::read(path)?;
+    let contents = match serde_json::from_slice(&bytes).map_err(|error| error.to_string())? {
+        Value::Object(contents) => contents,
+        _ => return Err("Settings file is not a JSON object.".into()),
+    };
+    Ok((blake3::hash(&bytes), contents))
+}
+
+/// Keys changed on disk since the last sync that the running app doesn't already hold.
+/// Our own saves only ever write what memory has, so they never show up here, even
+/// when memory has moved on again before the watcher catches up.
+fn external_changes(synced: &Map<String, Value>, file: &Map<String, Value>, memory: &Map<String, Value>) -> Vec<String> {
+    let mut keys: Vec<String> = synced
+        .keys()
+        .chain(file.keys())
+        .filter(|key| synced.get(*key) != file.get(*key) && memory.get(*key) != file.get(*key))
+        .cloned()
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Applies whatever changed externally and returns the keys, emitting `settings-changed`
+/// when there are any. With `force`, every key that differs from memory is taken from disk.
+fn sync_from_disk(app: &AppHandle, force: bool) -> Result<Vec<String>, LatticeError> {
+    let (hash, file) = read_settings_file(&settings_file_path(app)?)?;
+    let state = app.state::<SettingsWatchState>();
+    let mut synced = state.synced.lock().map_err(|error| error.to_string())?;
+    if !force && synced.as_ref().is_some_and(|synced| synced.hash == hash) {
+        return Ok(Vec::new());
+    }
+
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    let memory: Map<String, Value> = store.entries().into_iter().collect();
+    let keys = match synced.as_ref().filter(|_| !force) {
+        Some(previous) => external_changes(&previous.contents, &file, &memory),
+        None => external_changes(&memory, &file, &memory),
+    };
+    for key in &keys {
+        match file.get(key) {
+            Some(value) => store.set(key.clone(), value.clone()),
+            None => {
+                store.delete(key);
+            }
+        }
+    }
+    *synced = Some(Synced {
+        hash,
+        contents: file,
+    });
+    drop(synced);
+
+    if !keys.is_empty() {
+        log::info!("Settings changed outside Lattice: {}", keys.join(", "));
+        let _ = app.emit(SETTINGS_CHANGED_EVENT, SettingsChangedPayload { keys: keys.clone() });
+    }
+    Ok(keys)
+}
+
+/// Started during `setup`. Watches the folder rather than the file, since editors and
+/// sync clients often replace the file instead of writing to it.
+pub(crate) fn start_settings_watch(app: &AppHandle) -> Result<(), LatticeError> {
+    let path = settings_file_path(app)?;
+    let parent = path.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."));
+    let file_name = path.file_name().unwrap_or_default().to_os_string();
+
+    let state = app.state::<SettingsWatchState>();
+    if let Ok((hash, contents)) = read_settings_file(&path) {
+        *state.synced.lock().map_err(|error| error.to_string())? = Some(Synced {
+            hash,
+            contents,
+        });
+    }
+
+    fs::create_dir_all(&parent)?;
+    let (watcher, receiver) = create_event_watcher(&parent, RecursiveMode::NonRecursive)?;
+    let app_for_events = app.clone();
+    spawn_debounced_event_loop(receiver, move |batch| {
+        let touches_settings = batch
+            .iter()
+            .flat_map(|change| &change.paths)
+            .any(|changed| Path::new(changed).file_name() == Some(file_name.as_os_str()));
+        if touches_settings {
+            // A half-written file fails to parse; the write that completes it is another event.
+            if let Err(error) = sync_from_disk(&app_for_events, false) {
+                log::warn!("Ignoring unreadable settings file: {error}");
+            }
+        }
+        true
+    });
+    // Released from outside, external edits just stop being picked up until the next launch.
+    let app_for_release = app.clone();
+    let watch = register_watch(app, WatchOwner::Settings, WatchKind::File, path, None, watcher, move || {
+        let state = app_for_release.state::<SettingsWatchState>();
+        let released = state.watch.lock().ok().and_then(|mut watch| watch.take());
+        drop(released);
+    });
+    *state.watch.lock().map_err(|error| error.to_string())? = Some(watch);
+    Ok(())
+}
+
+/// Re-reads the settings file and returns the keys that changed.
+#[tauri::command]
+pub fn reload_settings(app: AppHandle) -> Result<Vec<String>, LatticeError> {
+    log_command("reload_settings", || sync_from_disk(&app, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn map(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn only_external_edits_count_as_changes() {
+        let synced = map(json!({ "theme": "dark", "log_level": "info", "old": 1 }));
+
+        // Our own save, with another change already made in memory since.
+        let own = map(json!({ "theme": "light", "log_level": "info", "old": 1 }));
+        let memory = map(json!({ "theme": "light", "log_level": "debug", "old": 1 }));
+        assert!(external_changes(&synced, &own, &memory).is_empty());
+
+        let edited = map(json!({ "theme": "dark", "log_level": "warn", "new": true }));
+        let memory = map(json!({ "theme": "dark", "log_level": "info", "old": 1 }));
+        assert_eq!(external_changes(&synced, &edited, &memory), vec!["log_level", "new", "old"]);
+    }
+}
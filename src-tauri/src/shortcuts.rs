@@ -0,0 +1,126 @@
+use std::str::FromStr;
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::{build_app_settings_from_store, save_app_settings};
+
+const MAIN_WINDOW_LABEL: &str = "main";
+
+fn parse_accelerator(accelerator: &str) -> Result<Shortcut, String> {
+    let trimmed = accelerator.trim();
+    if trimmed.is_empty() {
+        return Err("Shortcut cannot be empty.".to_string());
+    }
+    Shortcut::from_str(trimmed).map_err(|error| format!("Invalid shortcut \"{trimmed}\": {error}"))
+}
+
+fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
+        return;
+    };
+
+    let visible = window.is_visible().unwrap_or(false);
+    let focused = window.is_focused().unwrap_or(false);
+    let minimized = window.is_minimized().unwrap_or(false);
+    if visible && focused && !minimized {
+        let _ = window.hide();
+    } else {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Plugin handler; only the toggle shortcut is ever registered, so any press toggles.
+pub(crate) fn handle_global_shortcut(app: &AppHandle, _shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state == ShortcutState::Pressed {
+        toggle_main_window(app);
+    }
+}
+
+/// Re-registers the saved toggle shortcut during `setup`.
+pub(crate) fn restore_global_shortcut(app: &AppHandle) -> Result<(), String> {
+    let Some(accelerator) = build_app_settings_from_store(app)?.global_toggle_shortcut else {
+        return Ok(());
+    };
+    let shortcut = parse_accelerator(&accelerator)?;
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn register_global_shortcut(app: AppHandle, accelerator: String) -> Result<(), LatticeError> {
+    log_command("register_global_shortcut", || {
+        let shortcut = parse_accelerator(&accelerator).map_err(|message| LatticeError::InvalidInput { message })?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        let previous = settings
+            .global_toggle_shortcut
+            .as_deref()
+            .and_then(|saved| parse_accelerator(saved).ok());
+
+        if previous == Some(shortcut) {
+            return Ok(());
+        }
+        let global_shortcut = app.global_shortcut();
+        if global_shortcut.is_registered(shortcut) {
+            return Err(LatticeError::AlreadyExists {
+                message: format!("Shortcut is already registered: {}", accelerator.trim()),
+            });
+        }
+
+        // Registering can still fail when another application owns the combination.
+        global_shortcut
+            .register(shortcut)
+            .map_err(|error| format!("Could not register shortcut {}: {error}", accelerator.trim()))?;
+        if let Some(previous) = previous {
+            let _ = global_shortcut.unregister(previous);
+        }
+
+        settings.global_toggle_shortcut = Some(accelerator.trim().to_string());
+        save_app_settings(&app, settings)?;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn unregister_global_shortcut(app: AppHandle) -> Result<(), LatticeError> {
+    log_command("unregister_global_shortcut", || {
+        let mut settings = build_app_settings_from_store(&app)?;
+        if let Some(shortcut) = settings
+            .global_toggle_shortcut
+            .as_deref()
+            .and_then(|saved| parse_accelerator(saved).ok())
+        {
+            let global_shortcut = app.global_shortcut();
+            if global_shortcut.is_registered(shortcut) {
+                global_shortcut
+                    .unregister(shortcut)
+                    .map_err(|error| error.to_string())?;
+            }
+        }
+
+        settings.global_toggle_shortcut = None;
+        save_app_settings(&app, settings)?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accelerators_are_validated_before_registration() {
+        assert!(parse_accelerator("CmdOrCtrl+Shift+L").is_ok());
+        assert!(parse_accelerator("  ").is_err());
+        assert!(parse_accelerator("Shift+NotAKey").is_err());
+        assert_eq!(
+            parse_accelerator("ctrl+alt+k").unwrap(),
+            parse_accelerator("Control+Alt+K").unwrap()
+        );
+    }
+}
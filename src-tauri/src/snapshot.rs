@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex as StdMutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::duplicates::hash_file;
+use crate::error::LatticeError;
+use crate::fileops::timestamp_ms;
+use crate::ignore_rules::{matcher_for, IgnoreMatcher};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::transfer::TransferFailure;
+use crate::DesktopFsState;
+
+const SNAPSHOT_WORKERS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotEntry {
+    pub size: u64,
+    pub modified: Option<u64>,
+    /// BLAKE3 of the contents, as hex.
+    pub hash: String,
+}
+
+/// Keys are paths relative to `root` with `/` separators, so snapshots compare across platforms.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSnapshot {
+    pub root: String,
+    pub entries: BTreeMap<String, SnapshotEntry>,
+    /// Combines every path with its content hash; equal root hashes mean equal trees.
+    pub root_hash: String,
+    /// Files that couldn't be read; they're left out of `entries` and the root hash.
+    #[serde(default)]
+    pub failed: Vec<TransferFailure>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Present in both with different contents; a touched but unchanged file isn't listed.
+    pub modified: Vec<String>,
+}
+
+fn portable_path(relative: &Path) -> String {
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// Ignored entries and symlinks are left out; a link's target belongs to whatever it points into.
+fn collect_files(root: &Path, ignore: &IgnoreMatcher, failed: &mut Vec<TransferFailure>) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let entries = match fs::read_dir(root.join(&relative)) {
+            Ok(entries) => entries,
+            Err(error) => {
+                failed.push(TransferFailure {
+                    path: root.join(&relative).to_string_lossy().to_string(),
+                    error: error.to_string(),
+                });
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_symlink() || ignore.is_ignored(&entry.path(), file_type.is_dir()) {
+                continue;
+            }
+            let entry_relative = relative.join(entry.file_name());
+            if file_type.is_dir() {
+                pending.push(entry_relative);
+            } else {
+                files.push(entry_relative);
+            }
+        }
+    }
+    files
+}
+
+/// Reuses the previous hash when size and mtime both still match.
+fn snapshot_entry(path: &Path, previous: Option<&SnapshotEntry>) -> std::io::Result<SnapshotEntry> {
+    let metadata = fs::metadata(path)?;
+    let size = metadata.len();
+    let modified = timestamp_ms(metadata.modified());
+    let unchanged = previous.filter(|previous| previous.size == size && previous.modified == modified);
+    if let (Some(previous), Some(_)) = (unchanged, modified) {
+        return Ok(previous.clone());
+    }
+    Ok(SnapshotEntry {
+        size,
+        modified,
+        hash: hash_file(path)?,
+    })
+}
+
+fn root_hash(entries: &BTreeMap<String, SnapshotEntry>) -> String {
+    let mut hasher = blake3::Hasher::new();
+    for (path, entry) in entries {
+        hasher.update(path.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(entry.hash.as_bytes());
+        hasher.update(&[0]);
+    }
+    hasher.finalize().to_hex().to_string()
+}
+
+/// Hashes on a small pool of worker threads; `previous` only helps when it is of the same root.
+fn snapshot_folder_sync(root: &Path, ignore: &IgnoreMatcher, previous: Option<&FolderSnapshot>) -> FolderSnapshot {
+    let root_string = root.to_string_lossy().to_string();
+    let previous = previous.filter(|previous| previous.root == root_string);
+    let mut failed = Vec::new();
+    let files = collect_files(root, ignore, &mut failed);
+
+    type Slot = StdMutex<Option<std::io::Result<SnapshotEntry>>>;
+    let results: Vec<Slot> = files.iter().map(|_| StdMutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for _ in 0..SNAPSHOT_WORKERS.min(files.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(relative) = files.get(index) else {
+                    break;
+                };
+                let earlier = previous.and_then(|previous| previous.entries.get(&portable_path(relative)));
+                let result = snapshot_entry(&root.join(relative), earlier);
+                if let Ok(mut slot) = results[index].lock() {
+                    *slot = Some(result);
+                }
+            });
+        }
+    });
+
+    let mut entries = BTreeMap::new();
+    for (slot, relative) in results.into_iter().zip(&files) {
+        match slot.into_inner().ok().flatten() {
+            Some(Ok(entry)) => {
+                entries.insert(portable_path(relative), entry);
+            }
+            // Deleted since it was listed, or unreadable.
+            Some(Err(error)) => failed.push(TransferFailure {
+                path: root.join(relative).to_string_lossy().to_string(),
+                error: error.to_string(),
+            }),
+            None => failed.push(TransferFailure {
+                path: root.join(relative).to_string_lossy().to_string(),
+                error: "Snapshot worker failed.".to_string(),
+            }),
+        }
+    }
+    FolderSnapshot {
+        root: root_string,
+        root_hash: root_hash(&entries),
+        entries,
+        failed,
+    }
+}
+
+fn diff_snapshot_entries(old: &FolderSnapshot, new: &FolderSnapshot) -> SnapshotDiff {
+    let mut diff = SnapshotDiff::default();
+    for (path, entry) in &new.entries {
+        match old.entries.get(path) {
+            None => diff.added.push(path.clone()),
+            Some(previous) if previous.hash != entry.hash => diff.modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .entries
+        .keys()
+        .filter(|path| !new.entries.contains_key(*path))
+        .cloned()
+        .collect();
+    diff
+}
+
+async fn run_snapshot(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+    previous: Option<FolderSnapshot>,
+) -> Result<FolderSnapshot, LatticeError> {
+    let root = resolve_command_path(&app, &root)?;
+    let root = fs::canonicalize(&root).map_err(|error| LatticeError::at_path(&root, error))?;
+    if !root.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Path is not a directory: {}", root.display()),
+        });
+    }
+    let permit = fs_state
+        .read_dir_permits
+        .clone()
+        .acquire_owned()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        snapshot_folder_sync(&root, &matcher_for(&app, &root), previous.as_ref())
+    })
+    .await
+    .map_err(|error| error.to_string().into())
+}
+
+#[tauri::command]
+pub async fn snapshot_folder(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+) -> Result<FolderSnapshot, LatticeError> {
+    log_command_async("snapshot_folder", async move {
+        run_snapshot(app, fs_state, root, None).await
+    })
+    .await
+}
+
+/// Files whose size and mtime match `previous` keep its hash instead of being read again.
+#[tauri::command]
+pub async fn snapshot_folder_incremental(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+    previous: FolderSnapshot,
+) -> Result<FolderSnapshot, LatticeError> {
+    log_command_async("snapshot_folder_incremental", async move {
+        run_snapshot(app, fs_state, root, Some(previous)).await
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn diff_snapshots(old: FolderSnapshot, new: FolderSnapshot) -> SnapshotDiff {
+    diff_snapshot_entries(&old, &new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_diff_by_content_and_reuse_unchanged_hashes() {
+        let root = fs::canonicalize(std::env::temp_dir())
+            .unwrap()
+            .join(format!("lattice-snapshot-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::write(root.join("notes/a.md"), "alpha").unwrap();
+        fs::write(root.join("notes/b.md"), "beta").unwrap();
+        fs::write(root.join("draft.tmp"), "scratch").unwrap();
+        let ignore = IgnoreMatcher::new(&root, &["*.tmp".to_string()]);
+
+        let first = snapshot_folder_sync(&root, &ignore, None);
+        assert_eq!(first.entries.keys().collect::<Vec<_>>(), ["notes/a.md", "notes/b.md"]);
+        assert_eq!(first.entries["notes/a.md"].hash, hash_file(&root.join("notes/a.md")).unwrap());
+        assert_eq!(snapshot_folder_sync(&root, &ignore, None).root_hash, first.root_hash);
+
+        // An unchanged size and mtime means the stored hash is trusted without reading.
+        let mut previous = first.clone();
+        previous.entries.get_mut("notes/b.md").unwrap().hash = "stale".to_string();
+        let reused = snapshot_folder_sync(&root, &ignore, Some(&previous));
+        assert_eq!(reused.entries["notes/b.md"].hash, "stale");
+
+        fs::write(root.join("notes/a.md"), "ALPHA, longer").unwrap();
+        fs::remove_file(root.join("notes/b.md")).unwrap();
+        fs::write(root.join("notes/c.md"), "gamma").unwrap();
+        let second = snapshot_folder_sync(&root, &ignore, Some(&first));
+        assert_ne!(second.root_hash, first.root_hash);
+        assert_eq!(
+            diff_snapshot_entries(&first, &second),
+            SnapshotDiff {
+                added: vec!["notes/c.md".to_string()],
+                removed: vec!["notes/b.md".to_string()],
+                modified: vec!["notes/a.md".to_string()],
+            }
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
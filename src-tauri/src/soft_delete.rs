@@ -0,0 +1,370 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_store::StoreExt;
+
+use crate::error::LatticeError;
+use crate::fileops::{canonical_location, timestamp_ms, write_bytes_atomic};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_paths;
+use crate::settings_recovery::save_settings_store;
+use crate::workspace_settings::WORKSPACE_SETTINGS_DIR;
+use crate::{build_app_settings_from_store, settings_store_path, DesktopFsState, DesktopPreviewState};
+
+const TRASH_DIR: &str = ".trash";
+const MANIFEST_FILE: &str = "manifest.json";
+/// Token to the root whose `.lattice/.trash` holds it, so tokens can be found again.
+pub(crate) const SOFT_DELETES_KEY: &str = "soft_deletes";
+const DEFAULT_TTL_MINUTES: u64 = 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteToken {
+    pub token: String,
+    /// Unix timestamp in milliseconds.
+    pub deleted_at: u64,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestEntry {
+    original_path: PathBuf,
+    /// Path below the token directory, mirroring `original_path` relative to the root.
+    stored_path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    deleted_at: u64,
+    entries: Vec<ManifestEntry>,
+}
+
+fn token_dir(root: &Path, token: &str) -> PathBuf {
+    root.join(WORKSPACE_SETTINGS_DIR).join(TRASH_DIR).join(token)
+}
+
+fn read_manifest(dir: &Path) -> Result<Manifest, LatticeError> {
+    let path = dir.join(MANIFEST_FILE);
+    let raw = fs::read(&path).map_err(|error| LatticeError::at_path(&path, error))?;
+    serde_json::from_slice(&raw).map_err(|error| error.to_string().into())
+}
+
+/// The open workspace when every path is inside it, otherwise the folder holding them.
+fn trash_root(workspace_root: Option<&Path>, paths: &[PathBuf]) -> Result<PathBuf, LatticeError> {
+    if let Some(root) = workspace_root.filter(|root| paths.iter().all(|path| path.starts_with(root) && path != root)) {
+        return Ok(root.to_path_buf());
+    }
+    let first = paths
+        .first()
+        .and_then(|path| path.parent())
+        .ok_or_else(|| LatticeError::InvalidInput {
+            message: "No paths to delete.".to_string(),
+        })?;
+    first
+        .ancestors()
+        .find(|candidate| paths.iter().all(|path| path.starts_with(candidate) && path != candidate))
+        .map(Path::to_path_buf)
+        .ok_or_else(|| LatticeError::InvalidInput {
+            message: "Paths do not share a parent folder.".to_string(),
+        })
+}
+
+fn soft_delete_sync<P: AsRef<Path>>(
+    workspace_root: Option<&Path>,
+    paths: &[P],
+    token: &str,
+) -> Result<(PathBuf, DeleteToken), LatticeError> {
+    let mut sources = Vec::with_capacity(paths.len());
+    for path in paths {
+        let path = path.as_ref();
+        let source = canonical_location(path)
+            .ok()
+            .filter(|source| fs::symlink_metadata(source).is_ok())
+            .ok_or_else(|| LatticeError::NotFound {
+                message: format!("Path not found: {}", path.display()),
+            })?;
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+    let root = trash_root(workspace_root, &sources)?;
+    let dir = token_dir(&root, token);
+
+    let mut entries: Vec<ManifestEntry> = Vec::with_capacity(sources.len());
+    let mut moved = Vec::new();
+    let result = sources.iter().try_for_each(|source| -> Result<(), LatticeError> {
+        let stored_path = source.strip_prefix(&root).map_err(|error| error.to_string())?.to_path_buf();
+        let target = dir.join(&stored_path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(source, &target).map_err(|error| LatticeError::at_path(source, error))?;
+        moved.push((source.clone(), target));
+        entries.push(ManifestEntry {
+            original_path: source.clone(),
+            stored_path,
+        });
+        Ok(())
+    });
+
+    let manifest = Manifest {
+        deleted_at: timestamp_ms(Ok(SystemTime::now())).unwrap_or_default(),
+        entries,
+    };
+    let result = result.and_then(|_| {
+        let encoded = serde_json::to_vec_pretty(&manifest).map_err(|error| error.to_string())?;
+        write_bytes_atomic(&dir.join(MANIFEST_FILE), &encoded)
+    });
+    if let Err(error) = result {
+        // Put back whatever already moved so a failed delete changes nothing.
+        for (source, target) in moved.into_iter().rev() {
+            let _ = fs::rename(target, source);
+        }
+        let _ = fs::remove_dir_all(&dir);
+        return Err(error);
+    }
+
+    Ok((
+        root,
+        DeleteToken {
+            token: token.to_string(),
+            deleted_at: manifest.deleted_at,
+            paths: manifest
+                .entries
+                .iter()
+                .map(|entry| entry.original_path.to_string_lossy().to_string())
+                .collect(),
+        },
+    ))
+}
+
+fn undo_sync(root: &Path, token: &str) -> Result<Vec<String>, LatticeError> {
+    let dir = token_dir(root, token);
+    let manifest = read_manifest(&dir)?;
+    let conflicts: Vec<String> = manifest
+        .entries
+        .iter()
+        .filter(|entry| fs::symlink_metadata(&entry.original_path).is_ok())
+        .map(|entry| entry.original_path.to_string_lossy().to_string())
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(LatticeError::Conflict {
+            message: format!("{} original location(s) are now occupied.", conflicts.len()),
+            paths: conflicts,
+        });
+    }
+
+    let mut restored = Vec::with_capacity(manifest.entries.len());
+    for entry in &manifest.entries {
+        if let Some(parent) = entry.original_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(dir.join(&entry.stored_path), &entry.original_path)
+            .map_err(|error| LatticeError::at_path(&entry.original_path, error))?;
+        restored.push(entry.original_path.to_string_lossy().to_string());
+    }
+    fs::remove_dir_all(&dir).map_err(|error| LatticeError::at_path(&dir, error))?;
+    Ok(restored)
+}
+
+fn commit_sync(root: &Path, token: &str) -> Result<(), LatticeError> {
+    match fs::remove_dir_all(token_dir(root, token)) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn read_tokens(app: &AppHandle) -> BTreeMap<String, PathBuf> {
+    app.store(settings_store_path(app))
+        .ok()
+        .and_then(|store| store.get(SOFT_DELETES_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn update_tokens(app: &AppHandle, update: impl FnOnce(&mut BTreeMap<String, PathBuf>)) -> Result<(), LatticeError> {
+    let mut tokens = read_tokens(app);
+    update(&mut tokens);
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    store.set(SOFT_DELETES_KEY, serde_json::to_value(tokens).map_err(|error| error.to_string())?);
+    save_settings_store(app).map_err(Into::into)
+}
+
+fn token_root(app: &AppHandle, token: &str) -> Result<PathBuf, LatticeError> {
+    read_tokens(app).remove(token).ok_or_else(|| LatticeError::NotFound {
+        message: format!("Unknown delete token: {token}"),
+    })
+}
+
+/// Runs in the background after `setup`: permanently removes deletions older than
+/// the `softDeleteTtlMinutes` setting.
+pub(crate) fn commit_expired_soft_deletes(app: &AppHandle) {
+    let ttl_minutes = build_app_settings_from_store(app)
+        .ok()
+        .and_then(|settings| settings.soft_delete_ttl_minutes)
+        .unwrap_or(DEFAULT_TTL_MINUTES);
+    let now = timestamp_ms(Ok(SystemTime::now())).unwrap_or_default();
+    let cutoff = now.saturating_sub(ttl_minutes.saturating_mul(60_000));
+
+    let expired: Vec<String> = read_tokens(app)
+        .into_iter()
+        .filter(|(token, root)| {
+            // A token whose manifest is gone can't be undone anymore either.
+            read_manifest(&token_dir(root, token)).map_or(true, |manifest| manifest.deleted_at <= cutoff)
+        })
+        .filter(|(token, root)| commit_sync(root, token).is_ok())
+        .map(|(token, _)| token)
+        .collect();
+    if expired.is_empty() {
+        return;
+    }
+    if let Err(error) = update_tokens(app, |tokens| tokens.retain(|token, _| !expired.contains(token))) {
+        log::error!("Failed to forget expired soft deletes: {error:?}");
+    }
+}
+
+#[tauri::command]
+pub async fn soft_delete(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    paths: Vec<String>,
+) -> Result<DeleteToken, LatticeError> {
+    log_command_async("soft_delete", async move {
+        let paths = resolve_command_paths(&app, &paths)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+        let workspace_root = app
+            .state::<DesktopPreviewState>()
+            .workspace_root
+            .lock()
+            .ok()
+            .and_then(|root| root.clone());
+        let token = uuid::Uuid::new_v4().simple().to_string();
+
+        let (root, deleted) = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            soft_delete_sync(workspace_root.as_deref(), &paths, &token)
+        })
+        .await
+        .map_err(|error| error.to_string())??;
+        update_tokens(&app, |tokens| {
+            tokens.insert(deleted.token.clone(), root);
+        })?;
+        Ok(deleted)
+    })
+    .await
+}
+
+/// Returns the restored paths.
+#[tauri::command]
+pub async fn undo_soft_delete(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    token: String,
+) -> Result<Vec<String>, LatticeError> {
+    log_command_async("undo_soft_delete", async move {
+        let root = token_root(&app, &token)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let token_for_undo = token.clone();
+        let restored = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            undo_sync(&root, &token_for_undo)
+        })
+        .await
+        .map_err(|error| error.to_string())??;
+        update_tokens(&app, |tokens| {
+            tokens.remove(&token);
+        })?;
+        Ok(restored)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn commit_soft_delete(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    token: String,
+) -> Result<(), LatticeError> {
+    log_command_async("commit_soft_delete", async move {
+        let root = token_root(&app, &token)?;
+        let permit = fs_state
+            .mutate_path_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let token_for_commit = token.clone();
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            commit_sync(&root, &token_for_commit)
+        })
+        .await
+        .map_err(|error| error.to_string())??;
+        update_tokens(&app, |tokens| {
+            tokens.remove(&token);
+        })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn soft_deletes_mirror_structure_and_refuse_to_restore_over_new_files() {
+        let root = std::env::temp_dir().join(format!("lattice-soft-delete-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes/drafts")).unwrap();
+        fs::write(root.join("notes/a.md"), "alpha").unwrap();
+        fs::write(root.join("notes/drafts/b.md"), "beta").unwrap();
+        let root = fs::canonicalize(root).unwrap();
+        let paths = vec![
+            root.join("notes/a.md").to_string_lossy().to_string(),
+            root.join("notes/drafts").to_string_lossy().to_string(),
+        ];
+
+        let (trash_root, deleted) = soft_delete_sync(Some(&root), &paths, "first").unwrap();
+        assert_eq!(trash_root, root);
+        assert_eq!(deleted.paths, paths);
+        assert!(!root.join("notes/a.md").exists());
+        assert!(token_dir(&root, "first").join("notes/drafts/b.md").is_file());
+
+        fs::write(root.join("notes/a.md"), "replacement").unwrap();
+        match undo_sync(&root, "first") {
+            Err(LatticeError::Conflict { paths: conflicts, .. }) => assert_eq!(conflicts, vec![paths[0].clone()]),
+            other => panic!("expected a conflict, got {other:?}"),
+        }
+
+        fs::remove_file(root.join("notes/a.md")).unwrap();
+        assert_eq!(undo_sync(&root, "first").unwrap(), paths);
+        assert_eq!(fs::read_to_string(root.join("notes/drafts/b.md")).unwrap(), "beta");
+        assert!(!token_dir(&root, "first").exists());
+
+        // Outside the workspace, the shared parent folder holds the trash.
+        let (trash_root, _) = soft_delete_sync(None, &paths[..1], "second").unwrap();
+        assert_eq!(trash_root, root.join("notes"));
+        commit_sync(&trash_root, "second").unwrap();
+        assert!(!token_dir(&trash_root, "second").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
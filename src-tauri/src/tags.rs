@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex as StdMutex;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::LatticeError;
+use crate::fileops::{canonical_location, write_bytes_atomic};
+use crate::logging::log_command;
+use crate::paths::resolve_command_path;
+use crate::workspace_settings::WORKSPACE_SETTINGS_DIR;
+use crate::DesktopPreviewState;
+
+const TAGS_FILE: &str = "tags.json";
+
+/// Relative path (always `/`-separated) to the tags attached to it.
+type TagMap = BTreeMap<String, BTreeSet<String>>;
+
+/// Serializes read-modify-write cycles on the sidecar files.
+#[derive(Default)]
+pub struct TagStoreState {
+    lock: StdMutex<()>,
+}
+
+fn tags_path(root: &Path) -> PathBuf {
+    root.join(WORKSPACE_SETTINGS_DIR).join(TAGS_FILE)
+}
+
+fn read_tags(root: &Path) -> Result<TagMap, LatticeError> {
+    let path = tags_path(root);
+    match fs::read(&path) {
+        Ok(raw) => serde_json::from_slice(&raw).map_err(|error| format!("Invalid tags file {}: {error}", path.display()).into()),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(TagMap::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn write_tags(root: &Path, tags: &TagMap) -> Result<(), LatticeError> {
+    let path = tags_path(root);
+    if tags.is_empty() && !path.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let encoded = serde_json::to_vec_pretty(tags).map_err(|error| error.to_string())?;
+    write_bytes_atomic(&path, &encoded)
+}
+
+/// Tags live with the open workspace; outside it, with the nearest folder that
+/// already has a `.lattice` directory, or else next to the file.
+fn resolve_tag_root(workspace_root: Option<&Path>, start_dir: &Path) -> PathBuf {
+    if let Some(root) = workspace_root.filter(|root| start_dir.starts_with(root)) {
+        return root.to_path_buf();
+    }
+    start_dir
+        .ancestors()
+        .find(|directory| directory.join(WORKSPACE_SETTINGS_DIR).is_dir())
+        .unwrap_or(start_dir)
+        .to_path_buf()
+}
+
+fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let key = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    (!key.is_empty()).then_some(key)
+}
+
+fn normalize_tag(tag: &str) -> Result<String, LatticeError> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err(LatticeError::InvalidInput {
+            message: "Tag cannot be empty.".to_string(),
+        });
+    }
+    Ok(tag.to_string())
+}
+
+fn is_same_or_below(key: &str, prefix: &str) -> bool {
+    key == prefix || key.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Removes `key` and everything below it, returning the entries with `key` stripped.
+fn take_subtree(tags: &mut TagMap, key: &str) -> Vec<(String, BTreeSet<String>)> {
+    let keys: Vec<String> = tags.keys().filter(|existing| is_same_or_below(existing, key)).cloned().collect();
+    keys.into_iter()
+        .filter_map(|existing| {
+            let values = tags.remove(&existing)?;
+            Some((existing[key.len()..].to_string(), values))
+        })
+        .collect()
+}
+
+fn insert_subtree(tags: &mut TagMap, key: &str, subtree: Vec<(String, BTreeSet<String>)>) {
+    for (suffix, values) in subtree {
+        tags.entry(format!("{key}{suffix}")).or_default().extend(values);
+    }
+}
+
+struct TaggedLocation {
+    root: PathBuf,
+    key: String,
+}
+
+fn locate(workspace_root: Option<&Path>, path: &Path) -> Result<TaggedLocation, LatticeError> {
+    let path = canonical_location(path)?;
+    let start_dir = path.parent().unwrap_or(&path);
+    let root = resolve_tag_root(workspace_root, start_dir);
+    let key = relative_key(&root, &path).ok_or_else(|| format!("Cannot tag {}", path.display()))?;
+    Ok(TaggedLocation { root, key })
+}
+
+fn update_tags_sync(workspace_root: Option<&Path>, path: &Path, tag: &str, add: bool) -> Result<(), LatticeError> {
+    let tag = normalize_tag(tag)?;
+    let location = locate(workspace_root, path)?;
+    let mut tags = read_tags(&location.root)?;
+    if add {
+        tags.entry(location.key).or_default().insert(tag);
+    } else if let Some(values) = tags.get_mut(&location.key) {
+        values.remove(&tag);
+        if values.is_empty() {
+            tags.remove(&location.key);
+        }
+    }
+    write_tags(&location.root, &tags)
+}
+
+fn get_tags_sync(workspace_root: Option<&Path>, path: &Path) -> Result<Vec<String>, LatticeError> {
+    let location = locate(workspace_root, path)?;
+    Ok(read_tags(&location.root)?
+        .remove(&location.key)
+        .map(|values| values.into_iter().collect())
+        .unwrap_or_default())
+}
+
+fn files_with_tag_sync(workspace_root: Option<&Path>, folder: &Path, tag: &str) -> Result<Vec<String>, LatticeError> {
+    let tag = normalize_tag(tag)?;
+    let folder = fs::canonicalize(folder)?;
+    let root = resolve_tag_root(workspace_root, &folder);
+    Ok(read_tags(&root)?
+        .into_iter()
+        .filter(|(_, values)| values.contains(&tag))
+        .map(|(key, _)| root.join(key))
+        .filter(|path| path.starts_with(&folder))
+        .map(|path| path.to_string_lossy().to_string())
+        .collect())
+}
+
+fn prune_tags_sync(workspace_root: Option<&Path>, folder: &Path) -> Result<usize, LatticeError> {
+    let folder = fs::canonicalize(folder)?;
+    let root = resolve_tag_root(workspace_root, &folder);
+    let mut tags = read_tags(&root)?;
+    let before = tags.len();
+    tags.retain(|key, _| fs::symlink_metadata(root.join(key)).is_ok());
+    let pruned = before - tags.len();
+    if pruned > 0 {
+        write_tags(&root, &tags)?;
+    }
+    Ok(pruned)
+}
+
+fn follow_rename_sync(workspace_root: Option<&Path>, from: &Path, to: &Path) -> Result<(), LatticeError> {
+    let source = locate(workspace_root, from)?;
+    let target = locate(workspace_root, to)?;
+    let mut source_tags = read_tags(&source.root)?;
+    let subtree = take_subtree(&mut source_tags, &source.key);
+    if subtree.is_empty() {
+        return Ok(());
+    }
+
+    if source.root == target.root {
+        insert_subtree(&mut source_tags, &target.key, subtree);
+        return write_tags(&source.root, &source_tags);
+    }
+    let mut target_tags = read_tags(&target.root)?;
+    insert_subtree(&mut target_tags, &target.key, subtree);
+    write_tags(&target.root, &target_tags)?;
+    write_tags(&source.root, &source_tags)
+}
+
+fn workspace_root(app: &AppHandle) -> Option<PathBuf> {
+    app.state::<DesktopPreviewState>()
+        .workspace_root
+        .lock()
+        .ok()
+        .and_then(|root| root.clone())
+}
+
+/// Moves tag keys along with a path renamed or moved by our own commands.
+pub(crate) fn follow_rename(app: &AppHandle, from: &Path, to: &Path) {
+    let state = app.state::<TagStoreState>();
+    let Ok(_guard) = state.lock.lock() else {
+        return;
+    };
+    if let Err(error) = follow_rename_sync(workspace_root(app).as_deref(), from, to) {
+        log::warn!("Failed to update tags after rename: {error}");
+    }
+}
+
+#[tauri::command]
+pub fn add_tag(app: AppHandle, state: State<'_, TagStoreState>, path: String, tag: String) -> Result<(), LatticeError> {
+    log_command("add_tag", || {
+        let _guard = state.lock.lock().map_err(|error| error.to_string())?;
+        update_tags_sync(workspace_root(&app).as_deref(), &resolve_command_path(&app, &path)?, &tag, true)
+    })
+}
+
+#[tauri::command]
+pub fn remove_tag(app: AppHandle, state: State<'_, TagStoreState>, path: String, tag: String) -> Result<(), LatticeError> {
+    log_command("remove_tag", || {
+        let _guard = state.lock.lock().map_err(|error| error.to_string())?;
+        update_tags_sync(workspace_root(&app).as_deref(), &resolve_command_path(&app, &path)?, &tag, false)
+    })
+}
+
+#[tauri::command]
+pub fn get_tags(app: AppHandle, path: String) -> Result<Vec<String>, LatticeError> {
+    log_command("get_tags", || {
+        get_tags_sync(workspace_root(&app).as_deref(), &resolve_command_path(&app, &path)?)
+    })
+}
+
+#[tauri::command]
+pub fn files_with_tag(app: AppHandle, root: String, tag: String) -> Result<Vec<String>, LatticeError> {
+    log_command("files_with_tag", || {
+        files_with_tag_sync(workspace_root(&app).as_deref(), &resolve_command_path(&app, &root)?, &tag)
+    })
+}
+
+#[tauri::command]
+pub fn prune_tags(app: AppHandle, state: State<'_, TagStoreState>, root: String) -> Result<usize, LatticeError> {
+    log_command("prune_tags", || {
+        let _guard = state.lock.lock().map_err(|error| error.to_string())?;
+        prune_tags_sync(workspace_root(&app).as_deref(), &resolve_command_path(&app, &root)?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_fixture_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("lattice-tags-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes/archive")).unwrap();
+        fs::write(root.join("notes/a.md"), "alpha").unwrap();
+        fs::write(root.join("notes/archive/b.md"), "beta").unwrap();
+        fs::canonicalize(root).unwrap()
+    }
+
+    #[test]
+    fn tags_are_stored_relative_to_the_workspace_and_follow_renames() {
+        let root = create_fixture_root();
+        let workspace = Some(root.as_path());
+        update_tags_sync(workspace, &root.join("notes/a.md"), "todo", true).unwrap();
+        update_tags_sync(workspace, &root.join("notes/archive/b.md"), "todo", true).unwrap();
+        update_tags_sync(workspace, &root.join("notes/archive/b.md"), " archive ", true).unwrap();
+
+        let stored = read_tags(&root).unwrap();
+        assert_eq!(stored.keys().collect::<Vec<_>>(), vec!["notes/a.md", "notes/archive/b.md"]);
+        assert_eq!(get_tags_sync(workspace, &root.join("notes/archive/b.md")).unwrap(), vec!["archive", "todo"]);
+
+        fs::rename(root.join("notes"), root.join("journal")).unwrap();
+        follow_rename_sync(workspace, &root.join("notes"), &root.join("journal")).unwrap();
+        let tagged = files_with_tag_sync(workspace, &root, "todo").unwrap();
+        assert_eq!(
+            tagged,
+            vec![
+                root.join("journal/a.md").to_string_lossy().to_string(),
+                root.join("journal/archive/b.md").to_string_lossy().to_string(),
+            ]
+        );
+
+        update_tags_sync(workspace, &root.join("journal/a.md"), "todo", false).unwrap();
+        assert!(get_tags_sync(workspace, &root.join("journal/a.md")).unwrap().is_empty());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn prune_drops_entries_for_missing_files_and_prefixes_match_whole_segments() {
+        let root = create_fixture_root();
+        let workspace = Some(root.as_path());
+        update_tags_sync(workspace, &root.join("notes/a.md"), "todo", true).unwrap();
+        update_tags_sync(workspace, &root.join("notes/archive/b.md"), "todo", true).unwrap();
+        fs::remove_file(root.join("notes/a.md")).unwrap();
+
+        assert_eq!(prune_tags_sync(workspace, &root).unwrap(), 1);
+        assert_eq!(prune_tags_sync(workspace, &root).unwrap(), 0);
+
+        assert!(is_same_or_below("notes/archive/b.md", "notes/archive"));
+        assert!(!is_same_or_below("notes/archive-old/b.md", "notes/archive"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,71 @@
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Manager};
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+
+/// Maps a 0..=1 fraction onto the 0..=100 scale the taskbar uses.
+fn progress_percent(fraction: f64) -> Result<u64, LatticeError> {
+    if !fraction.is_finite() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Progress must be a number between 0 and 1: {fraction}"),
+        });
+    }
+    Ok((fraction.clamp(0.0, 1.0) * 100.0).round() as u64)
+}
+
+/// The dock and Unity launcher show one app-wide indicator, so the main window speaks for all.
+fn indicator_window(app: &AppHandle) -> Option<tauri::WebviewWindow> {
+    app.get_webview_window("main")
+}
+
+/// Returns whether the indicator could be shown; `false` means there was nowhere to show it.
+pub(crate) fn apply_taskbar_progress(app: &AppHandle, fraction: Option<f64>) -> Result<bool, LatticeError> {
+    let state = match fraction {
+        Some(fraction) => ProgressBarState {
+            status: Some(ProgressBarStatus::Normal),
+            progress: Some(progress_percent(fraction)?),
+        },
+        None => ProgressBarState {
+            status: Some(ProgressBarStatus::None),
+            progress: None,
+        },
+    };
+    let Some(window) = indicator_window(app) else {
+        return Ok(false);
+    };
+    Ok(window.set_progress_bar(state).is_ok())
+}
+
+#[tauri::command]
+pub fn set_taskbar_progress(app: AppHandle, fraction: Option<f64>) -> Result<bool, LatticeError> {
+    log_command("set_taskbar_progress", || apply_taskbar_progress(&app, fraction))
+}
+
+/// `None` or `0` clears the badge. Windows taskbars have no badge, so there it returns `false`.
+#[tauri::command]
+pub fn set_badge_count(app: AppHandle, count: Option<u32>) -> Result<bool, LatticeError> {
+    log_command("set_badge_count", || {
+        if cfg!(target_os = "windows") {
+            return Ok(false);
+        }
+        let Some(window) = indicator_window(&app) else {
+            return Ok(false);
+        };
+        let count = count.filter(|count| *count > 0).map(i64::from);
+        Ok(window.set_badge_count(count).is_ok())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fractions_are_clamped_to_whole_percentages() {
+        assert_eq!(progress_percent(0.426).unwrap(), 43);
+        assert_eq!(progress_percent(-1.0).unwrap(), 0);
+        assert_eq!(progress_percent(7.0).unwrap(), 100);
+        assert!(progress_percent(f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use time::format_description::parse_strftime_borrowed;
+use time::OffsetDateTime;
+
+use crate::data_dir::data_dir;
+use crate::error::LatticeError;
+use crate::fileops::{create_unique_entry, timestamp_ms, validate_entry_name, write_bytes_atomic, TEMPLATES_DIR};
+use crate::logging::{log_command, log_command_async};
+use crate::paths::resolve_command_path;
+use crate::settings_recovery::save_settings_store;
+use crate::settings_store_path;
+
+pub(crate) const TEMPLATE_USAGE_KEY: &str = "template_usage";
+/// `{{date}}` on its own, matching what `create_file` templates produce.
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateInfo {
+    pub name: String,
+    pub size: u64,
+    /// Milliseconds since the Unix epoch; `None` for templates never instantiated.
+    pub last_used_at: Option<u64>,
+}
+
+/// Shared by every folder, unlike the per-workspace `.lattice/templates`.
+fn templates_dir(app: &AppHandle) -> PathBuf {
+    data_dir(app).join(TEMPLATES_DIR)
+}
+
+fn template_path(app: &AppHandle, name: &str) -> Result<PathBuf, LatticeError> {
+    let name = name.trim();
+    validate_entry_name(name).map_err(|message| LatticeError::InvalidInput { message })?;
+    Ok(templates_dir(app).join(name))
+}
+
+fn read_usage(app: &AppHandle) -> Result<Map<String, Value>, LatticeError> {
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    Ok(match store.get(TEMPLATE_USAGE_KEY) {
+        Some(Value::Object(usage)) => usage,
+        _ => Map::new(),
+    })
+}
+
+fn write_usage(app: &AppHandle, usage: Map<String, Value>) -> Result<(), LatticeError> {
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    store.set(TEMPLATE_USAGE_KEY, Value::Object(usage));
+    save_settings_store(app).map_err(Into::into)
+}
+
+fn format_date(now: OffsetDateTime, format: &str) -> Result<String, LatticeError> {
+    let invalid = |error: &dyn std::fmt::Display| LatticeError::InvalidInput {
+        message: format!("Invalid date format {format:?}: {error}"),
+    };
+    let items = parse_strftime_borrowed(format).map_err(|error| invalid(&error))?;
+    now.format(&items).map_err(|error| invalid(&error))
+}
+
+/// Replaces `{{key}}` with `vars[key]`, and `{{date}}` or `{{date:FORMAT}}` (strftime
+/// syntax) with `now`. Unknown placeholders are left as written.
+fn render(template: &str, vars: &HashMap<String, String>, now: OffsetDateTime) -> Result<String, LatticeError> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let key = after[..end].trim();
+        let placeholder = &rest[start..start + end + 4];
+        if let Some(value) = vars.get(key) {
+            output.push_str(value);
+        } else if key == "date" {
+            output.push_str(&format_date(now, DEFAULT_DATE_FORMAT)?);
+        } else if let Some(format) = key.strip_prefix("date:") {
+            output.push_str(&format_date(now, format)?);
+        } else {
+            output.push_str(placeholder);
+        }
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Named after the template, or `vars.title` plus the template's extension when given.
+fn file_name_for(template: &str, vars: &HashMap<String, String>) -> String {
+    let Some(title) = vars.get("title").map(|title| title.trim()).filter(|title| !title.is_empty()) else {
+        return template.to_string();
+    };
+    match Path::new(template).extension() {
+        Some(extension) => format!("{title}.{}", extension.to_string_lossy()),
+        None => title.to_string(),
+    }
+}
+
+/// Most recently used first, then by name.
+#[tauri::command]
+pub fn list_templates(app: AppHandle) -> Result<Vec<TemplateInfo>, LatticeError> {
+    log_command("list_templates", || {
+        let entries = match fs::read_dir(templates_dir(&app)) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error.into()),
+        };
+        let usage = read_usage(&app)?;
+        let mut templates: Vec<TemplateInfo> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok().filter(|metadata| metadata.is_file())?;
+                let name = entry.file_name().to_string_lossy().to_string();
+                Some(TemplateInfo {
+                    last_used_at: usage.get(&name).and_then(Value::as_u64),
+                    name,
+                    size: metadata.len(),
+                })
+            })
+            .collect();
+        templates.sort_by(|left, right| right.last_used_at.cmp(&left.last_used_at).then(left.name.cmp(&right.name)));
+        Ok(templates)
+    })
+}
+
+/// Replaces any template with the same name.
+#[tauri::command]
+pub fn save_template(app: AppHandle, name: String, contents: String) -> Result<(), LatticeError> {
+    log_command("save_template", || {
+        let path = template_path(&app, &name)?;
+        fs::create_dir_all(templates_dir(&app))?;
+        write_bytes_atomic(&path, contents.as_bytes())
+    })
+}
+
+#[tauri::command]
+pub fn delete_template(app: AppHandle, name: String) -> Result<(), LatticeError> {
+    log_command("delete_template", || {
+        let path = template_path(&app, &name)?;
+        fs::remove_file(&path).map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => LatticeError::NotFound {
+                message: format!("Template not found: {}", name.trim()),
+            },
+            _ => LatticeError::at_path(&path, error),
+        })?;
+        let mut usage = read_usage(&app)?;
+        if usage.remove(name.trim()).is_some() {
+            write_usage(&app, usage)?;
+        }
+        Ok(())
+    })
+}
+
+/// Writes the rendered template into `dest_dir`, suffixing the name on a collision, and
+/// returns the new file's path.
+#[tauri::command]
+pub async fn instantiate_template(
+    app: AppHandle,
+    name: String,
+    dest_dir: String,
+    vars: HashMap<String, String>,
+) -> Result<String, LatticeError> {
+    log_command_async("instantiate_template", async move {
+        let name = name.trim().to_string();
+        let source = template_path(&app, &name)?;
+        let dest_dir = resolve_command_path(&app, &dest_dir)?;
+        let (name, path) = tokio::task::spawn_blocking(move || {
+            if !dest_dir.is_dir() {
+                return Err(LatticeError::InvalidInput {
+                    message: format!("Destination is not a directory: {}", dest_dir.display()),
+                });
+            }
+            let template = fs::read_to_string(&source).map_err(|error| match error.kind() {
+                std::io::ErrorKind::NotFound => LatticeError::NotFound {
+                    message: format!("Template not found: {name}"),
+                },
+                _ => LatticeError::at_path(&source, error),
+            })?;
+            let file_name = file_name_for(&name, &vars);
+            validate_entry_name(&file_name).map_err(|message| LatticeError::InvalidInput { message })?;
+            let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+            let rendered = render(&template, &vars, now)?;
+
+            let path = create_unique_entry(&dest_dir, &file_name, |candidate| {
+                fs::OpenOptions::new().write(true).create_new(true).open(candidate).map(|_| ())
+            })?;
+            fs::write(&path, rendered).map_err(|error| LatticeError::at_path(&path, error))?;
+            Ok((name, path))
+        })
+        .await
+        .map_err(|error| error.to_string())??;
+
+        let mut usage = read_usage(&app)?;
+        usage.insert(name, Value::from(timestamp_ms(Ok(SystemTime::now())).unwrap_or_default()));
+        write_usage(&app, usage)?;
+        Ok(path.to_string_lossy().to_string())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_filled_from_vars_and_the_date() {
+        // 2026-10-14 09:05 UTC.
+        let now = OffsetDateTime::from_unix_timestamp(1_791_968_700).unwrap();
+        let vars = HashMap::from([("title".to_string(), "Standup".to_string())]);
+
+        let rendered = render("# {{ title }} {{date}}\n{{date:%d/%m %H:%M}} {{who}} {{", &vars, now).unwrap();
+        assert_eq!(rendered, "# Standup 2026-10-14\n14/10 09:05 {{who}} {{");
+        assert!(render("{{date:%Q}}", &vars, now).is_err());
+
+        assert_eq!(file_name_for("daily.md", &vars), "Standup.md");
+        assert_eq!(file_name_for("daily.md", &HashMap::new()), "daily.md");
+    }
+}
@@ -0,0 +1,133 @@
+use std::path::Path;
+use std::process::Command;
+
+use tauri::AppHandle;
+
+use crate::build_app_settings_from_store;
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::paths::resolve_command_path;
+
+const DIR_PLACEHOLDER: &str = "{dir}";
+
+/// Splits a `terminal_command` template into words and substitutes `{dir}` in each, so a
+/// folder with spaces stays one argument. Double quotes group words; there is no escaping.
+fn expand_terminal_template(template: &str, dir: &Path) -> Vec<String> {
+    let dir = dir.to_string_lossy();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quoted = false;
+    for character in template.chars() {
+        match character {
+            '"' => {
+                quoted = !quoted;
+                in_word = true;
+            }
+            character if character.is_whitespace() && !quoted => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            character => {
+                word.push(character);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+        .into_iter()
+        .map(|word| word.replace(DIR_PLACEHOLDER, &dir))
+        .collect()
+}
+
+/// Tried in order until one starts; each is also run with `dir` as its working directory.
+fn default_terminals(dir: &Path) -> Vec<(&'static str, Vec<String>)> {
+    let dir = dir.to_string_lossy().to_string();
+
+    #[cfg(target_os = "windows")]
+    return vec![
+        ("wt", vec!["-d".to_string(), dir]),
+        ("cmd", ["/C", "start", "", "cmd"].map(String::from).to_vec()),
+    ];
+
+    #[cfg(target_os = "macos")]
+    return vec![("open", vec!["-a".to_string(), "Terminal".to_string(), dir])];
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    vec![
+        ("x-terminal-emulator", Vec::new()),
+        ("gnome-terminal", vec![format!("--working-directory={dir}")]),
+        ("konsole", vec!["--workdir".to_string(), dir.clone()]),
+        ("xfce4-terminal", vec!["--working-directory".to_string(), dir]),
+        ("xterm", Vec::new()),
+    ]
+}
+
+/// Opens a terminal in `dir`, using `terminal_command` from the settings when it is set.
+pub(crate) fn launch_terminal(app: &AppHandle, dir: &Path) -> Result<(), LatticeError> {
+    if !dir.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Terminal path must be an existing directory: {}", dir.display()),
+        });
+    }
+
+    let configured = build_app_settings_from_store(app)?.terminal_command;
+    if let Some(template) = configured {
+        let words = expand_terminal_template(&template, dir);
+        let Some((program, args)) = words.split_first() else {
+            return Err(LatticeError::InvalidInput {
+                message: "The terminal command is empty.".to_string(),
+            });
+        };
+        // A configured command that fails is reported rather than silently replaced.
+        return Command::new(program)
+            .args(args)
+            .current_dir(dir)
+            .spawn()
+            .map(|_| ())
+            .map_err(|error| LatticeError::Unsupported {
+                message: format!("Could not start the terminal command \"{program}\": {error}"),
+            });
+    }
+
+    for (program, args) in default_terminals(dir) {
+        if Command::new(program).args(args).current_dir(dir).spawn().is_ok() {
+            return Ok(());
+        }
+    }
+    Err(LatticeError::Unsupported {
+        message: format!(
+            "No supported terminal application was found. Set a terminal command in the settings, \
+             e.g. \"alacritty --working-directory {DIR_PLACEHOLDER}\"."
+        ),
+    })
+}
+
+#[tauri::command]
+pub fn open_terminal(app: AppHandle, cwd: String) -> Result<(), LatticeError> {
+    log_command("open_terminal", || launch_terminal(&app, &resolve_command_path(&app, &cwd)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_substitute_the_folder_into_single_arguments() {
+        let dir = Path::new("/home/ada/My Notes");
+        assert_eq!(
+            expand_terminal_template("kitty --directory {dir}", dir),
+            ["kitty", "--directory", "/home/ada/My Notes"]
+        );
+        assert_eq!(
+            expand_terminal_template("  \"/opt/My Term/term\"   --cwd={dir} -e \"\" ", dir),
+            ["/opt/My Term/term", "--cwd=/home/ada/My Notes", "-e", ""]
+        );
+        assert!(expand_terminal_template("   ", dir).is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::search::looks_binary;
+
+const SNIFF_BYTES: usize = 8 * 1024;
+const DEFAULT_WORDS_PER_MINUTE: u32 = 200;
+const BOM: char = '\u{feff}';
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TextStatsOpts {
+    pub words_per_minute: Option<u32>,
+    /// Leave frontmatter and fenced code out of the word count and reading time.
+    pub markdown_prose_only: bool,
+}
+
+/// Counts follow `wc`: characters include line breaks, words are whitespace-separated runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextStats {
+    pub lines: u64,
+    pub words: u64,
+    pub characters: u64,
+    pub characters_excluding_whitespace: u64,
+    pub reading_time_secs: u64,
+    pub words_per_minute: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Prose,
+    /// Opened by `---` or `+++` on the first line.
+    Frontmatter { toml: bool },
+    Fence { marker: char, length: usize },
+}
+
+/// `(marker, run length, rest)` for a line opening or closing a code fence.
+fn fence_run(line: &str) -> Option<(char, usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let line = &line[indent..];
+    let marker = line.chars().next().filter(|marker| *marker == '`' || *marker == '~')?;
+    let length = line.len() - line.trim_start_matches(marker).len();
+    (length >= 3).then(|| (marker, length, &line[length..]))
+}
+
+struct StatsCounter {
+    prose_only: bool,
+    stats: TextStats,
+    section: Section,
+    /// Frontmatter words are held back until the block closes; an unclosed block is prose.
+    frontmatter_words: u64,
+}
+
+impl StatsCounter {
+    fn count_line(&mut self, line: &str) {
+        let first_line = self.stats.lines == 0;
+        self.stats.lines += 1;
+        self.stats.characters += line.chars().count() as u64;
+        self.stats.characters_excluding_whitespace +=
+            line.chars().filter(|character| !character.is_whitespace()).count() as u64;
+
+        let words = line.split_whitespace().count() as u64;
+        if !self.prose_only {
+            self.stats.words += words;
+            return;
+        }
+        let content = line.trim_end_matches(['\n', '\r']);
+        match self.section {
+            Section::Prose if first_line && (content == "---" || content == "+++") => {
+                self.section = Section::Frontmatter { toml: content == "+++" };
+                self.frontmatter_words = words;
+            }
+            Section::Prose => match fence_run(content) {
+                Some((marker, length, rest)) if marker == '~' || !rest.contains('`') => {
+                    self.section = Section::Fence { marker, length };
+                }
+                _ => self.stats.words += words,
+            },
+            Section::Frontmatter { toml } => {
+                let closes = if toml { content == "+++" } else { content == "---" || content == "..." };
+                if closes {
+                    self.frontmatter_words = 0;
+                    self.section = Section::Prose;
+                } else {
+                    self.frontmatter_words += words;
+                }
+            }
+            Section::Fence { marker, length } => {
+                let closes = fence_run(content).is_some_and(|(closing, closing_length, rest)| {
+                    closing == marker && closing_length >= length && rest.trim().is_empty()
+                });
+                if closes {
+                    self.section = Section::Prose;
+                }
+            }
+        }
+    }
+
+    fn finish(mut self, words_per_minute: u32) -> TextStats {
+        if matches!(self.section, Section::Frontmatter { .. }) {
+            self.stats.words += self.frontmatter_words;
+        }
+        self.stats.words_per_minute = words_per_minute;
+        self.stats.reading_time_secs = (self.stats.words * 60).div_ceil(u64::from(words_per_minute));
+        self.stats
+    }
+}
+
+/// Reads a line at a time, so memory stays flat however large the file is.
+fn text_stats_from_reader(reader: &mut impl BufRead, opts: &TextStatsOpts) -> Result<TextStats, LatticeError> {
+    if looks_binary(reader.fill_buf()?) {
+        return Err(LatticeError::BinaryFile {
+            message: "File appears to be binary.".to_string(),
+        });
+    }
+
+    let mut counter = StatsCounter {
+        prose_only: opts.markdown_prose_only,
+        stats: TextStats::default(),
+        section: Section::Prose,
+        frontmatter_words: 0,
+    };
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        let decoded = String::from_utf8_lossy(&buffer);
+        let line = if counter.stats.lines == 0 {
+            decoded.trim_start_matches(BOM)
+        } else {
+            &decoded
+        };
+        counter.count_line(line);
+    }
+    let words_per_minute = opts.words_per_minute.filter(|wpm| *wpm > 0).unwrap_or(DEFAULT_WORDS_PER_MINUTE);
+    Ok(counter.finish(words_per_minute))
+}
+
+fn text_stats_sync(path: &Path, opts: &TextStatsOpts) -> Result<TextStats, LatticeError> {
+    let file = fs::File::open(path).map_err(|error| LatticeError::at_path(path, error))?;
+    text_stats_from_reader(&mut BufReader::with_capacity(SNIFF_BYTES, file), opts)
+}
+
+#[tauri::command]
+pub async fn text_stats(app: AppHandle, path: String, opts: TextStatsOpts) -> Result<TextStats, LatticeError> {
+    log_command_async("text_stats", async move {
+        let path = resolve_command_path(&app, &path)?;
+        tokio::task::spawn_blocking(move || text_stats_sync(&path, &opts))
+            .await
+            .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(text: &str, prose_only: bool) -> TextStats {
+        let opts = TextStatsOpts {
+            words_per_minute: Some(120),
+            markdown_prose_only: prose_only,
+        };
+        text_stats_from_reader(&mut text.as_bytes(), &opts).unwrap()
+    }
+
+    #[test]
+    fn counts_follow_wc_and_prose_only_skips_frontmatter_and_code() {
+        let note = "---\ntitle: Lab notes\n---\n# Results\n\n```rust\nlet x = 1;\n```\nThe lattice holds.\n";
+        let all = stats(note, false);
+        assert_eq!((all.lines, all.words, all.characters), (9, 16, note.chars().count() as u64));
+        assert_eq!(all.characters_excluding_whitespace, note.split_whitespace().map(str::len).sum::<usize>() as u64);
+        assert_eq!(all.reading_time_secs, 8);
+
+        let prose = stats(note, true);
+        assert_eq!((prose.lines, prose.words, prose.reading_time_secs), (9, 5, 3));
+
+        // A frontmatter block that never closes was prose all along.
+        assert_eq!(stats("---\nnot really frontmatter\n", true).words, 4);
+        assert_eq!(stats("", false), TextStats {
+            words_per_minute: 120,
+            ..TextStats::default()
+        });
+        let binary = text_stats_from_reader(&mut &b"PK\0\x03"[..], &TextStatsOpts::default());
+        assert!(matches!(binary, Err(LatticeError::BinaryFile { .. })));
+        let missing = std::env::temp_dir().join(format!("lattice-text-stats-{}.md", uuid::Uuid::new_v4()));
+        assert!(matches!(
+            text_stats_sync(&missing, &TextStatsOpts::default()),
+            Err(LatticeError::NotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,290 @@
+use std::sync::Mutex as StdMutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Theme};
+
+use crate::error::LatticeError;
+use crate::logging::{log_command, log_command_async};
+use crate::{build_app_settings_from_store, save_app_settings};
+
+const THEME_CHANGED_EVENT: &str = "theme-changed";
+const SYSTEM_APPEARANCE_CHANGED_EVENT: &str = "system-appearance-changed";
+const DEFAULT_THEME: &str = "system";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ThemePreference {
+    Light,
+    Dark,
+    System,
+}
+
+impl ThemePreference {
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "light" => Some(Self::Light),
+            "dark" => Some(Self::Dark),
+            "system" => Some(Self::System),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+            Self::System => "system",
+        }
+    }
+
+    /// `None` lets the native chrome follow the OS.
+    pub(crate) fn native(self) -> Option<Theme> {
+        match self {
+            Self::Light => Some(Theme::Light),
+            Self::Dark => Some(Theme::Dark),
+            Self::System => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ThemeChangedPayload {
+    theme: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemAppearance {
+    /// The OS's `"dark"` or `"light"`, regardless of the theme Lattice is set to.
+    pub theme: &'static str,
+    /// `#rrggbb`; `None` where the OS has no accent color to report.
+    pub accent_color: Option<String>,
+}
+
+/// The appearance last announced, so only actual OS changes are emitted.
+#[derive(Default)]
+pub struct SystemAppearanceState {
+    last: StdMutex<Option<SystemAppearance>>,
+}
+
+fn theme_name(theme: Theme) -> &'static str {
+    match theme {
+        Theme::Dark => "dark",
+        _ => "light",
+    }
+}
+
+/// `reg` prints DWORDs as `0xAABBGGRR`.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn accent_from_dword(output: &str) -> Option<String> {
+    let value = output.split_whitespace().last()?.strip_prefix("0x")?;
+    let abgr = u32::from_str_radix(value, 16).ok()?;
+    let [red, green, blue, _] = abgr.to_le_bytes();
+    Some(format!("#{red:02x}{green:02x}{blue:02x}"))
+}
+
+/// macOS stores the accent as an index into its palette; no value means the default blue.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn accent_from_macos_index(index: Option<&str>) -> Option<String> {
+    let color = match index.map(str::trim) {
+        Some("-1") => "#8c8c8c",
+        Some("0") => "#ff5257",
+        Some("1") => "#f7821b",
+        Some("2") => "#ffc600",
+        Some("3") => "#62ba46",
+        Some("5") => "#a550a7",
+        Some("6") => "#f74f9e",
+        _ => "#007aff",
+    };
+    Some(color.to_string())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const CREATE_NO_WINDOW_FLAG: u32 = 0x08000000;
+
+    fn reg_value(key: &str, value: &str) -> Option<String> {
+        let output = Command::new("reg")
+            .args(["query", key, "/v", value])
+            .creation_flags(CREATE_NO_WINDOW_FLAG)
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout.lines().find(|line| line.trim_start().starts_with(value))?;
+        output.status.success().then(|| line.to_string())
+    }
+
+    pub(super) fn dark_mode() -> Option<bool> {
+        let key = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
+        let line = reg_value(key, "AppsUseLightTheme")?;
+        Some(line.split_whitespace().last()? == "0x0")
+    }
+
+    pub(super) fn accent_color() -> Option<String> {
+        super::accent_from_dword(&reg_value(r"HKCU\Software\Microsoft\Windows\DWM", "AccentColor")?)
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::process::Command;
+
+    fn global_default(key: &str) -> Option<String> {
+        let output = Command::new("defaults").args(["read", "-g", key]).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// The key only exists while dark mode is on.
+    pub(super) fn dark_mode() -> Option<bool> {
+        Some(global_default("AppleInterfaceStyle").is_some_and(|style| style == "Dark"))
+    }
+
+    pub(super) fn accent_color() -> Option<String> {
+        super::accent_from_macos_index(global_default("AppleAccentColor").as_deref())
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    /// Desktop environments disagree on where this lives, so the window's theme stands in.
+    pub(super) fn dark_mode() -> Option<bool> {
+        None
+    }
+
+    pub(super) fn accent_color() -> Option<String> {
+        None
+    }
+}
+
+/// `hint` is the theme the OS just reported, which beats asking it again. Runs commands
+/// on some platforms, so keep it off the main thread.
+fn read_system_appearance(app: &AppHandle, hint: Option<Theme>) -> SystemAppearance {
+    let theme = hint
+        .or_else(|| platform::dark_mode().map(|dark| if dark { Theme::Dark } else { Theme::Light }))
+        .or_else(|| app.get_webview_window("main")?.theme().ok())
+        .unwrap_or(Theme::Light);
+    SystemAppearance {
+        theme: theme_name(theme),
+        accent_color: platform::accent_color(),
+    }
+}
+
+/// Records `appearance`, returning what it replaced when it differs.
+fn swap_appearance(app: &AppHandle, appearance: &SystemAppearance) -> Option<Option<SystemAppearance>> {
+    let state = app.state::<SystemAppearanceState>();
+    let mut last = state.last.lock().ok()?;
+    if last.as_ref() == Some(appearance) {
+        return None;
+    }
+    Some(last.replace(appearance.clone()))
+}
+
+pub(crate) fn saved_theme_preference(app: &AppHandle) -> ThemePreference {
+    build_app_settings_from_store(app)
+        .ok()
+        .and_then(|settings| settings.theme)
+        .and_then(|theme| ThemePreference::parse(&theme))
+        .unwrap_or(ThemePreference::System)
+}
+
+fn apply_theme(app: &AppHandle, preference: ThemePreference) -> Result<(), LatticeError> {
+    for window in app.webview_windows().values() {
+        window
+            .set_theme(preference.native())
+            .map_err(|error| error.to_string())?;
+    }
+    Ok(())
+}
+
+/// Applies the saved theme during `setup`, before the webview paints.
+pub(crate) fn restore_theme(app: &AppHandle) -> Result<(), LatticeError> {
+    apply_theme(app, saved_theme_preference(app))
+}
+
+/// Announces OS appearance changes, and while the user follows the system theme, forwards
+/// light/dark switches as `theme-changed` in the same pass so scheme and accent move together.
+/// Accent changes have no event of their own, so this also runs when a window regains focus.
+pub(crate) fn check_system_appearance(app: &AppHandle, hint: Option<Theme>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let appearance = read_system_appearance(&app, hint);
+        let Some(previous) = swap_appearance(&app, &appearance) else {
+            return;
+        };
+        let theme_changed = previous.is_none_or(|previous| previous.theme != appearance.theme);
+        if theme_changed && saved_theme_preference(&app) == ThemePreference::System {
+            let _ = app.emit(THEME_CHANGED_EVENT, ThemeChangedPayload { theme: appearance.theme });
+        }
+        let _ = app.emit(SYSTEM_APPEARANCE_CHANGED_EVENT, appearance);
+    });
+}
+
+pub(crate) fn handle_system_theme_changed(app: &AppHandle, theme: Theme) {
+    // A window with a forced theme reports that one rather than the OS's.
+    let hint = (saved_theme_preference(app) == ThemePreference::System).then_some(theme);
+    check_system_appearance(app, hint);
+}
+
+#[tauri::command]
+pub async fn get_system_appearance(app: AppHandle) -> Result<SystemAppearance, LatticeError> {
+    log_command_async("get_system_appearance", async move {
+        let appearance = tauri::async_runtime::spawn_blocking(move || {
+            let appearance = read_system_appearance(&app, None);
+            swap_appearance(&app, &appearance);
+            appearance
+        })
+        .await
+        .map_err(|error| error.to_string())?;
+        Ok(appearance)
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn get_theme(app: AppHandle) -> Result<String, LatticeError> {
+    log_command("get_theme", || {
+        Ok(build_app_settings_from_store(&app)?
+            .theme
+            .unwrap_or_else(|| DEFAULT_THEME.to_string()))
+    })
+}
+
+#[tauri::command]
+pub fn set_theme(app: AppHandle, theme: String) -> Result<(), LatticeError> {
+    log_command("set_theme", || {
+        let preference = ThemePreference::parse(&theme)
+            .ok_or_else(|| format!("Unknown theme \"{}\"; expected light, dark or system.", theme.trim()))?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.theme = Some(preference.as_str().to_string());
+        save_app_settings(&app, settings)?;
+        apply_theme(&app, preference)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_preferences_parse_case_insensitively_and_map_to_native_themes() {
+        assert_eq!(ThemePreference::parse(" Dark "), Some(ThemePreference::Dark));
+        assert_eq!(ThemePreference::parse("LIGHT").and_then(ThemePreference::native), Some(Theme::Light));
+        assert_eq!(ThemePreference::parse("system").and_then(ThemePreference::native), None);
+        assert_eq!(ThemePreference::parse("sepia"), None);
+    }
+
+    #[test]
+    fn accent_colors_decode_to_hex() {
+        let output = "    AccentColor    REG_DWORD    0xffd77800";
+        assert_eq!(accent_from_dword(output).as_deref(), Some("#0078d7"));
+        assert_eq!(accent_from_dword("AccentColor REG_DWORD nonsense"), None);
+        assert_eq!(accent_from_macos_index(Some("3\n")).as_deref(), Some("#62ba46"));
+        assert_eq!(accent_from_macos_index(None).as_deref(), Some("#007aff"));
+    }
+}
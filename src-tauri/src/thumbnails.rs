@@ -0,0 +1,212 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use image::{ImageError, ImageFormat, ImageReader};
+use tauri::{AppHandle, State};
+
+use crate::data_dir::data_dir;
+use crate::error::LatticeError;
+use crate::fileops::write_bytes_atomic;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::workspace_settings::WORKSPACE_SETTINGS_DIR;
+use crate::DesktopFsState;
+
+const THUMBS_DIR: &str = "thumbs";
+const SUPPORTED_FORMATS: [ImageFormat; 4] = [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::Gif, ImageFormat::WebP];
+
+/// Thumbnails are app-level caches, so they follow a portable data directory.
+fn thumbnail_cache_dir(app: &AppHandle) -> PathBuf {
+    data_dir(app).join(WORKSPACE_SETTINGS_DIR).join(THUMBS_DIR)
+}
+
+fn hex_hash(parts: &[&[u8]]) -> String {
+    let mut hasher = blake3::Hasher::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().to_hex()[..32].to_string()
+}
+
+/// Cache files are named `<path>-<version>-<max_dim>.png`, hashing the path and the
+/// mtime and size. Any edit changes the version, and the older versions of the same path
+/// are found by their shared prefix.
+fn cache_prefixes(path: &Path, metadata: &fs::Metadata) -> (String, String) {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    let path_prefix = format!("{}-", hex_hash(&[path.to_string_lossy().as_bytes()]));
+    let version = hex_hash(&[&modified.to_le_bytes(), &metadata.len().to_le_bytes()]);
+    let version_prefix = format!("{path_prefix}{version}-");
+    (path_prefix, version_prefix)
+}
+
+/// Deletes the thumbnails of earlier versions of a file, keeping every size of this one.
+fn remove_stale_thumbnails(cache_dir: &Path, path_prefix: &str, version_prefix: &str) {
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with(path_prefix) && !name.starts_with(version_prefix) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn render_thumbnail(path: &Path, max_dim: u32) -> Result<Vec<u8>, LatticeError> {
+    let unsupported = || LatticeError::Unsupported {
+        message: format!("Not a supported image: {}", path.display()),
+    };
+    let reader = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|error| LatticeError::at_path(path, error))?;
+    if !reader.format().is_some_and(|format| SUPPORTED_FORMATS.contains(&format)) {
+        return Err(unsupported());
+    }
+
+    let image = reader.decode().map_err(|error| match error {
+        ImageError::Unsupported(_) => unsupported(),
+        error => LatticeError::Corrupt {
+            message: format!("Failed to decode {}: {error}", path.display()),
+        },
+    })?;
+    // Small images are returned as-is rather than blown up to `max_dim`.
+    let image = if image.width() > max_dim || image.height() > max_dim {
+        image.thumbnail(max_dim, max_dim)
+    } else {
+        image
+    };
+
+    let mut encoded = Cursor::new(Vec::new());
+    image.write_to(&mut encoded, ImageFormat::Png).map_err(|error| error.to_string())?;
+    Ok(encoded.into_inner())
+}
+
+fn thumbnail_sync(cache_dir: &Path, path: &Path, max_dim: u32) -> Result<Vec<u8>, LatticeError> {
+    if max_dim == 0 {
+        return Err(LatticeError::InvalidInput {
+            message: "max_dim must be greater than zero".to_string(),
+        });
+    }
+    let path = fs::canonicalize(path).map_err(|error| LatticeError::at_path(path, error))?;
+    let metadata = fs::metadata(&path).map_err(|error| LatticeError::at_path(&path, error))?;
+    if !metadata.is_file() {
+        return Err(LatticeError::Unsupported {
+            message: format!("Not a file: {}", path.display()),
+        });
+    }
+
+    let (path_prefix, version_prefix) = cache_prefixes(&path, &metadata);
+    let cached = cache_dir.join(format!("{version_prefix}{max_dim}.png"));
+    if let Ok(bytes) = fs::read(&cached) {
+        return Ok(bytes);
+    }
+
+    let bytes = render_thumbnail(&path, max_dim)?;
+    remove_stale_thumbnails(cache_dir, &path_prefix, &version_prefix);
+    // A cache that can't be written only costs a re-render next time.
+    if fs::create_dir_all(cache_dir).is_ok() {
+        let _ = write_bytes_atomic(&cached, &bytes);
+    }
+    Ok(bytes)
+}
+
+#[tauri::command]
+pub async fn get_thumbnail(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    path: String,
+    max_dim: u32,
+) -> Result<Vec<u8>, LatticeError> {
+    log_command_async("get_thumbnail", async move {
+        let path = resolve_command_path(&app, &path)?;
+        let permit = fs_state
+            .read_file_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+        let cache_dir = thumbnail_cache_dir(&app);
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            thumbnail_sync(&cache_dir, &path, max_dim)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn clear_thumbnail_cache(app: AppHandle) -> Result<(), LatticeError> {
+    log_command_async("clear_thumbnail_cache", async move {
+        let cache_dir = thumbnail_cache_dir(&app);
+        tokio::task::spawn_blocking(move || match fs::remove_dir_all(&cache_dir) {
+            Ok(()) => Ok(()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(error) => Err(error.into()),
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, RgbImage};
+
+    #[test]
+    fn thumbnails_fit_max_dim_are_cached_and_reject_bad_input() {
+        let root = std::env::temp_dir().join(format!("lattice-thumbnails-{}", uuid::Uuid::new_v4()));
+        let cache_dir = root.join("cache");
+        fs::create_dir_all(&root).unwrap();
+        DynamicImage::ImageRgb8(RgbImage::new(400, 100))
+            .save_with_format(root.join("wide.jpg"), ImageFormat::Jpeg)
+            .unwrap();
+        fs::write(root.join("broken.png"), b"\x89PNG\r\n\x1a\nnot really").unwrap();
+        fs::write(root.join("notes.md"), "# not an image").unwrap();
+
+        let bytes = thumbnail_sync(&cache_dir, &root.join("wide.jpg"), 64).unwrap();
+        let thumbnail = image::load_from_memory_with_format(&bytes, ImageFormat::Png).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (64, 16));
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 1);
+        assert_eq!(thumbnail_sync(&cache_dir, &root.join("wide.jpg"), 64).unwrap(), bytes);
+
+        let small = thumbnail_sync(&cache_dir, &root.join("wide.jpg"), 1000).unwrap();
+        let small = image::load_from_memory(&small).unwrap();
+        assert_eq!((small.width(), small.height()), (400, 100));
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 2);
+
+        // A new version replaces every cached size of the old one.
+        DynamicImage::ImageRgb8(RgbImage::new(200, 100))
+            .save_with_format(root.join("wide.jpg"), ImageFormat::Jpeg)
+            .unwrap();
+        thumbnail_sync(&cache_dir, &root.join("wide.jpg"), 64).unwrap();
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 1);
+
+        assert!(matches!(
+            thumbnail_sync(&cache_dir, &root.join("broken.png"), 64),
+            Err(LatticeError::Corrupt { .. })
+        ));
+        assert!(matches!(
+            thumbnail_sync(&cache_dir, &root.join("notes.md"), 64),
+            Err(LatticeError::Unsupported { .. })
+        ));
+        assert!(matches!(
+            thumbnail_sync(&cache_dir, &root.join("missing.png"), 64),
+            Err(LatticeError::NotFound { .. })
+        ));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
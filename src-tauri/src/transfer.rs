@@ -0,0 +1,595 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::case_collisions::check_destination;
+use crate::error::LatticeError;
+use crate::fileops::{atomic_temp_path, canonical_location, same_file_sync, suffixed_name};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+const TRANSFER_PROGRESS_EVENT: &str = "transfer-progress";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+const COPY_BUFFER_BYTES: usize = 1024 * 1024;
+const MAX_RENAME_ATTEMPTS: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    /// Replace files and merge into existing directories. A file never replaces a directory
+    /// or the reverse; that needs `Replace`.
+    Overwrite,
+    Skip,
+    /// Pick a free ` (n)` suffixed name next to the existing entry.
+    Rename,
+    /// Replace whatever is there, directories included, without merging.
+    Replace,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferReport {
+    /// Where the source ended up, or `None` when it was skipped.
+    pub destination: Option<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<TransferFailure>,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    /// Stopped by `cancel_job`. Entries it hadn't reached are untouched, and a file cut off
+    /// mid-copy is removed rather than left half-written.
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransferProgressPayload {
+    source: String,
+    destination: String,
+    bytes_done: u64,
+    bytes_total: u64,
+}
+
+struct Transfer<'a> {
+    policy: ConflictPolicy,
+    delete_source: bool,
+    report: TransferReport,
+    last_progress: Instant,
+    cancelled: &'a AtomicBool,
+    on_progress: &'a mut dyn FnMut(u64, u64),
+}
+
+/// Where an entry gets written. When it replaces something, it's written to a staged sibling
+/// first and only swapped in once it's complete, so a failed copy leaves the old entry alone.
+struct Placement {
+    path: PathBuf,
+    replaces: Option<PathBuf>,
+}
+
+impl Placement {
+    fn at(path: PathBuf) -> Self {
+        Self { path, replaces: None }
+    }
+
+    /// The path the entry has once it's in place.
+    fn destination(&self) -> &Path {
+        self.replaces.as_deref().unwrap_or(&self.path)
+    }
+}
+
+fn remove_entry(path: &Path) -> std::io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Moves `staged` over `target`. A file over a file is a single rename; anything else sets
+/// the old entry aside first and puts it back if the new one can't be moved in.
+fn swap_into_place(staged: &Path, target: &Path) -> std::io::Result<()> {
+    let files = !fs::symlink_metadata(staged)?.is_dir() && !fs::symlink_metadata(target)?.is_dir();
+    if files {
+        return fs::rename(staged, target);
+    }
+    let aside = atomic_temp_path(target).map_err(|error| std::io::Error::other(error.to_string()))?;
+    fs::rename(target, &aside)?;
+    if let Err(error) = fs::rename(staged, target) {
+        let _ = fs::rename(&aside, target);
+        return Err(error);
+    }
+    // The swap already happened; a leftover hidden entry is only clutter.
+    let _ = remove_entry(&aside);
+    Ok(())
+}
+
+/// `target` when nothing is there, else the first free ` (n)` variant of it.
+pub(crate) fn free_path(target: &Path) -> Result<PathBuf, LatticeError> {
+    if fs::symlink_metadata(target).is_err() {
+        return Ok(target.to_path_buf());
+    }
+    let name = target.file_name().unwrap_or_default().to_string_lossy().to_string();
+    (2..=MAX_RENAME_ATTEMPTS)
+        .map(|attempt| target.with_file_name(suffixed_name(&name, attempt)))
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .ok_or_else(|| LatticeError::AlreadyExists {
+            message: format!("Could not find a free name for {}", target.display()),
+        })
+}
+
+impl Transfer<'_> {
+    fn is_cancelled(&mut self) -> bool {
+        self.report.cancelled |= self.cancelled.load(Ordering::Relaxed);
+        self.report.cancelled
+    }
+
+    fn fail(&mut self, path: &Path, error: impl ToString) -> bool {
+        self.report.failed.push(TransferFailure {
+            path: path.to_string_lossy().to_string(),
+            error: error.to_string(),
+        });
+        false
+    }
+
+    fn advance(&mut self, bytes: u64) {
+        self.report.bytes_done += bytes;
+        if self.last_progress.elapsed() >= PROGRESS_INTERVAL {
+            (self.on_progress)(self.report.bytes_done, self.report.bytes_total);
+            self.last_progress = Instant::now();
+        }
+    }
+
+    /// Returns where to write, or `None` when the entry should be skipped. Nothing that
+    /// exists is removed here; a replaced entry is only swapped out after the copy succeeds.
+    fn resolve_conflict(&mut self, source: &Path, target: &Path) -> Result<Option<Placement>, LatticeError> {
+        let Ok(existing) = fs::symlink_metadata(target) else {
+            return Ok(Some(Placement::at(target.to_path_buf())));
+        };
+        let source_is_dir = fs::symlink_metadata(source).is_ok_and(|metadata| metadata.is_dir());
+
+        match self.policy {
+            ConflictPolicy::Skip => {
+                self.report.skipped.push(source.to_string_lossy().to_string());
+                Ok(None)
+            }
+            ConflictPolicy::Rename => free_path(target).map(|path| Some(Placement::at(path))),
+            ConflictPolicy::Overwrite if source_is_dir && existing.is_dir() => {
+                Ok(Some(Placement::at(target.to_path_buf())))
+            }
+            ConflictPolicy::Overwrite if source_is_dir || existing.is_dir() => {
+                let (existing_kind, source_kind) = if existing.is_dir() {
+                    ("folder", "file")
+                } else {
+                    ("file", "folder")
+                };
+                Err(LatticeError::TargetExists {
+                    message: format!(
+                        "{} is a {existing_kind}; replacing it with a {source_kind} needs the replace policy.",
+                        target.display()
+                    ),
+                })
+            }
+            ConflictPolicy::Overwrite | ConflictPolicy::Replace => Ok(Some(Placement {
+                path: atomic_temp_path(target)?,
+                replaces: Some(target.to_path_buf()),
+            })),
+        }
+    }
+
+    /// Swaps a staged entry in over the one it replaces, or throws it away when it
+    /// didn't make it across. Returns whether the entry ended up in place.
+    fn finish_placement(&mut self, source: &Path, placement: &Placement, complete: bool) -> bool {
+        let Some(replaced) = &placement.replaces else {
+            return complete;
+        };
+        if !complete {
+            let _ = remove_entry(&placement.path);
+            return false;
+        }
+        if let Err(error) = swap_into_place(&placement.path, replaced) {
+            let _ = remove_entry(&placement.path);
+            return self.fail(source, error);
+        }
+        true
+    }
+
+    fn copy_file(&mut self, source: &Path, target: &Path) -> std::io::Result<()> {
+        let mut reader = fs::File::open(source)?;
+        let mut writer = fs::File::create(target)?;
+        let mut buffer = vec![0u8; COPY_BUFFER_BYTES];
+        loop {
+            if self.is_cancelled() {
+                drop(writer);
+                fs::remove_file(target)?;
+                return Err(std::io::ErrorKind::Interrupted.into());
+            }
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buffer[..read])?;
+            self.advance(read as u64);
+        }
+        writer.set_permissions(reader.metadata()?.permissions())
+    }
+
+    #[cfg(unix)]
+    fn copy_symlink(&mut self, source: &Path, target: &Path) -> std::io::Result<()> {
+        std::os::unix::fs::symlink(fs::read_link(source)?, target)
+    }
+
+    #[cfg(not(unix))]
+    fn copy_symlink(&mut self, source: &Path, target: &Path) -> std::io::Result<()> {
+        self.copy_file(source, target)
+    }
+
+    /// Copies `source` to `target`, recording per-entry failures instead of stopping.
+    /// Returns whether everything under `source` made it across.
+    fn copy_entry(&mut self, source: &Path, target: &Path) -> bool {
+        if self.is_cancelled() {
+            return false;
+        }
+        let metadata = match fs::symlink_metadata(source) {
+            Ok(metadata) => metadata,
+            Err(error) => return self.fail(source, error),
+        };
+        match self.resolve_conflict(source, target) {
+            Ok(Some(placement)) => self.place_entry(source, &metadata, &placement),
+            // A skipped entry still exists at the source, so it must not be deleted.
+            Ok(None) => false,
+            Err(error) => self.fail(source, error),
+        }
+    }
+
+    fn place_entry(&mut self, source: &Path, metadata: &fs::Metadata, placement: &Placement) -> bool {
+        if self.is_cancelled() {
+            return false;
+        }
+        let target = &placement.path;
+        if metadata.is_dir() {
+            if let Err(error) = fs::create_dir_all(target) {
+                return self.fail(source, error);
+            }
+            let entries = match fs::read_dir(source) {
+                Ok(entries) => entries,
+                Err(error) => {
+                    self.finish_placement(source, placement, false);
+                    return self.fail(source, error);
+                }
+            };
+
+            // A staged folder is thrown away if it's incomplete, so a move only deletes
+            // its source once the whole folder has been swapped in.
+            let delete_source = self.delete_source;
+            self.delete_source &= placement.replaces.is_none();
+            let mut complete = true;
+            for entry in entries {
+                match entry {
+                    Ok(entry) => complete &= self.copy_entry(&entry.path(), &target.join(entry.file_name())),
+                    Err(error) => complete = self.fail(source, error),
+                }
+            }
+            self.delete_source = delete_source;
+
+            let staged = placement.replaces.is_some();
+            if !self.finish_placement(source, placement, complete) {
+                return false;
+            }
+            if delete_source {
+                let removed = if staged { fs::remove_dir_all(source) } else { fs::remove_dir(source) };
+                if let Err(error) = removed {
+                    return self.fail(source, error);
+                }
+            }
+            return true;
+        }
+
+        let copied = if metadata.file_type().is_symlink() {
+            self.copy_symlink(source, target)
+        } else {
+            self.copy_file(source, target)
+        };
+        if let Err(error) = copied {
+            self.finish_placement(source, placement, false);
+            if self.report.cancelled {
+                return false;
+            }
+            return self.fail(source, error);
+        }
+        if !self.finish_placement(source, placement, true) {
+            return false;
+        }
+        if self.delete_source {
+            if let Err(error) = fs::remove_file(source) {
+                return self.fail(source, error);
+            }
+        }
+        true
+    }
+}
+
+fn measure_bytes(path: &Path) -> u64 {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| measure_bytes(&entry.path())).sum())
+            .unwrap_or(0),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+pub(crate) fn transfer_path(
+    source: &Path,
+    target: &Path,
+    policy: ConflictPolicy,
+    delete_source: bool,
+    cancelled: &AtomicBool,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<TransferReport, LatticeError> {
+    let metadata = fs::symlink_metadata(source).map_err(|error| LatticeError::at_path(source, error))?;
+    let source_location = canonical_location(source)?;
+    let target_location = canonical_location(target)?;
+    let same_file = fs::symlink_metadata(target).is_ok() && same_file_sync(source, target)?;
+    let same_location = same_file || source_location == target_location;
+    // Copying onto itself with Rename duplicates the entry under a suffixed name.
+    let duplicating = same_location && !delete_source && policy == ConflictPolicy::Rename;
+    if same_location && !duplicating {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Source and destination are the same: {}", source.display()),
+        });
+    }
+    if !same_location && target_location.starts_with(&source_location) {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Cannot place {} inside itself.", source.display()),
+        });
+    }
+
+    let mut transfer = Transfer {
+        policy,
+        delete_source,
+        report: TransferReport {
+            bytes_total: measure_bytes(source),
+            ..TransferReport::default()
+        },
+        last_progress: Instant::now(),
+        cancelled,
+        on_progress,
+    };
+
+    let Some(placement) = transfer.resolve_conflict(source, target)? else {
+        return Ok(transfer.report);
+    };
+    transfer.report.destination = Some(placement.destination().to_string_lossy().to_string());
+
+    // A same-filesystem move is a single rename; anything else falls back to copying.
+    let staging = &placement.path;
+    let renamed = delete_source && fs::symlink_metadata(staging).is_err() && fs::rename(source, staging).is_ok();
+    if renamed {
+        if let Some(replaced) = &placement.replaces {
+            swap_into_place(staging, replaced).map_err(|error| {
+                let _ = fs::rename(staging, source);
+                LatticeError::at_path(replaced, error)
+            })?;
+        }
+        transfer.report.bytes_done = transfer.report.bytes_total;
+    } else {
+        transfer.place_entry(source, &metadata, &placement);
+    }
+
+    (transfer.on_progress)(transfer.report.bytes_done, transfer.report.bytes_total);
+    Ok(transfer.report)
+}
+
+async fn run_transfer(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    src: String,
+    dst: String,
+    on_conflict: ConflictPolicy,
+    delete_source: bool,
+    check_case: bool,
+) -> Result<TransferReport, LatticeError> {
+    let permit = fs_state
+        .mutate_path_permits
+        .clone()
+        .acquire_owned()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    let source = resolve_command_path(&app, &src)?;
+    let target = resolve_command_path(&app, &dst)?;
+    let app_for_tags = app.clone();
+    let source_for_tags = source.clone();
+    let report = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        if check_case {
+            check_destination(&source, &target)?;
+        }
+        let mut emit_progress = |bytes_done: u64, bytes_total: u64| {
+            let _ = app.emit(
+                TRANSFER_PROGRESS_EVENT,
+                TransferProgressPayload {
+                    source: source.to_string_lossy().to_string(),
+                    destination: target.to_string_lossy().to_string(),
+                    bytes_done,
+                    bytes_total,
+                },
+            );
+        };
+        transfer_path(&source, &target, on_conflict, delete_source, &AtomicBool::new(false), &mut emit_progress)
+    })
+    .await
+    .map_err(|error| error.to_string())??;
+
+    // Tags only follow a move that fully completed.
+    if let (true, true, Some(destination)) = (delete_source, report.failed.is_empty(), &report.destination) {
+        crate::tags::follow_rename(&app_for_tags, &source_for_tags, Path::new(destination));
+    }
+    Ok(report)
+}
+
+/// With `check_case`, a folder whose names differ only by case is refused before anything
+/// is copied when the destination's filesystem ignores case.
+#[tauri::command]
+pub async fn copy_path(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    src: String,
+    dst: String,
+    on_conflict: ConflictPolicy,
+    check_case: Option<bool>,
+) -> Result<TransferReport, LatticeError> {
+    log_command_async("copy_path", async move {
+        run_transfer(app, fs_state, src, dst, on_conflict, false, check_case.unwrap_or(false)).await
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn move_path(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    src: String,
+    dst: String,
+    on_conflict: ConflictPolicy,
+) -> Result<TransferReport, LatticeError> {
+    log_command_async("move_path", async move {
+        run_transfer(app, fs_state, src, dst, on_conflict, true, false).await
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static NOT_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+    fn create_fixture_root() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("lattice-transfer-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes/archive")).unwrap();
+        fs::write(root.join("notes/a.md"), "alpha").unwrap();
+        fs::write(root.join("notes/archive/b.md"), "beta").unwrap();
+        fs::create_dir_all(root.join("dest")).unwrap();
+        root
+    }
+
+    #[test]
+    fn copies_directories_and_applies_conflict_policies() {
+        let root = create_fixture_root();
+        let source = root.join("notes");
+        let target = root.join("dest/notes");
+        let mut last_progress = (0, 0);
+
+        let mut on_progress = |done, total| last_progress = (done, total);
+        let report = transfer_path(&source, &target, ConflictPolicy::Skip, false, &NOT_CANCELLED, &mut on_progress);
+        let report = report.unwrap();
+        assert_eq!(fs::read_to_string(target.join("archive/b.md")).unwrap(), "beta");
+        assert!(report.failed.is_empty());
+        assert_eq!(last_progress, (9, 9));
+
+        let skipped = transfer_path(&source, &target, ConflictPolicy::Skip, false, &NOT_CANCELLED, &mut |_, _| {});
+        let skipped = skipped.unwrap();
+        assert!(skipped.destination.is_none());
+        assert_eq!(skipped.skipped.len(), 1);
+
+        let renamed = transfer_path(&source, &target, ConflictPolicy::Rename, false, &NOT_CANCELLED, &mut |_, _| {});
+        let renamed = renamed.unwrap();
+        assert_eq!(renamed.destination, Some(root.join("dest/notes (2)").to_string_lossy().to_string()));
+        assert!(source.join("a.md").is_file());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn move_merges_into_existing_directories_when_overwriting() {
+        let root = create_fixture_root();
+        let source = root.join("notes");
+        let target = root.join("dest/notes");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("a.md"), "stale").unwrap();
+        fs::write(target.join("keep.md"), "keep").unwrap();
+
+        let report = transfer_path(&source, &target, ConflictPolicy::Overwrite, true, &NOT_CANCELLED, &mut |_, _| {});
+        let report = report.unwrap();
+        assert!(report.failed.is_empty());
+        assert!(!source.exists());
+        assert_eq!(fs::read_to_string(target.join("a.md")).unwrap(), "alpha");
+        assert_eq!(fs::read_to_string(target.join("keep.md")).unwrap(), "keep");
+        let inner = target.join("archive/inner");
+        assert!(transfer_path(&target, &inner, ConflictPolicy::Skip, true, &NOT_CANCELLED, &mut |_, _| {}).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn overwrite_swaps_in_complete_copies_and_only_replace_changes_kind() {
+        let root = create_fixture_root();
+        let file = root.join("notes/a.md");
+        fs::write(root.join("dest/a.md"), "stale").unwrap();
+        fs::create_dir_all(root.join("dest/archive")).unwrap();
+        fs::write(root.join("dest/archive/keep.md"), "keep").unwrap();
+
+        let stale = root.join("dest/a.md");
+        let report = transfer_path(&file, &stale, ConflictPolicy::Overwrite, false, &NOT_CANCELLED, &mut |_, _| {});
+        assert_eq!(report.unwrap().destination, Some(root.join("dest/a.md").to_string_lossy().to_string()));
+        assert_eq!(fs::read_to_string(root.join("dest/a.md")).unwrap(), "alpha");
+
+        let folder = root.join("dest/archive");
+        let refused = transfer_path(&file, &folder, ConflictPolicy::Overwrite, false, &NOT_CANCELLED, &mut |_, _| {});
+        assert!(matches!(refused, Err(LatticeError::TargetExists { .. })));
+        assert_eq!(fs::read_to_string(folder.join("keep.md")).unwrap(), "keep");
+
+        let replaced = transfer_path(&file, &folder, ConflictPolicy::Replace, true, &NOT_CANCELLED, &mut |_, _| {});
+        assert!(replaced.unwrap().failed.is_empty());
+        assert_eq!(fs::read_to_string(&folder).unwrap(), "alpha");
+        assert!(!file.exists());
+
+        let source = root.join("notes/archive");
+        let replaced = transfer_path(&source, &folder, ConflictPolicy::Replace, false, &NOT_CANCELLED, &mut |_, _| {});
+        assert!(replaced.unwrap().failed.is_empty());
+        assert_eq!(fs::read_to_string(folder.join("b.md")).unwrap(), "beta");
+        let entries = fs::read_dir(root.join("dest")).unwrap().flatten();
+        let mut names: Vec<_> = entries.map(|entry| entry.file_name().to_string_lossy().to_string()).collect();
+        names.sort();
+        assert_eq!(names, ["a.md", "archive"]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn copying_onto_itself_only_succeeds_with_rename() {
+        let root = create_fixture_root();
+        let file = root.join("notes/a.md");
+
+        let copied = transfer_path(&file, &file, ConflictPolicy::Rename, false, &NOT_CANCELLED, &mut |_, _| {});
+        let copied = copied.unwrap();
+        assert_eq!(copied.destination, Some(root.join("notes/a (2).md").to_string_lossy().to_string()));
+        assert_eq!(fs::read_to_string(root.join("notes/a (2).md")).unwrap(), "alpha");
+
+        let folder = root.join("notes");
+        let copied = transfer_path(&folder, &folder, ConflictPolicy::Rename, false, &NOT_CANCELLED, &mut |_, _| {});
+        assert_eq!(fs::read_to_string(root.join("notes (2)/archive/b.md")).unwrap(), "beta");
+        assert!(copied.unwrap().failed.is_empty());
+
+        for policy in [ConflictPolicy::Overwrite, ConflictPolicy::Skip] {
+            assert!(transfer_path(&file, &file, policy, false, &NOT_CANCELLED, &mut |_, _| {}).is_err());
+        }
+        assert!(transfer_path(&file, &file, ConflictPolicy::Rename, true, &NOT_CANCELLED, &mut |_, _| {}).is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "alpha");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::ser::{SerializeSeq, SerializeStruct};
+use serde::{Deserialize, Serialize, Serializer};
+use tauri::{AppHandle, State};
+
+use crate::error::LatticeError;
+use crate::file_tree::{walk_directory_tree, FileNode};
+use crate::fileops::atomic_temp_path;
+use crate::ignore_rules::{matcher_for, IgnoreMatcher};
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::DesktopFsState;
+
+/// Bumped whenever a field is renamed or removed, so scripts can tell exports apart.
+const TREE_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TreeExportOpts {
+    /// Levels below the root to include; `None` means the whole tree.
+    pub max_depth: Option<u32>,
+    pub include_hidden: bool,
+    /// One `entries` list with a `depth` per node instead of nested `children`.
+    pub flat: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum NodeType {
+    Dir,
+    File,
+}
+
+/// Children of each directory, dirs first and then by name, so repeated exports of an
+/// unchanged tree are byte-for-byte identical.
+struct ExportTree<'a> {
+    root: &'a Path,
+    children: HashMap<&'a Path, Vec<&'a FileNode>>,
+}
+
+impl<'a> ExportTree<'a> {
+    fn new(root: &'a Path, nodes: &'a [FileNode]) -> Self {
+        let mut children: HashMap<&Path, Vec<&FileNode>> = HashMap::new();
+        for node in nodes {
+            let parent = Path::new(&node.path).parent().unwrap_or(root);
+            children.entry(parent).or_default().push(node);
+        }
+        for entries in children.values_mut() {
+            entries.sort_by(|left, right| right.is_dir.cmp(&left.is_dir).then_with(|| left.name.cmp(&right.name)));
+        }
+        Self { root, children }
+    }
+
+    /// Root-relative with `/` separators, so exports from different machines compare.
+    fn relative_path(&self, node: &FileNode) -> String {
+        let path = Path::new(&node.path);
+        let relative = path.strip_prefix(self.root).unwrap_or(path);
+        relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn children_of(&self, dir: &Path) -> &[&'a FileNode] {
+        self.children.get(dir).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn flat_entries(&self, dir: &Path, entries: &mut Vec<&'a FileNode>) {
+        for node in self.children_of(dir) {
+            entries.push(node);
+            if node.is_dir {
+                self.flat_entries(Path::new(&node.path), entries);
+            }
+        }
+    }
+}
+
+fn node_type(node: &FileNode) -> NodeType {
+    if node.is_dir {
+        NodeType::Dir
+    } else {
+        NodeType::File
+    }
+}
+
+struct NestedNode<'t, 'a> {
+    tree: &'t ExportTree<'a>,
+    node: &'a FileNode,
+}
+
+impl Serialize for NestedNode<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TreeNode", 5)?;
+        state.serialize_field("name", &self.node.name)?;
+        state.serialize_field("path", &self.tree.relative_path(self.node))?;
+        state.serialize_field("type", &node_type(self.node))?;
+        state.serialize_field("size", &self.node.size)?;
+        if self.node.is_dir {
+            state.serialize_field("children", &NestedChildren {
+                tree: self.tree,
+                dir: Path::new(&self.node.path),
+            })?;
+        }
+        if let Some(error) = &self.node.error {
+            state.serialize_field("error", error)?;
+        }
+        state.end()
+    }
+}
+
+struct NestedChildren<'t, 'a> {
+    tree: &'t ExportTree<'a>,
+    dir: &'t Path,
+}
+
+impl Serialize for NestedChildren<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let children = self.tree.children_of(self.dir);
+        let mut seq = serializer.serialize_seq(Some(children.len()))?;
+        for node in children {
+            seq.serialize_element(&NestedNode { tree: self.tree, node })?;
+        }
+        seq.end()
+    }
+}
+
+struct FlatNode<'t, 'a> {
+    tree: &'t ExportTree<'a>,
+    node: &'a FileNode,
+}
+
+impl Serialize for FlatNode<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("TreeEntry", 5)?;
+        state.serialize_field("name", &self.node.name)?;
+        state.serialize_field("path", &self.tree.relative_path(self.node))?;
+        state.serialize_field("type", &node_type(self.node))?;
+        state.serialize_field("size", &self.node.size)?;
+        state.serialize_field("depth", &self.node.depth)?;
+        if let Some(error) = &self.node.error {
+            state.serialize_field("error", error)?;
+        }
+        state.end()
+    }
+}
+
+/// The whole document; serialized straight into the writer, so even a file export of a
+/// huge tree never holds its JSON in memory.
+struct TreeExport<'t, 'a> {
+    tree: &'t ExportTree<'a>,
+    flat: bool,
+}
+
+impl Serialize for TreeExport<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let root = self.tree.root;
+        let mut state = serializer.serialize_struct("TreeExport", 4)?;
+        state.serialize_field("schemaVersion", &TREE_EXPORT_SCHEMA_VERSION)?;
+        state.serialize_field("root", &root.to_string_lossy())?;
+        if self.flat {
+            let mut entries = Vec::new();
+            self.tree.flat_entries(root, &mut entries);
+            let entries: Vec<FlatNode> = entries
+                .into_iter()
+                .map(|node| FlatNode { tree: self.tree, node })
+                .collect();
+            state.serialize_field("entries", &entries)?;
+        } else {
+            state.serialize_field("children", &NestedChildren { tree: self.tree, dir: root })?;
+        }
+        state.end()
+    }
+}
+
+fn export_tree<W: Write>(
+    root: &Path,
+    opts: &TreeExportOpts,
+    ignore: &IgnoreMatcher,
+    writer: W,
+) -> Result<(), LatticeError> {
+    let max_depth = opts.max_depth.unwrap_or(u32::MAX).max(1);
+    let nodes = walk_directory_tree(root, max_depth, opts.include_hidden, ignore)?;
+    let tree = ExportTree::new(root, &nodes);
+    serde_json::to_writer_pretty(writer, &TreeExport { tree: &tree, flat: opts.flat })
+        .map_err(|error| error.to_string().into())
+}
+
+/// Streams through a sibling temp file that is renamed into place once complete.
+fn export_tree_to_file_sync(
+    root: &Path,
+    dest: &Path,
+    opts: &TreeExportOpts,
+    ignore: &IgnoreMatcher,
+) -> Result<(), LatticeError> {
+    if dest.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Export target is a directory: {}", dest.display()),
+        });
+    }
+    let temp_path = atomic_temp_path(dest)?;
+    let result = fs::File::create_new(&temp_path)
+        .map_err(LatticeError::from)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            export_tree(root, opts, ignore, &mut writer)?;
+            writer.write_all(b"\n")?;
+            writer.into_inner().map_err(|error| error.into_error())?.sync_all()?;
+            Ok(fs::rename(&temp_path, dest)?)
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn export_tree_json(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+    opts: TreeExportOpts,
+) -> Result<String, LatticeError> {
+    log_command_async("export_tree_json", async move {
+        let root = resolve_command_path(&app, &root)?;
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let ignore = matcher_for(&app, &fs::canonicalize(&root)?);
+            let mut json = Vec::new();
+            export_tree(&root, &opts, &ignore, &mut json)?;
+            String::from_utf8(json).map_err(|error| error.to_string().into())
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+/// Same document as `export_tree_json`, written to `dest` instead of returned.
+#[tauri::command]
+pub async fn export_tree_to_file(
+    app: AppHandle,
+    fs_state: State<'_, DesktopFsState>,
+    root: String,
+    dest: String,
+    opts: TreeExportOpts,
+) -> Result<(), LatticeError> {
+    log_command_async("export_tree_to_file", async move {
+        let root = resolve_command_path(&app, &root)?;
+        let dest = resolve_command_path(&app, &dest)?;
+        let permit = fs_state
+            .read_dir_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let ignore = matcher_for(&app, &fs::canonicalize(&root)?);
+            export_tree_to_file_sync(&root, &dest, &opts, &ignore)
+        })
+        .await
+        .map_err(|error| error.to_string())?
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn exports_sort_dirs_first_and_respect_depth() {
+        let root = std::env::temp_dir().join(format!("lattice-tree-export-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes/archive")).unwrap();
+        fs::write(root.join("a.md"), "hi").unwrap();
+        fs::write(root.join("notes/todo.md"), "todo").unwrap();
+        fs::write(root.join("notes/archive/old.md"), "old").unwrap();
+        let rules = IgnoreMatcher::new(&root, &[]);
+
+        let mut nested = Vec::new();
+        export_tree(&root, &TreeExportOpts::default(), &rules, &mut nested).unwrap();
+        let nested: Value = serde_json::from_slice(&nested).unwrap();
+        assert_eq!(nested["schemaVersion"], 1);
+        assert_eq!(nested["children"][0]["path"], "notes");
+        assert_eq!(nested["children"][0]["children"][0]["name"], "archive");
+        assert_eq!(nested["children"][0]["children"][1]["path"], "notes/todo.md");
+        assert_eq!(nested["children"][1], json!({ "name": "a.md", "path": "a.md", "type": "file", "size": 2 }));
+
+        let opts = TreeExportOpts {
+            max_depth: Some(2),
+            flat: true,
+            ..TreeExportOpts::default()
+        };
+        let dest = root.join("tree.json");
+        export_tree_to_file_sync(&root, &dest, &opts, &rules).unwrap();
+        let flat: Value = serde_json::from_str(&fs::read_to_string(&dest).unwrap()).unwrap();
+        let paths: Vec<&str> = flat["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["path"].as_str().unwrap())
+            .collect();
+        assert_eq!(paths, vec!["notes", "notes/archive", "notes/todo.md", "a.md"]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,773 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex as StdMutex, RwLock as StdRwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+use crate::error::LatticeError;
+use crate::fileops::timestamp_ms;
+use crate::logging::log_command;
+use crate::paths::resolve_command_path;
+
+const FS_CHANGE_EVENT: &str = "fs-change";
+const FILE_CHANGED_EVENT: &str = "file-changed";
+const FILE_REMOVED_EVENT: &str = "file-removed";
+const FILE_RENAMED_EVENT: &str = "file-renamed";
+const GLOB_MATCH_CHANGE_EVENT: &str = "glob-match-change";
+const WATCH_DEBOUNCE_WINDOW: Duration = Duration::from_millis(200);
+/// How long a removed file may stay missing before it counts as deleted rather than
+/// replaced by an editor's delete-and-recreate save.
+const ATOMIC_SAVE_GRACE: Duration = Duration::from_millis(500);
+const ATOMIC_SAVE_POLL: Duration = Duration::from_millis(50);
+
+pub type WatchId = String;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsChangeKind {
+    Create,
+    Modify,
+    Remove,
+    Rename,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsChange {
+    pub kind: FsChangeKind,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FsChangeEventPayload {
+    watch_id: WatchId,
+    kind: FsChangeKind,
+    paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileChangeEventPayload {
+    watch_id: WatchId,
+    path: String,
+    /// Only set for `file-renamed`, when the new location is known.
+    new_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchKind {
+    Folder,
+    File,
+    Glob,
+}
+
+/// Who started a watch. Only `User` watches come from the watch commands; the rest keep a
+/// feature's state current and are listed so leaks show up in `list_active_watches` too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchOwner {
+    User,
+    FuzzyCache,
+    SearchIndex,
+    AutoReload,
+    Settings,
+}
+
+/// A live watch, as reported by `list_active_watches`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchInfo {
+    pub id: WatchId,
+    pub kind: WatchKind,
+    /// The folder or file as watched; a glob watch's root.
+    pub path: String,
+    /// The current patterns of a glob watch; empty for the other kinds.
+    pub globs: Vec<String>,
+    pub window_label: Option<String>,
+    pub owner: WatchOwner,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+}
+
+struct ActiveWatch {
+    /// Dropping it closes the OS handle (an inotify watch on Linux) and ends the event loop.
+    _watcher: RecommendedWatcher,
+    kind: WatchKind,
+    path: PathBuf,
+    window_label: Option<String>,
+    owner: WatchOwner,
+    created_at: u64,
+    /// Run when the watch is released from here rather than by its owner dropping the
+    /// handle, so the owner stops relying on it.
+    on_release: Option<Box<dyn FnOnce() + Send>>,
+    /// Set for single-file watches, which observe the parent folder and filter to this path.
+    file: Option<PathBuf>,
+    /// Set for glob watches; `update_watch_globs` swaps it without restarting the watcher.
+    globs: Option<Arc<StdRwLock<GlobSet>>>,
+    /// The patterns `globs` was built from.
+    patterns: Vec<String>,
+}
+
+impl ActiveWatch {
+    fn new(watcher: RecommendedWatcher, kind: WatchKind, path: PathBuf, window_label: Option<String>) -> Self {
+        Self {
+            _watcher: watcher,
+            kind,
+            path,
+            window_label,
+            owner: WatchOwner::User,
+            created_at: timestamp_ms(Ok(SystemTime::now())).unwrap_or_default(),
+            on_release: None,
+            file: None,
+            globs: None,
+            patterns: Vec::new(),
+        }
+    }
+
+    fn info(&self, id: &str) -> WatchInfo {
+        WatchInfo {
+            id: id.to_string(),
+            kind: self.kind,
+            path: self.path.to_string_lossy().to_string(),
+            globs: self.patterns.clone(),
+            window_label: self.window_label.clone(),
+            owner: self.owner,
+            created_at: self.created_at,
+        }
+    }
+
+    /// Closes the OS handle, then tells the owner of an internal watch that it's gone.
+    fn release(self) {
+        let Self {
+            _watcher, on_release, ..
+        } = self;
+        drop(_watcher);
+        if let Some(on_release) = on_release {
+            on_release();
+        }
+    }
+}
+
+/// Keeps an internal watch registered for as long as its owner holds it.
+pub(crate) struct WatchHandle {
+    app: AppHandle,
+    id: WatchId,
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        release_watch(&self.app, &self.id);
+    }
+}
+
+#[derive(Default)]
+pub struct WatcherState {
+    watches: StdMutex<HashMap<WatchId, ActiveWatch>>,
+}
+
+fn classify_event_kind(kind: &EventKind) -> Option<FsChangeKind> {
+    match kind {
+        EventKind::Create(_) => Some(FsChangeKind::Create),
+        EventKind::Modify(ModifyKind::Name(_)) => Some(FsChangeKind::Rename),
+        EventKind::Modify(ModifyKind::Metadata(_)) => None,
+        EventKind::Modify(_) => Some(FsChangeKind::Modify),
+        EventKind::Remove(_) => Some(FsChangeKind::Remove),
+        _ => None,
+    }
+}
+
+fn coalesce_change(pending: &mut Vec<FsChange>, event: Event) {
+    let Some(kind) = classify_event_kind(&event.kind) else {
+        return;
+    };
+    let change = FsChange {
+        kind,
+        paths: event
+            .paths
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+    };
+    if !pending.contains(&change) {
+        pending.push(change);
+    }
+}
+
+fn collect_debounced_batch(receiver: &Receiver<Event>, first: Event) -> (Vec<FsChange>, bool) {
+    let mut pending = Vec::new();
+    coalesce_change(&mut pending, first);
+
+    let deadline = Instant::now() + WATCH_DEBOUNCE_WINDOW;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return (pending, true);
+        }
+        match receiver.recv_timeout(remaining) {
+            Ok(event) => coalesce_change(&mut pending, event),
+            Err(RecvTimeoutError::Timeout) => return (pending, true),
+            Err(RecvTimeoutError::Disconnected) => return (pending, false),
+        }
+    }
+}
+
+/// Drains notify events in 200ms bursts and hands each coalesced batch to `on_batch`.
+/// The loop ends when the watcher is dropped or `on_batch` returns `false`.
+pub(crate) fn spawn_debounced_event_loop<F>(receiver: Receiver<Event>, mut on_batch: F)
+where
+    F: FnMut(Vec<FsChange>) -> bool + Send + 'static,
+{
+    std::thread::spawn(move || {
+        while let Ok(first) = receiver.recv() {
+            let (batch, connected) = collect_debounced_batch(&receiver, first);
+            let keep_running = batch.is_empty() || on_batch(batch);
+            if !keep_running || !connected {
+                break;
+            }
+        }
+    });
+}
+
+pub(crate) fn create_event_watcher(path: &Path, mode: RecursiveMode) -> Result<(RecommendedWatcher, Receiver<Event>), String> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+        if let Ok(event) = result {
+            let _ = sender.send(event);
+        }
+    })
+    .map_err(|error| error.to_string())?;
+    watcher.watch(path, mode).map_err(|error| error.to_string())?;
+    Ok((watcher, receiver))
+}
+
+fn release_watch(app: &AppHandle, watch_id: &str) {
+    let state = app.state::<WatcherState>();
+    let removed = state
+        .watches
+        .lock()
+        .ok()
+        .and_then(|mut watches| watches.remove(watch_id));
+    drop(removed);
+}
+
+/// Oldest first, so repeated listings of the same watches come back in the same order.
+fn watch_infos(watches: &HashMap<WatchId, ActiveWatch>) -> Vec<WatchInfo> {
+    let mut infos: Vec<WatchInfo> = watches.iter().map(|(id, watch)| watch.info(id)).collect();
+    infos.sort_by(|left, right| left.created_at.cmp(&right.created_at).then_with(|| left.id.cmp(&right.id)));
+    infos
+}
+
+/// Adds a watch a feature keeps for itself. `on_release` runs if `unwatch` or `unwatch_all`
+/// tears it down; dropping the returned handle releases it without calling `on_release`.
+pub(crate) fn register_watch(
+    app: &AppHandle,
+    owner: WatchOwner,
+    kind: WatchKind,
+    path: PathBuf,
+    window_label: Option<String>,
+    watcher: RecommendedWatcher,
+    on_release: impl FnOnce() + Send + 'static,
+) -> WatchHandle {
+    let id = Uuid::new_v4().to_string();
+    let mut watch = ActiveWatch::new(watcher, kind, path, window_label);
+    watch.owner = owner;
+    watch.on_release = Some(Box::new(on_release));
+    let state = app.state::<WatcherState>();
+    if let Ok(mut watches) = state.watches.lock() {
+        watches.insert(id.clone(), watch);
+    }
+    WatchHandle { app: app.clone(), id }
+}
+
+/// Empties the registry; the caller releases the watches once the lock is dropped.
+fn take_all_watches(state: &WatcherState) -> Result<Vec<ActiveWatch>, String> {
+    let mut watches = state.watches.lock().map_err(|error| error.to_string())?;
+    Ok(std::mem::take(&mut *watches).into_values().collect())
+}
+
+pub fn release_window_watches(app: &AppHandle, window_label: &str) {
+    let state = app.state::<WatcherState>();
+    let released: Vec<ActiveWatch> = match state.watches.lock() {
+        Ok(mut watches) => {
+            let ids: Vec<WatchId> = watches
+                .iter()
+                .filter(|(_, watch)| watch.window_label.as_deref() == Some(window_label))
+                .map(|(id, _)| id.clone())
+                .collect();
+            ids.iter().filter_map(|id| watches.remove(id)).collect()
+        }
+        Err(_) => Vec::new(),
+    };
+    released.into_iter().for_each(ActiveWatch::release);
+}
+
+#[tauri::command]
+pub fn watch_folder(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    state: State<'_, WatcherState>,
+    path: String,
+) -> Result<WatchId, LatticeError> {
+    log_command("watch_folder", || {
+        let root = resolve_command_path(&app, &path)?;
+        if !root.is_dir() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Watch path is not a directory: {}", root.display()),
+            });
+        }
+
+        let watch_id = Uuid::new_v4().to_string();
+        let (watcher, receiver) = create_event_watcher(&root, RecursiveMode::Recursive)?;
+
+        let app_for_events = app.clone();
+        let watch_id_for_events = watch_id.clone();
+        let root_for_events = root.clone();
+        spawn_debounced_event_loop(receiver, move |batch| {
+            for change in batch {
+                for path in &change.paths {
+                    crate::child_counts::invalidate_child_counts(&app_for_events, Path::new(path));
+                }
+                let _ = app_for_events.emit(
+                    FS_CHANGE_EVENT,
+                    FsChangeEventPayload {
+                        watch_id: watch_id_for_events.clone(),
+                        kind: change.kind,
+                        paths: change.paths,
+                    },
+                );
+            }
+
+            // The watched folder itself is gone, so there is nothing left to observe.
+            if !root_for_events.exists() {
+                release_watch(&app_for_events, &watch_id_for_events);
+                return false;
+            }
+            true
+        });
+
+        state
+            .watches
+            .lock()
+            .map_err(|error| error.to_string())?
+            .insert(
+                watch_id.clone(),
+                ActiveWatch::new(watcher, WatchKind::Folder, root, Some(window.label().to_string())),
+            );
+
+        Ok(watch_id)
+    })
+}
+
+fn touches_path(batch: &[FsChange], target: &str) -> bool {
+    batch
+        .iter()
+        .any(|change| change.paths.iter().any(|path| path == target))
+}
+
+/// The destination of a rename away from `target`, when both ends were reported.
+fn renamed_to(batch: &[FsChange], target: &str) -> Option<String> {
+    batch.iter().find_map(|change| match change.paths.as_slice() {
+        [from, to] if change.kind == FsChangeKind::Rename && from == target && to != target => Some(to.clone()),
+        _ => None,
+    })
+}
+
+fn reappears_within(path: &Path, grace: Duration) -> bool {
+    let deadline = Instant::now() + grace;
+    while Instant::now() < deadline {
+        std::thread::sleep(ATOMIC_SAVE_POLL);
+        if path.exists() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Watching the parent folder rather than the file itself keeps the watch alive across
+/// saves that replace the file.
+#[tauri::command]
+pub fn watch_file(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    state: State<'_, WatcherState>,
+    path: String,
+) -> Result<WatchId, LatticeError> {
+    log_command("watch_file", || {
+        let target = fs::canonicalize(resolve_command_path(&app, &path)?)?;
+        if !target.is_file() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Watch path is not a file: {}", target.display()),
+            });
+        }
+        let parent = target
+            .parent()
+            .ok_or_else(|| format!("File has no parent folder: {}", target.display()))?
+            .to_path_buf();
+        let window_label = window.label().to_string();
+
+        let mut watches = state.watches.lock().map_err(|error| error.to_string())?;
+        if let Some((id, _)) = watches
+            .iter()
+            .find(|(_, watch)| {
+                watch.file.as_ref() == Some(&target) && watch.window_label.as_deref() == Some(&window_label)
+            })
+        {
+            return Ok(id.clone());
+        }
+
+        let watch_id = Uuid::new_v4().to_string();
+        let (watcher, receiver) = create_event_watcher(&parent, RecursiveMode::NonRecursive)?;
+
+        let app_for_events = app.clone();
+        let watch_id_for_events = watch_id.clone();
+        let label_for_events = window_label.clone();
+        let target_for_events = target.clone();
+        spawn_debounced_event_loop(receiver, move |batch| {
+            let target_path = target_for_events.to_string_lossy().to_string();
+            if !touches_path(&batch, &target_path) {
+                return true;
+            }
+
+            let payload = |new_path| FileChangeEventPayload {
+                watch_id: watch_id_for_events.clone(),
+                path: target_path.clone(),
+                new_path,
+            };
+            if target_for_events.exists() || reappears_within(&target_for_events, ATOMIC_SAVE_GRACE) {
+                let _ = app_for_events.emit_to(&label_for_events, FILE_CHANGED_EVENT, payload(None));
+                return true;
+            }
+
+            match renamed_to(&batch, &target_path) {
+                Some(new_path) => {
+                    let _ = app_for_events.emit_to(&label_for_events, FILE_RENAMED_EVENT, payload(Some(new_path)));
+                }
+                None => {
+                    let _ = app_for_events.emit_to(&label_for_events, FILE_REMOVED_EVENT, payload(None));
+                }
+            }
+            release_watch(&app_for_events, &watch_id_for_events);
+            false
+        });
+
+        watches.insert(
+            watch_id.clone(),
+            ActiveWatch {
+                file: Some(target.clone()),
+                ..ActiveWatch::new(watcher, WatchKind::File, target, Some(window_label))
+            },
+        );
+
+        Ok(watch_id)
+    })
+}
+
+/// Every pattern is checked so the error can say which one is malformed.
+fn build_glob_set(globs: &[String]) -> Result<GlobSet, LatticeError> {
+    let mut builder = GlobSetBuilder::new();
+    let mut added = 0;
+    for pattern in globs.iter().map(|pattern| pattern.trim()).filter(|pattern| !pattern.is_empty()) {
+        let glob = Glob::new(pattern).map_err(|error| LatticeError::InvalidInput {
+            message: format!("Invalid glob {pattern:?}: {}", error.kind()),
+        })?;
+        builder.add(glob);
+        added += 1;
+    }
+    if added == 0 {
+        return Err(LatticeError::InvalidInput {
+            message: "A glob watch needs at least one pattern.".to_string(),
+        });
+    }
+    builder.build().map_err(|error| error.to_string().into())
+}
+
+/// Globs see paths relative to `root` with `/` separators. A rename is kept whole when
+/// either end matches, so moving a file out of the glob is still reported.
+fn matching_changes(batch: Vec<FsChange>, root: &Path, globs: &GlobSet) -> Vec<FsChange> {
+    let matches = |path: &String| {
+        Path::new(path)
+            .strip_prefix(root)
+            .is_ok_and(|relative| globs.is_match(relative.to_string_lossy().replace('\\', "/")))
+    };
+    batch
+        .into_iter()
+        .filter(|change| change.paths.iter().any(matches))
+        .collect()
+}
+
+/// Like `watch_folder`, but only changes to paths matching one of `globs` are emitted.
+#[tauri::command]
+pub fn watch_glob(
+    app: AppHandle,
+    window: tauri::WebviewWindow,
+    state: State<'_, WatcherState>,
+    root: String,
+    globs: Vec<String>,
+) -> Result<WatchId, LatticeError> {
+    log_command("watch_glob", || {
+        let patterns = globs;
+        let globs = Arc::new(StdRwLock::new(build_glob_set(&patterns)?));
+        let root = fs::canonicalize(resolve_command_path(&app, &root)?)?;
+        if !root.is_dir() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Watch path is not a directory: {}", root.display()),
+            });
+        }
+
+        let watch_id = Uuid::new_v4().to_string();
+        let (watcher, receiver) = create_event_watcher(&root, RecursiveMode::Recursive)?;
+
+        let app_for_events = app.clone();
+        let watch_id_for_events = watch_id.clone();
+        let globs_for_events = globs.clone();
+        let root_for_events = root.clone();
+        spawn_debounced_event_loop(receiver, move |batch| {
+            let changes = match globs_for_events.read() {
+                Ok(globs) => matching_changes(batch, &root_for_events, &globs),
+                Err(_) => Vec::new(),
+            };
+            for change in changes {
+                let _ = app_for_events.emit(
+                    GLOB_MATCH_CHANGE_EVENT,
+                    FsChangeEventPayload {
+                        watch_id: watch_id_for_events.clone(),
+                        kind: change.kind,
+                        paths: change.paths,
+                    },
+                );
+            }
+
+            if !root_for_events.exists() {
+                release_watch(&app_for_events, &watch_id_for_events);
+                return false;
+            }
+            true
+        });
+
+        state
+            .watches
+            .lock()
+            .map_err(|error| error.to_string())?
+            .insert(
+                watch_id.clone(),
+                ActiveWatch {
+                    globs: Some(globs),
+                    patterns,
+                    ..ActiveWatch::new(watcher, WatchKind::Glob, root, Some(window.label().to_string()))
+                },
+            );
+
+        Ok(watch_id)
+    })
+}
+
+/// Replaces the patterns of a `watch_glob` watch; events already in flight use the old set.
+#[tauri::command]
+pub fn update_watch_globs(state: State<'_, WatcherState>, id: WatchId, globs: Vec<String>) -> Result<(), LatticeError> {
+    log_command("update_watch_globs", || {
+        let updated = build_glob_set(&globs)?;
+        let mut watches = state.watches.lock().map_err(|error| error.to_string())?;
+        let watch = watches.get_mut(&id).ok_or_else(|| LatticeError::NotFound {
+            message: format!("Watch not found: {id}"),
+        })?;
+        let Some(current) = &watch.globs else {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Watch {id} is not a glob watch."),
+            });
+        };
+        *current.write().map_err(|error| error.to_string())? = updated;
+        watch.patterns = globs;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn unwatch_folder(state: State<'_, WatcherState>, id: WatchId) -> Result<(), LatticeError> {
+    log_command("unwatch_folder", || unwatch(state, id))
+}
+
+/// Releases any watch, folder or single file, including one a feature keeps for itself.
+#[tauri::command]
+pub fn unwatch(state: State<'_, WatcherState>, id: WatchId) -> Result<(), LatticeError> {
+    log_command("unwatch", || {
+        let removed = state
+            .watches
+            .lock()
+            .map_err(|error| error.to_string())?
+            .remove(&id);
+
+        match removed {
+            Some(watch) => {
+                watch.release();
+                Ok(())
+            }
+            None => Err(LatticeError::NotFound {
+                message: format!("Watch not found: {id}"),
+            }),
+        }
+    })
+}
+
+#[tauri::command]
+pub fn list_active_watches(state: State<'_, WatcherState>) -> Result<Vec<WatchInfo>, LatticeError> {
+    log_command("list_active_watches", || Ok(watch_infos(&*state.watches.lock().map_err(|error| error.to_string())?)))
+}
+
+/// Releases every watch, internal ones included, and returns how many there were.
+#[tauri::command]
+pub fn unwatch_all(state: State<'_, WatcherState>) -> Result<usize, LatticeError> {
+    log_command("unwatch_all", || {
+        let released = take_all_watches(&state)?;
+        let count = released.len();
+        released.into_iter().for_each(ActiveWatch::release);
+        Ok(count)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RenameMode};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn coalesces_duplicate_changes_within_a_burst() {
+        let (sender, receiver) = mpsc::channel();
+        let modify = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+        sender.send(event(modify, "/notes/a.md")).unwrap();
+        sender.send(event(modify, "/notes/a.md")).unwrap();
+        sender
+            .send(event(EventKind::Modify(ModifyKind::Name(RenameMode::Both)), "/notes/b.md"))
+            .unwrap();
+        drop(sender);
+
+        let first = event(EventKind::Create(CreateKind::File), "/notes/a.md");
+        let (batch, connected) = collect_debounced_batch(&receiver, first);
+
+        assert!(!connected);
+        let kinds: Vec<FsChangeKind> = batch.iter().map(|change| change.kind).collect();
+        assert_eq!(kinds, vec![FsChangeKind::Create, FsChangeKind::Modify, FsChangeKind::Rename]);
+    }
+
+    #[test]
+    fn single_file_watches_filter_to_their_path_and_follow_renames() {
+        let change = |kind, paths: &[&str]| FsChange {
+            kind,
+            paths: paths.iter().map(|path| path.to_string()).collect(),
+        };
+        let batch = vec![
+            change(FsChangeKind::Modify, &["/notes/other.md"]),
+            change(FsChangeKind::Rename, &["/notes/a.md", "/notes/b.md"]),
+        ];
+
+        assert!(touches_path(&batch, "/notes/a.md"));
+        assert!(!touches_path(&batch[..1], "/notes/a.md"));
+        assert_eq!(renamed_to(&batch, "/notes/a.md").as_deref(), Some("/notes/b.md"));
+        // An atomic save renames a temp file onto the target; that is not a rename away.
+        assert_eq!(renamed_to(&batch, "/notes/b.md"), None);
+    }
+
+    #[test]
+    fn glob_watches_keep_only_matching_changes() {
+        let change = |kind, paths: &[&str]| FsChange {
+            kind,
+            paths: paths.iter().map(|path| path.to_string()).collect(),
+        };
+        let globs = build_glob_set(&["**/*.md".to_string(), " ".to_string()]).unwrap();
+        let batch = vec![
+            change(FsChangeKind::Modify, &["/vault/notes/a.md"]),
+            change(FsChangeKind::Create, &["/vault/node_modules/x/index.js"]),
+            change(FsChangeKind::Rename, &["/vault/b.md", "/vault/b.txt"]),
+            change(FsChangeKind::Modify, &["/elsewhere/c.md"]),
+        ];
+
+        let kept = matching_changes(batch, Path::new("/vault"), &globs);
+        let kinds: Vec<_> = kept.iter().map(|change| change.kind).collect();
+        assert_eq!(kinds, [FsChangeKind::Modify, FsChangeKind::Rename]);
+
+        let error = build_glob_set(&["*.md".to_string(), "notes/[".to_string()]).unwrap_err();
+        assert!(error.message().contains("\"notes/[\""), "{}", error.message());
+        assert!(build_glob_set(&[]).is_err());
+    }
+
+    /// Inotify watches on `dir` held by this process, from the `ino:` of each watch line in
+    /// `/proc/self/fdinfo`.
+    #[cfg(target_os = "linux")]
+    fn inotify_watches_on(dir: &Path) -> usize {
+        use std::os::unix::fs::MetadataExt;
+        let inode = format!("ino:{:x} ", fs::metadata(dir).unwrap().ino());
+        fs::read_dir("/proc/self/fdinfo")
+            .unwrap()
+            .flatten()
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .map(|info| info.lines().filter(|line| line.starts_with("inotify") && line.contains(&inode)).count())
+            .sum()
+    }
+
+    #[test]
+    fn registry_lists_watches_and_unwatching_all_releases_them() {
+        let root = std::env::temp_dir().join(format!("lattice-watch-registry-{}", Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let state = WatcherState::default();
+        let released = Arc::new(AtomicBool::new(false));
+        {
+            let mut watches = state.watches.lock().unwrap();
+            let (folder, _folder_events) = create_event_watcher(&root, RecursiveMode::Recursive).unwrap();
+            watches.insert("b".to_string(), ActiveWatch::new(folder, WatchKind::Folder, root.clone(), None));
+            let (glob, _glob_events) = create_event_watcher(&root, RecursiveMode::Recursive).unwrap();
+            let mut glob = ActiveWatch::new(glob, WatchKind::Glob, root.clone(), Some("main".to_string()));
+            glob.created_at = 0;
+            glob.patterns = vec!["**/*.md".to_string()];
+            watches.insert("a".to_string(), glob);
+            let (cache, _cache_events) = create_event_watcher(&root, RecursiveMode::Recursive).unwrap();
+            let mut cache = ActiveWatch::new(cache, WatchKind::Folder, root.clone(), None);
+            cache.owner = WatchOwner::FuzzyCache;
+            let released = released.clone();
+            cache.on_release = Some(Box::new(move || released.store(true, Ordering::Relaxed)));
+            watches.insert("c".to_string(), cache);
+        }
+
+        let infos = watch_infos(&state.watches.lock().unwrap());
+        let ids: Vec<&str> = infos.iter().map(|info| info.id.as_str()).collect();
+        assert_eq!(ids[0], "a");
+        assert_eq!(infos.len(), 3);
+        assert_eq!(infos[0].kind, WatchKind::Glob);
+        assert_eq!(infos[0].globs, ["**/*.md"]);
+        assert_eq!(infos[1].path, root.to_string_lossy());
+        let cache = infos.iter().find(|info| info.id == "c").unwrap();
+        assert_eq!(cache.owner, WatchOwner::FuzzyCache);
+        #[cfg(target_os = "linux")]
+        assert_eq!(inotify_watches_on(&root), 3);
+
+        let taken = take_all_watches(&state).unwrap();
+        assert_eq!(taken.len(), 3);
+        assert!(state.watches.lock().unwrap().is_empty());
+        taken.into_iter().for_each(ActiveWatch::release);
+        assert!(released.load(Ordering::Relaxed));
+        // The backend closes its descriptor on its own thread once the watcher is dropped.
+        #[cfg(target_os = "linux")]
+        {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while inotify_watches_on(&root) > 0 && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            assert_eq!(inotify_watches_on(&root), 0);
+        }
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::error::LatticeError;
+use crate::logging::log_command;
+use crate::{build_app_settings_from_store, save_app_settings};
+
+/// Anything fainter and a window left over a busy desktop becomes hard to find again.
+const MIN_WINDOW_OPACITY: f64 = 0.3;
+const OPAQUE: f64 = 1.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowFlags {
+    pub always_on_top: bool,
+    pub opacity: f64,
+    /// `false` where `set_window_opacity` returns `Unsupported`.
+    pub opacity_supported: bool,
+}
+
+fn main_window(app: &AppHandle) -> Result<WebviewWindow, LatticeError> {
+    app.get_webview_window("main").ok_or_else(|| LatticeError::NotFound {
+        message: "Main window is not available.".to_string(),
+    })
+}
+
+/// Settings outside `MIN_WINDOW_OPACITY..=1` are clamped rather than rejected.
+pub(crate) fn normalize_window_opacity(opacity: Option<f64>) -> Option<f64> {
+    opacity
+        .filter(|opacity| opacity.is_finite())
+        .map(|opacity| opacity.clamp(MIN_WINDOW_OPACITY, OPAQUE))
+        .filter(|opacity| *opacity < OPAQUE)
+}
+
+const OPACITY_SUPPORTED: bool = cfg!(any(windows, target_os = "linux"));
+
+#[cfg(windows)]
+fn apply_native_opacity(window: &WebviewWindow, opacity: f64) -> Result<(), String> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE, LWA_ALPHA, WS_EX_LAYERED,
+    };
+
+    let hwnd = window.hwnd().map_err(|error| error.to_string())?.0;
+    // SAFETY: `hwnd` is the live handle of this window, and these calls only touch its styles.
+    let applied = unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED as isize);
+        SetLayeredWindowAttributes(hwnd, 0, (opacity * 255.0).round() as u8, LWA_ALPHA)
+    };
+    if applied == 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(())
+}
+
+/// GTK objects live on the main thread, so the change is queued there.
+#[cfg(target_os = "linux")]
+fn apply_native_opacity(window: &WebviewWindow, opacity: f64) -> Result<(), String> {
+    use gtk::prelude::WidgetExt;
+
+    let target = window.clone();
+    window
+        .run_on_main_thread(move || {
+            if let Ok(gtk_window) = target.gtk_window() {
+                gtk_window.set_opacity(opacity);
+            }
+        })
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn apply_native_opacity(_window: &WebviewWindow, _opacity: f64) -> Result<(), String> {
+    Err("Window opacity isn't supported on this platform.".to_string())
+}
+
+/// Re-applies the saved flags during `setup`; an unsupported opacity is skipped quietly.
+pub(crate) fn restore_window_flags(app: &AppHandle) -> Result<(), LatticeError> {
+    let settings = build_app_settings_from_store(app)?;
+    let window = main_window(app)?;
+    if settings.always_on_top {
+        window.set_always_on_top(true).map_err(|error| error.to_string())?;
+    }
+    if let Some(opacity) = settings.window_opacity.filter(|_| OPACITY_SUPPORTED) {
+        apply_native_opacity(&window, opacity)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_window_flags(app: AppHandle) -> Result<WindowFlags, LatticeError> {
+    log_command("get_window_flags", || {
+        let window = main_window(&app)?;
+        let opacity = build_app_settings_from_store(&app)?.window_opacity;
+        Ok(WindowFlags {
+            always_on_top: window.is_always_on_top().map_err(|error| error.to_string())?,
+            opacity: opacity.filter(|_| OPACITY_SUPPORTED).unwrap_or(OPAQUE),
+            opacity_supported: OPACITY_SUPPORTED,
+        })
+    })
+}
+
+#[tauri::command]
+pub fn set_always_on_top(app: AppHandle, on: bool) -> Result<(), LatticeError> {
+    log_command("set_always_on_top", || {
+        main_window(&app)?
+            .set_always_on_top(on)
+            .map_err(|error| error.to_string())?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.always_on_top = on;
+        save_app_settings(&app, settings)?;
+        Ok(())
+    })
+}
+
+/// Returns the opacity actually applied, after clamping to `MIN_WINDOW_OPACITY..=1`.
+#[tauri::command]
+pub fn set_window_opacity(app: AppHandle, opacity: f64) -> Result<f64, LatticeError> {
+    log_command("set_window_opacity", || {
+        if !OPACITY_SUPPORTED {
+            return Err(LatticeError::Unsupported {
+                message: "Window opacity isn't supported on this platform.".to_string(),
+            });
+        }
+        if !opacity.is_finite() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Opacity must be a number between {MIN_WINDOW_OPACITY} and 1: {opacity}"),
+            });
+        }
+        let applied = opacity.clamp(MIN_WINDOW_OPACITY, OPAQUE);
+        apply_native_opacity(&main_window(&app)?, applied)?;
+        let mut settings = build_app_settings_from_store(&app)?;
+        settings.window_opacity = normalize_window_opacity(Some(applied));
+        save_app_settings(&app, settings)?;
+        Ok(applied)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_opacity_is_clamped_and_opaque_means_unset() {
+        assert_eq!(normalize_window_opacity(Some(0.05)), Some(MIN_WINDOW_OPACITY));
+        assert_eq!(normalize_window_opacity(Some(0.8)), Some(0.8));
+        assert_eq!(normalize_window_opacity(Some(3.0)), None);
+        assert_eq!(normalize_window_opacity(Some(f64::NAN)), None);
+        assert_eq!(normalize_window_opacity(None), None);
+    }
+}
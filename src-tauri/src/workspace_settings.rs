@@ -0,0 +1,247 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::error::LatticeError;
+use crate::fileops::write_bytes_atomic;
+use crate::ignore_rules::invalidate_ignore_matchers;
+use crate::logging::log_command;
+use crate::paths::{resolve_command_path, without_verbatim_prefix};
+use crate::settings_recovery::save_settings_store;
+use crate::settings_store_path;
+
+pub(crate) const WORKSPACE_SETTINGS_DIR: &str = ".lattice";
+const WORKSPACE_SETTINGS_FILE: &str = "workspace.json";
+/// Extra ignore globs (gitignore syntax) applied to tree, search and fuzzy walks.
+pub(crate) const IGNORE_PATTERNS_KEY: &str = "ignorePatterns";
+/// Global store entry holding settings for folders we couldn't write into,
+/// keyed by folder path.
+pub(crate) const WORKSPACE_SETTINGS_FALLBACK_KEY: &str = "workspace_settings";
+
+fn workspace_settings_path(folder: &Path) -> PathBuf {
+    folder.join(WORKSPACE_SETTINGS_DIR).join(WORKSPACE_SETTINGS_FILE)
+}
+
+fn resolve_workspace_folder(app: &AppHandle, folder: &str) -> Result<PathBuf, LatticeError> {
+    let root = resolve_command_path(app, folder)?;
+    if !root.is_dir() {
+        return Err(LatticeError::InvalidInput {
+            message: format!("Workspace folder is not a directory: {}", root.display()),
+        });
+    }
+    Ok(root)
+}
+
+/// Symlinked and differently spelled paths to one folder share an entry.
+fn fallback_folder_key(folder: &Path) -> String {
+    let folder = fs::canonicalize(folder)
+        .map(|canonical| without_verbatim_prefix(&canonical))
+        .unwrap_or_else(|_| folder.to_path_buf());
+    folder
+        .to_string_lossy()
+        .trim_end_matches(['/', '\\'])
+        .to_string()
+}
+
+fn read_workspace_file(folder: &Path) -> Result<Map<String, Value>, LatticeError> {
+    let path = workspace_settings_path(folder);
+    let raw = match fs::read(&path) {
+        Ok(raw) => raw,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(error) => return Err(LatticeError::at_path(&path, error)),
+    };
+
+    match serde_json::from_slice::<Value>(&raw) {
+        Ok(Value::Object(entries)) => Ok(entries),
+        Ok(_) => Err(format!("Workspace settings must be a JSON object: {}", path.display()).into()),
+        Err(error) => Err(format!("Invalid workspace settings in {}: {error}", path.display()).into()),
+    }
+}
+
+/// `None` when the folder's file can't be read or isn't a JSON object. Such a file is left
+/// for the user to fix and treated like an unwritable one, so settings use the fallback.
+fn usable_workspace_file(folder: &Path) -> Option<Map<String, Value>> {
+    read_workspace_file(folder)
+        .map_err(|error| log::warn!("Ignoring workspace settings: {error}"))
+        .ok()
+}
+
+fn write_workspace_file(folder: &Path, entries: &Map<String, Value>) -> Result<(), LatticeError> {
+    let path = workspace_settings_path(folder);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let encoded = serde_json::to_vec_pretty(entries).map_err(|error| error.to_string())?;
+    write_bytes_atomic(&path, &encoded)
+}
+
+fn read_fallback_entries(app: &AppHandle, folder: &Path) -> Result<Map<String, Value>, LatticeError> {
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    Ok(store
+        .get(WORKSPACE_SETTINGS_FALLBACK_KEY)
+        .and_then(|value| value.get(fallback_folder_key(folder)).cloned())
+        .and_then(|value| match value {
+            Value::Object(entries) => Some(entries),
+            _ => None,
+        })
+        .unwrap_or_default())
+}
+
+fn update_fallback_entries<F>(app: &AppHandle, folder: &Path, update: F) -> Result<(), LatticeError>
+where
+    F: FnOnce(&mut Map<String, Value>),
+{
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    let mut folders = match store.get(WORKSPACE_SETTINGS_FALLBACK_KEY) {
+        Some(Value::Object(folders)) => folders,
+        _ => Map::new(),
+    };
+
+    let folder_key = fallback_folder_key(folder);
+    let mut entries = match folders.remove(&folder_key) {
+        Some(Value::Object(entries)) => entries,
+        _ => Map::new(),
+    };
+    update(&mut entries);
+    if !entries.is_empty() {
+        folders.insert(folder_key, Value::Object(entries));
+    }
+
+    if folders.is_empty() {
+        store.delete(WORKSPACE_SETTINGS_FALLBACK_KEY);
+    } else {
+        store.set(WORKSPACE_SETTINGS_FALLBACK_KEY, Value::Object(folders));
+    }
+    save_settings_store(app).map_err(Into::into)
+}
+
+pub(crate) fn read_workspace_value(app: &AppHandle, folder: &Path, key: &str) -> Result<Option<Value>, LatticeError> {
+    // Fallback entries only exist while the folder's file can't be written, so they're newer.
+    if let Some(value) = read_fallback_entries(app, folder)?.remove(key) {
+        return Ok(Some(value));
+    }
+    Ok(usable_workspace_file(folder).and_then(|mut entries| entries.remove(key)))
+}
+
+/// The folder's `ignorePatterns`; unreadable or mistyped settings count as none.
+pub(crate) fn read_ignore_patterns(app: &AppHandle, folder: &Path) -> Vec<String> {
+    read_workspace_value(app, folder, IGNORE_PATTERNS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_value::<Vec<String>>(value).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_workspace_setting(app: AppHandle, folder: String, key: String) -> Result<Option<Value>, LatticeError> {
+    log_command("get_workspace_setting", || {
+        let folder = resolve_workspace_folder(&app, &folder)?;
+        read_workspace_value(&app, &folder, &key)
+    })
+}
+
+pub(crate) fn write_workspace_value(
+    app: &AppHandle,
+    folder: &Path,
+    key: String,
+    value: Value,
+) -> Result<(), LatticeError> {
+    let written = usable_workspace_file(folder).is_some_and(|mut entries| {
+        entries.insert(key.clone(), value.clone());
+        write_workspace_file(folder, &entries).is_ok()
+    });
+    if key == IGNORE_PATTERNS_KEY {
+        invalidate_ignore_matchers(app, folder);
+    }
+
+    if written {
+        // The folder copy is authoritative now, so drop a stale fallback entry if there is one;
+        // otherwise the global store isn't touched at all.
+        if !read_fallback_entries(app, folder)?.contains_key(&key) {
+            return Ok(());
+        }
+        update_fallback_entries(app, folder, |fallback| {
+            fallback.remove(&key);
+        })
+    } else {
+        // Read-only folders (mounted media, shared drives) keep their settings globally.
+        update_fallback_entries(app, folder, |fallback| {
+            fallback.insert(key, value);
+        })
+    }
+}
+
+#[tauri::command]
+pub fn set_workspace_setting(app: AppHandle, folder: String, key: String, value: Value) -> Result<(), LatticeError> {
+    log_command("set_workspace_setting", || {
+        let folder = resolve_workspace_folder(&app, &folder)?;
+        write_workspace_value(&app, &folder, key, value)
+    })
+}
+
+#[tauri::command]
+pub fn list_workspace_keys(app: AppHandle, folder: String) -> Result<Vec<String>, LatticeError> {
+    log_command("list_workspace_keys", || {
+        let folder = resolve_workspace_folder(&app, &folder)?;
+        let mut keys: BTreeSet<String> = usable_workspace_file(&folder)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        keys.extend(read_fallback_entries(&app, &folder)?.into_iter().map(|(key, _)| key));
+        Ok(keys.into_iter().collect())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn workspace_file_roundtrips_entries_inside_the_folder() {
+        let root = std::env::temp_dir().join(format!("lattice-workspace-settings-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        assert!(read_workspace_file(&root).unwrap().is_empty());
+
+        let mut entries = Map::new();
+        entries.insert("sortOrder".to_string(), json!("modified"));
+        entries.insert("pinnedFiles".to_string(), json!(["readme.md"]));
+        write_workspace_file(&root, &entries).unwrap();
+
+        assert!(root.join(".lattice/workspace.json").is_file());
+        let loaded = read_workspace_file(&root).unwrap();
+        assert_eq!(loaded.get("sortOrder"), Some(&json!("modified")));
+        assert_eq!(loaded.get("pinnedFiles"), Some(&json!(["readme.md"])));
+
+        fs::write(root.join(".lattice/workspace.json"), "{\"sortOrder\":").unwrap();
+        assert!(read_workspace_file(&root).is_err());
+        assert_eq!(usable_workspace_file(&root), None);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn fallback_keys_ignore_trailing_separators() {
+        assert_eq!(fallback_folder_key(Path::new("/notes/")), "/notes");
+        assert_eq!(fallback_folder_key(Path::new("C:\\notes\\")), "C:\\notes");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fallback_keys_follow_symlinks_to_the_same_folder() {
+        let root = std::env::temp_dir().join(format!("lattice-workspace-key-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("notes")).unwrap();
+        std::os::unix::fs::symlink(root.join("notes"), root.join("linked")).unwrap();
+
+        let key = fallback_folder_key(&root.join("notes"));
+        assert_eq!(fallback_folder_key(&root.join("linked/")), key);
+        assert_eq!(key, fs::canonicalize(root.join("notes")).unwrap().to_string_lossy());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
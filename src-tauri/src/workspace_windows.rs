@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, WebviewWindow, WebviewWindowBuilder};
+use tauri_plugin_store::StoreExt;
+use uuid::Uuid;
+
+use crate::error::LatticeError;
+use crate::logging::log_command_async;
+use crate::paths::resolve_command_path;
+use crate::settings_recovery::save_settings_store;
+use crate::theme::saved_theme_preference;
+use crate::{build_app_settings_from_store, settings_store_path};
+
+pub(crate) const OPEN_WINDOWS_KEY: &str = "open_windows";
+pub(crate) const WORKSPACE_WINDOW_LABEL_PREFIX: &str = "workspace-";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OpenWindowRecord {
+    pub(crate) label: String,
+    pub(crate) folder: String,
+}
+
+pub(crate) fn read_open_windows(app: &AppHandle) -> Vec<OpenWindowRecord> {
+    app.store(settings_store_path(app))
+        .ok()
+        .and_then(|store| store.get(OPEN_WINDOWS_KEY))
+        .and_then(|value| serde_json::from_value::<Vec<OpenWindowRecord>>(value).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn write_open_windows(app: &AppHandle, records: &[OpenWindowRecord]) -> Result<(), String> {
+    let store = app.store(settings_store_path(app)).map_err(|error| error.to_string())?;
+    if records.is_empty() {
+        store.delete(OPEN_WINDOWS_KEY);
+    } else {
+        store.set(
+            OPEN_WINDOWS_KEY,
+            serde_json::to_value(records).map_err(|error| error.to_string())?,
+        );
+    }
+    save_settings_store(app)
+}
+
+fn initial_folder_script(folder: &str) -> String {
+    // A JSON string literal is also a valid JS string literal.
+    format!(
+        "window.__LATTICE_INITIAL_FOLDER__ = {};",
+        Value::String(folder.to_string())
+    )
+}
+
+fn build_workspace_window(app: &AppHandle, label: &str, folder: &str) -> Result<WebviewWindow, String> {
+    // Secondary windows share the main window's configuration; only the label differs,
+    // which also gives each one its own window-state entry.
+    let mut config = app.config().app.windows.first().cloned().unwrap_or_default();
+    config.label = label.to_string();
+    config.maximized = false;
+
+    WebviewWindowBuilder::from_config(app, &config)
+        .map_err(|error| error.to_string())?
+        .initialization_script(initial_folder_script(folder))
+        .theme(saved_theme_preference(app).native())
+        .build()
+        .map_err(|error| error.to_string())
+}
+
+fn restorable_windows(records: Vec<OpenWindowRecord>) -> Vec<OpenWindowRecord> {
+    let mut seen_labels = HashSet::new();
+    records
+        .into_iter()
+        .filter(|record| record.label.starts_with(WORKSPACE_WINDOW_LABEL_PREFIX))
+        .filter(|record| Path::new(&record.folder).is_dir())
+        .filter(|record| seen_labels.insert(record.label.clone()))
+        .collect()
+}
+
+/// Reopens the secondary windows from the previous session when the user opted in.
+pub(crate) fn restore_open_windows(app: &AppHandle) {
+    let enabled = build_app_settings_from_store(app)
+        .map(|settings| settings.restore_open_windows)
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+
+    let restored: Vec<OpenWindowRecord> = restorable_windows(read_open_windows(app))
+        .into_iter()
+        .filter(|record| {
+            app.get_webview_window(&record.label).is_some()
+                || build_workspace_window(app, &record.label, &record.folder).is_ok()
+        })
+        .collect();
+    let _ = write_open_windows(app, &restored);
+}
+
+pub(crate) fn forget_closed_window(app: &AppHandle, label: &str) {
+    if !label.starts_with(WORKSPACE_WINDOW_LABEL_PREFIX) {
+        return;
+    }
+    // When this was the last window the app is exiting, so keep it for the next launch.
+    let other_windows_open = app.webview_windows().keys().any(|other| other != label);
+    if !other_windows_open {
+        return;
+    }
+
+    let mut records = read_open_windows(app);
+    let before = records.len();
+    records.retain(|record| record.label != label);
+    if records.len() != before {
+        let _ = write_open_windows(app, &records);
+    }
+}
+
+/// Opens `folder` in a fresh window without recording it; returns the window.
+pub(crate) fn open_workspace_window(app: &AppHandle, folder: &str) -> Result<WebviewWindow, LatticeError> {
+    let label = format!("{WORKSPACE_WINDOW_LABEL_PREFIX}{}", Uuid::new_v4());
+    Ok(build_workspace_window(app, &label, folder)?)
+}
+
+#[tauri::command]
+pub async fn open_folder_in_new_window(app: AppHandle, folder: String) -> Result<String, LatticeError> {
+    log_command_async("open_folder_in_new_window", async move {
+        let folder = resolve_command_path(&app, &folder)?.to_string_lossy().to_string();
+        if !Path::new(&folder).is_dir() {
+            return Err(LatticeError::InvalidInput {
+                message: format!("Folder is not a directory: {folder}"),
+            });
+        }
+
+        let label = open_workspace_window(&app, &folder)?.label().to_string();
+
+        let mut records = read_open_windows(&app);
+        records.push(OpenWindowRecord {
+            label: label.clone(),
+            folder,
+        });
+        write_open_windows(&app, &records)?;
+        Ok(label)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restorable_windows_skip_missing_folders_and_duplicate_labels() {
+        let existing = std::env::temp_dir().to_string_lossy().to_string();
+        let record = |label: &str, folder: &str| OpenWindowRecord {
+            label: label.to_string(),
+            folder: folder.to_string(),
+        };
+
+        let restored = restorable_windows(vec![
+            record("workspace-a", &existing),
+            record("workspace-a", &existing),
+            record("workspace-b", "/definitely/missing/lattice-folder"),
+            record("main", &existing),
+        ]);
+
+        assert_eq!(restored, vec![record("workspace-a", &existing)]);
+        assert!(initial_folder_script("C:\\notes \"x\"").contains(r#""C:\\notes \"x\"""#));
+    }
+}